@@ -0,0 +1,113 @@
+//! Quote dispatch under a 500-symbol load. `quote_dispatch_500_symbols`
+//! sends every `qsd` frame from a local mock server through parsing, the
+//! handler chain and the data channel, `quote_parse_dispatch_500_symbols`
+//! feeds the same frames straight to the parser and handler, without the
+//! socket round-trip.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tradingview::{
+    live::{
+        handler::{message::TradingViewResponse, types::TradingViewHandler},
+        mock::{MockFeed, MockFrame, MockScript, MockServer, encode},
+    },
+    websocket::WebSocketClient,
+};
+
+const SYMBOLS: usize = 500;
+
+fn quote_load(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let symbols: Vec<String> = (0..SYMBOLS)
+        .map(|i| format!("BINANCE:SYM{i}USDT"))
+        .collect();
+    let quote = json!({
+        "lp": 101.5, "bid": 101.4, "ask": 101.6, "volume": 1250.0, "ch": 0.5, "chp": 0.49,
+    });
+    let script = MockScript::new().quotes(
+        symbols
+            .iter()
+            .map(|symbol| (symbol.as_str(), quote.clone()))
+            .collect(),
+    );
+
+    let (_server, ws, mut rx) = runtime.block_on(async {
+        let server = MockServer::start(script).await.unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        // A user handler next to the channel forward, so payloads fan out to two consumers
+        let ws = WebSocketClient::builder()
+            .data_tx(tx)
+            .handler(TradingViewHandler::default().on_quote_data(|_, _| {}))
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+        Arc::clone(&ws).spawn_reader_task();
+        (server, ws, rx)
+    });
+
+    let mut params = vec![json!("qs_bench")];
+    params.extend(symbols.iter().map(|symbol| Value::from(symbol.as_str())));
+
+    c.bench_function("quote_dispatch_500_symbols", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                ws.send("quote_add_symbols", &params).await.unwrap();
+                let mut quotes = 0;
+                while quotes < SYMBOLS {
+                    match rx.recv().await {
+                        Some(TradingViewResponse::QuoteData(_)) => quotes += 1,
+                        Some(_) => {}
+                        None => panic!("data channel closed"),
+                    }
+                }
+            })
+        })
+    });
+}
+
+fn quote_parse_dispatch(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let quote = json!({
+        "lp": 101.5, "bid": 101.4, "ask": 101.6, "volume": 1250.0, "ch": 0.5, "chp": 0.49,
+    });
+    // One websocket message with a `qsd` frame per symbol, as the server batches them
+    let message = encode(
+        (0..SYMBOLS)
+            .map(|i| {
+                let name = format!("BINANCE:SYM{i}USDT");
+                MockFrame::new(
+                    "qsd",
+                    vec![
+                        json!("qs_bench"),
+                        json!({ "n": name, "s": "ok", "v": quote }),
+                    ],
+                )
+            })
+            .collect(),
+    )
+    .unwrap();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut feed = MockFeed::builder()
+        .data_tx(tx)
+        .handler(TradingViewHandler::default().on_quote_data(|_, _| {}))
+        .build();
+
+    c.bench_function("quote_parse_dispatch_500_symbols", |b| {
+        b.iter(|| {
+            runtime.block_on(feed.feed(&message));
+            let mut quotes = 0;
+            while let Ok(response) = rx.try_recv() {
+                if let TradingViewResponse::QuoteData(_) = response {
+                    quotes += 1;
+                }
+            }
+            assert_eq!(quotes, SYMBOLS);
+        })
+    });
+}
+
+criterion_group!(benches, quote_load, quote_parse_dispatch);
+criterion_main!(benches);
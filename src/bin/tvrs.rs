@@ -0,0 +1,336 @@
+//! `tvrs` - a small command line tool built on top of the public `tradingview` API.
+//!
+//! ```text
+//! tvrs candles BINANCE:BTCUSDT 1h --limit 1000 --csv
+//! tvrs quote AAPL NASDAQ:MSFT
+//! tvrs search "tesla"
+//! tvrs serve 127.0.0.1:8080   # with the `server` feature
+//! ```
+use clap::{Parser, Subcommand};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::{sync::mpsc, time::timeout};
+use tradingview::{
+    CancellationToken, Config, Error, Interval, OHLCV, Result, get_symbol, history,
+    live::handler::{command::CommandRunner, message::Command, message::TradingViewResponse},
+    paginate_symbols,
+    quote::{models::QuoteValue, options::QuoteSessionOptions},
+    websocket::WebSocketClient,
+};
+use ustr::Ustr;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "tvrs",
+    version,
+    about = "TradingView market data from the command line"
+)]
+struct Cli {
+    /// Auth token used for the websocket session, overrides `TV_AUTH_TOKEN`
+    #[arg(long, global = true)]
+    auth_token: Option<String>,
+
+    /// TOML configuration file, `TV_*` environment variables are used when omitted
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Fetch historical candles for a symbol
+    Candles {
+        /// Symbol as `EXCHANGE:SYMBOL`, or a bare symbol to resolve through search
+        symbol: String,
+        /// Bar interval, e.g. `1m`, `1h`, `1d`, or a TradingView resolution
+        /// such as `1W` or `1M`
+        #[arg(default_value = "1d")]
+        interval: String,
+        /// Maximum number of bars to fetch
+        #[arg(long)]
+        limit: Option<u64>,
+        /// Fetch the full history through replay mode
+        #[arg(long)]
+        replay: bool,
+        /// Print the bars as CSV
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Print a quote snapshot for one or more symbols
+    Quote {
+        /// Symbols as `EXCHANGE:SYMBOL`, or bare symbols to resolve through search
+        #[arg(required = true)]
+        symbols: Vec<String>,
+        /// Seconds to wait for the quote session to complete
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Search for symbols
+    Search {
+        query: String,
+        /// Restrict the search to a single exchange
+        #[arg(long, default_value = "")]
+        exchange: String,
+        /// Maximum number of results to print
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Serve bars, indicators and live events over HTTP
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(default_value = "127.0.0.1:8080")]
+        addr: String,
+        /// Bearer token required on every route
+        #[arg(long, env = "TV_GATEWAY_TOKEN")]
+        token: Option<String>,
+        /// Queue commands from `POST /commands` and `/ws` on the shared connection
+        #[arg(long)]
+        accept_commands: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    let cancel = CancellationToken::new();
+    let ctrl_c = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            ctrl_c.cancel();
+        }
+    });
+
+    if let Err(e) = run(cli, &cancel).await {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli, cancel: &CancellationToken) -> Result<()> {
+    let mut config = match &cli.config {
+        Some(path) => Config::from_file(path)?,
+        None => Config::from_env()?,
+    };
+    if cli.auth_token.is_some() {
+        config.auth_token = cli.auth_token;
+    }
+    config.resolve_auth_token().await?;
+    tradingview::config::set_proxy(config.proxy.clone());
+    tradingview::config::set_tls(config.tls.clone())?;
+    tradingview::config::set_request_timeout(config.timeouts.request());
+    tradingview::client::rate_limit::set_rate_limit(&config.rate_limit);
+
+    match cli.command {
+        Commands::Candles {
+            symbol,
+            interval,
+            limit,
+            replay,
+            csv,
+        } => candles(&config, cancel, &symbol, &interval, limit, replay, csv).await,
+        Commands::Quote { symbols, timeout } => {
+            quote(&config, cancel, &symbols, Duration::from_secs(timeout)).await
+        }
+        Commands::Search {
+            query,
+            exchange,
+            limit,
+        } => search(&query, &exchange, limit).await,
+        #[cfg(feature = "server")]
+        Commands::Serve {
+            addr,
+            token,
+            accept_commands,
+        } => {
+            tradingview::server::Gateway::connect()
+                .maybe_auth_token(config.auth_token.as_deref())
+                .server(config.server)
+                .cancellation_token(cancel)
+                .maybe_bearer_token(token.as_deref())
+                .accept_commands(accept_commands)
+                .call()
+                .await?
+                .serve(addr)
+                .await
+        }
+    }
+}
+
+async fn candles(
+    config: &Config,
+    cancel: &CancellationToken,
+    symbol: &str,
+    interval: &str,
+    limit: Option<u64>,
+    replay: bool,
+    csv: bool,
+) -> Result<()> {
+    let interval = Interval::from_name(interval).map_or_else(|| interval.parse(), Ok)?;
+    let (exchange, symbol) = resolve_symbol(symbol).await?;
+
+    let (info, data) = history::single::retrieve()
+        .symbol(&symbol)
+        .exchange(&exchange)
+        .interval(interval)
+        .maybe_num_bars(limit)
+        .with_replay(replay)
+        .config(config)
+        .cancellation_token(cancel)
+        .call()
+        .await?;
+
+    let bars = match limit {
+        Some(limit) => &data[data.len().saturating_sub(limit as usize)..],
+        None => &data[..],
+    };
+
+    if csv {
+        println!("timestamp,open,high,low,close,volume");
+        for bar in bars {
+            println!(
+                "{},{},{},{},{},{}",
+                bar.timestamp(),
+                bar.open(),
+                bar.high(),
+                bar.low(),
+                bar.close(),
+                bar.volume()
+            );
+        }
+    } else {
+        println!("{} - {} ({} bars)", info.name, info.description, bars.len());
+        for bar in bars {
+            println!(
+                "{}  O {:<12} H {:<12} L {:<12} C {:<12} V {}",
+                bar.datetime().format("%Y-%m-%d %H:%M:%S"),
+                bar.open(),
+                bar.high(),
+                bar.low(),
+                bar.close(),
+                bar.volume()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn quote(
+    config: &Config,
+    cancel: &CancellationToken,
+    symbols: &[String],
+    wait: Duration,
+) -> Result<()> {
+    let mut tickers = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let (exchange, symbol) = resolve_symbol(symbol).await?;
+        tickers.push(format!("{exchange}:{symbol}"));
+    }
+
+    let (data_tx, mut data_rx) = mpsc::unbounded_channel();
+    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+
+    let ws = WebSocketClient::builder()
+        .maybe_auth_token(config.auth_token.as_deref())
+        .server(config.server)
+        .data_tx(data_tx)
+        .cancellation_token(cancel)
+        .build()
+        .await?;
+
+    let runner = CommandRunner::with_config(cmd_rx, Arc::clone(&ws), config.into())
+        .with_cancellation_token(cancel);
+    let shutdown = runner.shutdown_token();
+    let runner_handle = tokio::spawn(async move { runner.run().await });
+
+    let options = QuoteSessionOptions::builder()
+        .symbols(tickers.iter().map(|t| Ustr::from(t)).collect())
+        .build();
+    cmd_tx
+        .send(Command::create_quote_session_with(options))
+        .map_err(|e| Error::Internal(Ustr::from(&e.to_string())))?;
+
+    let mut completed = 0;
+    let collect = async {
+        while let Some(response) = data_rx.recv().await {
+            match response {
+                TradingViewResponse::QuoteData(data) => print_quote(&data),
+                TradingViewResponse::QuoteCompleted(_) => {
+                    completed += 1;
+                    if completed >= tickers.len() {
+                        break;
+                    }
+                }
+                TradingViewResponse::Error(e, _) => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    };
+
+    let result = tokio::select! {
+        result = timeout(wait, collect) => match result {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout(Ustr::from("quote session timed out"))),
+        },
+        _ = cancel.cancelled() => Err(Error::Cancelled),
+    };
+
+    let _ = cmd_tx.send(Command::Delete);
+    shutdown.cancel();
+    let _ = runner_handle.await;
+
+    result
+}
+
+fn print_quote(quote: &QuoteValue) {
+    let fmt = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+    println!(
+        "{}:{}  price {}  change {} ({}%)  bid {}  ask {}  volume {}",
+        quote.exchange.unwrap_or_default(),
+        quote.symbol.unwrap_or_default(),
+        fmt(quote.price),
+        fmt(quote.change),
+        fmt(quote.change_percent),
+        fmt(quote.bid),
+        fmt(quote.ask),
+        fmt(quote.volume),
+    );
+}
+
+async fn search(query: &str, exchange: &str, limit: usize) -> Result<()> {
+    let symbols = paginate_symbols()
+        .search(query)
+        .exchange(exchange)
+        .limit(limit)
+        .call()
+        .collect_all()
+        .await?;
+    for symbol in &symbols {
+        println!(
+            "{:<24} {:<10} {:<8} {}",
+            format!("{}:{}", symbol.exchange, symbol.symbol),
+            symbol.market_type,
+            symbol.currency_code,
+            symbol.description
+        );
+    }
+    Ok(())
+}
+
+/// Split `EXCHANGE:SYMBOL`, falling back to the first search hit for bare symbols.
+async fn resolve_symbol(input: &str) -> Result<(String, String)> {
+    if let Some((exchange, symbol)) = input.split_once(':') {
+        return Ok((exchange.to_uppercase(), symbol.to_uppercase()));
+    }
+
+    match get_symbol(input, "").await {
+        Some(symbol) => Ok((symbol.exchange, symbol.symbol)),
+        None => Err(Error::Internal(Ustr::from(&format!(
+            "no symbol found for {input}"
+        )))),
+    }
+}
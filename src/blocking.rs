@@ -0,0 +1,200 @@
+//! Synchronous wrappers around the async APIs, for CLI tools and scripts.
+//!
+//! Every call blocks the current thread on a runtime owned by this module,
+//! so none of them may be made from inside an async runtime. Anything not
+//! wrapped here can be driven with [`block_on`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tradingview::{Interval, blocking};
+//!
+//! let (info, bars) = blocking::history()
+//!     .symbol("AAPL")
+//!     .exchange("NASDAQ")
+//!     .interval(Interval::OneDay)
+//!     .num_bars(100)
+//!     .call()?;
+//! println!("{}: {} bars", info.name, bars.len());
+//! # Ok::<(), tradingview::Error>(())
+//! ```
+
+use crate::{
+    Config, DataPoint, Interval, MarketSymbol, Range, Result, Symbol, SymbolDetails, SymbolInfo,
+    UserCookies,
+    live::handler::message::TradingViewResponse,
+    pine_indicator::{PineMetadata, PineSearchResult},
+    quote::{
+        options::{QuoteSessionKind, QuoteSessionOptions},
+        state::QuoteSnapshot,
+    },
+    websocket::WebSocketClient,
+};
+use bon::builder;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::{Builder, Runtime};
+use ustr::Ustr;
+
+lazy_static::lazy_static! {
+    static ref RUNTIME: Runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build the blocking runtime");
+}
+
+/// Run `future` to completion on the module's runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+/// Blocking [`crate::history::single::retrieve`].
+#[builder]
+pub fn history(
+    symbol: &str,
+    exchange: &str,
+    interval: Interval,
+    range: Option<Range>,
+    num_bars: Option<u64>,
+    auth_token: Option<&str>,
+    config: Option<&Config>,
+) -> Result<(SymbolInfo, Vec<DataPoint>)> {
+    block_on(
+        crate::history::single::retrieve()
+            .symbol(symbol)
+            .exchange(exchange)
+            .interval(interval)
+            .maybe_range(range)
+            .maybe_num_bars(num_bars)
+            .maybe_auth_token(auth_token)
+            .maybe_config(config)
+            .call(),
+    )
+}
+
+/// Blocking [`crate::history::batch::retrieve`], keyed by `EXCHANGE:SYMBOL`.
+#[builder]
+pub fn history_batch(
+    symbols: &[impl MarketSymbol],
+    interval: Interval,
+    range: Option<Range>,
+    num_bars: Option<u64>,
+    auth_token: Option<&str>,
+    config: Option<&Config>,
+) -> Result<HashMap<String, (SymbolInfo, Vec<DataPoint>)>> {
+    block_on(
+        crate::history::batch::retrieve()
+            .symbols(symbols)
+            .interval(interval)
+            .maybe_range(range)
+            .maybe_num_bars(num_bars)
+            .maybe_auth_token(auth_token)
+            .maybe_config(config)
+            .call(),
+    )
+}
+
+/// Latest quote of every symbol in `symbols`, e.g. `NASDAQ:AAPL`, from a
+/// one-off snapshot session. Symbols without a quote before `timeout` are
+/// left out.
+#[builder]
+pub fn quotes(
+    symbols: &[&str],
+    auth_token: Option<&str>,
+    #[builder(default = Duration::from_secs(10))] timeout: Duration,
+) -> Result<Vec<QuoteSnapshot>> {
+    block_on(async {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .maybe_auth_token(auth_token)
+            .data_tx(tx)
+            .build()
+            .await?;
+        Arc::clone(&ws).spawn_reader_task();
+        ws.create_quote_session_with(
+            QuoteSessionOptions::builder()
+                .kind(QuoteSessionKind::Snapshot)
+                .symbols(symbols.iter().map(|s| Ustr::from(s)).collect())
+                .emit_snapshots(true)
+                .build(),
+        )
+        .await?;
+
+        let mut snapshots: HashMap<Ustr, QuoteSnapshot> = HashMap::new();
+        let mut completed: HashSet<Ustr> = HashSet::new();
+        let collect = async {
+            while completed.len() < symbols.len() {
+                match rx.recv().await {
+                    Some(TradingViewResponse::QuoteSnapshot(snapshot)) => {
+                        snapshots.insert(snapshot.symbol, snapshot);
+                    }
+                    Some(TradingViewResponse::QuoteCompleted(message)) => {
+                        if let Some(symbol) = message.get(1).and_then(Value::as_str) {
+                            completed.insert(Ustr::from(symbol));
+                        }
+                    }
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+        };
+        let _ = tokio::time::timeout(timeout, collect).await;
+        ws.close().await?;
+
+        Ok(symbols
+            .iter()
+            .filter_map(|symbol| snapshots.get(&Ustr::from(symbol)).copied())
+            .collect())
+    })
+}
+
+/// Blocking [`crate::search_symbols`].
+pub fn search_symbols(search: &str, exchange: &str) -> Result<Vec<Symbol>> {
+    block_on(crate::search_symbols(search, exchange))
+}
+
+/// Blocking [`crate::client::symbols::get_symbol_details`].
+pub fn symbol_details(client: Option<&UserCookies>, symbol: &str) -> Result<SymbolDetails> {
+    block_on(crate::client::symbols::get_symbol_details(client, symbol))
+}
+
+/// Blocking [`crate::search_indicator`].
+pub fn search_indicator(
+    client: Option<&UserCookies>,
+    search: &str,
+    offset: i32,
+) -> Result<Vec<PineSearchResult>> {
+    block_on(crate::search_indicator(client, search, offset))
+}
+
+/// Blocking [`crate::get_indicator_metadata`].
+pub fn indicator_metadata(
+    client: Option<&UserCookies>,
+    pinescript_id: &str,
+    pinescript_version: &str,
+) -> Result<PineMetadata> {
+    block_on(crate::get_indicator_metadata(
+        client,
+        pinescript_id,
+        pinescript_version,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_on() {
+        let value = block_on(async {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            42
+        });
+        assert_eq!(value, 42);
+    }
+}
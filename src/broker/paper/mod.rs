@@ -0,0 +1,256 @@
+//! TradingView's built-in Paper Trading broker.
+//!
+//! Orders are simulated by TradingView against live prices, so strategies built on
+//! this crate's market data can be run end to end without a real broker account.
+
+pub mod models;
+
+pub use models::*;
+
+use crate::{
+    CancellationToken, Result, UserCookies,
+    error::{Error, TradingViewError},
+    utils::{delete, get, post_form, put_form},
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{collections::HashSet, time::Duration};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+use tracing::{debug, error};
+use ustr::Ustr;
+
+static BASE_PAPER_URL: &str = "https://papertrading.tradingview.com/trading";
+
+/// Client for one paper trading account.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{UserCookies, broker::paper::{OrderRequest, PaperTrading, Side}};
+///
+/// # async fn run(user: UserCookies) -> tradingview::Result<()> {
+/// let paper = PaperTrading::connect(user).await?;
+/// println!("balance: {}", paper.state().await?.balance);
+///
+/// let order_id = paper
+///     .place_order(&OrderRequest::builder().instrument("NASDAQ:AAPL").qty(5.0).side(Side::Buy).build())
+///     .await?;
+/// for position in paper.positions().await? {
+///     println!("{} {:?} {}", position.instrument, position.side, position.qty);
+/// }
+/// # let _ = order_id;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaperTrading {
+    client: UserCookies,
+    account_id: String,
+}
+
+impl PaperTrading {
+    pub fn new(client: UserCookies, account_id: &str) -> Self {
+        Self {
+            client,
+            account_id: account_id.to_owned(),
+        }
+    }
+
+    /// Use the first paper trading account of the user.
+    pub async fn connect(client: UserCookies) -> Result<Self> {
+        let account = Self::accounts(&client)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| broker_error("no paper trading account found"))?;
+        Ok(Self::new(client, &account.id))
+    }
+
+    #[tracing::instrument(skip(client))]
+    pub async fn accounts(client: &UserCookies) -> Result<Vec<PaperAccount>> {
+        let res = get(Some(client), &format!("{BASE_PAPER_URL}/accounts"), &[]).await?;
+        unwrap_response(res).await
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub async fn state(&self) -> Result<AccountState> {
+        self.get("state").await
+    }
+
+    pub async fn positions(&self) -> Result<Vec<Position>> {
+        self.get("positions").await
+    }
+
+    pub async fn orders(&self) -> Result<Vec<Order>> {
+        self.get("orders").await
+    }
+
+    pub async fn executions(&self) -> Result<Vec<Execution>> {
+        self.get("executions").await
+    }
+
+    /// Place a simulated order and return its id.
+    #[tracing::instrument(skip(self))]
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<String> {
+        let res = post_form(Some(&self.client), &self.url("orders"), order).await?;
+        let data: Value = unwrap_response(res).await?;
+        data.get("orderId")
+            .and_then(|id| match id {
+                Value::String(id) => Some(id.clone()),
+                Value::Number(id) => Some(id.to_string()),
+                _ => None,
+            })
+            .ok_or_else(|| broker_error("order placed without an order id"))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn modify_order(&self, order_id: &str, changes: &OrderModification) -> Result<()> {
+        let res = put_form(
+            Some(&self.client),
+            &self.url(&format!("orders/{order_id}")),
+            changes,
+        )
+        .await?;
+        check_response(res).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let res = delete(Some(&self.client), &self.url(&format!("orders/{order_id}"))).await?;
+        check_response(res).await
+    }
+
+    /// Close a position at market.
+    #[tracing::instrument(skip(self))]
+    pub async fn close_position(&self, position_id: &str) -> Result<()> {
+        let res = delete(
+            Some(&self.client),
+            &self.url(&format!("positions/{position_id}")),
+        )
+        .await?;
+        check_response(res).await
+    }
+
+    /// Poll the account every `interval` and send each new execution to `tx`.
+    ///
+    /// Executions that already exist when the task starts are skipped. The task
+    /// ends once `tx` is dropped or the token is cancelled.
+    pub fn stream_executions(
+        &self,
+        tx: UnboundedSender<Execution>,
+        interval: Duration,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> JoinHandle<()> {
+        let paper = self.clone();
+        let cancel = cancellation_token
+            .map(CancellationToken::child_token)
+            .unwrap_or_default();
+
+        tokio::spawn(async move {
+            let mut seen: Option<HashSet<String>> = None;
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tx.closed() => break,
+                    _ = ticker.tick() => {}
+                }
+
+                let executions = match paper.executions().await {
+                    Ok(executions) => executions,
+                    Err(e) => {
+                        error!("failed to poll paper trading executions: {e}");
+                        continue;
+                    }
+                };
+
+                match &mut seen {
+                    None => seen = Some(executions.into_iter().map(|e| e.id).collect()),
+                    Some(seen) => {
+                        for execution in executions {
+                            if seen.insert(execution.id.clone()) && tx.send(execution).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            debug!("paper trading execution stream stopped");
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{BASE_PAPER_URL}/accounts/{}/{path}", self.account_id)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let res = get(Some(&self.client), &self.url(path), &[]).await?;
+        unwrap_response(res).await
+    }
+}
+
+async fn unwrap_response<T: DeserializeOwned>(res: reqwest::Response) -> Result<T> {
+    parse_response(res)
+        .await?
+        .ok_or_else(|| broker_error("response without data"))
+}
+
+async fn check_response(res: reqwest::Response) -> Result<()> {
+    parse_response::<Value>(res).await?;
+    Ok(())
+}
+
+async fn parse_response<T: DeserializeOwned>(res: reqwest::Response) -> Result<Option<T>> {
+    let res = res.json::<BrokerResponse<T>>().await?;
+    if res.s != "ok" {
+        return Err(broker_error(&res.errmsg.unwrap_or(res.s)));
+    }
+    Ok(res.d)
+}
+
+fn broker_error(msg: &str) -> Error {
+    Error::TradingView {
+        source: TradingViewError::BrokerError(Ustr::from(msg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::http;
+
+    fn response(body: &str) -> reqwest::Response {
+        reqwest::Response::from(http::Response::new(body.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_parse_response() {
+        let state: AccountState = unwrap_response(response(
+            r#"{"s":"ok","d":{"balance":100000.0,"unrealizedPl":-12.5}}"#,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(state.balance, 100000.0);
+        assert_eq!(state.unrealized_pl, -12.5);
+
+        check_response(response(r#"{"s":"ok"}"#)).await.unwrap();
+        assert!(
+            unwrap_response::<AccountState>(response(r#"{"s":"ok"}"#))
+                .await
+                .is_err()
+        );
+
+        let err = check_response(response(r#"{"s":"error","errmsg":"Not enough funds"}"#))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TradingView {
+                source: TradingViewError::BrokerError(msg)
+            } if msg == "Not enough funds"
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    #[default]
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    #[default]
+    Market,
+    Limit,
+    Stop,
+    #[serde(rename = "stoplimit")]
+    StopLimit,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    #[default]
+    Placing,
+    Inactive,
+    Working,
+    Rejected,
+    Filled,
+    Cancelled,
+}
+
+impl OrderStatus {
+    /// Whether the order can still be modified or cancelled.
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Placing | OrderStatus::Inactive | OrderStatus::Working
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PaperAccount {
+    pub id: String,
+    pub name: String,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AccountState {
+    pub balance: f64,
+    pub equity: Option<f64>,
+    pub unrealized_pl: f64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Position {
+    pub id: String,
+    /// `EXCHANGE:SYMBOL`
+    pub instrument: String,
+    pub qty: f64,
+    pub side: Side,
+    pub avg_price: f64,
+    pub unrealized_pl: Option<f64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Order {
+    pub id: String,
+    pub instrument: String,
+    pub qty: f64,
+    pub side: Side,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub filled_qty: Option<f64>,
+    pub avg_price: Option<f64>,
+    pub limit_price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub status: OrderStatus,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Execution {
+    pub id: String,
+    pub instrument: String,
+    pub price: f64,
+    /// Unix timestamp in milliseconds.
+    pub time: i64,
+    pub qty: f64,
+    pub side: Side,
+}
+
+/// A simulated order to place with [`super::PaperTrading::place_order`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::broker::paper::{OrderRequest, OrderType, Side};
+///
+/// let order = OrderRequest::builder()
+///     .instrument("NASDAQ:AAPL")
+///     .qty(10.0)
+///     .side(Side::Buy)
+///     .order_type(OrderType::Limit)
+///     .limit_price(180.0)
+///     .build();
+/// assert_eq!(order.stop_loss, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    #[builder(into)]
+    pub instrument: String,
+    pub qty: f64,
+    pub side: Side,
+    #[builder(default)]
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<f64>,
+}
+
+/// Changes applied by [`super::PaperTrading::modify_order`], unset fields are kept.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderModification {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<f64>,
+}
+
+/// `{"s": "ok", "d": ...}` envelope used by the trading API.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct BrokerResponse<T> {
+    pub s: String,
+    pub d: Option<T>,
+    pub errmsg: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_order_type_names() {
+        for (order_type, name) in [
+            (OrderType::Market, "market"),
+            (OrderType::Limit, "limit"),
+            (OrderType::Stop, "stop"),
+            (OrderType::StopLimit, "stoplimit"),
+        ] {
+            assert_eq!(serde_json::to_value(order_type).unwrap(), json!(name));
+            assert_eq!(
+                serde_json::from_value::<OrderType>(json!(name)).unwrap(),
+                order_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_order_request_serialization() {
+        let order = OrderRequest::builder()
+            .instrument("NASDAQ:AAPL")
+            .qty(10.0)
+            .side(Side::Sell)
+            .order_type(OrderType::StopLimit)
+            .limit_price(180.0)
+            .stop_price(181.5)
+            .build();
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "instrument": "NASDAQ:AAPL",
+                "qty": 10.0,
+                "side": "sell",
+                "type": "stoplimit",
+                "limitPrice": 180.0,
+                "stopPrice": 181.5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_order_deserialization() {
+        let order: Order = serde_json::from_value(json!({
+            "id": "42",
+            "instrument": "BINANCE:BTCUSDT",
+            "qty": 0.5,
+            "side": "buy",
+            "type": "limit",
+            "limitPrice": 60000.0,
+            "status": "working",
+        }))
+        .unwrap();
+        assert_eq!(order.order_type, OrderType::Limit);
+        assert_eq!(order.limit_price, Some(60000.0));
+        assert_eq!(order.filled_qty, None);
+        assert!(order.status.is_open());
+        assert!(!OrderStatus::Filled.is_open());
+        assert!(!OrderStatus::Cancelled.is_open());
+    }
+}
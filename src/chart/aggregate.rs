@@ -0,0 +1,314 @@
+use crate::{DataPoint, Interval, OHLCV, Ohlcv, SymbolInfo};
+use bon::bon;
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use std::collections::BTreeMap;
+
+/// Where the trading day starts, used to align candles of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSpec {
+    pub timezone: Tz,
+    /// Local time of the session open.
+    pub open: NaiveTime,
+}
+
+impl Default for SessionSpec {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+impl SessionSpec {
+    /// Days starting at midnight UTC, as for crypto pairs.
+    pub fn utc() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            open: NaiveTime::MIN,
+        }
+    }
+
+    /// The exchange timezone and the open of the `regular` subsession of `info`,
+    /// falling back to [`SessionSpec::utc`] for anything it cannot parse.
+    pub fn from_symbol_info(info: &SymbolInfo) -> Self {
+        let timezone = info.tz();
+        let session = info
+            .subsessions
+            .iter()
+            .find(|s| s.id == "regular")
+            .or(info.subsessions.first());
+        let open = session
+            .and_then(|s| s.session.get(..4))
+            .and_then(|hhmm| NaiveTime::parse_from_str(hhmm, "%H%M").ok())
+            .unwrap_or(NaiveTime::MIN);
+        Self { timezone, open }
+    }
+
+    /// Local date of the trading day `timestamp` belongs to.
+    fn trading_day(&self, timestamp: i64) -> NaiveDate {
+        let Some(utc) = DateTime::from_timestamp(timestamp, 0) else {
+            // Outside of what chrono can represent, only corrupt bars get here
+            return if timestamp < 0 {
+                NaiveDate::MIN
+            } else {
+                NaiveDate::MAX
+            };
+        };
+        let local = utc.with_timezone(&self.timezone).naive_local();
+        if local.time() >= self.open {
+            local.date()
+        } else {
+            local.date().pred_opt().unwrap_or(local.date())
+        }
+    }
+
+    /// Unix time at which the trading day `date` opens.
+    fn open_of(&self, date: NaiveDate) -> i64 {
+        let local = date.and_time(self.open);
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            // The open falls into a DST gap, take the same wall time on the
+            // clock from before the gap, i.e. one hour later after it
+            .or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(local + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or_else(|| local.and_utc().timestamp(), |open| open.timestamp())
+    }
+}
+
+fn intraday_seconds(timeframe: Interval) -> Option<i64> {
+    let seconds = match timeframe {
+        Interval::OneSecond => 1,
+        Interval::FiveSeconds => 5,
+        Interval::TenSeconds => 10,
+        Interval::FifteenSeconds => 15,
+        Interval::ThirtySeconds => 30,
+        Interval::OneMinute => 60,
+        Interval::ThreeMinutes => 3 * 60,
+        Interval::FiveMinutes => 5 * 60,
+        Interval::FifteenMinutes => 15 * 60,
+        Interval::ThirtyMinutes => 30 * 60,
+        Interval::FortyFiveMinutes => 45 * 60,
+        Interval::OneHour => 3_600,
+        Interval::TwoHours => 2 * 3_600,
+        Interval::FourHours => 4 * 3_600,
+        _ => return None,
+    };
+    Some(seconds)
+}
+
+/// Open time of the `timeframe` candle containing `timestamp`.
+///
+/// Intraday candles are counted from the session open, longer ones start at
+/// the open of their first trading day.
+pub fn candle_start(timeframe: Interval, session: &SessionSpec, timestamp: i64) -> i64 {
+    let day = session.trading_day(timestamp);
+    if let Some(period) = intraday_seconds(timeframe) {
+        let open = session.open_of(day);
+        return open + (timestamp - open).div_euclid(period) * period;
+    }
+    let first_day = match timeframe {
+        Interval::OneWeek => day - Days::new(day.weekday().num_days_from_monday().into()),
+        Interval::OneMonth => day.with_day(1).unwrap(),
+        Interval::OneQuarter => {
+            NaiveDate::from_ymd_opt(day.year(), (day.month0() / 3) * 3 + 1, 1).unwrap()
+        }
+        Interval::SixMonths => {
+            NaiveDate::from_ymd_opt(day.year(), (day.month0() / 6) * 6 + 1, 1).unwrap()
+        }
+        Interval::Yearly => NaiveDate::from_ymd_opt(day.year(), 1, 1).unwrap(),
+        _ => day,
+    };
+    session.open_of(first_day)
+}
+
+/// A higher timeframe candle produced by [`CandleAggregator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AggregatedCandle {
+    pub bar: Ohlcv,
+    /// `false` while bars of the candle may still arrive.
+    pub closed: bool,
+}
+
+/// Builds higher timeframe candles from the base bars of one series, e.g.
+/// 5 minute and 1 hour candles from a single 1 minute series.
+///
+/// Feed it every batch from `on_chart_data`. Repeated updates of a forming
+/// base bar replace the previous values instead of being counted twice. A
+/// candle is closed once the first bar of the next one arrives.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{
+///     DataPoint, Interval,
+///     chart::aggregate::{CandleAggregator, SessionSpec},
+/// };
+///
+/// let bar = |time: f64, close: f64| DataPoint {
+///     index: 0,
+///     value: vec![time, close, close, close, close, 1.0],
+/// };
+/// let mut five_minutes = CandleAggregator::builder()
+///     .timeframe(Interval::FiveMinutes)
+///     .session(SessionSpec::utc())
+///     .build();
+///
+/// let candles = five_minutes.update(&[bar(0.0, 10.0), bar(60.0, 12.0), bar(300.0, 11.0)]);
+/// assert!(candles[0].closed);
+/// assert_eq!(candles[0].bar.high, 12.0);
+/// assert_eq!(candles[0].bar.volume, 2.0);
+/// assert!(!candles[1].closed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    timeframe: Interval,
+    session: SessionSpec,
+    start: Option<i64>,
+    bars: BTreeMap<i64, Ohlcv>,
+}
+
+#[bon]
+impl CandleAggregator {
+    #[builder]
+    pub fn new(timeframe: Interval, #[builder(default)] session: SessionSpec) -> Self {
+        Self {
+            timeframe,
+            session,
+            start: None,
+            bars: BTreeMap::new(),
+        }
+    }
+}
+
+impl CandleAggregator {
+    pub fn timeframe(&self) -> Interval {
+        self.timeframe
+    }
+
+    /// Merge `bars` and return the candles they closed, followed by the
+    /// candle still in progress. Bars older than that candle are ignored.
+    pub fn update(&mut self, bars: &[DataPoint]) -> Vec<AggregatedCandle> {
+        let mut sorted: Vec<Ohlcv> = bars.iter().map(OHLCV::to_ohlcv).collect();
+        sorted.sort_by_key(|bar| bar.time);
+
+        let mut candles = Vec::new();
+        for bar in sorted {
+            let start = candle_start(self.timeframe, &self.session, bar.time);
+            match self.start {
+                Some(current) if start < current => continue,
+                Some(current) if start > current => {
+                    candles.extend(
+                        self.current()
+                            .map(|bar| AggregatedCandle { bar, closed: true }),
+                    );
+                    self.bars.clear();
+                    self.start = Some(start);
+                }
+                Some(_) => {}
+                None => self.start = Some(start),
+            }
+            self.bars.insert(bar.time, bar);
+        }
+        candles.extend(
+            self.current()
+                .map(|bar| AggregatedCandle { bar, closed: false }),
+        );
+        candles
+    }
+
+    /// The candle currently in progress.
+    pub fn current(&self) -> Option<Ohlcv> {
+        let (first, last) = (self.bars.values().next()?, self.bars.values().next_back()?);
+        Some(Ohlcv {
+            time: self.start?,
+            open: first.open,
+            high: self.bars.values().map(|b| b.high).fold(f64::MIN, f64::max),
+            low: self.bars.values().map(|b| b.low).fold(f64::MAX, f64::min),
+            close: last.close,
+            volume: self.bars.values().map(|b| b.volume).sum(),
+        })
+    }
+}
+
+/// Aggregate a complete history of base bars into `timeframe` candles, the
+/// last one possibly still in progress.
+pub fn aggregate(bars: &[DataPoint], timeframe: Interval, session: SessionSpec) -> Vec<Ohlcv> {
+    let mut aggregator = CandleAggregator::builder()
+        .timeframe(timeframe)
+        .session(session)
+        .build();
+    aggregator
+        .update(bars)
+        .into_iter()
+        .map(|candle| candle.bar)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_edge_cases() {
+        let nyse = SessionSpec {
+            timezone: chrono_tz::America::New_York,
+            open: NaiveTime::from_hms_opt(2, 30, 0).unwrap(),
+        };
+        // 02:30 does not exist on 2024-03-10, clocks jump from 02:00 EST to 03:00 EDT
+        let gap = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let open = DateTime::parse_from_rfc3339("2024-03-10T02:30:00-05:00").unwrap();
+        assert_eq!(nyse.open_of(gap), open.timestamp());
+
+        assert_eq!(nyse.trading_day(i64::MAX), NaiveDate::MAX);
+        assert_eq!(nyse.trading_day(i64::MIN), NaiveDate::MIN);
+    }
+
+    #[test]
+    fn test_candle_start_follows_session() {
+        let nyse = SessionSpec {
+            timezone: chrono_tz::America::New_York,
+            open: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+        };
+        // 2024-03-11 10:45 EDT and 2024-03-08 10:45 EST
+        let monday = DateTime::parse_from_rfc3339("2024-03-11T10:45:00-04:00")
+            .unwrap()
+            .timestamp();
+        let friday = DateTime::parse_from_rfc3339("2024-03-08T10:45:00-05:00")
+            .unwrap()
+            .timestamp();
+
+        let hour = DateTime::parse_from_rfc3339("2024-03-11T10:30:00-04:00").unwrap();
+        assert_eq!(
+            candle_start(Interval::OneHour, &nyse, monday),
+            hour.timestamp()
+        );
+        let open = DateTime::parse_from_rfc3339("2024-03-08T09:30:00-05:00").unwrap();
+        assert_eq!(
+            candle_start(Interval::OneDay, &nyse, friday),
+            open.timestamp()
+        );
+        let week = DateTime::parse_from_rfc3339("2024-03-04T09:30:00-05:00").unwrap();
+        assert_eq!(
+            candle_start(Interval::OneWeek, &nyse, friday),
+            week.timestamp()
+        );
+
+        let bar = |time: i64, close: f64| DataPoint {
+            index: 0,
+            value: vec![time as f64, close, close + 1.0, close - 1.0, close, 10.0],
+        };
+        let mut aggregator = CandleAggregator::builder()
+            .timeframe(Interval::FourHours)
+            .session(SessionSpec::utc())
+            .build();
+        aggregator.update(&[bar(0, 5.0), bar(3_600, 7.0)]);
+        let candles = aggregator.update(&[bar(3_600, 8.0)]);
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].bar.close, 8.0);
+        assert_eq!(candles[0].bar.high, 9.0);
+        assert_eq!(candles[0].bar.volume, 20.0);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{DataPoint, OHLCV, Result, quote::state::QuoteSnapshot};
+use arrow::{
+    array::{
+        ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray, UInt64Array,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+};
+use std::sync::Arc;
+
+const UTC: &str = "UTC";
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some(UTC.into()))
+}
+
+lazy_static::lazy_static! {
+    static ref BAR_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("time", timestamp_type(), false),
+        Field::new("open", DataType::Float64, false),
+        Field::new("high", DataType::Float64, false),
+        Field::new("low", DataType::Float64, false),
+        Field::new("close", DataType::Float64, false),
+        Field::new("volume", DataType::Float64, false),
+    ]));
+    static ref QUOTE_SNAPSHOT_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("updates", DataType::UInt64, false),
+        Field::new("time", timestamp_type(), true),
+        Field::new("price", DataType::Float64, true),
+        Field::new("bid", DataType::Float64, true),
+        Field::new("bid_size", DataType::Float64, true),
+        Field::new("ask", DataType::Float64, true),
+        Field::new("ask_size", DataType::Float64, true),
+        Field::new("open", DataType::Float64, true),
+        Field::new("high", DataType::Float64, true),
+        Field::new("low", DataType::Float64, true),
+        Field::new("prev_close", DataType::Float64, true),
+        Field::new("change", DataType::Float64, true),
+        Field::new("change_percent", DataType::Float64, true),
+        Field::new("volume", DataType::Float64, true),
+        Field::new("currency", DataType::Utf8, true),
+        Field::new("exchange", DataType::Utf8, true),
+        Field::new("short_name", DataType::Utf8, true),
+        Field::new("market_type", DataType::Utf8, true),
+    ]));
+}
+
+/// Schema of [`ToRecordBatch`] for bars: `time` in UTC milliseconds followed by
+/// OHLCV, missing volume is `NaN`.
+pub fn bar_schema() -> SchemaRef {
+    Arc::clone(&BAR_SCHEMA)
+}
+
+/// Schema of [`ToRecordBatch`] for quote snapshots, fields that were never
+/// received are null.
+pub fn quote_snapshot_schema() -> SchemaRef {
+    Arc::clone(&QUOTE_SNAPSHOT_SCHEMA)
+}
+
+/// Conversion into an Arrow [`RecordBatch`] with a fixed schema, see
+/// [`bar_schema`] and [`quote_snapshot_schema`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{DataPoint, chart::arrow::{ToRecordBatch, bar_schema}};
+///
+/// let bars = vec![DataPoint {
+///     index: 0,
+///     value: vec![1_700_000_000.0, 10.0, 12.0, 9.0, 11.0, 500.0],
+/// }];
+/// let batch = bars.to_record_batch().unwrap();
+///
+/// assert_eq!(batch.schema(), bar_schema());
+/// assert_eq!(batch.num_rows(), 1);
+/// ```
+pub trait ToRecordBatch {
+    fn to_record_batch(&self) -> Result<RecordBatch>;
+}
+
+impl ToRecordBatch for [DataPoint] {
+    fn to_record_batch(&self) -> Result<RecordBatch> {
+        let column = |f: fn(&DataPoint) -> f64| -> ArrayRef {
+            Arc::new(Float64Array::from_iter_values(self.iter().map(f)))
+        };
+        let time =
+            TimestampMillisecondArray::from_iter_values(self.iter().map(|p| p.timestamp() * 1000))
+                .with_timezone(UTC);
+        let batch = RecordBatch::try_new(
+            bar_schema(),
+            vec![
+                Arc::new(time),
+                column(DataPoint::open),
+                column(DataPoint::high),
+                column(DataPoint::low),
+                column(DataPoint::close),
+                column(DataPoint::volume),
+            ],
+        )?;
+        Ok(batch)
+    }
+}
+
+impl ToRecordBatch for Vec<DataPoint> {
+    fn to_record_batch(&self) -> Result<RecordBatch> {
+        self.as_slice().to_record_batch()
+    }
+}
+
+impl ToRecordBatch for [QuoteSnapshot] {
+    fn to_record_batch(&self) -> Result<RecordBatch> {
+        let float = |f: fn(&QuoteSnapshot) -> Option<f64>| -> ArrayRef {
+            Arc::new(self.iter().map(f).collect::<Float64Array>())
+        };
+        let string = |f: fn(&QuoteSnapshot) -> Option<&str>| -> ArrayRef {
+            Arc::new(self.iter().map(f).collect::<StringArray>())
+        };
+        let time = self
+            .iter()
+            .map(|q| q.value.timestamp.map(|t| (t * 1000.0) as i64))
+            .collect::<TimestampMillisecondArray>()
+            .with_timezone(UTC);
+        let batch = RecordBatch::try_new(
+            quote_snapshot_schema(),
+            vec![
+                string(|q| Some(q.symbol.as_str())),
+                Arc::new(UInt64Array::from_iter_values(
+                    self.iter().map(|q| q.updates),
+                )),
+                Arc::new(time),
+                float(|q| q.value.price),
+                float(|q| q.value.bid),
+                float(|q| q.value.bid_size),
+                float(|q| q.value.ask),
+                float(|q| q.value.ask_size),
+                float(|q| q.value.open),
+                float(|q| q.value.high),
+                float(|q| q.value.low),
+                float(|q| q.value.prev_close),
+                float(|q| q.value.change),
+                float(|q| q.value.change_percent),
+                float(|q| q.value.volume),
+                string(|q| q.value.currency.as_ref().map(|s| s.as_str())),
+                string(|q| q.value.exchange.as_ref().map(|s| s.as_str())),
+                string(|q| q.value.symbol.as_ref().map(|s| s.as_str())),
+                string(|q| q.value.market_type.as_ref().map(|s| s.as_str())),
+            ],
+        )?;
+        Ok(batch)
+    }
+}
+
+impl ToRecordBatch for Vec<QuoteSnapshot> {
+    fn to_record_batch(&self) -> Result<RecordBatch> {
+        self.as_slice().to_record_batch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quote::models::QuoteValue;
+    use arrow::array::Array;
+
+    #[test]
+    fn test_quote_snapshot_batch() {
+        let snapshots = vec![QuoteSnapshot {
+            symbol: "NASDAQ:AAPL".into(),
+            value: QuoteValue {
+                price: Some(190.0),
+                timestamp: Some(1_700_000_000.0),
+                ..Default::default()
+            },
+            updates: 3,
+        }];
+        let batch = snapshots.to_record_batch().unwrap();
+
+        assert_eq!(batch.schema(), quote_snapshot_schema());
+        let time = batch
+            .column_by_name("time")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(time.value(0), 1_700_000_000_000);
+        assert!(batch.column_by_name("bid").unwrap().is_null(0));
+    }
+}
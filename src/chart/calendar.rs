@@ -0,0 +1,460 @@
+use crate::{MarketSession, SymbolDetails, SymbolInfo};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How far [`TradingCalendar::next_open`] and [`TradingCalendar::next_close`]
+/// look ahead before giving up.
+const MAX_LOOKAHEAD_DAYS: u64 = 370;
+/// Sessions of symbols that trade around the clock.
+const ALL_DAY: &str = "0000-0000:1234567";
+
+/// One `HHMM-HHMM` range of a session spec and the weekdays it trades on.
+///
+/// Windows that close at or before they open span midnight and belong to
+/// the day they close on, like TradingView's own session specs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionWindow {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+    /// Weekdays the window closes on, bit 0 for Sunday.
+    pub days: u8,
+}
+
+impl SessionWindow {
+    /// Parse a session spec such as `0930-1600:23456` or `1700-1600`, with
+    /// ranges separated by `|` or `,`. Ranges without days trade every day.
+    pub fn parse(spec: &str) -> Vec<Self> {
+        spec.split(['|', ','])
+            .filter_map(|range| {
+                let (hours, days) = range.trim().split_once(':').unwrap_or((range, "1234567"));
+                let (open, close) = hours.split_once('-')?;
+                let days = days
+                    .chars()
+                    .filter_map(|d| d.to_digit(10))
+                    .filter(|d| (1..=7).contains(d))
+                    .fold(0, |days, d| days | (1 << (d - 1)));
+                Some(Self {
+                    open: parse_time(open)?,
+                    close: parse_time(close)?,
+                    days,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_overnight(&self) -> bool {
+        self.close <= self.open
+    }
+
+    /// Whether the window closes on `date`'s weekday.
+    pub fn trades_on(&self, date: NaiveDate) -> bool {
+        self.days & (1 << date.weekday().num_days_from_sunday()) != 0
+    }
+
+    /// Exchange local open and close of the window closing on `date`.
+    pub fn bounds(&self, date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+        let open_date = if self.is_overnight() {
+            date.pred_opt().unwrap_or(date)
+        } else {
+            date
+        };
+        (open_date.and_time(self.open), date.and_time(self.close))
+    }
+}
+
+/// `HHMM`, where `2400` is the midnight ending the day.
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    let time: u32 = time.trim().parse().ok()?;
+    NaiveTime::from_hms_opt(time / 100 % 24, time % 100, 0)
+}
+
+/// Parse session corrections such as `0930-1300:20231124,20241129;1000-1600:20240102`.
+fn parse_corrections(corrections: &str) -> BTreeMap<NaiveDate, Vec<SessionWindow>> {
+    let mut parsed: BTreeMap<NaiveDate, Vec<SessionWindow>> = BTreeMap::new();
+    for correction in corrections.split(';') {
+        let Some((hours, dates)) = correction.trim().split_once(':') else {
+            continue;
+        };
+        let windows = SessionWindow::parse(hours);
+        for date in dates.split(',').filter_map(parse_date) {
+            parsed
+                .entry(date)
+                .or_default()
+                .extend(windows.iter().map(|window| SessionWindow {
+                    days: 0x7f,
+                    ..*window
+                }));
+        }
+    }
+    parsed
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%Y%m%d").ok()
+}
+
+/// Trading hours of one symbol: the regular session, the pre and post market
+/// windows, exchange holidays and the dates the regular session deviates on,
+/// e.g. early closes.
+///
+/// Built from [`SymbolInfo::calendar`] or [`SymbolDetails::calendar`]. All
+/// timestamps are unix seconds, sessions are evaluated in the exchange
+/// timezone.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{SymbolInfo, Subsession};
+/// use ustr::Ustr;
+///
+/// let info = SymbolInfo {
+///     timezone: Ustr::from("America/New_York"),
+///     subsessions: vec![Subsession {
+///         id: Ustr::from("regular"),
+///         session: Ustr::from("0930-1600:23456"),
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+/// let calendar = info.calendar();
+/// // Monday 2024-07-01 10:00 in New York
+/// assert!(calendar.is_open_at(1_719_842_400));
+/// // The next open is Tuesday 09:30
+/// assert_eq!(calendar.next_open(1_719_842_400), Some(1_719_927_000));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradingCalendar {
+    pub timezone: Tz,
+    pub regular: Vec<SessionWindow>,
+    pub premarket: Vec<SessionWindow>,
+    pub postmarket: Vec<SessionWindow>,
+    pub holidays: BTreeSet<NaiveDate>,
+    /// Regular session windows replacing the usual ones on a date.
+    pub corrections: BTreeMap<NaiveDate, Vec<SessionWindow>>,
+}
+
+impl TradingCalendar {
+    /// Calendar of a symbol trading `session` in `timezone`, e.g.
+    /// `America/New_York`, closed on the comma separated `YYYYMMDD` `holidays`.
+    pub fn new(timezone: &str, session: &str, holidays: &str) -> Self {
+        Self {
+            timezone: timezone.parse().unwrap_or(Tz::UTC),
+            regular: SessionWindow::parse(session),
+            premarket: Vec::new(),
+            postmarket: Vec::new(),
+            holidays: holidays.split(',').filter_map(parse_date).collect(),
+            corrections: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    /// Whether the regular session closes earlier than usual on `date`.
+    pub fn is_early_close(&self, date: NaiveDate) -> bool {
+        let last_close = |windows: &[SessionWindow]| {
+            windows
+                .iter()
+                .filter(|window| window.trades_on(date))
+                .map(|window| window.bounds(date).1)
+                .max()
+        };
+        let Some(corrected) = self.corrections.get(&date) else {
+            return false;
+        };
+        match (last_close(corrected), last_close(&self.regular)) {
+            (Some(corrected), Some(usual)) => corrected < usual,
+            _ => false,
+        }
+    }
+
+    /// Open and close of the regular sessions closing on the exchange local
+    /// `date`, oldest first. Empty on holidays and days without trading.
+    pub fn sessions_on(&self, date: NaiveDate) -> Vec<(i64, i64)> {
+        if self.is_holiday(date) {
+            return Vec::new();
+        }
+        match self.corrections.get(&date) {
+            Some(corrected) => self.resolve(corrected, date),
+            None => self.resolve(&self.regular, date),
+        }
+    }
+
+    /// Whether the regular session is open at `timestamp`.
+    pub fn is_open_at(&self, timestamp: i64) -> bool {
+        self.session_at(timestamp) == MarketSession::Regular
+    }
+
+    /// Session `timestamp` falls into, [`MarketSession::Closed`] outside of
+    /// the regular, pre and post market windows.
+    pub fn session_at(&self, timestamp: i64) -> MarketSession {
+        let Some(date) = self.local_date(timestamp) else {
+            return MarketSession::Closed;
+        };
+        // Overnight sessions belong to the day they close on
+        let dates = [Some(date), date.succ_opt()];
+        let within = |sessions: Vec<(i64, i64)>| {
+            sessions
+                .into_iter()
+                .any(|(open, close)| (open..close).contains(&timestamp))
+        };
+        for date in dates.into_iter().flatten() {
+            if within(self.sessions_on(date)) {
+                return MarketSession::Regular;
+            }
+            if self.is_holiday(date) {
+                continue;
+            }
+            if within(self.resolve(&self.premarket, date)) {
+                return MarketSession::PreMarket;
+            }
+            if within(self.resolve(&self.postmarket, date)) {
+                return MarketSession::PostMarket;
+            }
+        }
+        MarketSession::Closed
+    }
+
+    /// First regular open after `timestamp`, `None` if the symbol does not
+    /// trade within the next year.
+    pub fn next_open(&self, timestamp: i64) -> Option<i64> {
+        self.upcoming(timestamp)
+            .map(|(open, _)| open)
+            .find(|&open| open > timestamp)
+    }
+
+    /// First regular close after `timestamp`, `None` if the symbol does not
+    /// trade within the next year.
+    pub fn next_close(&self, timestamp: i64) -> Option<i64> {
+        self.upcoming(timestamp)
+            .map(|(_, close)| close)
+            .find(|&close| close > timestamp)
+    }
+
+    /// Regular sessions closing on the local date of `timestamp` and after.
+    fn upcoming(&self, timestamp: i64) -> impl Iterator<Item = (i64, i64)> + '_ {
+        let start = self.local_date(timestamp);
+        (0..=MAX_LOOKAHEAD_DAYS)
+            .filter_map(move |days| start?.checked_add_days(Days::new(days)))
+            .flat_map(|date| self.sessions_on(date))
+    }
+
+    /// Sessions closing between the local dates of `from` and the day after
+    /// `to`, with the pre and post market windows if `extended_hours`,
+    /// oldest first and merged where they touch.
+    pub(crate) fn windows_between(
+        &self,
+        from: i64,
+        to: i64,
+        extended_hours: bool,
+    ) -> Vec<(i64, i64)> {
+        let (Some(first), Some(last)) = (self.local_date(from), self.local_date(to)) else {
+            return Vec::new();
+        };
+        let mut windows = Vec::new();
+        for date in first
+            .iter_days()
+            .take_while(|date| *date <= last.succ_opt().unwrap_or(last))
+        {
+            windows.extend(self.sessions_on(date));
+            if extended_hours && !self.is_holiday(date) {
+                windows.extend(self.resolve(&self.premarket, date));
+                windows.extend(self.resolve(&self.postmarket, date));
+            }
+        }
+        windows.sort_unstable();
+
+        let mut merged: Vec<(i64, i64)> = Vec::with_capacity(windows.len());
+        for (open, close) in windows {
+            match merged.last_mut() {
+                Some(last) if open <= last.1 => last.1 = last.1.max(close),
+                _ => merged.push((open, close)),
+            }
+        }
+        merged
+    }
+
+    pub(crate) fn local_date(&self, timestamp: i64) -> Option<NaiveDate> {
+        let local = self.timezone.timestamp_opt(timestamp, 0).single()?;
+        Some(local.date_naive())
+    }
+
+    /// Unix bounds of the `windows` closing on `date` that trade that day.
+    fn resolve(&self, windows: &[SessionWindow], date: NaiveDate) -> Vec<(i64, i64)> {
+        let mut sessions: Vec<(i64, i64)> = windows
+            .iter()
+            .filter(|window| window.trades_on(date))
+            .filter_map(|window| {
+                let (open, close) = window.bounds(date);
+                Some((self.unix(open)?, self.unix(close)?))
+            })
+            .collect();
+        sessions.sort_unstable();
+        sessions
+    }
+
+    /// Unix seconds of the exchange local `time`, skipping ahead over DST gaps.
+    fn unix(&self, time: NaiveDateTime) -> Option<i64> {
+        let local = self.timezone.from_local_datetime(&time);
+        local
+            .earliest()
+            .or_else(|| {
+                let later = time + chrono::Duration::hours(1);
+                self.timezone.from_local_datetime(&later).earliest()
+            })
+            .map(|time| time.timestamp())
+    }
+}
+
+impl From<&SymbolInfo> for TradingCalendar {
+    fn from(info: &SymbolInfo) -> Self {
+        let windows = |id: &str| {
+            info.subsessions
+                .iter()
+                .find(|s| s.id == id)
+                .map(|s| SessionWindow::parse(&s.session))
+                .unwrap_or_default()
+        };
+        let regular = info.subsessions.iter().find(|s| s.id == "regular");
+        let session = match (regular, info.session.as_str()) {
+            (Some(regular), _) => regular.session.as_str(),
+            (None, "") if info.subsessions.is_empty() => ALL_DAY,
+            (None, session) => session,
+        };
+        let corrections = regular
+            .map(|s| s.session_correction)
+            .filter(|c| !c.is_empty())
+            .unwrap_or(info.corrections);
+
+        Self {
+            premarket: windows("premarket"),
+            postmarket: windows("postmarket"),
+            corrections: parse_corrections(&corrections),
+            ..Self::new(&info.timezone, session, &info.session_holidays)
+        }
+    }
+}
+
+impl From<&SymbolDetails> for TradingCalendar {
+    fn from(details: &SymbolDetails) -> Self {
+        Self::new(
+            details.timezone.as_deref().unwrap_or("Etc/UTC"),
+            details.session.as_deref().unwrap_or(ALL_DAY),
+            details.session_holidays.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Subsession;
+    use ustr::Ustr;
+
+    fn date(date: &str) -> NaiveDate {
+        parse_date(date).unwrap()
+    }
+
+    /// Unix seconds of a New York local time.
+    fn ny(time: &str) -> i64 {
+        let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap();
+        Tz::America__New_York
+            .from_local_datetime(&time)
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn test_trading_calendar() {
+        let subsession = |id: &str, session: &str, correction: &str| Subsession {
+            id: Ustr::from(id),
+            session: Ustr::from(session),
+            session_correction: Ustr::from(correction),
+            ..Default::default()
+        };
+        let info = SymbolInfo {
+            timezone: Ustr::from("America/New_York"),
+            session_holidays: Ustr::from("20240704,20241128"),
+            subsessions: vec![
+                subsession("regular", "0930-1600:23456", "0930-1300:20240703,20241129"),
+                subsession("premarket", "0400-0930:23456", ""),
+                subsession("postmarket", "1600-2000:23456", ""),
+            ],
+            ..Default::default()
+        };
+        let calendar = info.calendar();
+
+        assert!(calendar.is_open_at(ny("2024-07-01 09:30")));
+        assert!(!calendar.is_open_at(ny("2024-07-01 16:00")));
+        assert_eq!(
+            calendar.session_at(ny("2024-07-01 08:00")),
+            MarketSession::PreMarket
+        );
+        assert_eq!(
+            calendar.session_at(ny("2024-07-01 17:00")),
+            MarketSession::PostMarket
+        );
+        assert_eq!(
+            calendar.session_at(ny("2024-07-06 12:00")),
+            MarketSession::Closed
+        );
+
+        // Early close on the 3rd, closed on the 4th
+        assert!(calendar.is_early_close(date("20240703")));
+        assert!(!calendar.is_open_at(ny("2024-07-03 14:00")));
+        assert_eq!(
+            calendar.next_close(ny("2024-07-03 10:00")),
+            Some(ny("2024-07-03 13:00"))
+        );
+        assert!(calendar.sessions_on(date("20240704")).is_empty());
+        assert_eq!(
+            calendar.session_at(ny("2024-07-04 08:00")),
+            MarketSession::Closed
+        );
+        assert_eq!(
+            calendar.next_open(ny("2024-07-03 14:00")),
+            Some(ny("2024-07-05 09:30"))
+        );
+        // Over the weekend
+        assert_eq!(
+            calendar.next_open(ny("2024-07-05 10:00")),
+            Some(ny("2024-07-08 09:30"))
+        );
+
+        // Futures trading overnight belong to the day they close on
+        let futures = TradingCalendar::new("America/Chicago", "1700-1600:23456", "");
+        let sunday_evening = ny("2024-07-07 19:00");
+        assert!(futures.is_open_at(sunday_evening));
+        assert_eq!(
+            futures.next_close(sunday_evening),
+            Some(ny("2024-07-08 17:00"))
+        );
+        let crypto = SymbolInfo::default().calendar();
+        assert!(crypto.is_open_at(ny("2024-07-06 03:00")));
+
+        // The symbol answers from its calendar, corrections and holidays included
+        assert_eq!(
+            info.market_session(ny("2024-07-03 14:00")),
+            MarketSession::Closed
+        );
+        assert_eq!(
+            info.market_session(ny("2024-07-04 10:00")),
+            MarketSession::Closed
+        );
+        assert!(info.is_holiday(date("20241128")));
+        assert!(!info.trades_on(date("20241128")));
+        assert!(info.trades_on(date("20241129")));
+        let session_only = SymbolInfo {
+            timezone: Ustr::from("America/New_York"),
+            session: Ustr::from("0930-1600:23456"),
+            ..Default::default()
+        };
+        assert_eq!(
+            session_only.market_session(ny("2024-07-01 08:00")),
+            MarketSession::Closed
+        );
+    }
+}
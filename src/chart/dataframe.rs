@@ -0,0 +1,104 @@
+use crate::{DataPoint, OHLCV, Result, StudyResponseData};
+use polars::prelude::*;
+
+fn time_column(points: &[DataPoint]) -> Result<Column> {
+    let millis: Vec<i64> = points.iter().map(|p| p.timestamp() * 1000).collect();
+    let time = Column::new("time".into(), millis).cast(&DataType::Datetime(
+        TimeUnit::Milliseconds,
+        Some("UTC".into()),
+    ))?;
+    Ok(time)
+}
+
+/// Conversion into a polars [`DataFrame`] with a UTC `time` column.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{DataPoint, chart::dataframe::ToDataFrame};
+///
+/// let bars = vec![DataPoint {
+///     index: 0,
+///     value: vec![1_700_000_000.0, 10.0, 12.0, 9.0, 11.0, 500.0],
+/// }];
+/// let df = bars.to_dataframe().unwrap();
+///
+/// assert_eq!(df.get_column_names_str(), ["time", "open", "high", "low", "close", "volume"]);
+/// ```
+pub trait ToDataFrame {
+    fn to_dataframe(&self) -> Result<DataFrame>;
+}
+
+/// Bars from `on_chart_data`, missing volume becomes `NaN`.
+impl ToDataFrame for [DataPoint] {
+    fn to_dataframe(&self) -> Result<DataFrame> {
+        let column = |name: &str, f: fn(&DataPoint) -> f64| {
+            Column::new(name.into(), self.iter().map(f).collect::<Vec<f64>>())
+        };
+        let df = DataFrame::new(vec![
+            time_column(self)?,
+            column("open", DataPoint::open),
+            column("high", DataPoint::high),
+            column("low", DataPoint::low),
+            column("close", DataPoint::close),
+            column("volume", DataPoint::volume),
+        ])?;
+        Ok(df)
+    }
+}
+
+impl ToDataFrame for Vec<DataPoint> {
+    fn to_dataframe(&self) -> Result<DataFrame> {
+        self.as_slice().to_dataframe()
+    }
+}
+
+/// One `plot_<n>` column per study plot, in the order TradingView sends them.
+/// Rows with fewer plots are padded with `NaN`.
+impl ToDataFrame for StudyResponseData {
+    fn to_dataframe(&self) -> Result<DataFrame> {
+        let plots = self
+            .studies
+            .iter()
+            .map(|p| p.value.len().saturating_sub(1))
+            .max()
+            .unwrap_or(0);
+        let mut columns = vec![time_column(&self.studies)?];
+        columns.extend((0..plots).map(|plot| {
+            let values: Vec<f64> = self
+                .studies
+                .iter()
+                .map(|p| p.value.get(plot + 1).copied().unwrap_or(f64::NAN))
+                .collect();
+            Column::new(format!("plot_{plot}").into(), values)
+        }));
+        Ok(DataFrame::new(columns)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_study_dataframe() {
+        let data: StudyResponseData = serde_json::from_value(serde_json::json!({
+            "st": [
+                { "i": 0, "v": [1_700_000_000.0, 1.0, 2.0] },
+                { "i": 1, "v": [1_700_000_060.0, 3.0] },
+            ],
+            "ns": { "d": "", "indexes": [] },
+        }))
+        .unwrap();
+        let df = data.to_dataframe().unwrap();
+
+        assert_eq!(df.shape(), (2, 3));
+        assert_eq!(
+            df.column("time").unwrap().dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".into()))
+        );
+        let plot = df.column("plot_1").unwrap().f64().unwrap();
+        assert_eq!(plot.get(0), Some(2.0));
+        assert!(plot.get(1).unwrap().is_nan());
+    }
+}
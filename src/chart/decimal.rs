@@ -0,0 +1,209 @@
+use crate::{DataPoint, Error, OHLCV, QuoteValue, Result, SymbolInfo};
+use rust_decimal::{Decimal, prelude::FromPrimitive};
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+/// Tick grid a symbol is quoted on, prices move in multiples of
+/// `minmov / pricescale`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_decimal::Decimal;
+/// use tradingview::chart::decimal::PricePrecision;
+///
+/// let precision = PricePrecision::new(100.0, 25.0).unwrap();
+/// assert_eq!(precision.to_decimal(101.2649), Some(Decimal::new(10125, 2)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricePrecision {
+    pricescale: f64,
+    minmov: f64,
+}
+
+impl Default for PricePrecision {
+    fn default() -> Self {
+        Self {
+            pricescale: 100.0,
+            minmov: 1.0,
+        }
+    }
+}
+
+impl PricePrecision {
+    /// Fails unless both `pricescale` and `minmov` are finite and positive.
+    pub fn new(pricescale: f64, minmov: f64) -> Result<Self> {
+        let valid = |v: f64| v.is_finite() && v > 0.0;
+        if !valid(pricescale) || !valid(minmov) {
+            return Err(Error::TypeConversion(Ustr::from(&format!(
+                "invalid price precision: pricescale {pricescale}, minmov {minmov}"
+            ))));
+        }
+        Ok(Self { pricescale, minmov })
+    }
+
+    /// Precision of `info`, cents when TradingView sent no usable `pricescale`.
+    pub fn from_symbol_info(info: &SymbolInfo) -> Self {
+        Self::new(info.pricescale, info.minmov).unwrap_or_default()
+    }
+
+    pub fn pricescale(&self) -> f64 {
+        self.pricescale
+    }
+
+    pub fn minmov(&self) -> f64 {
+        self.minmov
+    }
+
+    pub fn tick_size(&self) -> f64 {
+        self.minmov / self.pricescale
+    }
+
+    /// Decimal places needed to write every tick exactly, e.g. 5 for 1/32.
+    pub fn scale(&self) -> u32 {
+        let tick = self.tick_size();
+        (0..=12)
+            .find(|&dp| {
+                let scaled = tick * 10f64.powi(dp as i32);
+                (scaled - scaled.round()).abs() < 1e-6
+            })
+            .unwrap_or(12)
+    }
+
+    /// `value` rounded to the nearest tick, `None` for `NaN` and infinities.
+    pub fn to_decimal(&self, value: f64) -> Option<Decimal> {
+        if !value.is_finite() {
+            return None;
+        }
+        let ticks = Decimal::from_f64((value / self.tick_size()).round())?;
+        let mut price =
+            ticks * Decimal::from_f64(self.minmov)? / Decimal::from_f64(self.pricescale)?;
+        price.rescale(self.scale());
+        Some(price)
+    }
+
+    /// Study plot values of `point` on the tick grid, skipping the timestamp.
+    pub fn plot_values(&self, point: &DataPoint) -> Vec<Option<Decimal>> {
+        point
+            .value
+            .iter()
+            .skip(1)
+            .map(|&v| self.to_decimal(v))
+            .collect()
+    }
+}
+
+/// A bar with prices on the symbol's tick grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecimalBar {
+    pub time: i64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Volume as reported, not rounded, zero when missing.
+    pub volume: Decimal,
+}
+
+/// Prices of a quote on the symbol's tick grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DecimalQuote {
+    pub price: Option<Decimal>,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+    pub open: Option<Decimal>,
+    pub high: Option<Decimal>,
+    pub low: Option<Decimal>,
+    pub prev_close: Option<Decimal>,
+    pub change: Option<Decimal>,
+}
+
+/// Conversion of `f64` prices into [`Decimal`]s rounded to a [`PricePrecision`].
+pub trait ToDecimal {
+    type Output;
+
+    fn to_decimal(&self, precision: PricePrecision) -> Option<Self::Output>;
+}
+
+/// `None` when any price is not finite.
+impl ToDecimal for DataPoint {
+    type Output = DecimalBar;
+
+    fn to_decimal(&self, precision: PricePrecision) -> Option<DecimalBar> {
+        Some(DecimalBar {
+            time: self.timestamp(),
+            open: precision.to_decimal(self.open())?,
+            high: precision.to_decimal(self.high())?,
+            low: precision.to_decimal(self.low())?,
+            close: precision.to_decimal(self.close())?,
+            volume: Decimal::from_f64(self.volume()).unwrap_or_default(),
+        })
+    }
+}
+
+impl ToDecimal for QuoteValue {
+    type Output = DecimalQuote;
+
+    fn to_decimal(&self, precision: PricePrecision) -> Option<DecimalQuote> {
+        let convert = |value: Option<f64>| value.and_then(|v| precision.to_decimal(v));
+        Some(DecimalQuote {
+            price: convert(self.price),
+            bid: convert(self.bid),
+            ask: convert(self.ask),
+            open: convert(self.open),
+            high: convert(self.high),
+            low: convert(self.low),
+            prev_close: convert(self.prev_close),
+            change: convert(self.change),
+        })
+    }
+}
+
+impl ToDecimal for [DataPoint] {
+    type Output = Vec<DecimalBar>;
+
+    fn to_decimal(&self, precision: PricePrecision) -> Option<Vec<DecimalBar>> {
+        self.iter().map(|bar| bar.to_decimal(precision)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_to_decimal() {
+        // Treasury futures tick in 1/32 of a point
+        let precision = PricePrecision::new(32.0, 1.0).unwrap();
+        assert_eq!(precision.scale(), 5);
+        assert_eq!(precision.to_decimal(110.1), Some(Decimal::new(11009375, 5)));
+
+        let bar = DataPoint {
+            index: 0,
+            value: vec![1_700_000_000.0, 0.1 + 0.2, 0.35, 0.25, 0.3, 1200.0],
+        };
+        let bar = bar.to_decimal(PricePrecision::default()).unwrap();
+        assert_eq!(bar.open, Decimal::new(30, 2));
+        assert_eq!(bar.close.to_string(), "0.30");
+        assert_eq!(bar.volume, Decimal::from(1200));
+
+        let missing = DataPoint {
+            index: 0,
+            value: vec![1_700_000_000.0, f64::NAN, 1.0, 1.0, 1.0],
+        };
+        assert!(missing.to_decimal(PricePrecision::default()).is_none());
+    }
+
+    #[test]
+    fn test_invalid_precision() {
+        assert!(PricePrecision::new(0.0, 1.0).is_err());
+        assert!(PricePrecision::new(100.0, f64::NAN).is_err());
+        assert!(PricePrecision::new(-100.0, 1.0).is_err());
+
+        let info = SymbolInfo::default();
+        assert_eq!(
+            PricePrecision::from_symbol_info(&info),
+            PricePrecision::default()
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
-    DataPoint, DataServer, Error, Interval, MarketSymbol, OHLCV as _, Result, SymbolInfo,
+    Config, Currency, DataPoint, DataServer, Error, Interval, MarketSymbol, OHLCV as _, Result,
+    SymbolInfo,
     chart::ChartOptions,
+    config::ProxyConfig,
     error::TradingViewError,
     live::handler::{
-        command::CommandRunner,
-        message::{Command, TradingViewResponse},
+        command::{CommandRunner, CommandRunnerConfig},
+        message::{Command, SeriesCompletedMsg, TradingViewResponse},
         types::{CommandTx, DataRx, DataTx},
     },
     options::Range,
@@ -22,7 +24,7 @@ use std::{
     time::Duration,
 };
 use tokio::{
-    spawn,
+    select, spawn,
     sync::{Mutex, mpsc, oneshot},
     time::{sleep, timeout},
 };
@@ -154,10 +156,10 @@ impl BatchTracker {
             .is_ok()
         {
             // First to complete - send signal
-            if let Ok(mut sender) = self.completion_tx.try_lock() {
-                if let Some(tx) = sender.take() {
-                    let _ = tx.send(signal);
-                }
+            if let Ok(mut sender) = self.completion_tx.try_lock()
+                && let Some(tx) = sender.take()
+            {
+                let _ = tx.send(signal);
             }
         }
     }
@@ -190,12 +192,12 @@ impl BatchDataCollector {
         }
     }
 
-    fn add_data_points(&self, symbol: Ustr, points: Vec<DataPoint>) -> usize {
+    fn add_data_points(&self, symbol: Ustr, points: &[DataPoint]) -> usize {
         let mut entry = self
             .data
             .entry(symbol)
             .or_insert_with(|| Vec::with_capacity(1000));
-        entry.extend(points);
+        entry.extend_from_slice(points);
         entry.len()
     }
 
@@ -222,11 +224,15 @@ async fn setup_websocket(
     auth_token: Option<&str>,
     server: Option<DataServer>,
     data_tx: DataTx,
+    cancellation_token: &CancellationToken,
+    proxy: Option<ProxyConfig>,
 ) -> Result<Arc<WebSocketClient>> {
     let websocket = WebSocketClient::builder()
         .server(server.unwrap_or(DataServer::ProData))
         .maybe_auth_token(auth_token)
         .data_tx(data_tx)
+        .cancellation_token(cancellation_token)
+        .maybe_proxy(proxy)
         .build()
         .await?;
 
@@ -252,9 +258,7 @@ async fn setup_initial_session(cmd_tx: &CommandTx) -> Result<()> {
 async fn setup_markets_in_batches(
     cmd_tx: &CommandTx,
     symbols: &[impl MarketSymbol],
-    interval: Interval,
-    range: Option<Range>,
-    num_bars: Option<u64>,
+    template: ChartOptions,
     batch_size: usize,
     tracker: &BatchTracker, // Add tracker parameter
 ) -> Result<()> {
@@ -265,14 +269,11 @@ async fn setup_markets_in_batches(
         let symbol_ustr = ustr(&format!("{}:{}", symbol.exchange(), symbol.symbol()));
         tracker.register_symbol_by_index(index, symbol_ustr);
 
-        let options = ChartOptions::builder()
-            .symbol(symbol.symbol().into())
-            .exchange(symbol.exchange().into())
-            .interval(interval)
-            .maybe_range(range.map(|r| r.into()))
-            .maybe_bar_count(num_bars)
-            .replay_mode(false)
-            .build();
+        let options = ChartOptions {
+            symbol: symbol.symbol().into(),
+            exchange: symbol.exchange().into(),
+            ..template
+        };
 
         commands.push(Command::set_market(options));
     }
@@ -309,12 +310,11 @@ async fn setup_markets_in_batches(
 fn extract_symbol_index(message: &[Value]) -> Option<usize> {
     // Look for patterns like "sds_sym_3" to extract the index
     for value in message {
-        if let Some(s) = value.as_str() {
-            if s.starts_with("sds_sym_") {
-                if let Ok(index) = s.strip_prefix("sds_sym_").unwrap_or("").parse::<usize>() {
-                    return Some(index);
-                }
-            }
+        if let Some(s) = value.as_str()
+            && s.starts_with("sds_sym_")
+            && let Ok(index) = s.strip_prefix("sds_sym_").unwrap_or("").parse::<usize>()
+        {
+            return Some(index);
         }
     }
     None
@@ -379,7 +379,7 @@ async fn handle_chart_data(
     collector: &BatchDataCollector,
     tracker: &BatchTracker,
     series_info: SeriesInfo,
-    data_points: Vec<DataPoint>,
+    data_points: Arc<[DataPoint]>,
 ) {
     if data_points.is_empty() {
         return;
@@ -396,7 +396,7 @@ async fn handle_chart_data(
 
     // Store series info and data
     collector.add_series_info(symbol, series_info);
-    let total_count = collector.add_data_points(symbol, data_points.clone());
+    let total_count = collector.add_data_points(symbol, &data_points);
 
     tracing::debug!(
         "Received {} points for {} (total: {})",
@@ -406,13 +406,12 @@ async fn handle_chart_data(
     );
 }
 
-async fn handle_series_completed(tracker: &BatchTracker, message: &[Value]) {
-    if let Some(series_id) = extract_series_id(message) {
-        if let Some(entry) = tracker.series_to_symbol.get(&series_id) {
-            let symbol = *entry.value();
-            tracing::debug!("Series {} completed for symbol {}", series_id, symbol);
-            tracker.mark_symbol_complete(symbol);
-        }
+async fn handle_series_completed(tracker: &BatchTracker, message: &SeriesCompletedMsg) {
+    let series_id = message.session;
+    if let Some(entry) = tracker.series_to_symbol.get(&series_id) {
+        let symbol = *entry.value();
+        tracing::debug!("Series {} completed for symbol {}", series_id, symbol);
+        tracker.mark_symbol_complete(symbol);
     }
 }
 
@@ -425,21 +424,20 @@ async fn handle_error(tracker: &BatchTracker, error: Error, message: Vec<Value>)
         Error::TradingView {
             source: TradingViewError::SymbolError
         }
-    ) {
-        if let Some(symbol_index) = extract_symbol_index(&message) {
-            tracker.mark_symbol_failed_by_index(symbol_index, "Symbol resolution failed");
-            return;
-        }
+    ) && let Some(symbol_index) = extract_symbol_index(&message)
+    {
+        tracker.mark_symbol_failed_by_index(symbol_index, "Symbol resolution failed");
+        return;
     }
 
     // Extract series ID and symbol from error message to mark as failed
-    if let Some(series_id) = extract_series_id(&message) {
-        if let Some(entry) = tracker.series_to_symbol.get(&series_id) {
-            let symbol = *entry.value();
-            let error_reason = extract_error_reason(&message);
-            tracker.mark_symbol_failed(symbol, &error_reason);
-            return; // Don't count as general error if we can attribute it to a specific symbol
-        }
+    if let Some(series_id) = extract_series_id(&message)
+        && let Some(entry) = tracker.series_to_symbol.get(&series_id)
+    {
+        let symbol = *entry.value();
+        let error_reason = extract_error_reason(&message);
+        tracker.mark_symbol_failed(symbol, &error_reason);
+        return; // Don't count as general error if we can attribute it to a specific symbol
     }
 
     // For unattributed errors, use the original logic
@@ -453,10 +451,10 @@ async fn handle_error(tracker: &BatchTracker, error: Error, message: Vec<Value>)
 fn extract_error_reason(message: &[Value]) -> String {
     // Look for error description in the message
     for value in message {
-        if let Some(s) = value.as_str() {
-            if s.contains("invalid symbol") || s.contains("resolve error") || s.contains("error") {
-                return s.to_string();
-            }
+        if let Some(s) = value.as_str()
+            && (s.contains("invalid symbol") || s.contains("resolve error") || s.contains("error"))
+        {
+            return s.to_string();
         }
     }
     "Unknown error".to_string()
@@ -503,13 +501,24 @@ pub async fn retrieve(
     range: Option<Range>,
     server: Option<DataServer>,
     num_bars: Option<u64>,
+    /// Price every series in this currency instead of the symbol's own.
+    currency: Option<Currency>,
     #[builder(default = 8)] batch_size: usize,
-    #[builder(default = Duration::from_secs(180))] timeout_duration: Duration,
+    timeout_duration: Option<Duration>,
+    config: Option<&Config>,
+    cancellation_token: Option<&CancellationToken>,
 ) -> Result<HashMap<String, (SymbolInfo, Vec<DataPoint>)>> {
     if symbols.is_empty() {
         return Err(Error::Internal(ustr("No symbols provided")));
     }
 
+    let auth_token = auth_token.or(config.and_then(|c| c.auth_token.as_deref()));
+    let server = server.or(config.map(|c| c.server));
+    let timeout_duration = timeout_duration
+        .or(config.map(|c| c.timeouts.history()))
+        .unwrap_or(Duration::from_secs(180));
+    let cancel = cancellation_token.cloned().unwrap_or_default();
+
     // let auth_token = resolve_auth_token(auth_token)?;
     let symbol_count = symbols.len();
 
@@ -523,9 +532,19 @@ pub async fn retrieve(
     let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
 
     // Setup WebSocket and command runner
-    let websocket = setup_websocket(auth_token, server, data_tx).await?;
+    let websocket = setup_websocket(
+        auth_token,
+        server,
+        data_tx.into(),
+        &cancel,
+        config.and_then(|c| c.proxy.clone()),
+    )
+    .await?;
     let websocket_shared = Arc::new(websocket);
-    let command_runner = CommandRunner::new(cmd_rx, Arc::clone(&websocket_shared));
+    let runner_config = config.map(CommandRunnerConfig::from).unwrap_or_default();
+    let command_runner =
+        CommandRunner::with_config(cmd_rx, Arc::clone(&websocket_shared), runner_config)
+            .with_cancellation_token(&cancel);
     let shutdown_token = command_runner.shutdown_token();
 
     // Start background tasks
@@ -543,17 +562,28 @@ pub async fn retrieve(
 
     // Setup session and send market commands
     setup_initial_session(&cmd_tx).await?;
-    setup_markets_in_batches(
-        &cmd_tx, symbols, interval, range, num_bars, batch_size, &tracker,
-    )
-    .await?;
+    let template = ChartOptions::builder()
+        .interval(interval)
+        .maybe_range(range.map(|r| r.into()))
+        .maybe_bar_count(num_bars)
+        .maybe_currency(currency)
+        .replay_mode(false)
+        .build();
+    setup_markets_in_batches(&cmd_tx, symbols, template, batch_size, &tracker).await?;
 
     // Wait for completion with timeout
-    let result = timeout(timeout_duration, completion_rx).await;
+    let result = select! {
+        result = timeout(timeout_duration, completion_rx) => Some(result),
+        _ = cancel.cancelled() => None,
+    };
 
     // Cleanup
     cleanup_batch_tasks(shutdown_token, runner_task, data_task).await;
 
+    let Some(result) = result else {
+        return Err(Error::Cancelled);
+    };
+
     // Process results
     match result {
         Ok(Ok(BatchCompletionSignal::Success)) => {
@@ -0,0 +1,552 @@
+use crate::{
+    ChartOptions, Config, DataPoint, DataServer, Error, Interval, OHLCV as _, Result, SymbolInfo,
+    live::{
+        handler::{
+            command::{CommandRunner, CommandRunnerConfig},
+            message::{Command, SeriesCompletedMsg, TradingViewResponse},
+            types::DataRx,
+        },
+        metadata::SeriesInfo,
+        protocol,
+    },
+    options::Range,
+    websocket::WebSocketClient,
+};
+use bon::builder;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    select, spawn,
+    sync::mpsc,
+    time::{Instant, sleep, timeout, timeout_at},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use url::Url;
+use ustr::{Ustr, ustr};
+
+/// Bars of one answered request and the series they belong to.
+struct Page {
+    bars: Vec<DataPoint>,
+    completed: SeriesCompletedMsg,
+}
+
+/// The answers that belong to the request a page is collected for.
+enum Answers {
+    /// Those of a `set_market`, on any chart session but the abandoned ones.
+    Opened { abandoned: Vec<Ustr> },
+    /// Those on the series paged back with `request_more_data`.
+    Series {
+        session: Ustr,
+        series_id: Ustr,
+        symbol_series_id: Ustr,
+    },
+}
+
+impl Answers {
+    /// The series paged back from `completed`.
+    fn series(completed: &SeriesCompletedMsg) -> Self {
+        let (symbol_series_id, _) = protocol::series_ids(&completed.series_id);
+        Self::Series {
+            session: completed.session,
+            series_id: completed.series_id,
+            symbol_series_id: Ustr::from(&symbol_series_id),
+        }
+    }
+
+    fn accepts_data(&self, series: &SeriesInfo) -> bool {
+        match self {
+            Self::Opened { abandoned } => !abandoned.contains(&series.chart_session),
+            Self::Series {
+                session,
+                symbol_series_id,
+                ..
+            } => series.chart_session == *session && series.symbol_series_id == *symbol_series_id,
+        }
+    }
+
+    fn accepts_completed(&self, completed: &SeriesCompletedMsg) -> bool {
+        match self {
+            Self::Opened { abandoned } => !abandoned.contains(&completed.session),
+            Self::Series {
+                session, series_id, ..
+            } => completed.session == *session && completed.series_id == *series_id,
+        }
+    }
+}
+
+/// Download every bar between `from` and `to`, both unix seconds and
+/// inclusive, however long the range.
+///
+/// One connection opens a series over the range and then pages further back
+/// with `request_more_data`, `chunk_bars` bars per page, until `from` is
+/// reached or the server has no older bars. A page that fails or times out
+/// is requested again up to `retries` times with a doubling delay. Bars
+/// outside the range and bars repeated across pages are dropped.
+///
+/// With a `chunk_tx` every page is sent there as soon as it is complete, the
+/// newest first, and the returned bars stay empty, so archives of any size
+/// never sit in memory.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{Interval, history::download::download_history};
+///
+/// # async fn run() -> tradingview::Result<()> {
+/// // All of 2023 in minute bars
+/// let (_info, bars) = download_history()
+///     .symbol("BTCUSDT")
+///     .exchange("BINANCE")
+///     .interval(Interval::OneMinute)
+///     .from(1_672_531_200)
+///     .to(1_704_067_199)
+///     .call()
+///     .await?;
+/// println!("Downloaded {} bars", bars.len());
+/// # Ok(())
+/// # }
+/// ```
+#[builder]
+pub async fn download_history(
+    symbol: &str,
+    exchange: &str,
+    interval: Interval,
+    from: i64,
+    to: i64,
+    #[builder(default = 5_000)] chunk_bars: u64,
+    #[builder(default = 3)] retries: u32,
+    #[builder(default = Duration::from_secs(1))] retry_delay: Duration,
+    chunk_tx: Option<mpsc::UnboundedSender<Vec<DataPoint>>>,
+    auth_token: Option<&str>,
+    server: Option<DataServer>,
+    /// Time to wait for each page.
+    timeout_duration: Option<Duration>,
+    config: Option<&Config>,
+    cancellation_token: Option<&CancellationToken>,
+    /// Replaces the url of `server`, e.g. with the url of a `test-util` `MockServer`.
+    endpoint: Option<Url>,
+) -> Result<(SymbolInfo, Vec<DataPoint>)> {
+    if from > to {
+        return Err(Error::Internal(ustr(&format!(
+            "Download range starts after it ends ({from} > {to})"
+        ))));
+    }
+    let auth_token = auth_token.or(config.and_then(|c| c.auth_token.as_deref()));
+    let server = server.or(config.map(|c| c.server));
+    let timeout_duration = timeout_duration
+        .or(config.map(|c| c.timeouts.history()))
+        .unwrap_or(Duration::from_secs(30));
+    let cancel = cancellation_token.cloned().unwrap_or_default();
+
+    let (data_tx, mut data_rx) = mpsc::unbounded_channel();
+    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+    let websocket = WebSocketClient::builder()
+        .server(server.unwrap_or(DataServer::ProData))
+        .maybe_auth_token(auth_token)
+        .data_tx(data_tx)
+        .cancellation_token(&cancel)
+        .maybe_proxy(config.and_then(|c| c.proxy.clone()))
+        .maybe_endpoint(endpoint)
+        .build()
+        .await?;
+    let runner_config = config.map(CommandRunnerConfig::from).unwrap_or_default();
+    let runner = CommandRunner::with_config(cmd_rx, Arc::clone(&websocket), runner_config)
+        .with_cancellation_token(&cancel);
+    let shutdown = runner.shutdown_token();
+    let runner_task = spawn(async move {
+        if let Err(e) = runner.run().await {
+            tracing::error!("Command runner failed: {}", e);
+        }
+    });
+
+    let options = ChartOptions::builder()
+        .symbol(symbol.into())
+        .exchange(exchange.into())
+        .interval(interval)
+        .range(Range::FromTo(from.max(0) as u64, to.max(0) as u64).into())
+        .bar_count(chunk_bars.max(1))
+        .replay_mode(false)
+        .build();
+
+    let mut symbol_info = None;
+    let mut pages = Vec::new();
+    // Time of the oldest bar received so far, in or out of the range
+    let mut oldest: Option<i64> = None;
+    let mut request = Command::set_market(options);
+    let mut answers = Answers::Opened {
+        abandoned: Vec::new(),
+    };
+    let result = loop {
+        let mut attempt = 0;
+        let page = loop {
+            let page = match cmd_tx.send(request.clone()) {
+                Ok(()) => {
+                    let next =
+                        next_page(&mut data_rx, &answers, &mut symbol_info, timeout_duration);
+                    select! {
+                        page = next => page,
+                        _ = cancel.cancelled() => Err(Error::Cancelled),
+                    }
+                }
+                Err(_) => Err(Error::Internal(ustr("Command runner stopped"))),
+            };
+            match page {
+                Err(e) if attempt < retries && is_retryable(&e) => {
+                    attempt += 1;
+                    let delay = retry_delay.saturating_mul(1 << (attempt - 1).min(16));
+                    warn!("History page failed ({e}), retry {attempt}/{retries} in {delay:?}");
+                    sleep(delay).await;
+                    if let Answers::Opened { abandoned } = &mut answers {
+                        // Close the series of the unanswered `set_market`
+                        // and ignore whatever still arrives on it
+                        for series in websocket.snapshot().await.series {
+                            abandoned.push(series.chart_session);
+                            let _ = cmd_tx.send(Command::remove_series(
+                                series.chart_session,
+                                series.series_id,
+                            ));
+                        }
+                    }
+                }
+                page => break page,
+            }
+        };
+        let page = match page {
+            Ok(page) => page,
+            Err(e) => break Err(e),
+        };
+
+        let Some(earliest) = page.bars.iter().map(|bar| bar.timestamp()).min() else {
+            break Ok(());
+        };
+        if oldest.is_some_and(|oldest| earliest >= oldest) {
+            // Nothing older than what we have, the history is exhausted
+            break Ok(());
+        }
+        let bars = older_bars(page.bars, from, to, oldest);
+        oldest = Some(earliest);
+        debug!("History page down to {earliest} has {} bars", bars.len());
+        if !bars.is_empty() {
+            match &chunk_tx {
+                Some(tx) => {
+                    if tx.send(bars).is_err() {
+                        break Err(Error::Cancelled);
+                    }
+                }
+                None => pages.push(bars),
+            }
+        }
+        if earliest <= from {
+            break Ok(());
+        }
+        answers = Answers::series(&page.completed);
+        request = Command::request_more_data(
+            page.completed.session,
+            page.completed.series_id,
+            chunk_bars.max(1),
+        );
+    };
+
+    shutdown.cancel();
+    if timeout(Duration::from_secs(2), runner_task).await.is_err() {
+        debug!("Command runner cleanup timeout");
+    }
+    result?;
+
+    let symbol_info =
+        symbol_info.ok_or_else(|| Error::Internal(ustr("No symbol info received")))?;
+    // Pages come in newest first
+    let bars = pages.into_iter().rev().flatten().collect();
+    Ok((symbol_info, bars))
+}
+
+/// Collect the bars of the next `series_completed` of `answers`, waiting at
+/// most `wait`.
+async fn next_page(
+    data_rx: &mut DataRx,
+    answers: &Answers,
+    symbol_info: &mut Option<SymbolInfo>,
+    wait: Duration,
+) -> Result<Page> {
+    let deadline = Instant::now() + wait;
+    let mut bars = Vec::new();
+    loop {
+        let response = timeout_at(deadline, data_rx.recv())
+            .await
+            .map_err(|_| Error::Timeout(ustr("History page timed out")))?
+            .ok_or_else(|| Error::Internal(ustr("Data channel closed")))?;
+        match response {
+            TradingViewResponse::ChartData(series, data) if answers.accepts_data(&series) => {
+                bars.extend(data.iter().cloned())
+            }
+            TradingViewResponse::SymbolInfo(info) => *symbol_info = Some(info),
+            TradingViewResponse::SeriesCompleted(completed)
+                if answers.accepts_completed(&completed) =>
+            {
+                return Ok(Page { bars, completed });
+            }
+            TradingViewResponse::Error(e, message) => {
+                warn!("History page error: {e} {message:?}");
+                if matches!(e, Error::TradingView { .. } | Error::WebSocket(_)) {
+                    return Err(e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sorted bars of `from..=to` older than `oldest`, the oldest bar already taken.
+fn older_bars(mut bars: Vec<DataPoint>, from: i64, to: i64, oldest: Option<i64>) -> Vec<DataPoint> {
+    bars.retain(|bar| {
+        let time = bar.timestamp();
+        (from..=to).contains(&time) && oldest.is_none_or(|oldest| time < oldest)
+    });
+    bars.sort_by_key(|bar| bar.timestamp());
+    bars.dedup_by_key(|bar| bar.timestamp());
+    bars
+}
+
+fn is_retryable(error: &Error) -> bool {
+    !matches!(error, Error::Cancelled | Error::TradingView { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live::mock::{MockFrame, MockScript, MockServer};
+    use serde_json::{Value, json};
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    fn bar(time: i64) -> DataPoint {
+        DataPoint {
+            index: 0,
+            value: vec![time as f64, 1.0, 1.0, 1.0, 1.0, 0.0],
+        }
+    }
+
+    fn times(bars: &[DataPoint]) -> Vec<i64> {
+        bars.iter().map(|bar| bar.timestamp()).collect()
+    }
+
+    /// Series of the bars at `start..=end` every 60s, paged back in pages of
+    /// 3 bars that overlap by one, down to 0. The first `ignored` requests
+    /// for more data go unanswered.
+    fn script(start: i64, end: i64, ignored: usize) -> MockScript {
+        let points = |from: i64, to: i64| -> Vec<Value> {
+            (from.max(0)..=to)
+                .step_by(60)
+                .map(|t| json!({ "i": 0, "v": [t, 1.0, 1.0, 1.0, 1.0, 0.0] }))
+                .collect()
+        };
+        let requests = Arc::new(AtomicUsize::new(0));
+        let info =
+            json!({ "name": "BTCUSDT", "exchange": "BINANCE", "pro_name": "BINANCE:BTCUSDT" });
+        MockScript::new()
+            .chart(info, (start..=end).step_by(60).map(bar).collect())
+            .on("request_more_data", move |p| {
+                let Some(page) = requests
+                    .fetch_add(1, Ordering::Relaxed)
+                    .checked_sub(ignored)
+                else {
+                    return Vec::new();
+                };
+                let to = start - page as i64 * 120;
+                let key = p[1].as_str().unwrap_or_default().to_string();
+                let bars = if to < 0 {
+                    Vec::new()
+                } else {
+                    points(to - 120, to)
+                };
+                vec![
+                    MockFrame::new(
+                        "timescale_update",
+                        vec![p[0].clone(), json!({ key: { "node": "mock", "s": bars } })],
+                    ),
+                    MockFrame::new(
+                        "series_completed",
+                        vec![p[0].clone(), p[1].clone(), json!("streaming"), json!("s1")],
+                    ),
+                ]
+            })
+    }
+
+    #[test]
+    fn test_older_bars() {
+        // The server answers with a bar before the range and one already taken
+        let bars = older_bars(
+            vec![bar(180), bar(60), bar(120), bar(0), bar(120)],
+            60,
+            180,
+            Some(180),
+        );
+        assert_eq!(times(&bars), vec![60, 120]);
+    }
+
+    #[tokio::test]
+    async fn test_pages_back_on_one_connection() {
+        let server = MockServer::start(script(600, 900, 0)).await.unwrap();
+
+        let (info, bars) = download_history()
+            .symbol("BTCUSDT")
+            .exchange("BINANCE")
+            .interval(Interval::OneMinute)
+            .from(120)
+            .to(840)
+            .endpoint(server.url())
+            .call()
+            .await
+            .unwrap();
+
+        assert_eq!(info.name, "BTCUSDT");
+        assert_eq!(times(&bars), (120..=840).step_by(60).collect::<Vec<_>>());
+        let received = server.received().await;
+        let sent = |method: &str| {
+            received
+                .iter()
+                .filter(|f| f.method.as_str() == method)
+                .count()
+        };
+        assert_eq!(sent("create_series"), 1);
+        // 600 -> 480 -> 360 -> 240 -> 120 reaches the start
+        assert_eq!(sent("request_more_data"), 4);
+        assert_eq!(sent("chart_create_session"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_streams_pages_newest_first() {
+        let server = MockServer::start(script(300, 420, 0)).await.unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let (_, bars) = download_history()
+            .symbol("BTCUSDT")
+            .exchange("BINANCE")
+            .interval(Interval::OneMinute)
+            .from(0)
+            .to(420)
+            .chunk_tx(tx)
+            .endpoint(server.url())
+            .call()
+            .await
+            .unwrap();
+
+        assert!(bars.is_empty());
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = rx.try_recv() {
+            chunks.push(times(&chunk));
+        }
+        // Newest first, the overlapping bar is only sent once
+        assert_eq!(
+            chunks,
+            vec![vec![300, 360, 420], vec![180, 240], vec![60, 120], vec![0]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_unanswered_pages() {
+        let server = MockServer::start(script(120, 240, 1)).await.unwrap();
+
+        let (_, bars) = download_history()
+            .symbol("BTCUSDT")
+            .exchange("BINANCE")
+            .interval(Interval::OneMinute)
+            .from(0)
+            .to(240)
+            .timeout_duration(Duration::from_millis(200))
+            .retry_delay(Duration::from_millis(10))
+            .endpoint(server.url())
+            .call()
+            .await
+            .unwrap();
+
+        assert_eq!(times(&bars), vec![0, 60, 120, 180, 240]);
+        let received = server.received().await;
+        let requested = received
+            .iter()
+            .filter(|f| f.method.as_str() == "request_more_data")
+            .count();
+        assert_eq!(requested, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pages_only_take_their_own_series() {
+        // The first series goes unanswered
+        let opened = Arc::new(AtomicUsize::new(0));
+        let script = script(120, 240, 0)
+            .on("create_series", move |p| {
+                if opened.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Vec::new();
+                }
+                let key = p[1].as_str().unwrap_or_default();
+                let bars: Vec<Value> = (120..=240)
+                    .step_by(60)
+                    .map(|t| json!({ "i": 0, "v": [t, 1.0, 1.0, 1.0, 1.0, 0.0] }))
+                    .collect();
+                vec![
+                    MockFrame::new(
+                        "timescale_update",
+                        vec![p[0].clone(), json!({ key: { "node": "mock", "s": bars } })],
+                    ),
+                    MockFrame::new(
+                        "series_completed",
+                        vec![p[0].clone(), p[1].clone(), json!("streaming"), json!("s1")],
+                    ),
+                ]
+            })
+            // Older bars come after a page completed on another series
+            .on("request_more_data", |p| {
+                let key = p[1].as_str().unwrap_or_default();
+                let completed = |series_id: Value| {
+                    MockFrame::new(
+                        "series_completed",
+                        vec![p[0].clone(), series_id, json!("streaming"), json!("s1")],
+                    )
+                };
+                vec![
+                    completed(json!("sds_9")),
+                    MockFrame::new(
+                        "timescale_update",
+                        vec![
+                            p[0].clone(),
+                            json!({ key: { "node": "mock", "s": [
+                        { "i": 0, "v": [0, 1.0, 1.0, 1.0, 1.0, 0.0] },
+                        { "i": 1, "v": [60, 1.0, 1.0, 1.0, 1.0, 0.0] },
+                    ] } }),
+                        ],
+                    ),
+                    completed(p[1].clone()),
+                ]
+            });
+        let server = MockServer::start(script).await.unwrap();
+
+        let (_, bars) = download_history()
+            .symbol("BTCUSDT")
+            .exchange("BINANCE")
+            .interval(Interval::OneMinute)
+            .from(0)
+            .to(240)
+            .timeout_duration(Duration::from_millis(200))
+            .retry_delay(Duration::from_millis(10))
+            .endpoint(server.url())
+            .call()
+            .await
+            .unwrap();
+
+        assert_eq!(times(&bars), vec![0, 60, 120, 180, 240]);
+        let received = server.received().await;
+        let sent = |method: &str| -> Vec<&MockFrame> {
+            received
+                .iter()
+                .filter(|f| f.method.as_str() == method)
+                .collect()
+        };
+        let created = sent("create_series");
+        assert_eq!(created.len(), 2);
+        let removed = sent("remove_series");
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].params[..2], created[0].params[..2]);
+    }
+}
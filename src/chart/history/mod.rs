@@ -1,4 +1,61 @@
-use crate::{Error, Result, error::LoginError};
+use crate::{DataPoint, Error, Interval, Result, error::TradingViewError};
 
 pub mod batch;
+pub mod download;
 pub mod single;
+
+/// Fetch the last `bars` bars of `symbol`, given as `EXCHANGE:SYMBOL`.
+///
+/// Opens a connection, waits for the bars and closes it again, no handler
+/// or channels involved. See [`single::retrieve`] for ranges, currencies and
+/// the other options.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{Interval, fetch_ohlcv};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let bars = fetch_ohlcv("NASDAQ:AAPL", Interval::OneDay, 100, None).await?;
+///     println!("{} bars", bars.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_ohlcv(
+    symbol: &str,
+    interval: Interval,
+    bars: u64,
+    auth_token: Option<&str>,
+) -> Result<Vec<DataPoint>> {
+    let (exchange, symbol) = symbol.split_once(':').ok_or(Error::TradingView {
+        source: TradingViewError::MissingExchange,
+    })?;
+    let (_, data) = single::retrieve()
+        .maybe_auth_token(auth_token)
+        .symbol(symbol)
+        .exchange(exchange)
+        .interval(interval)
+        .num_bars(bars)
+        .call()
+        .await?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_ohlcv_needs_exchange() {
+        let err = fetch_ohlcv("AAPL", Interval::OneDay, 10, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TradingView {
+                source: TradingViewError::MissingExchange
+            }
+        ));
+    }
+}
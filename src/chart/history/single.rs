@@ -1,10 +1,12 @@
 use crate::{
-    DataPoint, DataServer, Error, Interval, MarketSymbol, OHLCV as _, Result, SymbolInfo, Ticker,
+    Config, Currency, DataPoint, DataServer, Error, Interval, MarketSymbol, OHLCV as _, Result,
+    SymbolInfo, Ticker,
     chart::ChartOptions,
+    config::ProxyConfig,
     error::TradingViewError,
     live::handler::{
-        command::CommandRunner,
-        message::{Command, TradingViewResponse},
+        command::{CommandRunner, CommandRunnerConfig},
+        message::{Command, SeriesCompletedMsg, TradingViewResponse},
         types::{CommandTx, DataRx, DataTx},
     },
     options::Range,
@@ -14,7 +16,7 @@ use bon::builder;
 use serde_json::Value;
 use std::{sync::Arc, time::Duration};
 use tokio::{
-    spawn,
+    select, spawn,
     sync::{Mutex, mpsc, oneshot},
     time::timeout,
 };
@@ -49,10 +51,10 @@ impl DataCollector {
     }
 
     async fn signal_completion(&self, signal: CompletionSignal) {
-        if let Some(sender) = self.completion_tx.lock().await.take() {
-            if let Err(e) = sender.send(signal) {
-                tracing::error!("Failed to send completion signal: {:?}", e);
-            }
+        if let Some(sender) = self.completion_tx.lock().await.take()
+            && let Err(e) = sender.send(signal)
+        {
+            tracing::error!("Failed to send completion signal: {:?}", e);
         }
     }
 }
@@ -76,9 +78,19 @@ pub async fn retrieve(
     range: Option<Range>,
     server: Option<DataServer>,
     num_bars: Option<u64>,
+    /// Price the series in this currency instead of the symbol's own.
+    currency: Option<Currency>,
     #[builder(default = false)] with_replay: bool,
-    #[builder(default = Duration::from_secs(30))] timeout_duration: Duration,
+    timeout_duration: Option<Duration>,
+    config: Option<&Config>,
+    cancellation_token: Option<&CancellationToken>,
 ) -> Result<(SymbolInfo, Vec<DataPoint>)> {
+    let auth_token = auth_token.or(config.and_then(|c| c.auth_token.as_deref()));
+    let server = server.or(config.map(|c| c.server));
+    let timeout_duration = timeout_duration
+        .or(config.map(|c| c.timeouts.history()))
+        .unwrap_or(Duration::from_secs(30));
+    let cancel = cancellation_token.cloned().unwrap_or_default();
     let range: Option<Ustr> = range.map(|r| r.into());
 
     let (symbol, exchange) = extract_symbol_exchange(ticker, symbol, exchange)?;
@@ -89,6 +101,7 @@ pub async fn retrieve(
         .interval(interval)
         .maybe_range(range)
         .maybe_bar_count(num_bars)
+        .maybe_currency(currency)
         .replay_mode(false)
         .build();
 
@@ -107,11 +120,21 @@ pub async fn retrieve(
     let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
 
     // Initialize WebSocket client with the standard data handler
-    let websocket = setup_websocket(auth_token, server, data_tx).await?;
+    let websocket = setup_websocket(
+        auth_token,
+        server,
+        data_tx.into(),
+        &cancel,
+        config.and_then(|c| c.proxy.clone()),
+    )
+    .await?;
     let websocket_shared = Arc::new(websocket);
 
     // Create and start command runner
-    let command_runner = CommandRunner::new(cmd_rx, Arc::clone(&websocket_shared));
+    let runner_config = config.map(CommandRunnerConfig::from).unwrap_or_default();
+    let command_runner =
+        CommandRunner::with_config(cmd_rx, Arc::clone(&websocket_shared), runner_config)
+            .with_cancellation_token(&cancel);
     let shutdown_token = command_runner.shutdown_token();
     let runner_task = spawn(async move {
         if let Err(e) = command_runner.run().await {
@@ -135,11 +158,18 @@ pub async fn retrieve(
     }
 
     // Wait for completion with timeout
-    let result = timeout(timeout_duration, completion_rx).await;
+    let result = select! {
+        result = timeout(timeout_duration, completion_rx) => Some(result),
+        _ = cancel.cancelled() => None,
+    };
 
     // Cleanup
     cleanup_tasks(shutdown_token, runner_task, data_task).await;
 
+    let Some(result) = result else {
+        return Err(Error::Cancelled);
+    };
+
     match result {
         Ok(Ok(CompletionSignal::Success)) => {
             let data = data_collector.data.lock().await;
@@ -198,11 +228,15 @@ async fn setup_websocket(
     auth_token: Option<&str>,
     server: Option<DataServer>,
     data_tx: DataTx,
+    cancellation_token: &CancellationToken,
+    proxy: Option<ProxyConfig>,
 ) -> Result<Arc<WebSocketClient>> {
     let websocket = WebSocketClient::builder()
         .server(server.unwrap_or(DataServer::ProData))
         .maybe_auth_token(auth_token)
         .data_tx(data_tx)
+        .cancellation_token(cancellation_token)
+        .maybe_proxy(proxy)
         .build()
         .await?;
 
@@ -276,7 +310,7 @@ async fn handle_chart_data(
     cmd_tx: &CommandTx,
     options: &ChartOptions,
     series_info: SeriesInfo,
-    data_points: Vec<DataPoint>,
+    data_points: Arc<[DataPoint]>,
 ) {
     tracing::debug!("Received data batch with {} points", data_points.len());
 
@@ -319,7 +353,7 @@ async fn handle_symbol_info(collector: &DataCollector, symbol_info: SymbolInfo)
 async fn handle_series_completed(
     collector: &DataCollector,
     replay_state: &Arc<Mutex<ReplayState>>,
-    message: Vec<Value>,
+    message: SeriesCompletedMsg,
 ) {
     tracing::debug!("Series completed with message: {:?}", message);
 
@@ -0,0 +1,308 @@
+#[cfg(all(feature = "native", feature = "chart"))]
+use crate::{Config, Result, history, options::Range};
+use crate::{DataPoint, Interval, OHLCV, SymbolInfo};
+use bon::builder;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Consecutive bars missing from a series, both ends inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Gap {
+    /// Open time of the first missing bar.
+    pub from: i64,
+    /// Open time of the last missing bar.
+    pub to: i64,
+    pub missing: u64,
+}
+
+/// Problems found in a series by [`check_integrity`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesIntegrityReport {
+    pub bars: usize,
+    /// Timestamps received more than once.
+    pub duplicates: Vec<i64>,
+    /// Timestamps received after a later bar.
+    pub out_of_order: Vec<i64>,
+    pub gaps: Vec<Gap>,
+}
+
+impl SeriesIntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.duplicates.is_empty() && self.out_of_order.is_empty() && self.gaps.is_empty()
+    }
+
+    pub fn missing_bars(&self) -> u64 {
+        self.gaps.iter().map(|gap| gap.missing).sum()
+    }
+}
+
+/// Check `bars` for duplicate timestamps, out of order bars and gaps.
+///
+/// A bar is only expected while the symbol trades according to the sessions
+/// and holidays of `symbol_info`, including pre- and post-market with
+/// `extended_hours`. Without symbol info every slot is expected, which suits
+/// round the clock markets such as crypto. Gaps are not looked for in weekly
+/// and longer series.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{DataPoint, Interval, chart::integrity::check_integrity};
+///
+/// let bar = |time: f64| DataPoint { index: 0, value: vec![time, 1.0, 1.0, 1.0, 1.0, 0.0] };
+/// let bars = [bar(0.0), bar(60.0), bar(240.0)];
+/// let report = check_integrity().bars(&bars).interval(Interval::OneMinute).call();
+///
+/// assert_eq!(report.missing_bars(), 2);
+/// ```
+#[builder]
+pub fn check_integrity(
+    bars: &[DataPoint],
+    interval: Interval,
+    symbol_info: Option<&SymbolInfo>,
+    #[builder(default)] extended_hours: bool,
+) -> SeriesIntegrityReport {
+    let mut report = SeriesIntegrityReport {
+        bars: bars.len(),
+        ..Default::default()
+    };
+
+    let mut latest = i64::MIN;
+    for bar in bars {
+        if bar.timestamp() < latest {
+            report.out_of_order.push(bar.timestamp());
+        }
+        latest = latest.max(bar.timestamp());
+    }
+
+    let mut times: Vec<i64> = bars.iter().map(|bar| bar.timestamp()).collect();
+    times.sort_unstable();
+    for pair in times.windows(2) {
+        if pair[0] == pair[1] && report.duplicates.last() != Some(&pair[0]) {
+            report.duplicates.push(pair[0]);
+        }
+    }
+    times.dedup();
+
+    let step = Duration::from(interval).num_seconds();
+    let day = Duration::days(1).num_seconds();
+    if step > day {
+        return report;
+    }
+    let calendar = symbol_info.map(SymbolInfo::calendar);
+    for pair in times.windows(2) {
+        match &calendar {
+            None => report.gaps.extend(missing(pair[0] + step, pair[1], step)),
+            Some(calendar) if step == day => {
+                let trades = |time: i64| {
+                    calendar
+                        .local_date(time)
+                        .is_some_and(|date| !calendar.sessions_on(date).is_empty())
+                };
+                let mut gap: Option<Gap> = None;
+                let mut time = pair[0] + step;
+                while time < pair[1] {
+                    if !trades(time) {
+                        report.gaps.extend(gap.take());
+                    } else if let Some(gap) = &mut gap {
+                        gap.to = time;
+                        gap.missing += 1;
+                    } else {
+                        gap = missing(time, time + 1, step);
+                    }
+                    time += step;
+                }
+                report.gaps.extend(gap);
+            }
+            Some(calendar) => {
+                // Bars resume at the open of the next session
+                for (open, close) in calendar.windows_between(pair[0], pair[1], extended_hours) {
+                    let from = if open <= pair[0] {
+                        pair[0] + step
+                    } else {
+                        open
+                    };
+                    report.gaps.extend(missing(from, close.min(pair[1]), step));
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Gap of the slots `step` apart from `from` up to, excluding, `to`.
+fn missing(from: i64, to: i64, step: i64) -> Option<Gap> {
+    if from >= to {
+        return None;
+    }
+    let missing = (to - from + step - 1) / step;
+    Some(Gap {
+        from,
+        to: from + (missing - 1) * step,
+        missing: missing as u64,
+    })
+}
+
+/// Refetch the bars of every gap in `report` with one history request each
+/// and merge them into `bars`, keeping it sorted and free of duplicates.
+/// Returns the number of bars added.
+#[cfg(all(feature = "native", feature = "chart"))]
+#[builder]
+pub async fn fill_gaps(
+    bars: &mut Vec<DataPoint>,
+    report: &SeriesIntegrityReport,
+    symbol: &str,
+    exchange: &str,
+    interval: Interval,
+    auth_token: Option<&str>,
+    config: Option<&Config>,
+) -> Result<usize> {
+    let before = bars.len();
+    for gap in &report.gaps {
+        let (_, fetched) = history::single::retrieve()
+            .symbol(symbol)
+            .exchange(exchange)
+            .interval(interval)
+            .range(Range::FromTo(gap.from as u64, gap.to as u64))
+            .maybe_auth_token(auth_token)
+            .maybe_config(config)
+            .call()
+            .await?;
+        bars.extend(
+            fetched
+                .into_iter()
+                .filter(|bar| (gap.from..=gap.to).contains(&bar.timestamp())),
+        );
+    }
+    bars.sort_by_key(|bar| bar.timestamp());
+    bars.dedup_by_key(|bar| bar.timestamp());
+    Ok(bars.len().saturating_sub(before))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::Subsession;
+    use ustr::Ustr;
+
+    fn bar(time: i64) -> DataPoint {
+        DataPoint {
+            index: 0,
+            value: vec![time as f64, 1.0, 1.0, 1.0, 1.0, 0.0],
+        }
+    }
+
+    #[test]
+    fn test_check_integrity() {
+        let bars = [bar(0), bar(120), bar(60), bar(60), bar(300)];
+        let report = check_integrity()
+            .bars(&bars)
+            .interval(Interval::OneMinute)
+            .call();
+        assert_eq!(report.duplicates, vec![60]);
+        assert_eq!(report.out_of_order, vec![60, 60]);
+        assert_eq!(
+            report.gaps,
+            vec![Gap {
+                from: 180,
+                to: 240,
+                missing: 2
+            }]
+        );
+
+        // Closing bar to the next open of a regular hours stock is no gap
+        let info = SymbolInfo {
+            timezone: Ustr::from("America/New_York"),
+            session_holidays: Ustr::from("20240704"),
+            subsessions: vec![Subsession {
+                id: Ustr::from("regular"),
+                session: Ustr::from("0930-1600:23456"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        // 2024-07-03 15:30 and 2024-07-05 09:30 New York time, the 4th is a holiday
+        let bars = [bar(1_720_035_000), bar(1_720_186_200)];
+        let report = check_integrity()
+            .bars(&bars)
+            .interval(Interval::ThirtyMinutes)
+            .symbol_info(&info)
+            .call();
+        assert!(report.is_clean(), "{report:?}");
+    }
+
+    #[test]
+    fn test_gaps_jump_between_sessions() {
+        let subsession = |id: &str, session: &str| Subsession {
+            id: Ustr::from(id),
+            session: Ustr::from(session),
+            ..Default::default()
+        };
+        let info = SymbolInfo {
+            timezone: Ustr::from("America/New_York"),
+            subsessions: vec![
+                subsession("regular", "0930-1600:23456"),
+                subsession("premarket", "0400-0930:23456"),
+                subsession("postmarket", "1600-2000:23456"),
+            ],
+            ..Default::default()
+        };
+        let check = |bars: &[DataPoint], extended_hours: bool| {
+            check_integrity()
+                .bars(bars)
+                .interval(Interval::ThirtyMinutes)
+                .symbol_info(&info)
+                .extended_hours(extended_hours)
+                .call()
+                .gaps
+        };
+        // 2024-07-01 09:30 New York time
+        let open = 1_719_840_600;
+        let (hour, day) = (3_600, 86_400);
+
+        // 15:30 of the first day, 09:30 and 10:00 of the next
+        let bars = [bar(open + 5 * hour + hour / 2), bar(open + day + hour)];
+        assert_eq!(
+            check(&bars, false),
+            vec![
+                Gap {
+                    from: open + 6 * hour,
+                    to: open + 6 * hour,
+                    missing: 1
+                },
+                Gap {
+                    from: open + day,
+                    to: open + day + hour / 2,
+                    missing: 2
+                },
+            ]
+        );
+
+        // Pre-market runs into the regular session
+        let bars = [bar(open - hour / 2), bar(open + hour)];
+        assert_eq!(
+            check(&bars, true),
+            vec![Gap {
+                from: open,
+                to: open + hour / 2,
+                missing: 2
+            }]
+        );
+
+        // 19:30 to 04:30 of the next day only misses the 04:00 bar
+        let bars = [bar(open + 10 * hour), bar(open + day - 5 * hour)];
+        assert_eq!(
+            check(&bars, true),
+            vec![Gap {
+                from: open + day - 5 * hour - hour / 2,
+                to: open + day - 5 * hour - hour / 2,
+                missing: 1
+            }]
+        );
+
+        // A year without bars is counted session by session
+        let bars = [bar(open), bar(open + 365 * day)];
+        let gaps = check(&bars, false);
+        assert!((250..=262).contains(&gaps.len()), "{}", gaps.len());
+    }
+}
@@ -1,11 +1,29 @@
+pub mod aggregate;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod calendar;
+#[cfg(feature = "polars")]
+pub mod dataframe;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub(crate) mod options;
+#[cfg(feature = "pine")]
+pub mod strategy;
+#[cfg(feature = "pine")]
 pub mod study;
 pub(crate) mod utils;
+#[cfg(feature = "pine")]
+pub mod volume_profile;
 
+#[cfg(all(feature = "native", feature = "chart"))]
 pub mod history;
+pub mod integrity;
 mod models;
 
+pub use calendar::{SessionWindow, TradingCalendar};
 pub use models::*;
-pub use options::ChartOptions;
 pub use options::StudyOptions;
+pub use options::{BoxSize, ChartOptions, ChartStyle, PriceSource, Range};
 pub use utils::*;
+#[cfg(feature = "pine")]
+pub use volume_profile::{PriceLevel, VolumeProfile, VolumeProfileOptions};
@@ -1,6 +1,12 @@
-use crate::{MarketSymbol, MarketType, websocket::SeriesInfo};
+use crate::{
+    ContinuousFuture, Interval, MarketAdjustment, MarketSession, MarketSymbol, MarketType,
+    TradingCalendar, live::metadata::SeriesInfo,
+};
 use bon::Builder;
-use chrono::{DateTime, Utc};
+#[cfg(feature = "timezone")]
+use chrono::TimeZone;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+use chrono_tz::Tz;
 use iso_currency::Currency;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -71,9 +77,18 @@ pub struct ChartResponseData {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StudyResponseData {
+    /// Id the study was created under, set by the handler.
+    #[serde(default, skip_deserializing)]
+    pub study_id: Ustr,
+    /// `EXCHANGE:SYMBOL` of the series the study runs on, set by the handler.
+    #[serde(default, skip_deserializing)]
+    pub symbol: Ustr,
+    /// Interval of that series, set by the handler.
+    #[serde(default, skip_deserializing)]
+    pub interval: Interval,
     #[serde(default)]
     pub node: Option<Ustr>,
-    #[serde(rename(deserialize = "st"))]
+    #[serde(default, rename(deserialize = "st"))]
     pub studies: Vec<DataPoint>,
     #[serde(rename(deserialize = "ns"))]
     pub raw_graphics: GraphicDataResponse,
@@ -126,6 +141,10 @@ pub trait PriceIterable {
     fn timestamps(&self) -> impl Iterator<Item = i64> + '_ {
         self.to_vec().map(|dp| dp.timestamp())
     }
+
+    fn as_ohlcv(&self) -> impl Iterator<Item = Ohlcv> + '_ {
+        self.to_vec().map(|dp| dp.to_ohlcv())
+    }
 }
 
 impl PriceIterable for Vec<DataPoint> {
@@ -136,6 +155,14 @@ impl PriceIterable for Vec<DataPoint> {
     }
 }
 
+impl PriceIterable for Vec<Ohlcv> {
+    type Item = Ohlcv;
+
+    fn to_vec(&self) -> impl Iterator<Item = &Self::Item> + '_ {
+        self.iter()
+    }
+}
+
 pub trait OHLCV {
     fn datetime(&self) -> DateTime<Utc>;
     fn timestamp(&self) -> i64;
@@ -144,6 +171,13 @@ pub trait OHLCV {
     fn low(&self) -> f64;
     fn close(&self) -> f64;
     fn volume(&self) -> f64;
+
+    /// Bar time in `timezone`, e.g. the exchange timezone from [`SymbolInfo::tz`].
+    #[cfg(feature = "timezone")]
+    fn datetime_in(&self, timezone: Tz) -> DateTime<Tz> {
+        self.datetime().with_timezone(&timezone)
+    }
+
     fn validate(&self) -> bool {
         !(self.close() > self.high() || self.close() < self.low() || self.high() < self.low())
             && self.close() > 0.
@@ -197,6 +231,17 @@ pub trait OHLCV {
     fn is_falling(&self) -> bool {
         self.close() < self.open()
     }
+
+    fn to_ohlcv(&self) -> Ohlcv {
+        Ohlcv {
+            time: self.timestamp(),
+            open: self.open(),
+            high: self.high(),
+            low: self.low(),
+            close: self.close(),
+            volume: self.volume(),
+        }
+    }
 }
 
 impl OHLCV for DataPoint {
@@ -238,10 +283,94 @@ impl OHLCV for DataPoint {
     }
 
     fn volume(&self) -> f64 {
-        if self.value.len() < 5 {
-            return f64::NAN;
+        // Symbols without volume, such as indices, only send five values
+        self.value.get(5).copied().unwrap_or(f64::NAN)
+    }
+}
+
+/// A single bar with named fields, `time` is the bar's open time in unix seconds.
+///
+/// Missing prices and volume are `NaN`, as with the [`OHLCV`] accessors on [`DataPoint`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{DataPoint, Ohlcv, PriceIterable};
+///
+/// let bars = vec![DataPoint {
+///     index: 0,
+///     value: vec![1_700_000_000.0, 10.0, 12.0, 9.0, 11.0, 500.0],
+/// }];
+/// let ohlcv: Vec<Ohlcv> = bars.as_ohlcv().collect();
+///
+/// assert_eq!(ohlcv[0].close, 11.0);
+/// assert_eq!(ohlcv.closes().collect::<Vec<_>>(), vec![11.0]);
+/// ```
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Debug, Default)]
+pub struct Ohlcv {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl OHLCV for Ohlcv {
+    fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(self.time, 0).expect("Invalid timestamp")
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.time
+    }
+
+    fn open(&self) -> f64 {
+        self.open
+    }
+
+    fn high(&self) -> f64 {
+        self.high
+    }
+
+    fn low(&self) -> f64 {
+        self.low
+    }
+
+    fn close(&self) -> f64 {
+        self.close
+    }
+
+    fn volume(&self) -> f64 {
+        self.volume
+    }
+}
+
+impl From<&DataPoint> for Ohlcv {
+    fn from(dp: &DataPoint) -> Self {
+        dp.to_ohlcv()
+    }
+}
+
+impl From<DataPoint> for Ohlcv {
+    fn from(dp: DataPoint) -> Self {
+        dp.to_ohlcv()
+    }
+}
+
+impl From<Ohlcv> for DataPoint {
+    fn from(bar: Ohlcv) -> Self {
+        Self {
+            index: 0,
+            value: vec![
+                bar.time as f64,
+                bar.open,
+                bar.high,
+                bar.low,
+                bar.close,
+                bar.volume,
+            ],
         }
-        self.value[5]
     }
 }
 
@@ -365,8 +494,14 @@ pub struct SymbolInfo {
     #[serde(rename = "currency_code")]
     pub currency_code: Ustr,
 
+    /// Main session, e.g. `0930-1600`, the `regular` subsession if any is more precise.
+    pub session: Ustr,
+
     pub session_holidays: Ustr,
 
+    /// Session corrections such as early closes, e.g. `0930-1300:20241129`.
+    pub corrections: Ustr,
+
     pub subsessions: Vec<Subsession>,
 
     pub timezone: Ustr,
@@ -396,7 +531,30 @@ pub struct SymbolInfo {
 
     pub fractional: bool,
 
+    /// Prices are quoted in multiples of `minmov / pricescale`.
+    pub pricescale: f64,
+
+    pub minmov: f64,
+
     pub industry: Ustr,
+
+    /// Contract a continuous futures symbol currently points at, e.g.
+    /// `ESZ2024` for `ES1!`.
+    #[serde(rename(deserialize = "current_contract"))]
+    pub front_contract: Ustr,
+
+    /// Price adjustment the series was requested with, `None` for the
+    /// server default.
+    #[serde(skip_deserializing)]
+    pub adjustment: Option<MarketAdjustment>,
+
+    /// Whether the series was requested with back-adjusted rollovers.
+    #[serde(skip_deserializing)]
+    pub backadjustment: bool,
+
+    /// Currency the series was requested to be priced in, if converted.
+    #[serde(skip_deserializing)]
+    pub conversion_currency: Option<Currency>,
 }
 
 impl MarketSymbol for SymbolInfo {
@@ -421,6 +579,71 @@ impl MarketSymbol for SymbolInfo {
     }
 }
 
+impl SymbolInfo {
+    /// Exchange timezone, UTC when TradingView sent none or an unknown one.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// Unix `timestamp` in the exchange timezone.
+    #[cfg(feature = "timezone")]
+    pub fn localize(&self, timestamp: i64) -> Option<DateTime<Tz>> {
+        self.tz().timestamp_opt(timestamp, 0).single()
+    }
+
+    /// Every bar of `bars` paired with its time in the exchange timezone.
+    #[cfg(feature = "timezone")]
+    pub fn localize_series<'a, T: OHLCV>(&self, bars: &'a [T]) -> Vec<(DateTime<Tz>, &'a T)> {
+        let timezone = self.tz();
+        bars.iter()
+            .map(|bar| (bar.datetime_in(timezone), bar))
+            .collect()
+    }
+
+    /// Currency the series prices are in, the conversion currency if one was
+    /// requested and the symbol's own currency otherwise.
+    pub fn currency(&self) -> Option<Currency> {
+        self.conversion_currency
+            .or_else(|| Currency::from_code(self.currency_id.as_str()))
+    }
+
+    /// Whether this is a continuous futures symbol rolling between contracts.
+    pub fn is_continuous(&self) -> bool {
+        self.typespecs
+            .iter()
+            .any(|spec| spec.as_str() == "continuous")
+            || ContinuousFuture::parse(&self.id).is_some()
+    }
+
+    /// Session the bar or quote at unix `timestamp` falls into, see
+    /// [`TradingCalendar::session_at`]. Symbols without any session trade
+    /// regular hours around the clock.
+    ///
+    /// Useful to tell extended hours bars apart on a chart requested with
+    /// [`crate::SessionType::Extended`]. Build the [`SymbolInfo::calendar`]
+    /// once to classify many bars.
+    pub fn market_session(&self, timestamp: i64) -> MarketSession {
+        self.calendar().session_at(timestamp)
+    }
+
+    /// Whether the exchange is closed on `date` for a holiday listed in
+    /// [`SymbolInfo::session_holidays`].
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.calendar().is_holiday(date)
+    }
+
+    /// Whether a regular session closes on the exchange local `date`,
+    /// counting holidays and session corrections.
+    pub fn trades_on(&self, date: NaiveDate) -> bool {
+        !self.calendar().sessions_on(date).is_empty()
+    }
+
+    /// Trading hours, holidays and early closes of the symbol.
+    pub fn calendar(&self) -> TradingCalendar {
+        TradingCalendar::from(self)
+    }
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize, Hash, Debug, Default, Copy)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Subsession {
@@ -430,4 +653,74 @@ pub struct Subsession {
     pub session: Ustr,
     #[serde(rename(deserialize = "session-display"))]
     pub session_display: Ustr,
+    #[serde(rename(deserialize = "session-correction"))]
+    pub session_correction: Ustr,
+}
+
+impl Subsession {
+    /// Whether the exchange local `time` lies within [`Subsession::session`],
+    /// e.g. `0930-1600:23456` or `1700-1600` for sessions spanning midnight.
+    pub fn contains(&self, time: &NaiveDateTime) -> bool {
+        let minute = (time.hour() * 100 + time.minute()) as u16;
+        self.session.split(['|', ',']).any(|range| {
+            let (hours, days) = range.split_once(':').unwrap_or((range, "1234567"));
+            let Some((start, end)) = hours.split_once('-') else {
+                return false;
+            };
+            let (Ok(start), Ok(end)) = (start.parse::<u16>(), end.parse::<u16>()) else {
+                return false;
+            };
+            let trades_on = |date: NaiveDate| trades_on_days(days, date);
+            if start < end {
+                (start..end).contains(&minute) && trades_on(time.date())
+            } else if minute >= start {
+                // Overnight sessions belong to the day they end on
+                time.date().succ_opt().is_some_and(trades_on)
+            } else {
+                minute < end && trades_on(time.date())
+            }
+        })
+    }
+
+    /// Whether any range of [`Subsession::session`] trades on `date`.
+    pub fn trades_on(&self, date: NaiveDate) -> bool {
+        self.session.split(['|', ',']).any(|range| {
+            let days = range.split_once(':').map_or("1234567", |(_, days)| days);
+            trades_on_days(days, date)
+        })
+    }
+}
+
+/// Whether `days` such as `23456` include `date`, TradingView numbers days
+/// from 1 for Sunday.
+fn trades_on_days(days: &str, date: NaiveDate) -> bool {
+    let day = date.weekday().num_days_from_sunday() + 1;
+    char::from_digit(day, 10).is_some_and(|d| days.contains(d))
+}
+
+#[cfg(all(test, feature = "timezone"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_series() {
+        let info = SymbolInfo {
+            timezone: Ustr::from("America/New_York"),
+            ..Default::default()
+        };
+        // 2024-07-01 13:30 UTC is the 09:30 open in New York
+        let bars = vec![DataPoint {
+            index: 0,
+            value: vec![1_719_840_600.0, 1.0, 1.0, 1.0, 1.0, 0.0],
+        }];
+        let localized = info.localize_series(&bars);
+        assert_eq!(localized[0].0.format("%H:%M").to_string(), "09:30");
+        assert_eq!(localized[0].0.timezone(), Tz::America__New_York);
+
+        let unknown = SymbolInfo {
+            timezone: Ustr::from("Etc/Nowhere"),
+            ..Default::default()
+        };
+        assert_eq!(unknown.tz(), Tz::UTC);
+    }
 }
@@ -1,7 +1,11 @@
-use crate::models::{Interval, MarketAdjustment, SessionType, pine_indicator::ScriptType};
+use crate::{
+    chart::ChartType,
+    models::{Interval, MarketAdjustment, SessionType, pine_indicator::ScriptType},
+};
 use bon::Builder;
 use iso_currency::Currency;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use ustr::Ustr;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Builder, Copy)]
@@ -22,10 +26,21 @@ pub struct ChartOptions {
     #[builder(default = 0)]
     pub replay_from: i64,
     pub replay_session: Option<Ustr>,
+    /// Price adjustment of the history, TradingView adjusts for splits by default.
     pub adjustment: Option<MarketAdjustment>,
+    /// Back-adjust continuous futures for the gaps between contract rollovers.
+    #[builder(default)]
+    #[serde(default)]
+    pub backadjustment: bool,
+    /// Use the settlement price as the daily close of futures.
+    pub settlement_as_close: Option<bool>,
     pub currency: Option<Currency>,
     pub session_type: Option<SessionType>,
     pub study_config: Option<StudyOptions>,
+    /// Bar type of the series, computed by TradingView.
+    #[builder(default)]
+    #[serde(default)]
+    pub style: ChartStyle,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Copy)]
@@ -59,6 +74,172 @@ impl From<Range> for Ustr {
     }
 }
 
+/// Price a non-time based chart style is built from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum PriceSource {
+    Open,
+    High,
+    Low,
+    #[default]
+    Close,
+    Hl2,
+    Hlc3,
+    Ohlc4,
+}
+
+impl PriceSource {
+    fn code(self) -> &'static str {
+        match self {
+            PriceSource::Open => "open",
+            PriceSource::High => "high",
+            PriceSource::Low => "low",
+            PriceSource::Close => "close",
+            PriceSource::Hl2 => "hl2",
+            PriceSource::Hlc3 => "hlc3",
+            PriceSource::Ohlc4 => "ohlc4",
+        }
+    }
+}
+
+/// Box or reversal size of Renko, Kagi and Point & Figure charts.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum BoxSize {
+    /// Derived from the average true range over `length` bars.
+    Atr(u32),
+    /// A fixed price amount.
+    Traditional(f64),
+}
+
+impl Default for BoxSize {
+    fn default() -> Self {
+        BoxSize::Atr(14)
+    }
+}
+
+/// Bar type of a chart series.
+///
+/// Styles other than [`ChartStyle::Candles`] are computed by TradingView and
+/// delivered as regular OHLCV bars, whose times are those of the underlying
+/// bar that completed each brick or box.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::chart::{BoxSize, ChartOptions, ChartStyle, PriceSource};
+///
+/// let options = ChartOptions::builder()
+///     .symbol("BTCUSDT".into())
+///     .exchange("BINANCE".into())
+///     .style(ChartStyle::Renko {
+///         source: PriceSource::Close,
+///         box_size: BoxSize::Traditional(100.0),
+///         wicks: true,
+///     })
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum ChartStyle {
+    #[default]
+    Candles,
+    HeikinAshi,
+    Renko {
+        source: PriceSource,
+        box_size: BoxSize,
+        wicks: bool,
+    },
+    /// Bars spanning a fixed price `range`.
+    Range {
+        range: f64,
+        phantom_bars: bool,
+    },
+    LineBreak {
+        source: PriceSource,
+        lines: u32,
+    },
+    Kagi {
+        source: PriceSource,
+        reversal: BoxSize,
+    },
+    PointAndFigure {
+        /// Build boxes from highs and lows instead of closes.
+        high_low: bool,
+        box_size: BoxSize,
+        reversal_amount: u32,
+        one_step_back_building: bool,
+    },
+}
+
+fn box_size_inputs(inputs: &mut Value, size: BoxSize, size_key: &str) {
+    match size {
+        BoxSize::Atr(length) => {
+            inputs["style"] = json!("ATR");
+            inputs["atrLength"] = json!(length);
+        }
+        BoxSize::Traditional(size) => {
+            inputs["style"] = json!("Traditional");
+            inputs[size_key] = json!(size);
+        }
+    }
+}
+
+impl ChartStyle {
+    /// Built-in study TradingView derives the bars from, `None` for candles.
+    pub fn chart_type(self) -> Option<ChartType> {
+        match self {
+            ChartStyle::Candles => None,
+            ChartStyle::HeikinAshi => Some(ChartType::HeikinAshi),
+            ChartStyle::Renko { .. } => Some(ChartType::Renko),
+            ChartStyle::Range { .. } => Some(ChartType::Range),
+            ChartStyle::LineBreak { .. } => Some(ChartType::LineBreak),
+            ChartStyle::Kagi { .. } => Some(ChartType::Kagi),
+            ChartStyle::PointAndFigure { .. } => Some(ChartType::PointAndFigure),
+        }
+    }
+
+    pub(crate) fn inputs(self) -> Value {
+        let mut inputs = json!({});
+        match self {
+            ChartStyle::Candles | ChartStyle::HeikinAshi => {}
+            ChartStyle::Renko {
+                source,
+                box_size,
+                wicks,
+            } => {
+                inputs["source"] = json!(source.code());
+                inputs["wicks"] = json!(wicks);
+                box_size_inputs(&mut inputs, box_size, "boxSize");
+            }
+            ChartStyle::Range {
+                range,
+                phantom_bars,
+            } => {
+                inputs["range"] = json!(range);
+                inputs["phantomBars"] = json!(phantom_bars);
+            }
+            ChartStyle::LineBreak { source, lines } => {
+                inputs["source"] = json!(source.code());
+                inputs["lb"] = json!(lines);
+            }
+            ChartStyle::Kagi { source, reversal } => {
+                inputs["source"] = json!(source.code());
+                box_size_inputs(&mut inputs, reversal, "reversalAmount");
+            }
+            ChartStyle::PointAndFigure {
+                high_low,
+                box_size,
+                reversal_amount,
+                one_step_back_building,
+            } => {
+                inputs["sources"] = json!(if high_low { "HL" } else { "Close" });
+                inputs["reversalAmount"] = json!(reversal_amount);
+                inputs["oneStepBackBuilding"] = json!(one_step_back_building);
+                box_size_inputs(&mut inputs, box_size, "boxSize");
+            }
+        }
+        inputs
+    }
+}
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Builder, Copy)]
 pub struct StudyOptions {
     pub script_id: Ustr,
@@ -142,6 +323,16 @@ impl ChartOptions {
         self
     }
 
+    pub fn backadjustment(mut self, backadjustment: bool) -> Self {
+        self.backadjustment = backadjustment;
+        self
+    }
+
+    pub fn settlement_as_close(mut self, settlement_as_close: bool) -> Self {
+        self.settlement_as_close = Some(settlement_as_close);
+        self
+    }
+
     pub fn currency(mut self, currency: Currency) -> Self {
         self.currency = Some(currency);
         self
@@ -152,6 +343,11 @@ impl ChartOptions {
         self
     }
 
+    pub fn style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
     pub fn study_config(
         mut self,
         script_id: &str,
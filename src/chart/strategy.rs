@@ -0,0 +1,188 @@
+use crate::Result;
+#[cfg(feature = "pine")]
+use crate::utils::_parse_compressed;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ustr::Ustr;
+
+/// Backtest report TradingView sends for studies attached as
+/// [`crate::pine_indicator::ScriptType::StrategyScript`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StrategyReport {
+    pub currency: Option<Ustr>,
+    pub performance: PerformanceSummary,
+    pub trades: Vec<TradeEntry>,
+    /// Strategy properties and backtest date range, as sent by TradingView.
+    pub settings: Value,
+    /// Equity curve, one value per closed trade.
+    pub equity: Vec<f64>,
+    pub equity_percent: Vec<f64>,
+    pub draw_down: Vec<f64>,
+    pub draw_down_percent: Vec<f64>,
+    pub buy_hold: Vec<f64>,
+    pub buy_hold_percent: Vec<f64>,
+}
+
+impl StrategyReport {
+    /// Extract the report from the payload of a study update, if it carries one.
+    ///
+    /// Reports arrive either as a plain `data.report` object or zipped and
+    /// base64 encoded in `dataCompressed`, the latter only decoded with the
+    /// `pine` feature.
+    pub fn from_study_data(data: &Value) -> Result<Option<Self>> {
+        let report = match (data.get("dataCompressed"), data.get("data")) {
+            #[cfg(feature = "pine")]
+            (Some(Value::String(compressed)), _) => {
+                _parse_compressed(compressed)?.get("report").cloned()
+            }
+            (_, Some(data)) => data.get("report").cloned(),
+            _ => None,
+        };
+        Ok(report.map(Self::deserialize).transpose()?)
+    }
+}
+
+/// Aggregated backtest metrics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PerformanceSummary {
+    pub all: TradeStats,
+    pub long: TradeStats,
+    pub short: TradeStats,
+    pub buy_hold_return: Option<f64>,
+    pub buy_hold_return_percent: Option<f64>,
+    pub max_strategy_draw_down: Option<f64>,
+    pub max_strategy_draw_down_percent: Option<f64>,
+    #[serde(rename(deserialize = "openPL"))]
+    pub open_pl: Option<f64>,
+    #[serde(rename(deserialize = "openPLPercent"))]
+    pub open_pl_percent: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+}
+
+/// Trade statistics for all, long-only or short-only trades.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TradeStats {
+    pub total_trades: Option<f64>,
+    pub total_open_trades: Option<f64>,
+    #[serde(rename(deserialize = "numberOfWiningTrades"))]
+    pub number_of_winning_trades: Option<f64>,
+    pub number_of_losing_trades: Option<f64>,
+    pub percent_profitable: Option<f64>,
+    pub net_profit: Option<f64>,
+    pub net_profit_percent: Option<f64>,
+    pub gross_profit: Option<f64>,
+    pub gross_profit_percent: Option<f64>,
+    pub gross_loss: Option<f64>,
+    pub gross_loss_percent: Option<f64>,
+    pub commission_paid: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub avg_trade: Option<f64>,
+    pub avg_trade_percent: Option<f64>,
+    pub avg_bars_in_trade: Option<f64>,
+    pub largest_win_trade: Option<f64>,
+    #[serde(rename(deserialize = "largestLosTrade"))]
+    pub largest_loss_trade: Option<f64>,
+    pub max_contracts_held: Option<f64>,
+    pub ratio_avg_win_avg_loss: Option<f64>,
+}
+
+/// One closed (or still open) backtest trade.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradeEntry {
+    #[serde(rename(deserialize = "e"))]
+    pub entry: TradeFill,
+    /// `None` while the trade is still open.
+    #[serde(rename(deserialize = "x"))]
+    pub exit: Option<TradeFill>,
+    #[serde(rename(deserialize = "q"))]
+    pub quantity: f64,
+    #[serde(rename(deserialize = "tp"))]
+    pub profit: TradeAmount,
+    #[serde(rename(deserialize = "cp"))]
+    pub cumulative_profit: TradeAmount,
+    #[serde(rename(deserialize = "rn"))]
+    pub run_up: TradeAmount,
+    #[serde(rename(deserialize = "dd"))]
+    pub draw_down: TradeAmount,
+}
+
+impl TradeEntry {
+    pub fn is_short(&self) -> bool {
+        self.entry.kind.starts_with('s')
+    }
+}
+
+/// Order fill opening or closing a [`TradeEntry`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradeFill {
+    /// Order id or comment given in the Pine script.
+    #[serde(rename(deserialize = "c"))]
+    pub name: Ustr,
+    /// `le`/`se` for long and short entries.
+    #[serde(rename(deserialize = "tp"))]
+    pub kind: Ustr,
+    #[serde(rename(deserialize = "p"))]
+    pub price: f64,
+    /// Unix timestamp in milliseconds.
+    #[serde(rename(deserialize = "tm"))]
+    pub time: i64,
+}
+
+/// Amount in the report currency along with its percentage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradeAmount {
+    #[serde(rename(deserialize = "v"))]
+    pub value: f64,
+    #[serde(rename(deserialize = "p"))]
+    pub percent: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_report_from_study_data() {
+        let data = json!({
+            "data": {
+                "report": {
+                    "currency": "USD",
+                    "performance": {
+                        "all": { "totalTrades": 2, "numberOfWiningTrades": 1, "netProfit": 150.5 },
+                        "sharpeRatio": 0.8,
+                        "openPL": -3.0
+                    },
+                    "trades": [{
+                        "e": { "c": "Long", "tp": "le", "p": 100.0, "tm": 1_700_000_000_000_i64 },
+                        "x": { "c": "Exit", "tp": "", "p": 110.0, "tm": 1_700_003_600_000_i64 },
+                        "q": 1.0,
+                        "tp": { "v": 10.0, "p": 10.0 }
+                    }],
+                    "equity": [10.0, 150.5]
+                }
+            }
+        });
+
+        let report = StrategyReport::from_study_data(&data).unwrap().unwrap();
+        assert_eq!(report.currency, Some(Ustr::from("USD")));
+        assert_eq!(report.performance.all.number_of_winning_trades, Some(1.0));
+        assert_eq!(report.performance.open_pl, Some(-3.0));
+        assert_eq!(report.trades[0].exit.as_ref().unwrap().price, 110.0);
+        assert_eq!(report.trades[0].profit.value, 10.0);
+        assert!(!report.trades[0].is_short());
+        assert_eq!(report.equity, vec![10.0, 150.5]);
+
+        assert_eq!(
+            StrategyReport::from_study_data(&json!({ "st": [] })).unwrap(),
+            None
+        );
+    }
+}
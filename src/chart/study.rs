@@ -1,23 +1,166 @@
-use serde::Serialize;
+pub use crate::pine_indicator::{IndicatorInput, InputValue};
+use crate::{
+    chart::StudyResponseData,
+    pine_indicator::{PineMetadataInfo, Plot},
+};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use ustr::Ustr;
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
-#[serde(untagged)]
-pub enum IndicatorInput {
-    String(Ustr),
-    IndicatorInput(InputValue),
+/// One output column of a study, labelled with its plot metadata.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StudySeries {
+    /// Plot id, e.g. `plot_0`.
+    pub id: Ustr,
+    /// Title from the indicator styles, falls back to the plot id.
+    pub title: Ustr,
+    /// Plot type, e.g. `line`, `colorer` or `shapes`.
+    pub plot_type: Ustr,
+    /// Plot id this plot applies to, set for colorers and similar helper plots.
+    pub target: Option<Ustr>,
+    /// `(timestamp, value)` pairs in the order TradingView sent them.
+    pub values: Vec<(i64, f64)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct InputValue {
-    pub v: Value,
-    pub f: Value,
-    pub t: Value,
+impl PineMetadataInfo {
+    /// Display title of `plot_id` from the indicator styles, if any.
+    pub fn plot_title(&self, plot_id: &str) -> Option<Ustr> {
+        self.styles
+            .get(plot_id)
+            .and_then(|style| style.get("title"))
+            .and_then(Value::as_str)
+            .filter(|title| !title.is_empty())
+            .map(Ustr::from)
+    }
+}
+
+impl StudyResponseData {
+    /// Split the positional study columns into one [`StudySeries`] per plot of
+    /// `metadata`, in plot order.
+    ///
+    /// Column 0 of every point is the bar timestamp, column `n + 1` belongs to
+    /// `metadata.plots[n]`. Points without a value for a plot are skipped.
+    pub fn series(&self, metadata: &PineMetadataInfo) -> Vec<StudySeries> {
+        metadata
+            .plots
+            .iter()
+            .enumerate()
+            .map(|(column, plot)| self.column(metadata, plot, column + 1))
+            .collect()
+    }
+
+    /// Like [`StudyResponseData::series`], keyed by plot title.
+    ///
+    /// Plots sharing a title with an earlier plot are keyed by their id instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn run(
+    /// #     data: tradingview::StudyResponseData,
+    /// #     rsi: tradingview::pine_indicator::PineIndicator,
+    /// # ) {
+    /// let series = data.named_series(&rsi.metadata.data);
+    /// if let Some(rsi) = series.get(&ustr::Ustr::from("RSI")) {
+    ///     println!("{:?}", rsi.values.last());
+    /// }
+    /// # }
+    /// ```
+    pub fn named_series(&self, metadata: &PineMetadataInfo) -> HashMap<Ustr, StudySeries> {
+        let mut named = HashMap::new();
+        for series in self.series(metadata) {
+            let key = if named.contains_key(&series.title) {
+                series.id
+            } else {
+                series.title
+            };
+            named.insert(key, series);
+        }
+        named
+    }
+
+    fn column(&self, metadata: &PineMetadataInfo, plot: &Plot, column: usize) -> StudySeries {
+        let id = Ustr::from(&plot.id);
+        StudySeries {
+            id,
+            title: metadata.plot_title(&plot.id).unwrap_or(id),
+            plot_type: Ustr::from(&plot.plot_type),
+            target: plot.target.as_deref().map(Ustr::from),
+            values: self
+                .studies
+                .iter()
+                .filter_map(|point| {
+                    let time = *point.value.first()?;
+                    let value = *point.value.get(column)?;
+                    Some((time as i64, value))
+                })
+                .collect(),
+        }
+    }
 }
 
-impl InputValue {
-    pub fn new(v: Value, f: Value, t: Value) -> InputValue {
-        InputValue { v, f, t }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::{DataPoint, GraphicDataResponse};
+    use serde_json::json;
+
+    #[test]
+    fn test_named_series() {
+        let metadata = PineMetadataInfo {
+            plots: vec![
+                Plot {
+                    id: "plot_0".into(),
+                    plot_type: "line".into(),
+                    target: None,
+                },
+                Plot {
+                    id: "plot_1".into(),
+                    plot_type: "line".into(),
+                    target: None,
+                },
+                Plot {
+                    id: "plot_2".into(),
+                    plot_type: "colorer".into(),
+                    target: Some("plot_0".into()),
+                },
+            ],
+            styles: HashMap::from([
+                ("plot_0".to_string(), json!({ "title": "RSI" })),
+                ("plot_1".to_string(), json!({ "title": "RSI" })),
+            ]),
+            ..Default::default()
+        };
+        let data = StudyResponseData {
+            study_id: Ustr::from("st1"),
+            symbol: Ustr::default(),
+            interval: Default::default(),
+            node: None,
+            studies: vec![
+                DataPoint {
+                    index: 0,
+                    value: vec![1_700_000_000.0, 55.0, 50.0, 1.0],
+                },
+                DataPoint {
+                    index: 1,
+                    value: vec![1_700_003_600.0, 60.0],
+                },
+            ],
+            raw_graphics: GraphicDataResponse {
+                d: Ustr::default(),
+                indexes: Value::Null,
+            },
+        };
+
+        let series = data.named_series(&metadata);
+        let get = |key: &str| &series[&Ustr::from(key)];
+        assert_eq!(
+            get("RSI").values,
+            vec![(1_700_000_000, 55.0), (1_700_003_600, 60.0)]
+        );
+        assert_eq!(get("plot_1").values, vec![(1_700_000_000, 50.0)]);
+        assert_eq!(get("plot_2").target, Some(Ustr::from("plot_0")));
+        assert_eq!(get("plot_2").plot_type.as_str(), "colorer");
     }
 }
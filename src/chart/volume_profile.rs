@@ -0,0 +1,226 @@
+use crate::{Result, chart::StudyResponseData, pine_indicator::ScriptType};
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Settings of a volume-by-price study, see
+/// `WebSocketClient::add_volume_profile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Builder)]
+pub struct VolumeProfileOptions {
+    /// Number of price rows of each profile.
+    #[builder(default = 24)]
+    pub rows: u32,
+    /// First and last bar to cover as unix seconds, `None` for one profile
+    /// per trading session.
+    pub range: Option<(i64, i64)>,
+    /// Share of the volume inside the value area, in percent.
+    #[builder(default = 70)]
+    pub value_area: u8,
+}
+
+impl Default for VolumeProfileOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl VolumeProfileOptions {
+    /// A fixed range profile for a range, session profiles otherwise.
+    pub fn script_type(&self) -> ScriptType {
+        match self.range {
+            Some(_) => ScriptType::FixedVolumeByPrice,
+            None => ScriptType::SessionVolumeByPrice,
+        }
+    }
+
+    /// `create_study` inputs payload.
+    pub fn inputs(&self) -> Value {
+        let mut inputs = json!({
+            "rowsLayout": "Number Of Rows",
+            "rows": self.rows,
+            "volume": "Up/Down",
+            "vaVolume": self.value_area,
+            "subscribeRealtime": false,
+        });
+        if let Some((from, to)) = self.range {
+            inputs["first_bar_time"] = json!(from * 1000);
+            inputs["last_bar_time"] = json!(to * 1000);
+            inputs["mapRightBoundaryToBarStartTime"] = json!(true);
+            inputs["extendToRight"] = json!(false);
+        }
+        inputs
+    }
+}
+
+/// One price row of a [`VolumeProfile`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price_low: f64,
+    pub price_high: f64,
+    /// Volume traded on bars closing up.
+    pub up_volume: f64,
+    /// Volume traded on bars closing down.
+    pub down_volume: f64,
+}
+
+impl PriceLevel {
+    pub fn volume(&self) -> f64 {
+        self.up_volume + self.down_volume
+    }
+
+    pub fn mid_price(&self) -> f64 {
+        (self.price_low + self.price_high) / 2.0
+    }
+}
+
+/// Volume histogram over the bars `first_bar` to `last_bar`, bar indexes as
+/// sent by TradingView, with its rows ordered from the lowest price up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeProfile {
+    pub first_bar: i64,
+    pub last_bar: i64,
+    pub levels: Vec<PriceLevel>,
+}
+
+impl VolumeProfile {
+    /// The row with the most volume.
+    pub fn point_of_control(&self) -> Option<&PriceLevel> {
+        self.levels
+            .iter()
+            .max_by(|a, b| a.volume().total_cmp(&b.volume()))
+    }
+
+    pub fn total_volume(&self) -> f64 {
+        self.levels.iter().map(PriceLevel::volume).sum()
+    }
+}
+
+#[derive(Deserialize)]
+struct Graphics {
+    #[serde(default, rename = "graphicsCmds")]
+    commands: GraphicsCommands,
+}
+
+#[derive(Default, Deserialize)]
+struct GraphicsCommands {
+    #[serde(default)]
+    create: GraphicsCreate,
+}
+
+#[derive(Default, Deserialize)]
+struct GraphicsCreate {
+    #[serde(default)]
+    hhists: Vec<HistogramGroup>,
+}
+
+#[derive(Deserialize)]
+struct HistogramGroup {
+    #[serde(default)]
+    data: Vec<HistogramRow>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistogramRow {
+    price_low: f64,
+    price_high: f64,
+    first_bar_time: i64,
+    last_bar_time: i64,
+    #[serde(default)]
+    rate: Vec<f64>,
+}
+
+impl StudyResponseData {
+    /// Parse the histograms of a volume-by-price study, one
+    /// [`VolumeProfile`] per range or session, oldest first.
+    pub fn volume_profiles(&self) -> Result<Vec<VolumeProfile>> {
+        if self.raw_graphics.d.is_empty() {
+            return Ok(Vec::new());
+        }
+        let graphics: Graphics = serde_json::from_str(&self.raw_graphics.d)?;
+        let mut profiles: Vec<VolumeProfile> = Vec::new();
+        let rows = graphics
+            .commands
+            .create
+            .hhists
+            .into_iter()
+            .flat_map(|group| group.data);
+        for row in rows {
+            let level = PriceLevel {
+                price_low: row.price_low,
+                price_high: row.price_high,
+                up_volume: row.rate.first().copied().unwrap_or_default(),
+                down_volume: row.rate.get(1).copied().unwrap_or_default(),
+            };
+            match profiles.iter_mut().find(|profile| {
+                profile.first_bar == row.first_bar_time && profile.last_bar == row.last_bar_time
+            }) {
+                Some(profile) => profile.levels.push(level),
+                None => profiles.push(VolumeProfile {
+                    first_bar: row.first_bar_time,
+                    last_bar: row.last_bar_time,
+                    levels: vec![level],
+                }),
+            }
+        }
+        for profile in &mut profiles {
+            profile
+                .levels
+                .sort_by(|a, b| a.price_low.total_cmp(&b.price_low));
+        }
+        profiles.sort_by_key(|profile| profile.first_bar);
+        Ok(profiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::GraphicDataResponse;
+    use ustr::Ustr;
+
+    #[test]
+    fn test_volume_profiles() {
+        let graphics = json!({"graphicsCmds": {"create": {"hhists": [{
+            "styleId": "histBars0",
+            "data": [
+                {"id": 2, "priceLow": 101.0, "priceHigh": 102.0, "firstBarTime": 0, "lastBarTime": 9, "rate": [30.0, 10.0]},
+                {"id": 1, "priceLow": 100.0, "priceHigh": 101.0, "firstBarTime": 0, "lastBarTime": 9, "rate": [5.0, 5.0]},
+                {"id": 3, "priceLow": 99.0, "priceHigh": 100.0, "firstBarTime": 10, "lastBarTime": 19, "rate": [1.0, 2.0]},
+            ],
+        }]}}});
+        let data = StudyResponseData {
+            study_id: Ustr::from("vp"),
+            symbol: Ustr::default(),
+            interval: Default::default(),
+            node: None,
+            studies: Vec::new(),
+            raw_graphics: GraphicDataResponse {
+                d: Ustr::from(&graphics.to_string()),
+                indexes: Value::Null,
+            },
+        };
+
+        let profiles = data.volume_profiles().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].levels[0].price_low, 100.0);
+        assert_eq!(profiles[0].total_volume(), 50.0);
+        let poc = profiles[0].point_of_control().unwrap();
+        assert_eq!(poc.mid_price(), 101.5);
+        assert_eq!(profiles[1].first_bar, 10);
+
+        let fixed = VolumeProfileOptions::builder()
+            .rows(50)
+            .range((1_700_000_000, 1_700_086_400))
+            .build();
+        assert_eq!(fixed.script_type(), ScriptType::FixedVolumeByPrice);
+        assert_eq!(
+            fixed.inputs()["first_bar_time"],
+            json!(1_700_000_000_000_i64)
+        );
+        assert_eq!(
+            VolumeProfileOptions::default().script_type(),
+            ScriptType::SessionVolumeByPrice
+        );
+    }
+}
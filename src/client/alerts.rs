@@ -0,0 +1,283 @@
+use crate::{
+    Alert, AlertCondition, AlertFire, Interval, Result, UserCookies,
+    chart::ChartOptions,
+    error::{Error, TradingViewError},
+    models::alerts::AlertResponse,
+    utils::{get, post_json, symbol_init},
+};
+use bon::builder;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use ustr::Ustr;
+
+// The pushstream is a plain websocket, only available on the native transport
+#[cfg(feature = "native")]
+use crate::{CancellationToken, live::models::WEBSOCKET_HEADERS, utils::session_cookie};
+#[cfg(feature = "native")]
+use futures_util::StreamExt;
+#[cfg(feature = "native")]
+use reqwest::header::{COOKIE, HeaderValue};
+#[cfg(feature = "native")]
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+#[cfg(feature = "native")]
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, protocol::Message},
+};
+#[cfg(feature = "native")]
+use tracing::{debug, error, info};
+
+static BASE_ALERTS_URL: &str = "https://pricealerts.tradingview.com";
+static PUSHSTREAM_URL: &str = "wss://pushstream.tradingview.com/message-pipe-ws";
+
+async fn unwrap_response<T: DeserializeOwned>(res: reqwest::Response) -> Result<Option<T>> {
+    let res = res.json::<AlertResponse<T>>().await?;
+    if res.s != "ok" {
+        let msg = res.errmsg.unwrap_or(res.s);
+        return Err(Error::TradingView {
+            source: TradingViewError::AlertError(Ustr::from(&msg)),
+        });
+    }
+    Ok(res.r)
+}
+
+async fn call<T: DeserializeOwned>(
+    client: &UserCookies,
+    endpoint: &str,
+    payload: Value,
+) -> Result<Option<T>> {
+    let res = post_json(
+        Some(client),
+        &format!("{BASE_ALERTS_URL}/{endpoint}"),
+        &json!({ "payload": payload }),
+    )
+    .await?;
+    unwrap_response(res).await
+}
+
+/// List every alert configured on the account.
+#[tracing::instrument(skip(client))]
+pub async fn list_alerts(client: &UserCookies) -> Result<Vec<Alert>> {
+    let res = get(Some(client), &format!("{BASE_ALERTS_URL}/list_alerts"), &[]).await?;
+    Ok(unwrap_response(res).await?.unwrap_or_default())
+}
+
+/// List recent alert fires, newest first.
+#[tracing::instrument(skip(client))]
+pub async fn list_fired_alerts(client: &UserCookies) -> Result<Vec<AlertFire>> {
+    let res = get(Some(client), &format!("{BASE_ALERTS_URL}/list_fires"), &[]).await?;
+    Ok(unwrap_response(res).await?.unwrap_or_default())
+}
+
+/// Create an alert on `exchange:symbol` and return it as stored by TradingView.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{AlertCondition, AlertConditionType, UserCookies, client::alerts};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// let alert = alerts::create_alert()
+///     .client(user)
+///     .symbol("BTCUSDT")
+///     .exchange("BINANCE")
+///     .condition(AlertCondition::price(AlertConditionType::CrossUp, 100_000.0))
+///     .message("BTC above 100k")
+///     .call()
+///     .await?;
+/// println!("created alert {:?}", alert.alert_id);
+/// # Ok(())
+/// # }
+/// ```
+#[builder]
+pub async fn create_alert(
+    client: &UserCookies,
+    symbol: &str,
+    exchange: &str,
+    #[builder(default = Interval::OneMinute)] interval: Interval,
+    condition: AlertCondition,
+    #[builder(default)] message: &str,
+    name: Option<&str>,
+    /// RFC 3339 timestamp, alerts without one stay open-ended on paid plans.
+    expiration: Option<&str>,
+    #[builder(default = true)] popup: bool,
+    #[builder(default)] email: bool,
+    #[builder(default)] mobile_push: bool,
+    web_hook: Option<&str>,
+) -> Result<Alert> {
+    let alert = Alert {
+        symbol: symbol_init(
+            &format!("{exchange}:{symbol}"),
+            &ChartOptions::default(),
+            None,
+        )?,
+        resolution: interval.to_string(),
+        condition,
+        name: name.map(str::to_owned),
+        message: message.to_owned(),
+        active: true,
+        expiration: expiration.map(str::to_owned),
+        auto_deactivate: true,
+        popup,
+        email,
+        mobile_push,
+        web_hook: web_hook.map(str::to_owned),
+        ..Default::default()
+    };
+    call::<Alert>(client, "create_alert", serde_json::to_value(&alert)?)
+        .await?
+        .ok_or_else(|| Error::Internal(Ustr::from("create_alert returned no alert")))
+}
+
+/// Replace an existing alert and restart it. `alert.alert_id` must be set.
+#[tracing::instrument(skip(client, alert))]
+pub async fn modify_alert(client: &UserCookies, alert: &Alert) -> Result<Alert> {
+    if alert.alert_id.is_none() {
+        return Err(Error::Internal(Ustr::from("alert_id is required")));
+    }
+    call::<Alert>(client, "modify_restart_alert", serde_json::to_value(alert)?)
+        .await?
+        .ok_or_else(|| Error::Internal(Ustr::from("modify_restart_alert returned no alert")))
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn delete_alerts(client: &UserCookies, alert_ids: &[i64]) -> Result<()> {
+    call::<Value>(client, "delete_alerts", json!({ "alert_ids": alert_ids })).await?;
+    Ok(())
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn stop_alerts(client: &UserCookies, alert_ids: &[i64]) -> Result<()> {
+    call::<Value>(client, "stop_alerts", json!({ "alert_ids": alert_ids })).await?;
+    Ok(())
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn restart_alerts(client: &UserCookies, alert_ids: &[i64]) -> Result<()> {
+    call::<Value>(client, "restart_alerts", json!({ "alert_ids": alert_ids })).await?;
+    Ok(())
+}
+
+/// Stream alert fire notifications for the account into `tx`.
+///
+/// Connects to the account's private push channel, which also delivers fires of
+/// alerts created in the TradingView UI. The returned task ends when the
+/// connection closes, `tx` is dropped or the token is cancelled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{UserCookies, client::alerts};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+/// let _task = alerts::listen_alert_fires().client(user).tx(tx).call().await?;
+/// while let Some(fire) = rx.recv().await {
+///     println!("{} fired: {}", fire.symbol, fire.message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+#[builder]
+pub async fn listen_alert_fires(
+    client: &UserCookies,
+    tx: UnboundedSender<AlertFire>,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<JoinHandle<()>> {
+    let url = format!("{PUSHSTREAM_URL}/private_{}", client.private_channel);
+    let mut request = url.into_client_request()?;
+    request.headers_mut().extend(WEBSOCKET_HEADERS.clone());
+    request
+        .headers_mut()
+        .insert(COOKIE, HeaderValue::from_str(&session_cookie(client))?);
+
+    let (socket, response) = connect_async(request).await?;
+    info!(
+        "Alert pushstream connected with status: {}",
+        response.status()
+    );
+
+    let cancel = cancellation_token
+        .map(CancellationToken::child_token)
+        .unwrap_or_default();
+    let (_, mut read) = socket.split();
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tx.closed() => break,
+                message = read.next() => message,
+            };
+            match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(fire) = parse_alert_fire(&text)
+                        && tx.send(fire).is_err()
+                    {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    error!("alert pushstream error: {e}");
+                    break;
+                }
+            }
+        }
+        debug!("alert pushstream closed");
+    }))
+}
+
+/// Extract an `alert_fired` event from a pushstream frame.
+#[cfg(feature = "native")]
+fn parse_alert_fire(text: &str) -> Option<AlertFire> {
+    let frame: Value = serde_json::from_str(text).ok()?;
+    let mut content = frame.get("text")?.get("content")?.clone();
+    // Some frames carry the content double-encoded as a string
+    if let Value::String(raw) = &content {
+        content = serde_json::from_str(raw).ok()?;
+    }
+    if content.get("m")?.as_str()? != "alert_fired" {
+        return None;
+    }
+    serde_json::from_value(content.get("p")?.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlertConditionType, AlertSeries};
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_parse_alert_fire() {
+        let frame = r#"{"id":1,"channel":"private_abc","text":{"channel":"alert","content":{"m":"alert_fired","p":{"id":7,"aid":42,"sym":"BINANCE:BTCUSDT","res":"1","desc":"BTC above 100k","fire_time":"2024-01-01T00:00:00Z"}}}}"#;
+        let fire = parse_alert_fire(frame).unwrap();
+        assert_eq!(fire.alert_id, 42);
+        assert_eq!(fire.symbol, "BINANCE:BTCUSDT");
+        assert_eq!(fire.message, "BTC above 100k");
+
+        let other = r#"{"text":{"content":{"m":"alerts_updated","p":{}}}}"#;
+        assert!(parse_alert_fire(other).is_none());
+    }
+
+    #[test]
+    fn test_condition_roundtrip() {
+        let condition = AlertCondition::price(AlertConditionType::CrossDown, 1.5);
+        let value = serde_json::to_value(&condition).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "type": "cross_down",
+                "frequency": "on_first_fire",
+                "series": [{"type": "barset"}, {"type": "value", "value": 1.5}]
+            })
+        );
+
+        let study = json!({"type": "cross", "series": [{"type": "study", "plot_id": "plot_0"}]});
+        let parsed: AlertCondition = serde_json::from_value(study).unwrap();
+        assert!(matches!(parsed.series[0], AlertSeries::Study(_)));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    UserCookies,
+    pine_indicator::{PineMetadata, TranslateResponse},
+};
+use bon::Builder;
+use dashmap::DashMap;
+use std::{
+    fs,
+    path::PathBuf,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+use urlencoding::encode;
+use ustr::Ustr;
+
+/// Suffix of the files written to [`MetadataCacheConfig::dir`].
+const FILE_SUFFIX: &str = ".pine.json";
+
+lazy_static::lazy_static! {
+    static ref CONFIG: RwLock<MetadataCacheConfig> = RwLock::new(MetadataCacheConfig::default());
+    static ref ENTRIES: DashMap<CacheKey, (Instant, PineMetadata)> = DashMap::new();
+}
+
+/// User id the metadata was fetched as (`0` for anonymous requests), script id
+/// and version. Private and invite-only scripts only translate for users with
+/// access, so entries are never shared between users.
+type CacheKey = (u32, Ustr, Ustr);
+
+fn cache_key(client: Option<&UserCookies>, script_id: &str, script_version: &str) -> CacheKey {
+    (
+        client.map_or(0, |user| user.id),
+        Ustr::from(script_id),
+        Ustr::from(script_version),
+    )
+}
+
+/// Cache settings for [`get_indicator_metadata`](crate::get_indicator_metadata).
+///
+/// Metadata of a published script version never changes, so the default
+/// keeps entries in memory for a day.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use tradingview::client::cache::{MetadataCacheConfig, set_metadata_cache};
+///
+/// set_metadata_cache(
+///     MetadataCacheConfig::builder()
+///         .ttl(Duration::from_secs(7 * 24 * 60 * 60))
+///         .dir("/tmp/tradingview-pine")
+///         .build(),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct MetadataCacheConfig {
+    #[builder(default = true)]
+    pub enabled: bool,
+    #[builder(default = Duration::from_secs(24 * 60 * 60))]
+    pub ttl: Duration,
+    /// Directory the raw translate responses are persisted to, memory only when `None`.
+    #[builder(into)]
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for MetadataCacheConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Replace the process-wide metadata cache settings.
+pub fn set_metadata_cache(config: MetadataCacheConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// The settings installed with [`set_metadata_cache`].
+pub fn metadata_cache() -> MetadataCacheConfig {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Drop the cached metadata of one script version for every user, in memory and on disk.
+pub fn invalidate_indicator_metadata(script_id: &str, script_version: &str) {
+    let (script_id, script_version) = (Ustr::from(script_id), Ustr::from(script_version));
+    ENTRIES.retain(|(_, id, version), _| *id != script_id || *version != script_version);
+
+    let Some(dir) = metadata_cache().dir else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let suffix = file_name(0, &script_id, &script_version);
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // Anonymous entries are named `{suffix}`, per-user ones `{user}_{suffix}`
+        if name == suffix
+            || name
+                .strip_suffix(&suffix)
+                .is_some_and(|user| user.ends_with('_'))
+        {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Drop every cached metadata entry, in memory and on disk.
+pub fn clear_indicator_metadata_cache() {
+    ENTRIES.clear();
+    let Some(dir) = metadata_cache().dir else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().ends_with(FILE_SUFFIX) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn file_name(user: u32, script_id: &str, script_version: &str) -> String {
+    let name = format!(
+        "{}_{}{FILE_SUFFIX}",
+        encode(script_id),
+        encode(script_version)
+    );
+    if user == 0 {
+        name
+    } else {
+        format!("{user}_{name}")
+    }
+}
+
+fn file_path(config: &MetadataCacheConfig, key: &CacheKey) -> Option<PathBuf> {
+    let (user, script_id, script_version) = key;
+    let dir = config.dir.as_ref()?;
+    Some(dir.join(file_name(*user, script_id, script_version)))
+}
+
+pub(crate) fn get(
+    client: Option<&UserCookies>,
+    script_id: &str,
+    script_version: &str,
+) -> Option<PineMetadata> {
+    let config = metadata_cache();
+    if !config.enabled {
+        return None;
+    }
+    let key = cache_key(client, script_id, script_version);
+
+    if let Some(entry) = ENTRIES.get(&key) {
+        let (stored, metadata) = entry.value();
+        if stored.elapsed() < config.ttl {
+            return Some(metadata.clone());
+        }
+    }
+    ENTRIES.remove(&key);
+
+    let path = file_path(&config, &key)?;
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    if age >= config.ttl {
+        return None;
+    }
+    let resp: TranslateResponse = match fs::read_to_string(&path)
+        .map_err(crate::Error::from)
+        .and_then(|raw| Ok(serde_json::from_str(&raw)?))
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            warn!(
+                "ignoring unreadable metadata cache file {}: {e}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    if !resp.success {
+        return None;
+    }
+    debug!(
+        "loaded metadata for {script_id}@{script_version} from {}",
+        path.display()
+    );
+    let stored = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+    ENTRIES.insert(key, (stored, resp.result.clone()));
+    Some(resp.result)
+}
+
+/// Store `metadata` along with the `raw` translate response it was parsed from.
+pub(crate) fn insert(
+    client: Option<&UserCookies>,
+    script_id: &str,
+    script_version: &str,
+    metadata: &PineMetadata,
+    raw: &str,
+) {
+    let config = metadata_cache();
+    if !config.enabled {
+        return;
+    }
+    let key = cache_key(client, script_id, script_version);
+    let path = file_path(&config, &key);
+    ENTRIES.insert(key, (Instant::now(), metadata.clone()));
+
+    let Some(path) = path else {
+        return;
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, raw));
+    if let Err(e) = written {
+        warn!(
+            "failed to write metadata cache file {}: {e}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_entries_expire() {
+        let metadata = PineMetadata {
+            il: Ustr::from("il"),
+            ..Default::default()
+        };
+        insert(None, "STD;CacheTest", "1.0", &metadata, "");
+        assert_eq!(get(None, "STD;CacheTest", "1.0"), Some(metadata));
+
+        ENTRIES
+            .get_mut(&cache_key(None, "STD;CacheTest", "1.0"))
+            .unwrap()
+            .0 = Instant::now() - Duration::from_secs(25 * 60 * 60);
+        assert_eq!(get(None, "STD;CacheTest", "1.0"), None);
+
+        insert(None, "STD;CacheTest", "2.0", &PineMetadata::default(), "");
+        invalidate_indicator_metadata("STD;CacheTest", "2.0");
+        assert_eq!(get(None, "STD;CacheTest", "2.0"), None);
+    }
+
+    #[test]
+    fn test_entries_are_per_user() {
+        let user = UserCookies {
+            id: 42,
+            ..Default::default()
+        };
+        let metadata = PineMetadata {
+            il: Ustr::from("private"),
+            ..Default::default()
+        };
+        insert(Some(&user), "USER;CacheTest", "1.0", &metadata, "");
+        assert_eq!(get(Some(&user), "USER;CacheTest", "1.0"), Some(metadata));
+        assert_eq!(get(None, "USER;CacheTest", "1.0"), None);
+
+        let other = UserCookies {
+            id: 7,
+            ..Default::default()
+        };
+        assert_eq!(get(Some(&other), "USER;CacheTest", "1.0"), None);
+
+        invalidate_indicator_metadata("USER;CacheTest", "1.0");
+        assert_eq!(get(Some(&user), "USER;CacheTest", "1.0"), None);
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(file_name(0, "PUB;abc", "1.0"), "PUB%3Babc_1.0.pine.json");
+        assert_eq!(
+            file_name(42, "PUB;abc", "1.0"),
+            "42_PUB%3Babc_1.0.pine.json"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    CancellationToken, EconomicEvent, EventImportance, Result, UserCookies, error::Error,
+    utils::get,
+};
+use bon::builder;
+use chrono::{DateTime, Duration as TimeDelta, SecondsFormat, Utc};
+use serde::Deserialize;
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+use tracing::{debug, error};
+use ustr::Ustr;
+
+static ECONOMIC_CALENDAR_URL: &str = "https://economic-calendar.tradingview.com/events";
+
+#[derive(Debug, Deserialize)]
+struct EventsResponse {
+    status: Ustr,
+    #[serde(default)]
+    result: Vec<EconomicEvent>,
+}
+
+/// Fetch the economic events scheduled between `from` and `to`.
+///
+/// `countries` takes alpha-2 codes such as `US` or `EU`, all countries when empty.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use chrono::{Duration, Utc};
+/// use tradingview::{EventImportance, client::economic_calendar::get_economic_events};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let events = get_economic_events()
+///         .from(Utc::now())
+///         .to(Utc::now() + Duration::days(7))
+///         .countries(&["US", "EU"])
+///         .min_importance(EventImportance::High)
+///         .call()
+///         .await?;
+///     for event in events {
+///         println!("{:?} {} {}: {:?}", event.date, event.country, event.title, event.forecast);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_economic_events(
+    client: Option<&UserCookies>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    #[builder(default)] countries: &[&str],
+    #[builder(default)] min_importance: EventImportance,
+) -> Result<Vec<EconomicEvent>> {
+    let from = from.to_rfc3339_opts(SecondsFormat::Millis, true);
+    let to = to.to_rfc3339_opts(SecondsFormat::Millis, true);
+    let countries = countries.join(",");
+    let min_importance = i8::from(min_importance).to_string();
+    let mut queries = vec![
+        ("from", from.as_str()),
+        ("to", to.as_str()),
+        ("minImportance", min_importance.as_str()),
+    ];
+    if !countries.is_empty() {
+        queries.push(("countries", countries.as_str()));
+    }
+
+    let resp: EventsResponse = get(client, ECONOMIC_CALENDAR_URL, &queries)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if resp.status.as_str() != "ok" {
+        return Err(Error::Internal(Ustr::from(&format!(
+            "economic calendar request failed with status {}",
+            resp.status
+        ))));
+    }
+    Ok(resp.result)
+}
+
+/// Poll the calendar every `interval` and send each event to `tx` once its
+/// actual value is published.
+///
+/// The polled window spans `lookback` before and `lookahead` after each poll.
+/// Events already released when the task starts are skipped. The task ends
+/// once `tx` is dropped or the token is cancelled.
+#[builder]
+pub fn watch_economic_events(
+    client: Option<UserCookies>,
+    tx: UnboundedSender<EconomicEvent>,
+    #[builder(default = Duration::from_secs(60))] interval: Duration,
+    #[builder(default = TimeDelta::days(1))] lookback: TimeDelta,
+    #[builder(default = TimeDelta::days(1))] lookahead: TimeDelta,
+    countries: Vec<String>,
+    #[builder(default)] min_importance: EventImportance,
+    cancellation_token: Option<&CancellationToken>,
+) -> JoinHandle<()> {
+    let cancel = cancellation_token
+        .map(CancellationToken::child_token)
+        .unwrap_or_default();
+
+    tokio::spawn(async move {
+        let countries: Vec<&str> = countries.iter().map(String::as_str).collect();
+        let mut released: Option<ReleasedEvents> = None;
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tx.closed() => break,
+                _ = ticker.tick() => {}
+            }
+
+            let now = Utc::now();
+            let events = get_economic_events()
+                .maybe_client(client.as_ref())
+                .from(now - lookback)
+                .to(now + lookahead)
+                .countries(&countries)
+                .min_importance(min_importance)
+                .call()
+                .await;
+            let events = match events {
+                Ok(events) => events,
+                Err(e) => {
+                    error!("failed to poll economic calendar: {e}");
+                    continue;
+                }
+            };
+
+            let window_start = now - lookback;
+            match &mut released {
+                None => released = Some(ReleasedEvents::seed(events, now)),
+                Some(released) => {
+                    for event in released.update(events, now, window_start) {
+                        if tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+        debug!("economic calendar watcher stopped");
+    })
+}
+
+/// Ids of the released events in the watched window, keyed to the time they
+/// are scheduled at (or were first seen at when undated).
+#[derive(Debug, Default)]
+struct ReleasedEvents(HashMap<Ustr, DateTime<Utc>>);
+
+impl ReleasedEvents {
+    /// Remember the events already released when the watcher starts.
+    fn seed(events: Vec<EconomicEvent>, now: DateTime<Utc>) -> Self {
+        let mut released = Self::default();
+        for event in events.into_iter().filter(EconomicEvent::is_released) {
+            released.0.insert(event.id, event.date.unwrap_or(now));
+        }
+        released
+    }
+
+    /// Return the events released since the last poll and forget the ones
+    /// scheduled before `window_start`, which the poll no longer returns.
+    fn update(
+        &mut self,
+        events: Vec<EconomicEvent>,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Vec<EconomicEvent> {
+        self.0.retain(|_, date| *date >= window_start);
+        events
+            .into_iter()
+            .filter(|event| {
+                event.is_released() && self.0.insert(event.id, event.date.unwrap_or(now)).is_none()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, date: DateTime<Utc>, actual: Option<f64>) -> EconomicEvent {
+        EconomicEvent {
+            id: Ustr::from(id),
+            date: Some(date),
+            actual,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_released_events() {
+        let start = Utc::now();
+        let mut released = ReleasedEvents::seed(
+            vec![
+                event("cpi", start, Some(3.1)),
+                event("nfp", start + TimeDelta::hours(1), None),
+            ],
+            start,
+        );
+
+        let now = start + TimeDelta::hours(2);
+        let new = released.update(
+            vec![
+                event("cpi", start, Some(3.1)),
+                event("nfp", start + TimeDelta::hours(1), Some(150.0)),
+            ],
+            now,
+            now - TimeDelta::days(1),
+        );
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, "nfp");
+        assert_eq!(released.0.len(), 2);
+
+        // Both events fall out of the window and are forgotten
+        let now = start + TimeDelta::days(2);
+        assert!(
+            released
+                .update(vec![], now, now - TimeDelta::days(1))
+                .is_empty()
+        );
+        assert!(released.0.is_empty());
+    }
+}
@@ -1 +1,181 @@
-// TODO:
+use crate::{
+    DividendEvent, EarningsEvent, Result, UserCookies,
+    screener::{Filter, ScreenerMarket, ScreenerQuery, SortOrder},
+};
+use bon::builder;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+
+const EARNINGS_COLUMNS: &[&str] = &[
+    "name",
+    "currency",
+    "earnings_release_next_date",
+    "earnings_release_date",
+    "earnings_per_share_forecast_next_fq",
+    "earnings_per_share_fq",
+    "eps_surprise_fq",
+    "eps_surprise_percent_fq",
+    "revenue_forecast_next_fq",
+    "revenue_fq",
+];
+
+const DIVIDEND_COLUMNS: &[&str] = &[
+    "name",
+    "currency",
+    "dividend_ex_date_upcoming",
+    "dividend_payment_date_upcoming",
+    "dividend_amount_upcoming",
+    "dividend_ex_date_recent",
+    "dividend_payment_date_recent",
+    "dividend_amount_recent",
+    "dividends_yield_current",
+];
+
+/// Scan `date_column` between `from` and `to` in `market`, or for `symbols` only.
+async fn scan_calendar<T: DeserializeOwned>(
+    client: Option<&UserCookies>,
+    query: ScreenerQuery,
+    date_column: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    symbols: &[&str],
+    limit: u64,
+) -> Result<Vec<T>> {
+    let mut query = query
+        .sort_by(date_column, SortOrder::Ascending)
+        .range(0, limit);
+    if !symbols.is_empty() {
+        query = query.tickers(symbols);
+    }
+    if from.is_some() || to.is_some() {
+        let from = from.map_or(0, |from| from.timestamp());
+        let to = to.map_or(i64::MAX, |to| to.timestamp());
+        query = query.filter(Filter::in_range(date_column, from, to));
+    }
+    query
+        .scan(client)
+        .await?
+        .rows
+        .iter()
+        .map(|row| row.typed())
+        .collect()
+}
+
+/// Upcoming earnings releases, with EPS and revenue estimates next to the last reported values.
+///
+/// Either restrict the scan to `symbols` or to releases between `from` and `to` in `market`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use chrono::{Duration, Utc};
+/// use tradingview::client::fin_calendar::get_earnings_calendar;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let earnings = get_earnings_calendar()
+///         .from(Utc::now())
+///         .to(Utc::now() + Duration::days(7))
+///         .call()
+///         .await?;
+///     for event in earnings {
+///         println!("{} {:?} est. {:?}", event.symbol, event.next_release, event.eps_estimate);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_earnings_calendar(
+    client: Option<&UserCookies>,
+    #[builder(default)] market: ScreenerMarket,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    #[builder(default)] symbols: &[&str],
+    #[builder(default = 100)] limit: u64,
+) -> Result<Vec<EarningsEvent>> {
+    let query = ScreenerQuery::new(market).columns(EARNINGS_COLUMNS);
+    scan_calendar(
+        client,
+        query,
+        "earnings_release_next_date",
+        from,
+        to,
+        symbols,
+        limit,
+    )
+    .await
+}
+
+/// Upcoming dividends with ex-date, payment date and amount, next to the last paid dividend.
+///
+/// Either restrict the scan to `symbols` or to ex-dates between `from` and `to` in `market`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::fin_calendar::get_dividend_calendar;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let dividends = get_dividend_calendar()
+///         .symbols(&["NASDAQ:AAPL", "NYSE:KO"])
+///         .call()
+///         .await?;
+///     for event in dividends {
+///         println!("{} ex {:?} pays {:?}", event.symbol, event.ex_date, event.amount);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_dividend_calendar(
+    client: Option<&UserCookies>,
+    #[builder(default)] market: ScreenerMarket,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    #[builder(default)] symbols: &[&str],
+    #[builder(default = 100)] limit: u64,
+) -> Result<Vec<DividendEvent>> {
+    let query = ScreenerQuery::new(market).columns(DIVIDEND_COLUMNS);
+    scan_calendar(
+        client,
+        query,
+        "dividend_ex_date_upcoming",
+        from,
+        to,
+        symbols,
+        limit,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DividendEvent, screener::ScreenerRow};
+    use serde_json::json;
+    use ustr::Ustr;
+
+    #[test]
+    fn test_dividend_event_from_row() {
+        let row = ScreenerRow {
+            symbol: Ustr::from("NYSE:KO"),
+            values: [
+                ("name", json!("KO")),
+                ("dividend_ex_date_upcoming", json!(1_718_841_600.0)),
+                ("dividend_payment_date_upcoming", json!(null)),
+                ("dividend_amount_upcoming", json!(0.485)),
+            ]
+            .into_iter()
+            .map(|(column, value)| (Ustr::from(column), value))
+            .collect(),
+        };
+
+        let event: DividendEvent = row.typed().unwrap();
+        assert_eq!(event.symbol.as_str(), "NYSE:KO");
+        assert_eq!(event.ex_date.unwrap().timestamp(), 1_718_841_600);
+        assert_eq!(event.pay_date, None);
+        assert_eq!(event.amount, Some(0.485));
+    }
+}
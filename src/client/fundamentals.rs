@@ -0,0 +1,167 @@
+use crate::{
+    Error, FinancialPeriod, FinancialPoint, FinancialStatement, Result, StatementKind, UserCookies,
+    utils::get_symbol_fields,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use ustr::Ustr;
+
+/// Fetch the history of a financial statement of `symbol` (`EXCHANGE:SYMBOL`)
+/// per fiscal `period`, as far back as TradingView reports it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{FinancialPeriod, StatementKind, client::fundamentals::get_financial_statement};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let income = get_financial_statement(
+///         None,
+///         "NASDAQ:AAPL",
+///         StatementKind::IncomeStatement,
+///         FinancialPeriod::FiscalQuarter,
+///     )
+///     .await?;
+///     for point in income.get("total_revenue").unwrap_or_default() {
+///         println!("{:?}: {:?}", point.period_end, point.value);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn get_financial_statement(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    statement: StatementKind,
+    period: FinancialPeriod,
+) -> Result<FinancialStatement> {
+    get_financials(client, symbol, statement.fields(), period).await
+}
+
+/// Like [`get_financial_statement`] for any scanner fields with a period
+/// history, e.g. `dividends_paid` or `shrhldrs_equity`.
+#[tracing::instrument(skip(client))]
+pub async fn get_financials(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    fields: &[&str],
+    period: FinancialPeriod,
+) -> Result<FinancialStatement> {
+    let suffix = period.to_string().to_lowercase();
+    let columns: Vec<String> = fields
+        .iter()
+        .copied()
+        .chain(["fiscal_period_end"])
+        .map(|field| format!("{field}_{suffix}_h"))
+        .chain(["fundamental_currency_code".to_owned()])
+        .collect();
+    let columns = columns.join(",");
+
+    let data: Value = get_symbol_fields(client, symbol, columns.as_str()).await?;
+    let values = data.as_object().filter(|values| !values.is_empty());
+    parse_financials(
+        symbol,
+        fields,
+        period,
+        values.ok_or(Error::NoScanDataFound)?,
+    )
+}
+
+/// Turn the newest first `_h` history columns into oldest first series.
+fn parse_financials(
+    symbol: &str,
+    fields: &[&str],
+    period: FinancialPeriod,
+    values: &Map<String, Value>,
+) -> Result<FinancialStatement> {
+    let suffix = period.to_string().to_lowercase();
+    let history = |field: &str| -> Vec<Option<f64>> {
+        values
+            .get(&format!("{field}_{suffix}_h"))
+            .and_then(Value::as_array)
+            .map(|history| history.iter().rev().map(Value::as_f64).collect())
+            .unwrap_or_default()
+    };
+    let ends = history("fiscal_period_end");
+
+    let series: BTreeMap<Ustr, Vec<FinancialPoint>> = fields
+        .iter()
+        .map(|&field| {
+            let history = history(field);
+            // Histories end at the latest period, so align them from the back
+            let offset = ends.len().saturating_sub(history.len());
+            let points = history
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| FinancialPoint {
+                    period_end: ends
+                        .get(i + offset)
+                        .copied()
+                        .flatten()
+                        .map(|end| end as i64),
+                    value,
+                })
+                .collect();
+            (Ustr::from(field), points)
+        })
+        .collect();
+    if series.values().all(Vec::is_empty) {
+        return Err(Error::NoScanDataFound);
+    }
+
+    Ok(FinancialStatement {
+        symbol: Ustr::from(symbol),
+        period,
+        currency: values
+            .get("fundamental_currency_code")
+            .and_then(Value::as_str)
+            .map(Ustr::from),
+        series,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_financials() {
+        let values = json!({
+            "total_revenue_fy_h": [383.0, 394.0, 365.0],
+            "net_income_fy_h": [97.0, null],
+            "fiscal_period_end_fy_h": [1_696_032_000, 1_664_496_000, 1_632_960_000],
+            "fundamental_currency_code": "USD",
+        });
+        let statement = parse_financials(
+            "NASDAQ:AAPL",
+            &["total_revenue", "net_income", "ebitda"],
+            FinancialPeriod::FiscalYear,
+            values.as_object().unwrap(),
+        )
+        .unwrap();
+
+        let revenue = statement.get("total_revenue").unwrap();
+        assert_eq!(revenue[0].value, Some(365.0));
+        assert_eq!(revenue[0].period_end, Some(1_632_960_000));
+        assert_eq!(statement.latest("total_revenue"), Some(383.0));
+        // A shorter history covers the latest periods
+        let net_income = statement.get("net_income").unwrap();
+        assert_eq!(net_income[0].period_end, Some(1_664_496_000));
+        assert_eq!(statement.latest("net_income"), Some(97.0));
+        assert!(statement.get("ebitda").unwrap().is_empty());
+        assert_eq!(statement.currency, Some(Ustr::from("USD")));
+
+        let empty = Map::new();
+        assert!(
+            parse_financials(
+                "X:Y",
+                &["total_revenue"],
+                FinancialPeriod::FiscalYear,
+                &empty
+            )
+            .is_err()
+        );
+    }
+}
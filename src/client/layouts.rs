@@ -0,0 +1,163 @@
+use crate::{
+    ChartLayout, Drawing, Result, SavedLayout, UserCookies,
+    client::misc::get_chart_token,
+    error::Error,
+    utils::{get, post_form},
+};
+use serde_json::{Value, json};
+use ustr::Ustr;
+
+static BASE_URL: &str = "https://www.tradingview.com";
+static CHARTS_STORAGE_URL: &str = "https://charts-storage.tradingview.com/charts-storage";
+
+/// List the chart layouts saved on the account.
+#[tracing::instrument(skip(client))]
+pub async fn list_layouts(client: &UserCookies) -> Result<Vec<SavedLayout>> {
+    let layouts = get(Some(client), &format!("{BASE_URL}/my-charts/"), &[])
+        .await?
+        .error_for_status()?
+        .json::<Vec<SavedLayout>>()
+        .await?;
+    Ok(layouts)
+}
+
+/// Create a layout, or overwrite the one with `layout.id`, and return its metadata.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{ChartLayout, Interval, LayoutStudy, UserCookies, client::layouts};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// let layout = ChartLayout::builder()
+///     .name("Generated setup")
+///     .symbol("NASDAQ:AAPL")
+///     .interval(Interval::OneHour)
+///     .studies(vec![LayoutStudy::builder().pine_id("STD;MACD").build()])
+///     .build();
+/// let saved = layouts::save_layout(user, &layout).await?;
+/// println!("https://www.tradingview.com/chart/{}/", saved.image_url);
+/// # Ok(())
+/// # }
+/// ```
+#[tracing::instrument(skip(client, layout), fields(name = %layout.name))]
+pub async fn save_layout(client: &UserCookies, layout: &ChartLayout) -> Result<SavedLayout> {
+    let id = layout.id.map(|id| id.to_string()).unwrap_or_default();
+    let resolution = layout.interval.to_string();
+    let content = serde_json::to_string(&layout.to_content())?;
+    let charts_symbols = json!({ "1": { "symbol": layout.symbol } }).to_string();
+    let form = [
+        ("id", id.as_str()),
+        ("name", layout.name.as_str()),
+        ("symbol", layout.symbol.as_str()),
+        ("resolution", resolution.as_str()),
+        ("content", content.as_str()),
+        ("charts_symbols", charts_symbols.as_str()),
+    ];
+
+    let res = post_form(Some(client), &format!("{BASE_URL}/savechart/"), &form)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    // New layouts answer with their numeric id, updates with a status object
+    let saved_id = match res.trim().parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => layout.id.ok_or_else(|| {
+            Error::Internal(Ustr::from(&format!("unexpected savechart response: {res}")))
+        })?,
+    };
+
+    list_layouts(client)
+        .await?
+        .into_iter()
+        .find(|l| l.id == saved_id)
+        .ok_or_else(|| Error::Internal(Ustr::from(&format!("saved layout {saved_id} not found"))))
+}
+
+/// Load the stored content of a layout by its short id (`image_url`).
+///
+/// The raw document is returned, use [`ChartLayout::from_content`] to read the
+/// symbol, interval and studies of its first chart.
+#[tracing::instrument(skip(client))]
+pub async fn load_layout(client: &UserCookies, layout_id: &str) -> Result<Value> {
+    let token = get_chart_token(client, layout_id).await?;
+    let res: Value = get(
+        Some(client),
+        &format!("{CHARTS_STORAGE_URL}/get/layout/{layout_id}/content"),
+        &[("jwt", token.as_str())],
+    )
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+
+    // The document is usually wrapped as `{"success": true, "payload": {"content": "<json>"}}`
+    let content = res
+        .get("payload")
+        .and_then(|p| p.get("content"))
+        .cloned()
+        .unwrap_or(res);
+    match content {
+        Value::String(raw) => Ok(serde_json::from_str(&raw)?),
+        content => Ok(content),
+    }
+}
+
+/// Every drawing on a layout, by its short id (`image_url`), both those
+/// stored in the layout itself and those synced to the drawings storage.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{DrawingKind, UserCookies, client::layouts};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// for drawing in layouts::get_drawings(user, "AbCdEf12").await? {
+///     if drawing.kind == DrawingKind::HorizontalLine {
+///         println!("level at {}", drawing.points[0].price);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn get_drawings(client: &UserCookies, layout_id: &str) -> Result<Vec<Drawing>> {
+    let content = load_layout(client, layout_id).await?;
+    let mut drawings = Drawing::from_content(&content);
+
+    let token = get_chart_token(client, layout_id).await?;
+    let symbol = ChartLayout::from_content(&content)
+        .map(|layout| layout.symbol)
+        .unwrap_or_default();
+    let res: Value = get(
+        Some(client),
+        &format!("{CHARTS_STORAGE_URL}/get/layout/{layout_id}/sources"),
+        &[
+            ("chart_id", "_shared"),
+            ("jwt", token.as_str()),
+            ("symbol", symbol.as_str()),
+        ],
+    )
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+
+    // The storage holds the latest version of a drawing also kept in the layout
+    let stored = res
+        .get("payload")
+        .and_then(|p| p.get("sources"))
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|sources| sources.values())
+        .filter_map(Drawing::from_source);
+    for drawing in stored {
+        match drawings.iter_mut().find(|d| d.id == drawing.id) {
+            Some(existing) => *existing = drawing,
+            None => drawings.push(drawing),
+        }
+    }
+    Ok(drawings)
+}
@@ -1,11 +1,16 @@
+use super::{
+    cache,
+    paginator::{Page, Paginator},
+};
 use crate::{
     ChartDrawing, Country, CryptoCentralization, EconomicCategory, EconomicSource,
     FuturesProductType, MarketType, Result, StockSector, Symbol, SymbolSearchResponse, UserCookies,
     error::Error,
     pine_indicator::{self, BuiltinIndicators, PineInfo, PineMetadata, PineSearchResult},
-    utils::build_request,
+    utils,
 };
 use bon::builder;
+use futures_util::FutureExt;
 use reqwest::Response;
 use serde_json::Value;
 use std::sync::Arc;
@@ -67,16 +72,7 @@ impl ParameterBuilder {
 ///
 /// A `Result` containing a `Response` struct representing the response from the server, or an error if the request failed.
 async fn get(client: Option<&UserCookies>, url: &str) -> Result<Response> {
-    if let Some(client) = client {
-        let cookie = format!(
-            "sessionid={}; sessionid_sign={}; device_t={};",
-            client.session, client.session_signature, client.device_token
-        );
-        let client = build_request(Some(&cookie))?;
-        let response = client.get(url).send().await?;
-        return Ok(response);
-    }
-    Ok(build_request(None)?.get(url).send().await?)
+    utils::get(client, url, &[]).await
 }
 
 pub async fn get_symbol(symbol: &str, exchange: &str) -> Option<Symbol> {
@@ -106,6 +102,52 @@ pub async fn search_symbols(search: &str, exchange: &str) -> Result<Vec<Symbol>>
     Ok(search_data.symbols)
 }
 
+/// Searches symbols matching `query`, narrowed down by exchange, instrument type and country.
+///
+/// Follows result pages until `limit` symbols are collected. Every [`Symbol`] implements
+/// [`crate::MarketSymbol`], so results can be handed straight to the history helpers, to a
+/// quote session via [`Symbol::id`] or to a chart via [`Symbol::chart_options`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{CryptoType, Interval, MarketType, symbol_search};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let symbols = symbol_search()
+///         .query("BTCUSDT")
+///         .exchange("BINANCE")
+///         .market_type(MarketType::Crypto(CryptoType::Spot))
+///         .limit(10)
+///         .call()
+///         .await?;
+///     for symbol in &symbols {
+///         println!("{} {} {}", symbol.id(), symbol.description, symbol.currency_code);
+///     }
+///     let options = symbols[0].chart_options(Interval::OneHour);
+///     Ok(())
+/// }
+/// ```
+#[builder]
+pub async fn symbol_search(
+    query: &str,
+    exchange: Option<&str>,
+    #[builder(default = MarketType::All)] market_type: MarketType,
+    country: Option<Country>,
+    #[builder(default = 50)] limit: usize,
+) -> Result<Vec<Symbol>> {
+    paginate_symbols()
+        .search(query)
+        .maybe_exchange(exchange)
+        .market_type(market_type)
+        .maybe_country(country)
+        .limit(limit)
+        .call()
+        .collect_all()
+        .await
+}
+
 /// Searches for a symbol using the specified search parameters.
 ///
 /// # Arguments
@@ -196,6 +238,67 @@ pub async fn advanced_search_symbol(
     Ok(search_data)
 }
 
+/// Lazily pages through symbol search results, 50 symbols per request.
+///
+/// Takes the same filters as [`advanced_search_symbol`], plus an optional
+/// `limit` on the total number of symbols to yield.
+#[builder]
+pub fn paginate_symbols(
+    search: Option<&str>,
+    exchange: Option<&str>,
+    #[builder(default = MarketType::All)] market_type: MarketType,
+    #[builder(default = 0)] start: u64,
+    country: Option<Country>,
+    #[builder(default = "production")] domain: &str,
+    futures_type: Option<FuturesProductType>,
+    stock_sector: Option<StockSector>,
+    crypto_centralization: Option<CryptoCentralization>,
+    economic_source: Option<EconomicSource>,
+    economic_category: Option<EconomicCategory>,
+    search_type: Option<&str>,
+    limit: Option<usize>,
+) -> Paginator<Symbol> {
+    let search = search.map(str::to_string);
+    let exchange = exchange.map(str::to_string);
+    let domain = domain.to_string();
+    let search_type = search_type.map(str::to_string);
+
+    let paginator = Paginator::new(start, move |offset| {
+        let search = search.clone();
+        let exchange = exchange.clone();
+        let domain = domain.clone();
+        let search_type = search_type.clone();
+        async move {
+            let resp = advanced_search_symbol()
+                .maybe_search(search.as_deref())
+                .maybe_exchange(exchange.as_deref())
+                .market_type(market_type)
+                .start(offset)
+                .maybe_country(country)
+                .domain(&domain)
+                .maybe_futures_type(futures_type)
+                .maybe_stock_sector(stock_sector)
+                .maybe_crypto_centralization(crypto_centralization)
+                .maybe_economic_source(economic_source)
+                .maybe_economic_category(economic_category)
+                .maybe_search_type(search_type.as_deref())
+                .call()
+                .await?;
+            let next = (resp.remaining > 0).then_some(offset + resp.symbols.len() as u64);
+            Ok(Page {
+                items: resp.symbols,
+                next,
+            })
+        }
+        .boxed()
+    });
+
+    match limit {
+        Some(limit) => paginator.with_limit(limit),
+        None => paginator,
+    }
+}
+
 fn add_market_specific_params(
     builder: &mut ParameterBuilder,
     market_type: MarketType,
@@ -498,6 +601,34 @@ pub async fn search_indicator(
     Ok(resp.results)
 }
 
+/// Lazily pages through public Pine script search results.
+///
+/// Unlike [`search_indicator`], an empty result simply ends the iteration.
+pub fn paginate_indicators(
+    client: Option<&UserCookies>,
+    search: &str,
+) -> Paginator<PineSearchResult> {
+    let client = client.cloned();
+    let search = encode(search).into_owned();
+
+    Paginator::new(0, move |offset| {
+        let client = client.clone();
+        let url = format!(
+            "https://www.tradingview.com/pubscripts-suggest-json/?search={search}&offset={offset}",
+        );
+        async move {
+            let resp: pine_indicator::SearchResponse =
+                get(client.as_ref(), &url).await?.json().await?;
+            let next = (!resp.next.is_empty()).then_some(offset + resp.results.len() as u64);
+            Ok(Page {
+                items: resp.results,
+                next,
+            })
+        }
+        .boxed()
+    })
+}
+
 /// Retrieves metadata for a TradingView Pine indicator.
 ///
 /// # Arguments
@@ -510,6 +641,10 @@ pub async fn search_indicator(
 ///
 /// Returns a `Result` containing a `PineMetadata` struct if successful, or an `Error` if unsuccessful.
 ///
+/// Results are cached per user, script id and version, see [`cache::set_metadata_cache`]. Version
+/// aliases such as `-1` are cached as well until the TTL expires; use
+/// [`get_indicator_metadata_uncached`] or [`cache::invalidate_indicator_metadata`] to refresh them.
+///
 /// # Examples
 ///
 /// ```rust
@@ -530,6 +665,22 @@ pub async fn get_indicator_metadata(
     client: Option<&UserCookies>,
     pinescript_id: &str,
     pinescript_version: &str,
+) -> Result<PineMetadata> {
+    if let Some(metadata) = cache::get(client, pinescript_id, pinescript_version) {
+        debug!("metadata cache hit for {pinescript_id}@{pinescript_version}");
+        return Ok(metadata);
+    }
+    get_indicator_metadata_uncached(client, pinescript_id, pinescript_version).await
+}
+
+/// Like [`get_indicator_metadata`], but always queries the translate endpoint.
+///
+/// The fresh response still replaces the cached entry.
+#[tracing::instrument(skip(client))]
+pub async fn get_indicator_metadata_uncached(
+    client: Option<&UserCookies>,
+    pinescript_id: &str,
+    pinescript_version: &str,
 ) -> Result<PineMetadata> {
     use urlencoding::encode;
     let url = format!(
@@ -538,9 +689,17 @@ pub async fn get_indicator_metadata(
         encode(pinescript_version)
     );
     debug!("URL: {}", url);
-    let resp: pine_indicator::TranslateResponse = get(client, &url).await?.json().await?;
+    let raw = get(client, &url).await?.text().await?;
+    let resp: pine_indicator::TranslateResponse = serde_json::from_str(&raw)?;
 
     if resp.success {
+        cache::insert(
+            client,
+            pinescript_id,
+            pinescript_version,
+            &resp.result,
+            &raw,
+        );
         return Ok(resp.result);
     }
 
@@ -1,3 +1,21 @@
+pub mod alerts;
+pub mod cache;
+pub mod economic_calendar;
 pub mod fin_calendar;
+pub mod fundamentals;
+pub mod layouts;
 pub mod misc;
 pub mod news;
+pub mod paginator;
+#[cfg(feature = "pine")]
+pub mod pine;
+pub mod rate_limit;
+pub mod ratings;
+pub mod retry;
+#[cfg(feature = "native")]
+pub mod session;
+pub mod session_pool;
+pub mod social;
+pub mod sparks;
+pub mod symbols;
+pub mod watchlists;
@@ -74,6 +74,50 @@ pub async fn list_news(
     Ok(res)
 }
 
+/// Fetch the latest headlines mentioning `symbol` (`EXCHANGE:SYMBOL`), newest first.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::news::get_news;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     for news in get_news().symbol("NASDAQ:AAPL").call().await? {
+///         println!("{} {}", news.published, news.title);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_news(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    category: Option<MarketType>,
+    section: Option<NewsSection>,
+) -> Result<Vec<News>> {
+    let mut queries = vec![
+        ("client", "web"),
+        ("lang", "en"),
+        ("streaming", "false"),
+        ("symbol", symbol),
+    ];
+    if let Some(category) = category {
+        queries.push(("category", get_news_category(category)));
+    }
+    if let Some(section) = section {
+        queries.push(("section", get_news_section(section)));
+    }
+    let res = get(client, &format!("{BASE_NEWS_URL}/headlines"), &queries)
+        .await?
+        .error_for_status()?
+        .json::<NewsHeadlines>()
+        .await?;
+
+    Ok(res.items)
+}
+
 async fn fetch_news(id: &str) -> Result<NewsContent> {
     let res = get(
         None,
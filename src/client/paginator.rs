@@ -0,0 +1,160 @@
+use crate::Result;
+use futures_util::{
+    StreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream},
+};
+
+/// A single page returned by a paginated endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Offset of the next page, `None` once the endpoint is exhausted.
+    pub next: Option<u64>,
+}
+
+type FetchFn<T> = Box<dyn FnMut(u64) -> BoxFuture<'static, Result<Page<T>>> + Send>;
+
+/// Lazily walks an offset-paginated endpoint one page at a time.
+///
+/// Pages are only requested when [`Paginator::next_page`] is called or the
+/// stream returned by [`Paginator::into_stream`] is polled, and fetching stops
+/// once the endpoint runs out of results or the optional item limit is reached.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use tradingview::paginate_symbols;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let mut symbols = paginate_symbols().search("tesla").limit(120).call().into_stream();
+///     while let Some(symbol) = symbols.next().await {
+///         println!("{}", symbol?.symbol);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Paginator<T> {
+    fetch: FetchFn<T>,
+    offset: Option<u64>,
+    fetched: usize,
+    limit: Option<usize>,
+}
+
+impl<T: Send + 'static> Paginator<T> {
+    /// Create a paginator from a fetch function that takes the offset of the
+    /// page to request, starting at `start`.
+    pub fn new<F>(start: u64, fetch: F) -> Self
+    where
+        F: FnMut(u64) -> BoxFuture<'static, Result<Page<T>>> + Send + 'static,
+    {
+        Self {
+            fetch: Box::new(fetch),
+            offset: Some(start),
+            fetched: 0,
+            limit: None,
+        }
+    }
+
+    /// Stop after `limit` items in total have been yielded.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.offset.is_none() || self.limit.is_some_and(|limit| self.fetched >= limit)
+    }
+
+    /// Fetch the next page, returning `None` once there is nothing left.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>> {
+        let Some(offset) = self.offset else {
+            return Ok(None);
+        };
+        if self.is_exhausted() {
+            return Ok(None);
+        }
+
+        let page = (self.fetch)(offset).await?;
+        let mut items = page.items;
+
+        self.offset = if items.is_empty() { None } else { page.next };
+
+        if let Some(limit) = self.limit {
+            items.truncate(limit - self.fetched);
+        }
+        self.fetched += items.len();
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(items))
+    }
+
+    /// Fetch every remaining page and collect the items.
+    pub async fn collect_all(mut self) -> Result<Vec<T>> {
+        let mut all = Vec::new();
+        while let Some(items) = self.next_page().await? {
+            all.extend(items);
+        }
+        Ok(all)
+    }
+
+    /// Turn the paginator into a stream of items, fetching pages on demand.
+    pub fn into_stream(self) -> BoxStream<'static, Result<T>> {
+        stream::unfold(Some(self), |state| async move {
+            let mut paginator = state?;
+            match paginator.next_page().await {
+                Ok(Some(items)) => Some((Ok(items), Some(paginator))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+        .flat_map(|page| match page {
+            Ok(items) => stream::iter(items.into_iter().map(Ok).collect::<Vec<_>>()),
+            Err(e) => stream::iter(vec![Err(e)]),
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    fn numbers(total: u64, page_size: u64) -> Paginator<u64> {
+        Paginator::new(0, move |offset| {
+            async move {
+                let end = (offset + page_size).min(total);
+                Ok(Page {
+                    items: (offset..end).collect(),
+                    next: (end < total).then_some(end),
+                })
+            }
+            .boxed()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_next_page() {
+        let mut pages = numbers(5, 2);
+        assert_eq!(pages.next_page().await.unwrap(), Some(vec![0, 1]));
+        assert_eq!(pages.next_page().await.unwrap(), Some(vec![2, 3]));
+        assert_eq!(pages.next_page().await.unwrap(), Some(vec![4]));
+        assert_eq!(pages.next_page().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_limit_and_stream() {
+        let items: Vec<u64> = numbers(100, 10)
+            .with_limit(25)
+            .into_stream()
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+        assert_eq!(items, (0..25).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,431 @@
+use super::misc::{get_builtin_indicators, paginate_indicators};
+use crate::{
+    Error, Result, UserCookies,
+    chart::StudyOptions,
+    pine_indicator::{
+        BuiltinIndicators, PineIndicator, PineInfo, PineSearchResult, PineSource, ScriptType,
+    },
+    utils::{get, post_form},
+};
+use bon::Builder;
+use futures_util::{StreamExt, future::try_join_all, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ustr::Ustr;
+
+static PINE_INFO_URL: &str = "https://pine-facade.tradingview.com/pine-facade/get_script_info/";
+static PINE_GET_URL: &str = "https://pine-facade.tradingview.com/pine-facade/get";
+static PINE_SAVE_URL: &str = "https://pine-facade.tradingview.com/pine-facade/save";
+/// `PineSearchResult::access` of scripts whose source is public.
+const OPEN_SOURCE_ACCESS: i64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PineScriptKind {
+    Study,
+    Strategy,
+}
+
+impl PineScriptKind {
+    fn script_type(self) -> ScriptType {
+        match self {
+            PineScriptKind::Study => ScriptType::Script,
+            PineScriptKind::Strategy => ScriptType::StrategyScript,
+        }
+    }
+}
+
+/// Client-side filters applied to Pine script search results.
+#[derive(Debug, Clone, Default, PartialEq, Builder)]
+pub struct PineSearchFilters {
+    pub kind: Option<PineScriptKind>,
+    /// Skip protected and invite-only scripts.
+    #[builder(default)]
+    pub open_source_only: bool,
+    /// Only keep scripts published by this username, compared case-insensitively.
+    #[builder(into)]
+    pub author: Option<Ustr>,
+    /// Stop after this many matching scripts.
+    pub limit: Option<usize>,
+}
+
+impl PineSearchFilters {
+    pub fn matches(&self, result: &PineSearchResult) -> bool {
+        self.kind
+            .is_none_or(|kind| result.extra.kind == Some(kind.script_type()))
+            && (!self.open_source_only || result.access == OPEN_SOURCE_ACCESS)
+            && self.author.is_none_or(|author| {
+                result
+                    .author
+                    .username
+                    .as_str()
+                    .eq_ignore_ascii_case(author.as_str())
+            })
+    }
+}
+
+/// Stream public Pine script search results matching `filters`, paging with
+/// [`paginate_indicators`] until the search or the filter limit is exhausted.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use tradingview::client::pine::{PineScriptKind, PineSearchFilters, search_indicators};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let filters = PineSearchFilters::builder()
+///         .kind(PineScriptKind::Strategy)
+///         .open_source_only(true)
+///         .limit(20)
+///         .build();
+///     let mut scripts = search_indicators(None, "rsi", filters);
+///     while let Some(script) = scripts.next().await {
+///         println!("{}", script?.script_name);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn search_indicators(
+    client: Option<&UserCookies>,
+    query: &str,
+    filters: PineSearchFilters,
+) -> BoxStream<'static, Result<PineSearchResult>> {
+    let limit = filters.limit.unwrap_or(usize::MAX);
+
+    paginate_indicators(client, query)
+        .into_stream()
+        .filter(move |result| {
+            let keep = result
+                .as_ref()
+                .map_or(true, |script| filters.matches(script));
+            async move { keep }
+        })
+        .take(limit)
+        .boxed()
+}
+
+/// A built-in indicator from the catalog, ready to be fetched or attached to a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinIndicator {
+    pub name: String,
+    pub short_description: String,
+    pub script_id: Ustr,
+    pub script_version: Ustr,
+    pub script_type: ScriptType,
+    /// Catalog the indicator was listed in, never [`BuiltinIndicators::All`].
+    pub category: BuiltinIndicators,
+}
+
+impl BuiltinIndicator {
+    fn new(info: PineInfo, category: BuiltinIndicators) -> Self {
+        Self {
+            name: info.script_name,
+            short_description: info.extra.short_description,
+            script_id: Ustr::from(&info.script_id),
+            script_version: Ustr::from(&info.script_version),
+            script_type: info.extra.kind,
+            category,
+        }
+    }
+
+    /// Fetch the indicator metadata, same as `PineIndicator::build().fetch(...)`.
+    pub async fn fetch(&self) -> Result<PineIndicator> {
+        PineIndicator::build()
+            .fetch(&self.script_id, &self.script_version, self.script_type)
+            .await
+    }
+
+    /// Study config for [`ChartOptions`](crate::ChartOptions), same as `ChartOptions::study_config`.
+    pub fn study_options(&self) -> StudyOptions {
+        StudyOptions {
+            script_id: self.script_id,
+            script_version: self.script_version,
+            script_type: self.script_type,
+        }
+    }
+}
+
+/// List the built-in indicators of `category`, [`BuiltinIndicators::All`]
+/// fetches every catalog concurrently.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{client::pine::builtin_indicators, pine_indicator::BuiltinIndicators};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let indicators = builtin_indicators(BuiltinIndicators::Standard).await?;
+///     let rsi = indicators.iter().find(|i| i.name == "Relative Strength Index").unwrap();
+///     let indicator = rsi.fetch().await?;
+///     println!("{}", indicator.script_id);
+///     Ok(())
+/// }
+/// ```
+#[tracing::instrument]
+pub async fn builtin_indicators(category: BuiltinIndicators) -> Result<Vec<BuiltinIndicator>> {
+    let categories = match category {
+        BuiltinIndicators::All => vec![
+            BuiltinIndicators::Fundamental,
+            BuiltinIndicators::Standard,
+            BuiltinIndicators::Candlestick,
+        ],
+        category => vec![category],
+    };
+    let lists = try_join_all(categories.into_iter().map(|category| async move {
+        let infos = get_builtin_indicators(category.clone()).await?;
+        Ok::<_, Error>(
+            infos
+                .into_iter()
+                .map(|info| BuiltinIndicator::new(info, category.clone()))
+                .collect::<Vec<_>>(),
+        )
+    }))
+    .await?;
+    Ok(lists.into_iter().flatten().collect())
+}
+
+/// Fetch the info of the script `script_id`, e.g. `PUB;abc123` or `STD;RSI`.
+///
+/// Invite-only and private scripts need the cookies of a user with access.
+#[tracing::instrument(skip(client))]
+pub async fn get_script_info(client: Option<&UserCookies>, script_id: &str) -> Result<PineInfo> {
+    let info = get(client, PINE_INFO_URL, &[("pine_id", script_id)])
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(info)
+}
+
+/// Fetch the Pine source of `version` of the script `script_id`, the latest
+/// version if `None`.
+///
+/// TradingView only hands out the source of open source scripts, and of
+/// protected, invite-only and private ones to users allowed to read it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::pine::get_script_source;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let script = get_script_source(None, "STD;RSI", None).await?;
+///     println!("{} v{}\n{}", script.script_name, script.version, script.source);
+///     Ok(())
+/// }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn get_script_source(
+    client: Option<&UserCookies>,
+    script_id: &str,
+    version: Option<&str>,
+) -> Result<PineSource> {
+    let url = format!(
+        "{PINE_GET_URL}/{}/{}",
+        urlencoding::encode(script_id),
+        urlencoding::encode(version.unwrap_or("last"))
+    );
+    let mut script: PineSource = get(client, &url, &[])
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if script.source.is_empty() {
+        return Err(Error::Internal(Ustr::from(&format!(
+            "No access to the source of Pine script {script_id}"
+        ))));
+    }
+    script.script_id = Ustr::from(script_id);
+    Ok(script)
+}
+
+/// A private script as saved by [`publish_script`] or [`update_script`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedScript {
+    pub name: String,
+    pub script_id: Ustr,
+    /// Version created by the save, every save adds one.
+    pub script_version: Ustr,
+    pub script_type: ScriptType,
+}
+
+impl SavedScript {
+    /// Fetch the compiled metadata, same as `PineIndicator::build().user(..).fetch(...)`.
+    pub async fn fetch(&self, client: &UserCookies) -> Result<PineIndicator> {
+        PineIndicator::build()
+            .user(client.clone())
+            .bypass_cache()
+            .fetch(&self.script_id, &self.script_version, self.script_type)
+            .await
+    }
+
+    /// Study config for [`ChartOptions`](crate::ChartOptions), same as `ChartOptions::study_config`.
+    pub fn study_options(&self) -> StudyOptions {
+        StudyOptions {
+            script_id: self.script_id,
+            script_version: self.script_version,
+            script_type: self.script_type,
+        }
+    }
+}
+
+/// Compile `source` and save it as a new private script called `name`.
+///
+/// Fails with the compiler messages if the source does not compile.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{UserCookies, client::pine::{publish_script, update_script}};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// let source = "//@version=5\nindicator(\"Generated\")\nplot(close)";
+/// let script = publish_script(user, "Generated", source).await?;
+/// let indicator = script.fetch(user).await?;
+///
+/// // Saving again bumps the version of the same script
+/// let script = update_script(user, &script.script_id, "Generated", source).await?;
+/// println!("{} v{}", script.script_id, script.script_version);
+/// # Ok(())
+/// # }
+/// ```
+#[tracing::instrument(skip(client, source))]
+pub async fn publish_script(client: &UserCookies, name: &str, source: &str) -> Result<SavedScript> {
+    let url = format!(
+        "{PINE_SAVE_URL}/new/?name={}&allow_overwrite=false",
+        urlencoding::encode(name)
+    );
+    save_script(client, &url, source).await
+}
+
+/// Save `source` as the next version of the private script `script_id`,
+/// renaming it to `name`.
+#[tracing::instrument(skip(client, source))]
+pub async fn update_script(
+    client: &UserCookies,
+    script_id: &str,
+    name: &str,
+    source: &str,
+) -> Result<SavedScript> {
+    let url = format!(
+        "{PINE_SAVE_URL}/next/{}?allow_create_new=false&name={}",
+        urlencoding::encode(script_id),
+        urlencoding::encode(name)
+    );
+    save_script(client, &url, source).await
+}
+
+async fn save_script(client: &UserCookies, url: &str, source: &str) -> Result<SavedScript> {
+    let response: Value = post_form(Some(client), url, &[("source", source)])
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    parse_saved_script(&response)
+}
+
+fn parse_saved_script(response: &Value) -> Result<SavedScript> {
+    let result = &response["result"];
+    let errors: Vec<String> = result["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|e| {
+            let line = e["start"]["line"].as_u64().unwrap_or_default();
+            let message = e["message"].as_str().unwrap_or("unknown error");
+            format!("line {line}: {message}")
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(Error::InvalidStudyInput(Ustr::from(&errors.join("; "))));
+    }
+    let (Some(true), Some(script_id), Some(version)) = (
+        response["success"].as_bool(),
+        result["scriptIdPart"].as_str(),
+        result["version"].as_str(),
+    ) else {
+        let reason = response["reason"].as_str().unwrap_or("unexpected response");
+        return Err(Error::Internal(Ustr::from(&format!(
+            "Failed to save Pine script: {reason}"
+        ))));
+    };
+    let script_type = if result["metaInfo"]["isTVScriptStrategy"].as_bool() == Some(true) {
+        ScriptType::StrategyScript
+    } else {
+        ScriptType::Script
+    };
+    Ok(SavedScript {
+        name: result["scriptName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        script_id: Ustr::from(script_id),
+        script_version: Ustr::from(version),
+        script_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pine_indicator::{PineSearchAuthor, PineSearchExtra};
+
+    fn script(kind: &str, access: i64, author: &str) -> PineSearchResult {
+        PineSearchResult {
+            access,
+            extra: PineSearchExtra {
+                kind: Some(ScriptType::from(kind)),
+                ..Default::default()
+            },
+            author: PineSearchAuthor {
+                username: Ustr::from(author),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_saved_script() {
+        let response = serde_json::json!({
+            "success": true,
+            "result": {
+                "scriptName": "Generated",
+                "scriptIdPart": "USER;abc123",
+                "version": "2.0",
+                "metaInfo": { "isTVScriptStrategy": true },
+            },
+        });
+        let script = parse_saved_script(&response).unwrap();
+        assert_eq!(script.script_id, "USER;abc123");
+        assert_eq!(script.script_version, "2.0");
+        assert_eq!(script.script_type, ScriptType::StrategyScript);
+
+        let response = serde_json::json!({
+            "success": true,
+            "result": { "errors": [{ "start": { "line": 3 }, "message": "Undeclared identifier 'clsoe'" }] },
+        });
+        let Err(Error::InvalidStudyInput(message)) = parse_saved_script(&response) else {
+            panic!("expected compile errors");
+        };
+        assert_eq!(message, "line 3: Undeclared identifier 'clsoe'");
+    }
+
+    #[test]
+    fn test_filters_match() {
+        let filters = PineSearchFilters::builder()
+            .kind(PineScriptKind::Strategy)
+            .open_source_only(true)
+            .author("LuxAlgo")
+            .build();
+
+        assert!(filters.matches(&script("strategy", 1, "luxalgo")));
+        assert!(!filters.matches(&script("study", 1, "LuxAlgo")));
+        assert!(!filters.matches(&script("strategy", 3, "LuxAlgo")));
+        assert!(!filters.matches(&script("strategy", 1, "someone")));
+        assert!(PineSearchFilters::default().matches(&script("study", 3, "someone")));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::config::RateLimitConfig;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+lazy_static::lazy_static! {
+    static ref LIMITER: RwLock<Arc<RateLimiter>> =
+        RwLock::new(Arc::new(RateLimiter::new(&RateLimitConfig::default())));
+}
+
+/// Apply `config` to every REST request made by this crate.
+///
+/// Requests already waiting keep the limits they were queued with.
+pub fn set_rate_limit(config: &RateLimitConfig) {
+    *LIMITER.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(RateLimiter::new(config));
+}
+
+/// Current limiter state, e.g. to lower concurrency while requests queue up.
+pub fn rate_limit_state() -> RateLimitState {
+    limiter().state()
+}
+
+/// Snapshot of the REST rate limiter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitState {
+    /// `None` when the global bucket is disabled.
+    pub global: Option<BucketState>,
+    pub endpoints: HashMap<String, BucketState>,
+    /// Requests currently holding a concurrency slot.
+    pub in_flight: usize,
+    /// Requests waiting for a token or a concurrency slot.
+    pub queued: usize,
+    pub max_concurrent_requests: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketState {
+    pub requests_per_second: f64,
+    pub burst: u32,
+    /// Tokens left, negative while requests are queued for future tokens.
+    pub available: f64,
+}
+
+/// Wait until `url` may be requested and hold a concurrency slot until the
+/// returned permit is dropped.
+pub(crate) async fn throttle(url: &str) -> OwnedSemaphorePermit {
+    let limiter = limiter();
+    limiter.queued.fetch_add(1, Ordering::Relaxed);
+    let wait = limiter.reserve(host(url), Instant::now());
+    if !wait.is_zero() {
+        debug!("rate limited, delaying request to {url} by {wait:?}");
+        tokio::time::sleep(wait).await;
+    }
+    let permit = Arc::clone(&limiter.concurrency)
+        .acquire_owned()
+        .await
+        .expect("rate limiter semaphore is never closed");
+    limiter.queued.fetch_sub(1, Ordering::Relaxed);
+    permit
+}
+
+fn limiter() -> Arc<RateLimiter> {
+    Arc::clone(&LIMITER.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+struct RateLimiter {
+    global: Option<Mutex<TokenBucket>>,
+    endpoints: HashMap<String, Mutex<TokenBucket>>,
+    concurrency: Arc<Semaphore>,
+    max_concurrent_requests: usize,
+    queued: AtomicUsize,
+}
+
+impl RateLimiter {
+    fn new(config: &RateLimitConfig) -> Self {
+        let max_concurrent_requests = match config.max_concurrent_requests {
+            0 => Semaphore::MAX_PERMITS,
+            max => max,
+        };
+        Self {
+            global: TokenBucket::new(config.requests_per_second, config.burst).map(Mutex::new),
+            endpoints: config
+                .endpoints
+                .iter()
+                .filter_map(|(host, limit)| {
+                    let bucket = TokenBucket::new(limit.requests_per_second, limit.burst)?;
+                    Some((host.clone(), Mutex::new(bucket)))
+                })
+                .collect(),
+            concurrency: Arc::new(Semaphore::new(max_concurrent_requests)),
+            max_concurrent_requests,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Take a token from the global and the endpoint bucket, returning how
+    /// long to wait until both tokens are due.
+    fn reserve(&self, host: &str, now: Instant) -> Duration {
+        let reserve = |bucket: &Mutex<TokenBucket>| {
+            bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .reserve(now)
+        };
+        let global = self.global.as_ref().map_or(Duration::ZERO, reserve);
+        let endpoint = self.endpoints.get(host).map_or(Duration::ZERO, reserve);
+        global.max(endpoint)
+    }
+
+    fn state(&self) -> RateLimitState {
+        let now = Instant::now();
+        let state = |bucket: &Mutex<TokenBucket>| {
+            bucket.lock().unwrap_or_else(|e| e.into_inner()).state(now)
+        };
+        RateLimitState {
+            global: self.global.as_ref().map(state),
+            endpoints: self
+                .endpoints
+                .iter()
+                .map(|(host, bucket)| (host.clone(), state(bucket)))
+                .collect(),
+            in_flight: self.max_concurrent_requests - self.concurrency.available_permits(),
+            queued: self.queued.load(Ordering::Relaxed),
+            max_concurrent_requests: self.max_concurrent_requests,
+        }
+    }
+}
+
+struct TokenBucket {
+    rate: f64,
+    burst: u32,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: u32) -> Option<Self> {
+        (rate > 0.0).then(|| Self {
+            rate,
+            burst: burst.max(1),
+            tokens: burst.max(1) as f64,
+            updated: Instant::now(),
+        })
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst as f64);
+        self.updated = self.updated.max(now);
+    }
+
+    /// Take a token, possibly one that is only due in the future.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    fn state(&mut self, now: Instant) -> BucketState {
+        self.refill(now);
+        BucketState {
+            requests_per_second: self.rate,
+            burst: self.burst,
+            available: self.tokens,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EndpointRateLimit;
+
+    #[test]
+    fn test_reserve_queues_past_burst() {
+        let config = RateLimitConfig {
+            requests_per_second: 10.0,
+            burst: 2,
+            endpoints: HashMap::from([(
+                "scanner.tradingview.com".to_string(),
+                EndpointRateLimit {
+                    requests_per_second: 1.0,
+                    burst: 1,
+                },
+            )]),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(&config);
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve("www.tradingview.com", now), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("scanner.tradingview.com", now),
+            Duration::ZERO
+        );
+        let wait = limiter.reserve("scanner.tradingview.com", now);
+        assert!((wait.as_secs_f64() - 1.0).abs() < 1e-6);
+        assert!((limiter.reserve("www.tradingview.com", now).as_secs_f64() - 0.2).abs() < 1e-6);
+
+        let state = limiter.state();
+        assert!(state.global.unwrap().available < 0.0);
+        assert_eq!(state.in_flight, 0);
+        assert_eq!(
+            host("https://scanner.tradingview.com/america/scan?x=1"),
+            "scanner.tradingview.com"
+        );
+    }
+}
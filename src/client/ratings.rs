@@ -0,0 +1,268 @@
+use crate::{
+    Error, Interval, Result, TechnicalRating, UserCookies, Vote, utils::get_symbol_fields,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use ustr::Ustr;
+
+/// Moving averages voting buy when below the close.
+const MOVING_AVERAGES: [&str; 12] = [
+    "EMA10", "SMA10", "EMA20", "SMA20", "EMA30", "SMA30", "EMA50", "SMA50", "EMA100", "SMA100",
+    "EMA200", "SMA200",
+];
+/// Votes the scanner computes itself, keyed by the name they are reported under.
+const OSCILLATOR_RECS: [(&str, &str); 4] = [
+    ("Stoch.RSI", "Rec.Stoch.RSI"),
+    ("W%R", "Rec.WR"),
+    ("BBP", "Rec.BBPower"),
+    ("UO", "Rec.UO"),
+];
+const MOVING_AVERAGE_RECS: [(&str, &str); 3] = [
+    ("Ichimoku", "Rec.Ichimoku"),
+    ("VWMA", "Rec.VWMA"),
+    ("HullMA9", "Rec.HullMA9"),
+];
+/// Raw indicator values the remaining oscillator votes are derived from.
+const OSCILLATOR_FIELDS: [&str; 20] = [
+    "RSI",
+    "RSI[1]",
+    "Stoch.K",
+    "Stoch.D",
+    "Stoch.K[1]",
+    "Stoch.D[1]",
+    "CCI20",
+    "CCI20[1]",
+    "ADX",
+    "ADX+DI",
+    "ADX-DI",
+    "ADX+DI[1]",
+    "ADX-DI[1]",
+    "AO",
+    "AO[1]",
+    "AO[2]",
+    "Mom",
+    "Mom[1]",
+    "MACD.macd",
+    "MACD.signal",
+];
+
+/// Fetch the technical ratings of `symbol` (`EXCHANGE:SYMBOL`) on `interval`
+/// from the scanner, with every oscillator and moving average vote.
+///
+/// Ratings exist for intervals from one minute to one month, except 45
+/// minutes and the quarterly and longer ones.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{Interval, client::ratings::get_technical_rating};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let rating = get_technical_rating(None, "NASDAQ:AAPL", Interval::OneHour).await?;
+///     println!("{:?} ({:.2})", rating.recommendation(), rating.all);
+///     Ok(())
+/// }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn get_technical_rating(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    interval: Interval,
+) -> Result<TechnicalRating> {
+    let suffix = interval_suffix(interval).ok_or_else(|| {
+        Error::Internal(Ustr::from(&format!(
+            "No technical ratings for the {interval} interval"
+        )))
+    })?;
+    let fields: Vec<String> = ["Recommend.All", "Recommend.MA", "Recommend.Other", "close"]
+        .into_iter()
+        .chain(MOVING_AVERAGES)
+        .chain(OSCILLATOR_RECS.map(|(_, field)| field))
+        .chain(MOVING_AVERAGE_RECS.map(|(_, field)| field))
+        .chain(OSCILLATOR_FIELDS)
+        .map(|field| format!("{field}{suffix}"))
+        .collect();
+    let fields = fields.join(",");
+
+    let data: Value = get_symbol_fields(client, symbol, fields.as_str()).await?;
+    let values = data.as_object().filter(|values| !values.is_empty());
+    parse_rating(
+        symbol,
+        interval,
+        suffix,
+        values.ok_or(Error::NoScanDataFound)?,
+    )
+}
+
+/// Scanner column suffix of `interval`, daily columns have none.
+fn interval_suffix(interval: Interval) -> Option<&'static str> {
+    let suffix = match interval {
+        Interval::OneMinute => "|1",
+        Interval::FiveMinutes => "|5",
+        Interval::FifteenMinutes => "|15",
+        Interval::ThirtyMinutes => "|30",
+        Interval::OneHour => "|60",
+        Interval::TwoHours => "|120",
+        Interval::FourHours => "|240",
+        Interval::OneDay => "",
+        Interval::OneWeek => "|1W",
+        Interval::OneMonth => "|1M",
+        _ => return None,
+    };
+    Some(suffix)
+}
+
+fn parse_rating(
+    symbol: &str,
+    interval: Interval,
+    suffix: &str,
+    values: &Map<String, Value>,
+) -> Result<TechnicalRating> {
+    let value = |field: &str| values.get(&format!("{field}{suffix}"))?.as_f64();
+    let (Some(all), Some(moving_averages), Some(oscillators)) = (
+        value("Recommend.All"),
+        value("Recommend.MA"),
+        value("Recommend.Other"),
+    ) else {
+        return Err(Error::NoScanDataFound);
+    };
+
+    let mut moving_average_votes = BTreeMap::new();
+    if let Some(close) = value("close") {
+        for ma in MOVING_AVERAGES {
+            if let Some(average) = value(ma) {
+                let vote = Vote::from_value(close - average);
+                moving_average_votes.insert(Ustr::from(ma), vote);
+            }
+        }
+    }
+    for (name, field) in MOVING_AVERAGE_RECS {
+        if let Some(rec) = value(field) {
+            moving_average_votes.insert(Ustr::from(name), Vote::from_value(rec));
+        }
+    }
+
+    let mut oscillator_votes = BTreeMap::new();
+    for name in ["RSI", "Stoch.K", "CCI20", "ADX", "AO", "Mom", "MACD"] {
+        if let Some(vote) = oscillator_vote(name, &value) {
+            oscillator_votes.insert(Ustr::from(name), vote);
+        }
+    }
+    for (name, field) in OSCILLATOR_RECS {
+        if let Some(rec) = value(field) {
+            oscillator_votes.insert(Ustr::from(name), Vote::from_value(rec));
+        }
+    }
+
+    Ok(TechnicalRating {
+        symbol: Ustr::from(symbol),
+        interval,
+        all,
+        moving_averages,
+        oscillators,
+        oscillator_votes,
+        moving_average_votes,
+    })
+}
+
+/// Vote of the oscillator `name` by the rules TradingView documents for its
+/// technical ratings, `None` if a value is missing.
+fn oscillator_vote(name: &str, value: &dyn Fn(&str) -> Option<f64>) -> Option<Vote> {
+    let (buy, sell) = match name {
+        "RSI" => {
+            let (rsi, prev) = (value("RSI")?, value("RSI[1]")?);
+            (rsi < 30.0 && prev < rsi, rsi > 70.0 && prev > rsi)
+        }
+        "Stoch.K" => {
+            let (k, d) = (value("Stoch.K")?, value("Stoch.D")?);
+            let (k1, d1) = (value("Stoch.K[1]")?, value("Stoch.D[1]")?);
+            (
+                k < 20.0 && d < 20.0 && k > d && k1 < d1,
+                k > 80.0 && d > 80.0 && k < d && k1 > d1,
+            )
+        }
+        "CCI20" => {
+            let (cci, prev) = (value("CCI20")?, value("CCI20[1]")?);
+            (cci < -100.0 && cci > prev, cci > 100.0 && cci < prev)
+        }
+        "ADX" => {
+            let (adx, plus, minus) = (value("ADX")?, value("ADX+DI")?, value("ADX-DI")?);
+            let (plus1, minus1) = (value("ADX+DI[1]")?, value("ADX-DI[1]")?);
+            (
+                adx > 20.0 && plus1 < minus1 && plus > minus,
+                adx > 20.0 && plus1 > minus1 && plus < minus,
+            )
+        }
+        "AO" => {
+            let (ao, ao1, ao2) = (value("AO")?, value("AO[1]")?, value("AO[2]")?);
+            (
+                (ao > 0.0 && ao1 < 0.0) || (ao > 0.0 && ao1 > 0.0 && ao > ao1 && ao2 > ao1),
+                (ao < 0.0 && ao1 > 0.0) || (ao < 0.0 && ao1 < 0.0 && ao < ao1 && ao2 < ao1),
+            )
+        }
+        "Mom" => {
+            let (mom, prev) = (value("Mom")?, value("Mom[1]")?);
+            (mom > prev, mom < prev)
+        }
+        "MACD" => {
+            let (macd, signal) = (value("MACD.macd")?, value("MACD.signal")?);
+            (macd > signal, macd < signal)
+        }
+        _ => return None,
+    };
+    Some(match (buy, sell) {
+        (true, _) => Vote::Buy,
+        (_, true) => Vote::Sell,
+        _ => Vote::Neutral,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Recommendation;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_rating() {
+        let values = json!({
+            "Recommend.All|60": 0.6,
+            "Recommend.MA|60": 0.8,
+            "Recommend.Other|60": 0.0,
+            "close|60": 100.0,
+            "EMA20|60": 95.0,
+            "SMA200|60": 110.0,
+            "RSI|60": 25.0,
+            "RSI[1]|60": 20.0,
+            "MACD.macd|60": -1.0,
+            "MACD.signal|60": 0.5,
+            "Rec.WR|60": 1,
+            "Rec.Ichimoku|60": 0,
+        });
+        let rating = parse_rating(
+            "NASDAQ:AAPL",
+            Interval::OneHour,
+            "|60",
+            values.as_object().unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(rating.recommendation(), Recommendation::StrongBuy);
+        assert_eq!(rating.oscillator_recommendation(), Recommendation::Neutral);
+        let ma = |name: &str| rating.moving_average_votes[&Ustr::from(name)];
+        assert_eq!(ma("EMA20"), Vote::Buy);
+        assert_eq!(ma("SMA200"), Vote::Sell);
+        assert_eq!(ma("Ichimoku"), Vote::Neutral);
+        let osc = |name: &str| rating.oscillator_votes[&Ustr::from(name)];
+        assert_eq!(osc("RSI"), Vote::Buy);
+        assert_eq!(osc("MACD"), Vote::Sell);
+        assert_eq!(osc("W%R"), Vote::Buy);
+        // Indicators without values are left out
+        assert!(!rating.oscillator_votes.contains_key(&Ustr::from("ADX")));
+
+        let empty = Map::new();
+        assert!(parse_rating("X:Y", Interval::OneDay, "", &empty).is_err());
+        assert_eq!(interval_suffix(Interval::FortyFiveMinutes), None);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{Error, Result};
+use bon::Builder;
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tracing::warn;
+
+lazy_static::lazy_static! {
+    static ref POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::default());
+}
+
+/// Retry every REST request made by this crate according to `policy`.
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// The policy installed with [`set_retry_policy`].
+pub fn retry_policy() -> RetryPolicy {
+    POLICY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub type RetryPredicate = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// Exponential backoff with jitter for transient REST failures.
+///
+/// Rate limited requests are always safe to repeat. Other failures are only
+/// retried for idempotent requests (`GET`, `PUT`, `DELETE`), so a `POST`
+/// that may already have been applied is never sent twice.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tradingview::client::retry::{RetryPolicy, set_retry_policy};
+///
+/// set_retry_policy(
+///     RetryPolicy::builder()
+///         .max_attempts(5)
+///         .initial_delay(Duration::from_millis(250))
+///         .build(),
+/// );
+/// ```
+#[derive(Clone, Builder)]
+pub struct RetryPolicy {
+    /// Attempts including the first one, `1` disables retries.
+    #[builder(default = 3)]
+    pub max_attempts: u32,
+    #[builder(default = Duration::from_millis(500))]
+    pub initial_delay: Duration,
+    #[builder(default = Duration::from_secs(30))]
+    pub max_delay: Duration,
+    #[builder(default = 2.0)]
+    pub multiplier: f64,
+    /// Random spread applied to each delay, `0.1` for ±10%.
+    #[builder(default = 0.1)]
+    pub jitter_percent: f64,
+    /// Which errors are worth retrying, defaults to [`Error::is_transient`].
+    #[builder(default = Arc::new(Error::is_transient) as RetryPredicate)]
+    pub retry_on: RetryPredicate,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("jitter_percent", &self.jitter_percent)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::builder().max_attempts(1).build()
+    }
+
+    pub fn should_retry(&self, error: &Error, idempotent: bool) -> bool {
+        (idempotent || error.is_rate_limited()) && (self.retry_on)(error)
+    }
+
+    /// Delay before retry number `retry`, starting at 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.initial_delay.as_secs_f64() * self.multiplier.powi(retry as i32 - 1);
+        let base = base.min(self.max_delay.as_secs_f64());
+        let jitter = (rand::random::<f64>() - 0.5) * 2.0 * base * self.jitter_percent;
+        Duration::from_secs_f64((base + jitter).max(0.0))
+    }
+}
+
+/// Run `f` until it succeeds, fails with a non-retryable error or the
+/// installed policy runs out of attempts.
+pub(crate) async fn with_retry<T, F, Fut>(url: &str, idempotent: bool, f: F) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let policy = retry_policy();
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Err(e) if attempt < policy.max_attempts && policy.should_retry(&e, idempotent) => {
+                let delay = policy.delay(attempt);
+                warn!(
+                    "request to {url} failed ({e}), retry {attempt}/{} in {delay:?}",
+                    policy.max_attempts - 1
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use ustr::Ustr;
+
+    #[test]
+    fn test_delay_and_predicate() {
+        let policy = RetryPolicy::builder()
+            .initial_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(3))
+            .jitter_percent(0.0)
+            .build();
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(3));
+
+        let server_error = Error::HttpStatus(503, Ustr::from("scanner"));
+        assert!(policy.should_retry(&server_error, true));
+        assert!(!policy.should_retry(&server_error, false));
+        assert!(policy.should_retry(&Error::RateLimited(Ustr::from("scanner")), false));
+        assert!(!policy.should_retry(&Error::HttpStatus(404, Ustr::from("scanner")), true));
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_gives_up_after_max_attempts() {
+        set_retry_policy(
+            RetryPolicy::builder()
+                .initial_delay(Duration::from_millis(1))
+                .build(),
+        );
+        let calls = AtomicU32::new(0);
+        let result: Result<()> = with_retry("https://example.com", true, || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(Error::HttpStatus(502, Ustr::from("example.com")))
+        })
+        .await;
+        set_retry_policy(RetryPolicy::default());
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    Result, UserCookies,
+    live::handler::{
+        message::SessionRefreshedMsg,
+        types::{TradingViewHandler, dispatch},
+    },
+    websocket::WebSocketClient,
+};
+use bon::bon;
+use futures_util::future::BoxFuture;
+use std::{future::Future, sync::Arc};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+use ustr::Ustr;
+
+/// Supplies fresh cookies once the current session has expired.
+///
+/// Implemented for closures returning a future, and by [`LoginProvider`]
+/// with the `user` feature.
+pub trait SessionProvider: Send + Sync {
+    fn refresh(&self, expired: &UserCookies) -> BoxFuture<'static, Result<UserCookies>>;
+}
+
+impl<F, Fut> SessionProvider for F
+where
+    F: Fn(UserCookies) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<UserCookies>> + Send + 'static,
+{
+    fn refresh(&self, expired: &UserCookies) -> BoxFuture<'static, Result<UserCookies>> {
+        Box::pin(self(expired.clone()))
+    }
+}
+
+/// Logs in again with stored credentials, optionally saving the new session
+/// with [`UserCookies::save_to_file`].
+#[cfg(feature = "user")]
+#[derive(Debug, Clone, bon::Builder)]
+pub struct LoginProvider {
+    #[builder(into)]
+    pub username: String,
+    #[builder(into)]
+    pub password: String,
+    #[builder(into)]
+    pub totp_secret: Option<String>,
+    #[builder(into)]
+    pub save_to: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "user")]
+impl SessionProvider for LoginProvider {
+    fn refresh(&self, _expired: &UserCookies) -> BoxFuture<'static, Result<UserCookies>> {
+        let provider = self.clone();
+        Box::pin(async move {
+            let cookies = UserCookies::new()
+                .login(
+                    &provider.username,
+                    &provider.password,
+                    provider.totp_secret.as_deref(),
+                )
+                .await?;
+            if let Some(path) = &provider.save_to {
+                cookies.save_to_file(path)?;
+            }
+            Ok(cookies)
+        })
+    }
+}
+
+/// Keeps authenticated calls working across session expiry.
+///
+/// When a call fails with an expired session (see [`crate::Error::is_session_expired`]),
+/// the guard asks its [`SessionProvider`] for new cookies, updates the attached
+/// websocket's auth token, emits `on_session_refreshed` and retries the call.
+/// Concurrent failures share a single refresh.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{
+///     UserCookies,
+///     client::{layouts::list_layouts, session::{LoginProvider, SessionGuard}},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let provider = LoginProvider::builder()
+///         .username("user")
+///         .password("password")
+///         .save_to("cookies.json")
+///         .build();
+///     let guard = SessionGuard::builder()
+///         .cookies(UserCookies::load_from_file("cookies.json")?)
+///         .provider(provider)
+///         .build();
+///
+///     let layouts = guard
+///         .call(|cookies| async move { list_layouts(&cookies).await })
+///         .await?;
+///     println!("{} layouts", layouts.len());
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SessionGuard {
+    cookies: Arc<RwLock<UserCookies>>,
+    provider: Arc<dyn SessionProvider>,
+    refreshing: Arc<Mutex<()>>,
+    websocket: Option<Arc<WebSocketClient>>,
+    handler: Option<TradingViewHandler>,
+    max_retries: u32,
+}
+
+#[bon]
+impl SessionGuard {
+    #[builder]
+    pub fn new(
+        cookies: UserCookies,
+        provider: impl SessionProvider + 'static,
+        /// Receives the new auth token and emits `on_session_refreshed` on its handler.
+        websocket: Option<Arc<WebSocketClient>>,
+        /// Notified through `on_session_refreshed` when no websocket is attached.
+        handler: Option<TradingViewHandler>,
+        /// Refreshes attempted per call before the expired-session error is returned.
+        #[builder(default = 1)]
+        max_retries: u32,
+    ) -> Self {
+        Self {
+            cookies: Arc::new(RwLock::new(cookies)),
+            provider: Arc::new(provider),
+            refreshing: Arc::new(Mutex::new(())),
+            websocket,
+            handler,
+            max_retries,
+        }
+    }
+}
+
+impl SessionGuard {
+    /// The current session cookies.
+    pub async fn cookies(&self) -> UserCookies {
+        self.cookies.read().await.clone()
+    }
+
+    /// Run `f` with the current cookies, refreshing the session and retrying
+    /// if it fails with an expired session.
+    pub async fn call<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn(UserCookies) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            let cookies = self.cookies().await;
+            match f(cookies.clone()).await {
+                Err(e) if e.is_session_expired() && retries < self.max_retries => {
+                    retries += 1;
+                    warn!("session of {} expired, refreshing", cookies.username);
+                    self.refresh(&cookies).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Replace `expired` with fresh cookies from the provider.
+    ///
+    /// Does nothing if another task already refreshed the session since
+    /// `expired` was read.
+    pub async fn refresh(&self, expired: &UserCookies) -> Result<()> {
+        let _refreshing = self.refreshing.lock().await;
+        if self.cookies.read().await.session != expired.session {
+            return Ok(());
+        }
+
+        let cookies = self.provider.refresh(expired).await?;
+        *self.cookies.write().await = cookies.clone();
+        info!("session of {} refreshed", cookies.username);
+
+        match (&self.websocket, &self.handler) {
+            (Some(websocket), _) => websocket.refresh_session(&cookies).await?,
+            (None, Some(handler)) => {
+                let msg = SessionRefreshedMsg {
+                    user_id: cookies.id,
+                    username: Ustr::from(&cookies.username),
+                };
+                dispatch(&handler.on_session_refreshed, msg).await;
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, error::TradingViewError};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_call_refreshes_and_retries() {
+        let refreshes = Arc::new(AtomicU32::new(0));
+        let provider = {
+            let refreshes = Arc::clone(&refreshes);
+            move |_: UserCookies| {
+                refreshes.fetch_add(1, Ordering::Relaxed);
+                async {
+                    Ok(UserCookies {
+                        session: "fresh".to_string(),
+                        ..Default::default()
+                    })
+                }
+            }
+        };
+        let guard = SessionGuard::builder()
+            .cookies(UserCookies {
+                session: "expired".to_string(),
+                ..Default::default()
+            })
+            .provider(provider)
+            .build();
+
+        let session = guard
+            .call(|cookies| async move {
+                match cookies.session.as_str() {
+                    "expired" => Err(Error::from(TradingViewError::InvalidSessionId)),
+                    session => Ok(session.to_string()),
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(session, "fresh");
+        assert_eq!(refreshes.load(Ordering::Relaxed), 1);
+
+        let stale = UserCookies {
+            session: "expired".to_string(),
+            ..Default::default()
+        };
+        guard.refresh(&stale).await.unwrap();
+        assert_eq!(refreshes.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{Error, Result, UserCookies};
+use bon::bon;
+use std::{
+    future::Future,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+use ustr::Ustr;
+
+fn no_account() -> Error {
+    Error::RateLimited(Ustr::from("no pooled account is available"))
+}
+
+/// How [`SessionPool`] picks the account for the next request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoolStrategy {
+    #[default]
+    RoundRobin,
+    /// The available account that has been idle the longest.
+    LeastRecentlyUsed,
+}
+
+/// Usage and health of one pooled account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountHealth {
+    pub user_id: u32,
+    pub username: String,
+    pub requests: u64,
+    pub failures: u64,
+    /// Rate limit responses received so far.
+    pub throttled: u64,
+    pub last_used: Option<Instant>,
+    /// Set after a rate limit response, the account is skipped until then.
+    pub cooldown_until: Option<Instant>,
+    /// Set when the session expired, cleared by [`SessionPool::replace`].
+    pub expired: bool,
+}
+
+impl AccountHealth {
+    pub fn is_available(&self) -> bool {
+        !self.expired
+            && self
+                .cooldown_until
+                .is_none_or(|until| until <= Instant::now())
+    }
+}
+
+struct PoolAccount {
+    cookies: Mutex<UserCookies>,
+    health: Mutex<AccountHealth>,
+}
+
+/// Spreads requests over several TradingView accounts.
+///
+/// Accounts that get rate limited are cooled down and accounts whose session
+/// expired are skipped until [`SessionPool::replace`]d, with
+/// [`SessionPool::call`] failing over to the next account in both cases.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{
+///     UserCookies,
+///     client::{session_pool::{PoolStrategy, SessionPool}, watchlists::list_watchlists},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let pool = SessionPool::builder()
+///         .accounts(vec![
+///             UserCookies::load_from_file("first.json")?,
+///             UserCookies::load_from_file("second.json")?,
+///         ])
+///         .strategy(PoolStrategy::LeastRecentlyUsed)
+///         .build();
+///
+///     let watchlists = pool
+///         .call(|cookies| async move { list_watchlists(&cookies).await })
+///         .await?;
+///     println!("{} watchlists", watchlists.len());
+///     Ok(())
+/// }
+/// ```
+pub struct SessionPool {
+    accounts: Vec<PoolAccount>,
+    strategy: PoolStrategy,
+    cooldown: Duration,
+    next: AtomicUsize,
+}
+
+#[bon]
+impl SessionPool {
+    #[builder]
+    pub fn new(
+        accounts: Vec<UserCookies>,
+        #[builder(default)] strategy: PoolStrategy,
+        /// How long a rate limited account is skipped.
+        #[builder(default = Duration::from_secs(60))]
+        cooldown: Duration,
+    ) -> Self {
+        let accounts = accounts
+            .into_iter()
+            .map(|cookies| PoolAccount {
+                health: Mutex::new(AccountHealth {
+                    user_id: cookies.id,
+                    username: cookies.username.clone(),
+                    requests: 0,
+                    failures: 0,
+                    throttled: 0,
+                    last_used: None,
+                    cooldown_until: None,
+                    expired: false,
+                }),
+                cookies: Mutex::new(cookies),
+            })
+            .collect();
+        Self {
+            accounts,
+            strategy,
+            cooldown,
+            next: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl SessionPool {
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Health of every account, in the order they were added.
+    pub fn health(&self) -> Vec<AccountHealth> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                account
+                    .health
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone()
+            })
+            .collect()
+    }
+
+    /// Pick the next available account and count it as used, e.g. to open a
+    /// websocket connection with its auth token.
+    pub fn acquire(&self) -> Result<UserCookies> {
+        let index = self.pick().ok_or_else(no_account)?;
+        let account = &self.accounts[index];
+        let mut health = account.health.lock().unwrap_or_else(|e| e.into_inner());
+        health.requests += 1;
+        health.last_used = Some(Instant::now());
+        Ok(account
+            .cookies
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone())
+    }
+
+    fn pick(&self) -> Option<usize> {
+        let available = |index: &usize| {
+            self.accounts[*index]
+                .health
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_available()
+        };
+        let count = self.accounts.len();
+        match self.strategy {
+            PoolStrategy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..count).map(|i| (start + i) % count).find(available)
+            }
+            PoolStrategy::LeastRecentlyUsed => (0..count).filter(available).min_by_key(|&index| {
+                self.accounts[index]
+                    .health
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .last_used
+            }),
+        }
+    }
+
+    /// Run `f` with a pooled account, failing over to the next one when the
+    /// account is rate limited or its session expired.
+    pub async fn call<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn(UserCookies) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for _ in 0..self.accounts.len() {
+            let cookies = match self.acquire() {
+                Ok(cookies) => cookies,
+                Err(e) => return Err(last_error.unwrap_or(e)),
+            };
+            match f(cookies.clone()).await {
+                Err(e) if e.is_rate_limited() => {
+                    self.report_rate_limited(&cookies);
+                    last_error = Some(e);
+                }
+                Err(e) if e.is_session_expired() => {
+                    self.report_expired(&cookies);
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    self.update(&cookies, |health| health.failures += 1);
+                    return Err(e);
+                }
+                ok => return ok,
+            }
+        }
+        Err(last_error.unwrap_or_else(no_account))
+    }
+
+    /// Cool down the account of `cookies` after a rate limit response.
+    pub fn report_rate_limited(&self, cookies: &UserCookies) {
+        let until = Instant::now() + self.cooldown;
+        warn!("{} is rate limited, cooling down", cookies.username);
+        self.update(cookies, |health| {
+            health.throttled += 1;
+            health.cooldown_until = Some(until);
+        });
+    }
+
+    /// Skip the account of `cookies` until it is [`SessionPool::replace`]d.
+    pub fn report_expired(&self, cookies: &UserCookies) {
+        warn!(
+            "session of {} expired, removing it from rotation",
+            cookies.username
+        );
+        self.update(cookies, |health| {
+            health.failures += 1;
+            health.expired = true;
+        });
+    }
+
+    /// Swap in refreshed cookies for the account with the same user id and put
+    /// it back into rotation. Returns `false` if no such account is pooled.
+    pub fn replace(&self, cookies: UserCookies) -> bool {
+        let Some(account) = self.account(cookies.id) else {
+            return false;
+        };
+        info!("replaced session of {}", cookies.username);
+        account
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .expired = false;
+        *account.cookies.lock().unwrap_or_else(|e| e.into_inner()) = cookies;
+        true
+    }
+
+    fn account(&self, user_id: u32) -> Option<&PoolAccount> {
+        self.accounts.iter().find(|account| {
+            account
+                .health
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .user_id
+                == user_id
+        })
+    }
+
+    fn update(&self, cookies: &UserCookies, f: impl FnOnce(&mut AccountHealth)) {
+        if let Some(account) = self.account(cookies.id) {
+            f(&mut account.health.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: u32) -> UserCookies {
+        UserCookies {
+            id,
+            username: format!("user{id}"),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_throttled_accounts() {
+        let pool = SessionPool::builder()
+            .accounts(vec![account(1), account(2), account(3)])
+            .build();
+
+        assert_eq!(pool.acquire().unwrap().id, 1);
+        assert_eq!(pool.acquire().unwrap().id, 2);
+
+        let id = pool
+            .call(|cookies| async move {
+                match cookies.id {
+                    3 => Err(Error::RateLimited(Ustr::from("scanner"))),
+                    id => Ok(id),
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(id, 1);
+
+        let health = pool.health();
+        assert_eq!(health[2].throttled, 1);
+        assert!(!health[2].is_available());
+        assert_eq!(health[0].requests, 2);
+
+        pool.report_expired(&account(1));
+        pool.report_expired(&account(2));
+        assert!(pool.acquire().unwrap_err().is_rate_limited());
+        assert!(pool.replace(account(2)));
+        assert_eq!(pool.acquire().unwrap().id, 2);
+    }
+}
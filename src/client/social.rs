@@ -0,0 +1,230 @@
+use super::paginator::{Page, Paginator};
+use crate::{Error, Idea, IdeaSort, MindsPage, MindsPost, Result, UserCookies, utils::get};
+use bon::builder;
+use futures_util::FutureExt;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use url::Url;
+use ustr::Ustr;
+
+static IDEAS_URL: &str = "https://www.tradingview.com/symbols";
+static MINDS_URL: &str = "https://www.tradingview.com/api/v1/minds/";
+
+/// Fetch one page of the public trade ideas on `symbol` (`EXCHANGE:SYMBOL`),
+/// pages start at 1.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::social::get_ideas;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let page = get_ideas().symbol("NASDAQ:AAPL").call().await?;
+///     for idea in page.items {
+///         println!("{} by {} ({} agrees)", idea.title, idea.author.username, idea.agrees);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_ideas(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    #[builder(default = 1)] page: u64,
+    #[builder(default)] sort: IdeaSort,
+) -> Result<Page<Idea>> {
+    let path = symbol.replace(':', "-");
+    let url = match page {
+        0 | 1 => format!("{IDEAS_URL}/{path}/ideas/"),
+        page => format!("{IDEAS_URL}/{path}/ideas/page-{page}/"),
+    };
+    let sort = match sort {
+        IdeaSort::Recent => "recent",
+        IdeaSort::Popular => "popular",
+    };
+    let data: Value = get(
+        client,
+        &url,
+        &[("component-data-only", "1"), ("sort", sort)],
+    )
+    .await?
+    .error_for_status()?
+    .json()
+    .await?;
+    parse_ideas(symbol, page.max(1), &data)
+}
+
+/// Lazily pages through the ideas on `symbol`, see [`get_ideas`].
+#[builder]
+pub fn paginate_ideas(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    #[builder(default)] sort: IdeaSort,
+    limit: Option<usize>,
+) -> Paginator<Idea> {
+    let client = client.cloned();
+    let symbol = symbol.to_string();
+
+    let paginator = Paginator::new(1, move |page| {
+        let client = client.clone();
+        let symbol = symbol.clone();
+        async move {
+            get_ideas()
+                .maybe_client(client.as_ref())
+                .symbol(&symbol)
+                .page(page)
+                .sort(sort)
+                .call()
+                .await
+        }
+        .boxed()
+    });
+    match limit {
+        Some(limit) => paginator.with_limit(limit),
+        None => paginator,
+    }
+}
+
+/// Fetch one page of the Minds feed of `symbol` (`EXCHANGE:SYMBOL`), newest
+/// first. Pass the `next` cursor of a page to get the one after it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::social::get_minds;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let page = get_minds().symbol("NASDAQ:AAPL").call().await?;
+///     for post in &page.posts {
+///         println!("{}: {}", post.author.username, post.text);
+///     }
+///     let _older = get_minds()
+///         .symbol("NASDAQ:AAPL")
+///         .maybe_cursor(page.next.as_deref())
+///         .call()
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_minds(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    cursor: Option<&str>,
+) -> Result<MindsPage> {
+    let mut queries = vec![("symbol", symbol)];
+    if let Some(cursor) = cursor {
+        queries.push(("c", cursor));
+    }
+    let data: Value = get(client, MINDS_URL, &queries)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    parse_minds(symbol, &data)
+}
+
+fn parse_ideas(symbol: &str, page: u64, data: &Value) -> Result<Page<Idea>> {
+    let items = data
+        .pointer("/data/ideas/data/items")
+        .and_then(Value::as_array)
+        .ok_or_else(|| Error::Internal(Ustr::from("Unexpected ideas response")))?;
+    let items: Vec<Idea> = parse_posts(symbol, items)?;
+    let last = data
+        .pointer("/data/ideas/data/next")
+        .is_some_and(Value::is_null);
+    let next = (!items.is_empty() && !last).then_some(page + 1);
+    Ok(Page { items, next })
+}
+
+fn parse_minds(symbol: &str, data: &Value) -> Result<MindsPage> {
+    let results = data
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or_else(|| Error::Internal(Ustr::from("Unexpected minds response")))?;
+    let posts: Vec<MindsPost> = parse_posts(symbol, results)?;
+    // The next page is given as a full url, keep only its cursor
+    let next = data
+        .get("next")
+        .and_then(Value::as_str)
+        .and_then(|next| Url::parse(next).ok())
+        .and_then(|next| {
+            next.query_pairs()
+                .find(|(key, _)| key == "c")
+                .map(|(_, cursor)| cursor.into_owned())
+        });
+    Ok(MindsPage { posts, next })
+}
+
+/// Deserialize feed items, tagged with the symbol they were fetched for.
+fn parse_posts<T: DeserializeOwned>(symbol: &str, items: &[Value]) -> Result<Vec<T>> {
+    items
+        .iter()
+        .cloned()
+        .map(|mut item| {
+            if let Some(item) = item.as_object_mut() {
+                item.insert("symbol".to_owned(), Value::from(symbol));
+            }
+            Ok(serde_json::from_value(item)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_feeds() {
+        let ideas = json!({"data": {"ideas": {"data": {
+            "items": [{
+                "id": 42,
+                "name": "AAPL breakout",
+                "description": "Ascending triangle",
+                "chart_url": "https://www.tradingview.com/chart/AAPL/abc/",
+                "image": {"big": "https://s3.tradingview.com/big.png", "middle": null},
+                "user": {"id": 7, "username": "trader"},
+                "likes_count": 12,
+                "comments_count": 3,
+                "date_timestamp": 1_700_000_000,
+                "symbol": {"name": "AAPL"},
+            }],
+            "next": "/symbols/NASDAQ-AAPL/ideas/page-3/",
+        }}}});
+        let page = parse_ideas("NASDAQ:AAPL", 2, &ideas).unwrap();
+        assert_eq!(page.next, Some(3));
+        let idea = &page.items[0];
+        assert_eq!(idea.symbol, "NASDAQ:AAPL");
+        assert_eq!(idea.title, "AAPL breakout");
+        assert_eq!(idea.author.username, "trader");
+        assert_eq!(idea.agrees, 12);
+        assert_eq!(idea.published, 1_700_000_000);
+        assert_eq!(idea.image_url(), Some("https://s3.tradingview.com/big.png"));
+
+        let minds = json!({
+            "next": "https://www.tradingview.com/api/v1/minds/?c=abc%3D&symbol=NASDAQ%3AAAPL",
+            "results": [{
+                "uid": "u1",
+                "text": "Bullish into earnings",
+                "url": "https://www.tradingview.com/minds/u1/",
+                "author": {"id": 8, "username": "mind"},
+                "total_likes": 5,
+                "total_comments": 1,
+                "created": "2023-11-14T22:13:20.000000Z",
+            }],
+        });
+        let page = parse_minds("NASDAQ:AAPL", &minds).unwrap();
+        assert_eq!(page.next.as_deref(), Some("abc="));
+        assert_eq!(page.posts[0].published, 1_700_000_000);
+        assert_eq!(page.posts[0].agrees, 5);
+
+        let last = parse_minds("NASDAQ:AAPL", &json!({"next": null, "results": []})).unwrap();
+        assert!(last.posts.is_empty() && last.next.is_none());
+        assert!(parse_ideas("NASDAQ:AAPL", 1, &json!({})).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Result, Spark, SparkRange, UserCookies, utils::get};
+use bon::builder;
+use serde_json::Value;
+
+static SPARKS_URL: &str = "https://www.tradingview.com/api/v1/sparks/";
+/// Symbols requested per call, longer lists are split into several requests.
+const SPARKS_CHUNK_SIZE: usize = 100;
+
+/// Fetch compact recent close prices for many symbols at once.
+///
+/// This is a single REST call per 100 symbols and does not open chart sessions,
+/// symbols without data are left out of the result.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{SparkRange, client::sparks::get_sparks};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let sparks = get_sparks()
+///         .symbols(&["NASDAQ:AAPL", "BINANCE:BTCUSDT"])
+///         .range(SparkRange::FiveDays)
+///         .call()
+///         .await?;
+///     for spark in sparks {
+///         println!("{} {:?}%", spark.symbol, spark.change_percent());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_sparks(
+    client: Option<&UserCookies>,
+    symbols: &[&str],
+    #[builder(default)] range: SparkRange,
+) -> Result<Vec<Spark>> {
+    let range = range.to_string();
+    let mut sparks = Vec::with_capacity(symbols.len());
+    for chunk in symbols.chunks(SPARKS_CHUNK_SIZE) {
+        let symbols = chunk.join(",");
+        let data: Value = get(
+            client,
+            SPARKS_URL,
+            &[("symbols", symbols.as_str()), ("range", range.as_str())],
+        )
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+        sparks.extend(parse_sparks(data)?);
+    }
+    Ok(sparks)
+}
+
+/// Accepts both a list of sparks and an object keyed by symbol.
+fn parse_sparks(data: Value) -> Result<Vec<Spark>> {
+    let data = match data {
+        Value::Object(mut map) if map.contains_key("data") => {
+            map.remove("data").unwrap_or_default()
+        }
+        data => data,
+    };
+    let sparks = match data {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(symbol, value)| {
+                let mut spark: Spark = serde_json::from_value(value)?;
+                spark.symbol = symbol;
+                Ok(spark)
+            })
+            .collect::<Result<Vec<_>>>()?,
+        data => serde_json::from_value(data)?,
+    };
+    Ok(sparks
+        .into_iter()
+        .filter(|s| !s.closes.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_sparks() {
+        let keyed = json!({
+            "NASDAQ:AAPL": {"t": [1, 2], "c": [10.0, 11.0], "prev_close": 10.0},
+            "NASDAQ:NONE": {"t": [], "c": []}
+        });
+        let sparks = parse_sparks(keyed).unwrap();
+        assert_eq!(sparks.len(), 1);
+        assert_eq!(sparks[0].symbol, "NASDAQ:AAPL");
+        assert_eq!(sparks[0].change_percent(), Some(10.0));
+
+        let listed = json!({"data": [{"symbol": "BINANCE:BTCUSDT", "prices": [1.0, 2.0]}]});
+        let sparks = parse_sparks(listed).unwrap();
+        assert_eq!(sparks[0].closes, vec![1.0, 2.0]);
+    }
+}
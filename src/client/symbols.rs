@@ -0,0 +1,78 @@
+use crate::{Error, Result, SymbolDetails, UserCookies, utils::get_symbol_fields};
+use serde_json::Value;
+use ustr::Ustr;
+
+/// Scanner columns mapped onto [`SymbolDetails`].
+static SYMBOL_FIELDS: &str = "name,description,type,subtype,exchange,listed_exchange,currency,\
+country,sector,industry,isin,timezone,session,session_holidays,pricescale,minmov,pointvalue,\
+fractional";
+
+/// Resolve `symbol` (`EXCHANGE:SYMBOL`) to its full metadata.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::client::symbols::get_symbol_details;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let details = get_symbol_details(None, "CME_MINI:ES1!").await?;
+///     println!(
+///         "{} tick {:?} point value {}",
+///         details.description,
+///         details.tick_size(),
+///         details.point_value()
+///     );
+///     Ok(())
+/// }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn get_symbol_details(
+    client: Option<&UserCookies>,
+    symbol: &str,
+) -> Result<SymbolDetails> {
+    let data: Value = get_symbol_fields(client, symbol, SYMBOL_FIELDS).await?;
+    parse_symbol_details(symbol, data)
+}
+
+fn parse_symbol_details(symbol: &str, data: Value) -> Result<SymbolDetails> {
+    if data.as_object().is_none_or(|fields| fields.is_empty()) {
+        return Err(Error::NoSearchDataFound);
+    }
+    let mut details: SymbolDetails = serde_json::from_value(data)?;
+    details.symbol = Ustr::from(symbol);
+    Ok(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_symbol_details() {
+        let details = parse_symbol_details(
+            "CME_MINI:ES1!",
+            json!({
+                "name": "ES1!",
+                "type": "futures",
+                "exchange": "CME",
+                "currency": "USD",
+                "session": "1700-1600",
+                "pricescale": 100,
+                "minmov": 25,
+                "pointvalue": 50,
+                "sector": null
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(details.symbol.as_str(), "CME_MINI:ES1!");
+        assert_eq!(details.market_type.as_str(), "futures");
+        assert_eq!(details.currency, Some(Ustr::from("USD")));
+        assert_eq!(details.tick_size(), Some(0.25));
+        assert_eq!(details.point_value(), 50.0);
+        assert_eq!(details.sector, None);
+        assert!(parse_symbol_details("X:Y", json!({})).is_err());
+    }
+}
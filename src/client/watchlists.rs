@@ -0,0 +1,167 @@
+use crate::{
+    Result, UserCookies, Watchlist,
+    utils::{delete, get, post_json},
+};
+use serde_json::json;
+
+static BASE_WATCHLIST_URL: &str = "https://www.tradingview.com/api/v1/symbols_list";
+
+/// List every watchlist of the account, custom and colored.
+#[tracing::instrument(skip(client))]
+pub async fn list_watchlists(client: &UserCookies) -> Result<Vec<Watchlist>> {
+    let lists = get(Some(client), &format!("{BASE_WATCHLIST_URL}/all/"), &[])
+        .await?
+        .error_for_status()?
+        .json::<Vec<Watchlist>>()
+        .await?;
+    Ok(lists)
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn get_watchlist(client: &UserCookies, id: i64) -> Result<Watchlist> {
+    let list = get(
+        Some(client),
+        &format!("{BASE_WATCHLIST_URL}/custom/{id}/"),
+        &[],
+    )
+    .await?
+    .error_for_status()?
+    .json::<Watchlist>()
+    .await?;
+    Ok(list)
+}
+
+/// Create a custom watchlist holding `symbols` (`EXCHANGE:SYMBOL`).
+#[tracing::instrument(skip(client))]
+pub async fn create_watchlist(
+    client: &UserCookies,
+    name: &str,
+    symbols: &[&str],
+) -> Result<Watchlist> {
+    let list = post_json(
+        Some(client),
+        &format!("{BASE_WATCHLIST_URL}/custom/"),
+        &json!({ "name": name, "symbols": symbols }),
+    )
+    .await?
+    .error_for_status()?
+    .json::<Watchlist>()
+    .await?;
+    Ok(list)
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn rename_watchlist(client: &UserCookies, id: i64, name: &str) -> Result<Watchlist> {
+    update(client, id, "rename", &json!({ "name": name })).await
+}
+
+/// Append symbols to the end of a watchlist.
+#[tracing::instrument(skip(client))]
+pub async fn add_symbols(client: &UserCookies, id: i64, symbols: &[&str]) -> Result<Watchlist> {
+    update(client, id, "append", &symbols).await
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn remove_symbols(client: &UserCookies, id: i64, symbols: &[&str]) -> Result<Watchlist> {
+    update(client, id, "remove", &symbols).await
+}
+
+/// Replace the whole content of a watchlist, section headers included.
+#[tracing::instrument(skip(client))]
+pub async fn replace_symbols(client: &UserCookies, id: i64, symbols: &[&str]) -> Result<Watchlist> {
+    update(client, id, "replace", &symbols).await
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn delete_watchlist(client: &UserCookies, id: i64) -> Result<()> {
+    delete(Some(client), &format!("{BASE_WATCHLIST_URL}/custom/{id}/"))
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Make the watchlist's symbols match `symbols`, adding and removing only what differs.
+///
+/// Section headers and the order of symbols already on the list are kept.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{UserCookies, client::watchlists};
+///
+/// # async fn run(user: &UserCookies) -> tradingview::Result<()> {
+/// let list = watchlists::list_watchlists(user).await?.remove(0);
+/// let universe = ["NASDAQ:AAPL", "NASDAQ:MSFT", "BINANCE:BTCUSDT"];
+/// let synced = watchlists::sync_watchlist(user, list.id, &universe).await?;
+/// assert_eq!(synced.tickers().count(), universe.len());
+/// # Ok(())
+/// # }
+/// ```
+#[tracing::instrument(skip(client))]
+pub async fn sync_watchlist(client: &UserCookies, id: i64, symbols: &[&str]) -> Result<Watchlist> {
+    let mut list = get_watchlist(client, id).await?;
+    let (added, removed) = diff(&list, symbols);
+    let removed: Vec<String> = removed.into_iter().map(str::to_owned).collect();
+
+    if !removed.is_empty() {
+        let removed: Vec<&str> = removed.iter().map(String::as_str).collect();
+        list = remove_symbols(client, id, &removed).await?;
+    }
+    if !added.is_empty() {
+        list = add_symbols(client, id, &added).await?;
+    }
+    Ok(list)
+}
+
+async fn update<T: serde::Serialize + ?Sized>(
+    client: &UserCookies,
+    id: i64,
+    action: &str,
+    body: &T,
+) -> Result<Watchlist> {
+    let list = post_json(
+        Some(client),
+        &format!("{BASE_WATCHLIST_URL}/custom/{id}/{action}/"),
+        body,
+    )
+    .await?
+    .error_for_status()?
+    .json::<Watchlist>()
+    .await?;
+    Ok(list)
+}
+
+/// Symbols missing from `list` and symbols on `list` that are not wanted.
+fn diff<'a, 'b>(list: &'a Watchlist, wanted: &[&'b str]) -> (Vec<&'b str>, Vec<&'a str>) {
+    let mut added: Vec<&str> = Vec::new();
+    for symbol in wanted {
+        if !list.contains(symbol) && !added.iter().any(|s| s.eq_ignore_ascii_case(symbol)) {
+            added.push(symbol);
+        }
+    }
+    let removed = list
+        .tickers()
+        .filter(|s| !wanted.iter().any(|w| w.eq_ignore_ascii_case(s)))
+        .collect();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let list = Watchlist {
+            symbols: vec![
+                "###Stocks".into(),
+                "NASDAQ:AAPL".into(),
+                "NASDAQ:TSLA".into(),
+            ],
+            ..Default::default()
+        };
+        let (added, removed) = diff(&list, &["nasdaq:aapl", "NASDAQ:MSFT", "NASDAQ:MSFT"]);
+        assert_eq!(added, vec!["NASDAQ:MSFT"]);
+        assert_eq!(removed, vec!["NASDAQ:TSLA"]);
+    }
+}
@@ -0,0 +1,740 @@
+#[cfg(feature = "user")]
+use crate::UserCookies;
+use crate::{DataServer, Result, error::Error};
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap, env, fmt, path::Path, path::PathBuf, sync::RwLock, time::Duration,
+};
+use url::Url;
+use ustr::Ustr;
+
+lazy_static::lazy_static! {
+    static ref PROXY: RwLock<Option<ProxyConfig>> = RwLock::new(None);
+    static ref TLS: RwLock<Option<TlsConfig>> = RwLock::new(None);
+    static ref REQUEST_TIMEOUT: RwLock<Duration> = RwLock::new(TimeoutConfig::default().request());
+}
+
+#[cfg(feature = "rustls-tls")]
+lazy_static::lazy_static! {
+    static ref TLS_CLIENT: RwLock<Option<std::sync::Arc<rustls::ClientConfig>>> =
+        RwLock::new(None);
+}
+
+/// Crate-wide configuration that can be loaded from the environment or a TOML file
+/// and handed to the websocket client, the command runner and the history helpers.
+///
+/// # Examples
+///
+/// ```toml
+/// auth_token = "my_token"
+/// server = "ProData"
+///
+/// [timeouts]
+/// command_secs = 10
+/// history_secs = 60
+///
+/// [reconnect]
+/// max_attempts = 5
+/// ```
+///
+/// `Debug` redacts the password, TOTP secret, session cookies and auth token.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Builder)]
+#[serde(default)]
+pub struct Config {
+    pub auth_token: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub totp_secret: Option<String>,
+    pub session: Option<String>,
+    pub session_signature: Option<String>,
+    /// Path of a JSON file holding serialized `UserCookies`.
+    pub cookies_path: Option<PathBuf>,
+    #[builder(default)]
+    pub server: DataServer,
+    #[builder(default)]
+    pub timeouts: TimeoutConfig,
+    #[builder(default)]
+    pub reconnect: ReconnectConfig,
+    #[builder(default)]
+    pub rate_limit: RateLimitConfig,
+    #[builder(default)]
+    pub keepalive: KeepaliveConfig,
+    #[builder(default)]
+    pub throttle: ThrottleConfig,
+    pub proxy: Option<ProxyConfig>,
+    pub tls: Option<TlsConfig>,
+}
+
+/// Shows whether a secret is set without printing it.
+fn redact(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| "<redacted>")
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("auth_token", &redact(&self.auth_token))
+            .field("username", &self.username)
+            .field("password", &redact(&self.password))
+            .field("totp_secret", &redact(&self.totp_secret))
+            .field("session", &redact(&self.session))
+            .field("session_signature", &redact(&self.session_signature))
+            .field("cookies_path", &self.cookies_path)
+            .field("server", &self.server)
+            .field("timeouts", &self.timeouts)
+            .field("reconnect", &self.reconnect)
+            .field("rate_limit", &self.rate_limit)
+            .field("keepalive", &self.keepalive)
+            .field("throttle", &self.throttle)
+            .field("proxy", &self.proxy)
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Timeout of REST requests, installed with [`set_request_timeout`].
+    pub request_secs: u64,
+    pub command_secs: u64,
+    pub reconnect_secs: u64,
+    pub history_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            request_secs: 30,
+            command_secs: 10,
+            reconnect_secs: 30,
+            history_secs: 30,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn request(&self) -> Duration {
+        Duration::from_secs(self.request_secs)
+    }
+
+    pub fn command(&self) -> Duration {
+        Duration::from_secs(self.command_secs)
+    }
+
+    pub fn reconnect(&self) -> Duration {
+        Duration::from_secs(self.reconnect_secs)
+    }
+
+    pub fn history(&self) -> Duration {
+        Duration::from_secs(self.history_secs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    pub initial_delay_ms: u64,
+    pub max_delay_secs: u64,
+    pub max_attempts: usize,
+    pub multiplier: f64,
+    pub jitter_percent: f64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 1000,
+            max_delay_secs: 60,
+            max_attempts: 10,
+            multiplier: 2.0,
+            jitter_percent: 0.1,
+        }
+    }
+}
+
+/// Token bucket limits for REST requests, see [`crate::client::rate_limit`].
+///
+/// Requests over the limit wait for a token instead of failing. A rate of `0`
+/// disables the bucket, a `max_concurrent_requests` of `0` lifts the cap.
+///
+/// # Examples
+///
+/// ```toml
+/// [rate_limit]
+/// requests_per_second = 5.0
+/// burst = 10
+///
+/// [rate_limit.endpoints."scanner.tradingview.com"]
+/// requests_per_second = 1.0
+/// burst = 3
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Shared by every request.
+    pub requests_per_second: f64,
+    /// Requests allowed back to back before the rate applies.
+    pub burst: u32,
+    pub max_concurrent_requests: usize,
+    /// Extra limits per host, e.g. `scanner.tradingview.com`.
+    pub endpoints: HashMap<String, EndpointRateLimit>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 5.0,
+            burst: 10,
+            max_concurrent_requests: 30,
+            endpoints: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EndpointRateLimit {
+    pub requests_per_second: f64,
+    #[serde(default = "default_endpoint_burst")]
+    pub burst: u32,
+}
+
+fn default_endpoint_burst() -> u32 {
+    1
+}
+
+/// Websocket keepalive settings.
+///
+/// A ping is sent whenever no frame arrived for `ping_interval_secs`. Once no
+/// frame arrived for `max_silence_secs` the connection is reported stale and,
+/// if `reconnect_on_stale` is set, closed so the command runner reconnects it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeepaliveConfig {
+    pub ping_interval_secs: u64,
+    pub max_silence_secs: u64,
+    pub reconnect_on_stale: bool,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval_secs: 30,
+            max_silence_secs: 90,
+            reconnect_on_stale: true,
+        }
+    }
+}
+
+impl KeepaliveConfig {
+    pub fn ping_interval(&self) -> Duration {
+        Duration::from_secs(self.ping_interval_secs.max(1))
+    }
+
+    pub fn max_silence(&self) -> Duration {
+        Duration::from_secs(self.max_silence_secs)
+    }
+}
+
+/// Caps on how many updates per second the websocket client emits for each
+/// symbol, `0` lifts the cap.
+///
+/// Updates over the cap are held back, and the newest of them is delivered
+/// when the one second window ends, quotes with the fields of every dropped
+/// update merged in. History loads and bar closes are always delivered.
+///
+/// # Examples
+///
+/// ```toml
+/// [throttle]
+/// quotes_per_second = 4
+/// chart_per_second = 1
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    pub quotes_per_second: u32,
+    /// Applied per series, i.e. per symbol and interval.
+    pub chart_per_second: u32,
+}
+
+/// HTTP or SOCKS5 proxy used for REST requests and the websocket connection.
+///
+/// Supported schemes are `http`, `socks5` and `socks5h`. SOCKS5 for REST
+/// requests requires the `socks` feature. `Debug` redacts the password.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+pub struct ProxyConfig {
+    #[builder(into)]
+    pub url: String,
+    #[builder(into)]
+    pub username: Option<String>,
+    #[builder(into)]
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &redact(&self.password))
+            .finish()
+    }
+}
+
+impl ProxyConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            username: None,
+            password: None,
+        }
+    }
+
+    /// Proxy URL with the configured credentials applied.
+    pub fn to_url(&self) -> Result<Url> {
+        let mut url = Url::parse(&self.url)?;
+        if !matches!(url.scheme(), "http" | "socks5" | "socks5h") {
+            return Err(Error::Config(Ustr::from(&format!(
+                "unsupported proxy scheme: {}",
+                url.scheme()
+            ))));
+        }
+        let invalid = |_| Error::Config(Ustr::from("proxy URL cannot carry credentials"));
+        if let Some(username) = &self.username {
+            url.set_username(username).map_err(invalid)?;
+        }
+        if let Some(password) = &self.password {
+            url.set_password(Some(password)).map_err(invalid)?;
+        }
+        Ok(url)
+    }
+}
+
+/// Route every REST request made by this crate through `proxy`, or directly when `None`.
+///
+/// Websocket clients fall back to this proxy unless one is passed to their builder.
+pub fn set_proxy(proxy: Option<ProxyConfig>) {
+    *PROXY.write().unwrap_or_else(|e| e.into_inner()) = proxy;
+}
+
+/// The proxy installed with [`set_proxy`].
+pub fn proxy() -> Option<ProxyConfig> {
+    PROXY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Give up on REST requests made by this crate after `timeout`, from
+/// connecting until the body is read. Defaults to
+/// [`TimeoutConfig::request_secs`] of a default config.
+///
+/// Has no effect on wasm, where `fetch` has no timeout.
+pub fn set_request_timeout(timeout: Duration) {
+    *REQUEST_TIMEOUT.write().unwrap_or_else(|e| e.into_inner()) = timeout;
+}
+
+/// The timeout installed with [`set_request_timeout`].
+pub fn request_timeout() -> Duration {
+    *REQUEST_TIMEOUT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Trust roots and client certificate for TLS connections, e.g. behind a
+/// corporate TLS inspection proxy or for mutual TLS.
+///
+/// Needs the `rustls-tls` feature. A full `rustls::ClientConfig` can be
+/// passed to [`crate::transport::TokioTransport::with_rustls`] instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM files with extra root certificates to trust.
+    #[builder(default)]
+    pub root_certificates: Vec<PathBuf>,
+    /// Also trust the bundled webpki roots.
+    #[builder(default = true)]
+    pub builtin_roots: bool,
+    /// PEM file with the client certificate chain, used with `client_key`.
+    pub client_certificate: Option<PathBuf>,
+    /// PEM file with the private key of `client_certificate`.
+    pub client_key: Option<PathBuf>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(feature = "rustls-tls")]
+impl TlsConfig {
+    /// Build the rustls client configuration on the `ring` provider, reading
+    /// every PEM file.
+    pub fn client_config(&self) -> Result<std::sync::Arc<rustls::ClientConfig>> {
+        use rustls::pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+
+        let mut roots = rustls::RootCertStore::empty();
+        if self.builtin_roots {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        for path in &self.root_certificates {
+            for cert in CertificateDer::pem_file_iter(path).map_err(tls_error)? {
+                roots.add(cert.map_err(tls_error)?).map_err(tls_error)?;
+            }
+        }
+
+        // Not the process default, which panics when several providers are enabled
+        let provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
+        let builder = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?
+            .with_root_certificates(roots);
+        let config = match (&self.client_certificate, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let chain = CertificateDer::pem_file_iter(cert)
+                    .map_err(tls_error)?
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(tls_error)?;
+                let key = PrivateKeyDer::from_pem_file(key).map_err(tls_error)?;
+                builder
+                    .with_client_auth_cert(chain, key)
+                    .map_err(tls_error)?
+            }
+            (None, None) => builder.with_no_client_auth(),
+            _ => {
+                return Err(Error::Config(Ustr::from(
+                    "client_certificate and client_key must be set together",
+                )));
+            }
+        };
+        Ok(std::sync::Arc::new(config))
+    }
+}
+
+#[cfg(feature = "rustls-tls")]
+fn tls_error(err: impl std::fmt::Display) -> Error {
+    Error::Config(Ustr::from(&format!("TLS: {err}")))
+}
+
+/// Use `tls` for every REST request made by this crate, or the default
+/// roots when `None`.
+///
+/// The PEM files are read once here, a changed file needs another call.
+/// Websocket clients fall back to this configuration unless one is passed to
+/// their builder.
+pub fn set_tls(tls: Option<TlsConfig>) -> Result<()> {
+    #[cfg(feature = "rustls-tls")]
+    {
+        let client = tls.as_ref().map(TlsConfig::client_config).transpose()?;
+        *TLS_CLIENT.write().unwrap_or_else(|e| e.into_inner()) = client;
+    }
+    *TLS.write().unwrap_or_else(|e| e.into_inner()) = tls;
+    Ok(())
+}
+
+/// The configuration installed with [`set_tls`].
+pub fn tls() -> Option<TlsConfig> {
+    TLS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The client configuration built by [`set_tls`].
+#[cfg(feature = "rustls-tls")]
+pub(crate) fn tls_client_config() -> Option<std::sync::Arc<rustls::ClientConfig>> {
+    TLS_CLIENT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+impl Config {
+    /// Load the configuration from `TV_*` environment variables, falling back to
+    /// defaults for anything that is not set.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self {
+            auth_token: env_var("TV_AUTH_TOKEN"),
+            username: env_var("TV_USERNAME"),
+            password: env_var("TV_PASSWORD"),
+            totp_secret: env_var("TV_TOTP_SECRET"),
+            session: env_var("TV_SESSION"),
+            session_signature: env_var("TV_SIGNATURE"),
+            cookies_path: env_var("TV_COOKIES_PATH").map(PathBuf::from),
+            ..Default::default()
+        };
+
+        if let Some(server) = env_var("TV_SERVER") {
+            config.server = parse_server(&server)?;
+        }
+
+        let timeouts = &mut config.timeouts;
+        env_parse("TV_REQUEST_TIMEOUT_SECS", &mut timeouts.request_secs)?;
+        env_parse("TV_COMMAND_TIMEOUT_SECS", &mut timeouts.command_secs)?;
+        env_parse("TV_RECONNECT_TIMEOUT_SECS", &mut timeouts.reconnect_secs)?;
+        env_parse("TV_HISTORY_TIMEOUT_SECS", &mut timeouts.history_secs)?;
+
+        let reconnect = &mut config.reconnect;
+        env_parse(
+            "TV_RECONNECT_INITIAL_DELAY_MS",
+            &mut reconnect.initial_delay_ms,
+        )?;
+        env_parse("TV_RECONNECT_MAX_DELAY_SECS", &mut reconnect.max_delay_secs)?;
+        env_parse("TV_RECONNECT_MAX_ATTEMPTS", &mut reconnect.max_attempts)?;
+        env_parse("TV_RECONNECT_MULTIPLIER", &mut reconnect.multiplier)?;
+        env_parse("TV_RECONNECT_JITTER_PERCENT", &mut reconnect.jitter_percent)?;
+
+        let rate_limit = &mut config.rate_limit;
+        env_parse(
+            "TV_RATE_LIMIT_REQUESTS_PER_SECOND",
+            &mut rate_limit.requests_per_second,
+        )?;
+        env_parse("TV_RATE_LIMIT_BURST", &mut rate_limit.burst)?;
+        env_parse(
+            "TV_RATE_LIMIT_MAX_CONCURRENT",
+            &mut rate_limit.max_concurrent_requests,
+        )?;
+
+        if let Some(url) = env_var("TV_PROXY_URL") {
+            config.proxy = Some(ProxyConfig {
+                url,
+                username: env_var("TV_PROXY_USERNAME"),
+                password: env_var("TV_PROXY_PASSWORD"),
+            });
+        }
+
+        let tls = TlsConfig {
+            root_certificates: env_var("TV_TLS_CA_FILE")
+                .map(|roots| env::split_paths(&roots).collect())
+                .unwrap_or_default(),
+            client_certificate: env_var("TV_TLS_CLIENT_CERT").map(PathBuf::from),
+            client_key: env_var("TV_TLS_CLIENT_KEY").map(PathBuf::from),
+            ..Default::default()
+        };
+        if tls != TlsConfig::default() {
+            config.tls = Some(tls);
+        }
+
+        let keepalive = &mut config.keepalive;
+        env_parse("TV_PING_INTERVAL_SECS", &mut keepalive.ping_interval_secs)?;
+        env_parse("TV_MAX_SILENCE_SECS", &mut keepalive.max_silence_secs)?;
+        env_parse("TV_RECONNECT_ON_STALE", &mut keepalive.reconnect_on_stale)?;
+
+        Ok(config)
+    }
+
+    /// Load the configuration from a TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        toml::from_str(contents).map_err(|e| Error::Config(Ustr::from(&e.to_string())))
+    }
+
+    /// Resolve the account session from the configured credentials.
+    ///
+    /// A session saved at `cookies_path` is reused, logging in with `username`
+    /// and `password` and saving the result there when there is none. Without
+    /// a cookies file the credentials are used to log in directly, and a bare
+    /// `session` and `session_signature` pair is taken as is. Returns `None`
+    /// when no credentials are configured.
+    #[cfg(feature = "user")]
+    pub async fn user_cookies(&self) -> Result<Option<UserCookies>> {
+        let credentials = self.username.as_deref().zip(self.password.as_deref());
+        let totp_secret = self.totp_secret.as_deref();
+        let cookies = match (&self.cookies_path, credentials) {
+            (Some(path), Some((username, password))) => {
+                UserCookies::login_cached(path, username, password, totp_secret).await?
+            }
+            (Some(path), None) => UserCookies::load_from_file(path)?,
+            (None, Some((username, password))) => {
+                UserCookies::new()
+                    .login(username, password, totp_secret)
+                    .await?
+            }
+            (None, None) => match (&self.session, &self.session_signature) {
+                (Some(session), Some(session_signature)) => UserCookies {
+                    session: session.clone(),
+                    session_signature: session_signature.clone(),
+                    auth_token: self.auth_token.clone().unwrap_or_default(),
+                    ..Default::default()
+                },
+                _ => return Ok(None),
+            },
+        };
+        Ok(Some(cookies))
+    }
+
+    /// Fill in `auth_token` from [`Config::user_cookies`] unless one is set.
+    #[cfg(feature = "user")]
+    pub async fn resolve_auth_token(&mut self) -> Result<()> {
+        if self.auth_token.is_none()
+            && let Some(cookies) = self.user_cookies().await?
+            && !cookies.auth_token.is_empty()
+        {
+            self.auth_token = Some(cookies.auth_token);
+        }
+        Ok(())
+    }
+}
+
+fn env_var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.is_empty())
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, target: &mut T) -> Result<()>
+where
+    T::Err: std::fmt::Display,
+{
+    if let Some(value) = env_var(key) {
+        *target = value
+            .parse()
+            .map_err(|e| Error::Config(Ustr::from(&format!("{key}: {e}"))))?;
+    }
+    Ok(())
+}
+
+fn parse_server(value: &str) -> Result<DataServer> {
+    match value.to_lowercase().as_str() {
+        "data" => Ok(DataServer::Data),
+        "prodata" => Ok(DataServer::ProData),
+        "widgetdata" => Ok(DataServer::WidgetData),
+        "mobile-data" | "mobiledata" => Ok(DataServer::MobileData),
+        _ => Err(Error::Config(Ustr::from(&format!(
+            "unknown data server: {value}"
+        )))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let config = Config::from_toml(
+            r#"
+            auth_token = "token"
+            server = "WidgetData"
+
+            [timeouts]
+            history_secs = 120
+
+            [reconnect]
+            max_attempts = 3
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.auth_token.as_deref(), Some("token"));
+        assert_eq!(config.server, DataServer::WidgetData);
+        assert_eq!(config.timeouts.history(), Duration::from_secs(120));
+        assert_eq!(config.timeouts.command_secs, 10);
+        assert_eq!(config.reconnect.max_attempts, 3);
+        assert_eq!(config.rate_limit, RateLimitConfig::default());
+        assert_eq!(config.keepalive.max_silence(), Duration::from_secs(90));
+        assert_eq!(config.throttle, ThrottleConfig::default());
+    }
+
+    #[cfg(feature = "user")]
+    #[tokio::test]
+    async fn test_user_cookies_from_config() {
+        assert!(Config::default().user_cookies().await.unwrap().is_none());
+
+        let path = std::env::temp_dir().join(format!("tv_config_{}.json", std::process::id()));
+        let saved = UserCookies {
+            auth_token: "saved_token".into(),
+            session: "saved_session".into(),
+            ..Default::default()
+        };
+        saved.save_to_file(&path).unwrap();
+
+        let mut config = Config::builder().cookies_path(path.clone()).build();
+        config.resolve_auth_token().await.unwrap();
+        assert_eq!(config.auth_token.as_deref(), Some("saved_token"));
+        std::fs::remove_file(&path).unwrap();
+
+        let config = Config::builder()
+            .session("session".into())
+            .session_signature("signature".into())
+            .build();
+        let cookies = config.user_cookies().await.unwrap().unwrap();
+        assert_eq!(cookies.session, "session");
+        assert_eq!(cookies.session_signature, "signature");
+    }
+
+    #[test]
+    fn test_proxy_url_credentials() {
+        let proxy = ProxyConfig::builder()
+            .url("socks5h://proxy.local:1080")
+            .username("user")
+            .password("p@ss")
+            .build();
+        let url = proxy.to_url().unwrap();
+        assert_eq!(url.username(), "user");
+        assert_eq!(url.password(), Some("p%40ss"));
+        assert!(ProxyConfig::new("ftp://proxy.local").to_url().is_err());
+        assert!(ProxyConfig::new("https://proxy.local").to_url().is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let config = Config::builder()
+            .auth_token("hunter2_token".into())
+            .username("user".into())
+            .password("hunter2_password".into())
+            .totp_secret("hunter2_totp".into())
+            .session("hunter2_session".into())
+            .session_signature("hunter2_signature".into())
+            .proxy(
+                ProxyConfig::builder()
+                    .url("http://proxy.local:8080")
+                    .password("hunter2_proxy")
+                    .build(),
+            )
+            .build();
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("hunter2"), "{debug}");
+        assert!(debug.contains("\"user\""));
+        assert!(debug.contains("proxy.local"));
+        assert!(debug.contains("password: Some(\"<redacted>\")"));
+    }
+
+    #[test]
+    fn test_tls_config() {
+        let config = Config::from_toml(
+            r#"
+            [tls]
+            root_certificates = ["/etc/ssl/corp-ca.pem"]
+            "#,
+        )
+        .unwrap();
+        let tls = config.tls.unwrap();
+        assert_eq!(
+            tls.root_certificates,
+            [PathBuf::from("/etc/ssl/corp-ca.pem")]
+        );
+        assert!(tls.builtin_roots);
+
+        #[cfg(feature = "rustls-tls")]
+        {
+            let half = TlsConfig::builder()
+                .client_certificate(PathBuf::from("client.pem"))
+                .build();
+            assert!(matches!(half.client_config(), Err(Error::Config(_))));
+            assert!(TlsConfig::default().client_config().is_ok());
+
+            // The client configuration is built once and kept on errors
+            set_tls(Some(TlsConfig::default())).unwrap();
+            let cached = tls_client_config().unwrap();
+            assert!(set_tls(Some(half)).is_err());
+            assert!(std::sync::Arc::ptr_eq(
+                &cached,
+                &tls_client_config().unwrap()
+            ));
+            set_tls(None).unwrap();
+            assert!(tls_client_config().is_none());
+        }
+    }
+
+    #[test]
+    fn test_parse_server() {
+        assert_eq!(parse_server("prodata").unwrap(), DataServer::ProData);
+        assert_eq!(parse_server("mobile-data").unwrap(), DataServer::MobileData);
+        assert!(parse_server("nope").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 use ustr::Ustr;
 
@@ -55,6 +56,9 @@ pub enum Error {
     #[error("Indicator not found or unsupported: {0}")]
     IndicatorDataNotFound(Ustr),
 
+    #[error("Invalid study input: {0}")]
+    InvalidStudyInput(Ustr),
+
     #[error("Task join failed: {0}")]
     TokioJoin(Ustr),
 
@@ -73,12 +77,27 @@ pub enum Error {
     #[error("Date/time out of range: {0}")]
     ChronoOutOfRange(Ustr),
 
+    #[error("Rate limited: {0}")]
+    RateLimited(Ustr),
+
+    #[error("HTTP status {0}: {1}")]
+    HttpStatus(u16, Ustr),
+
     #[error("Timeout: {0}")]
     Timeout(Ustr),
 
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("I/O error: {0}")]
     Io(Ustr),
 
+    #[error("Configuration error: {0}")]
+    Config(Ustr),
+
+    #[error("Database error: {0}")]
+    Database(Ustr),
+
     #[error("TradingView error: {source}")]
     TradingView {
         #[source]
@@ -86,6 +105,140 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Whether the request failed because the session cookies or auth token
+    /// are no longer accepted.
+    pub fn is_session_expired(&self) -> bool {
+        matches!(
+            self,
+            Error::TradingView {
+                source: TradingViewError::InvalidSessionId
+            }
+        )
+    }
+
+    /// Whether TradingView rejected the request for exceeding its rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Error::RateLimited(_))
+    }
+
+    /// Whether repeating the request may succeed: rate limits, server errors,
+    /// timeouts and failures to reach TradingView at all.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::HttpStatus(status, _) => *status >= 500,
+            Error::RateLimited(_) | Error::Request(_) | Error::Timeout(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// What went wrong in an error reported by the TradingView server, see
+/// [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// `critical_error`, usually a malformed or unsupported command.
+    Critical,
+    /// `protocol_error`, the server gave up on the session.
+    Protocol,
+    SymbolNotFound,
+    /// The interval is not offered for the symbol.
+    InvalidResolution,
+    /// The account lacks the plan or data subscription, e.g. intraday data
+    /// or more bars than the plan allows.
+    PermissionDenied,
+    Series,
+    Study,
+    Replay,
+    /// A quote arrived with a status other than `ok`.
+    Quote,
+    /// Not an error reported by the server.
+    Other,
+}
+
+impl ErrorKind {
+    /// Whether sending the same request again, on a new connection if need
+    /// be, may succeed. Unknown symbols, unsupported intervals, missing
+    /// permissions, rejected commands and broken studies fail the same way
+    /// every time.
+    pub fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            ErrorKind::Protocol
+                | ErrorKind::Series
+                | ErrorKind::Replay
+                | ErrorKind::Quote
+                | ErrorKind::Other
+        )
+    }
+}
+
+impl Error {
+    /// Classify an error handed to `on_error` by the error code in the
+    /// message field of its server `payload`, falling back to the event it
+    /// was reported with. Ids and other fields of the payload are ignored.
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use tradingview::error::{Error, ErrorKind, TradingViewError};
+    ///
+    /// let error = Error::from(TradingViewError::SeriesError);
+    /// let payload = [json!("cs_1"), json!("sds_1"), json!("s1"), json!("resolution_not_entitled")];
+    /// assert_eq!(error.kind(&payload), ErrorKind::PermissionDenied);
+    /// ```
+    pub fn kind(&self, payload: &[Value]) -> ErrorKind {
+        let Error::TradingView { source } = self else {
+            return ErrorKind::Other;
+        };
+        if let Some(kind) = server_message(source, payload).and_then(code_kind) {
+            return kind;
+        }
+        match source {
+            TradingViewError::CriticalError => ErrorKind::Critical,
+            TradingViewError::ProtocolError => ErrorKind::Protocol,
+            TradingViewError::SymbolError => ErrorKind::SymbolNotFound,
+            TradingViewError::SeriesError => ErrorKind::Series,
+            TradingViewError::StudyError => ErrorKind::Study,
+            TradingViewError::ReplayError => ErrorKind::Replay,
+            TradingViewError::QuoteDataStatusError(_) => ErrorKind::Quote,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// Message field of the payload of a server error event, e.g. `invalid symbol`
+/// in `symbol_error` `[session, symbol_id, message]`. The status of a quote.
+fn server_message<'a>(source: &'a TradingViewError, payload: &'a [Value]) -> Option<&'a str> {
+    let index = match source {
+        TradingViewError::ProtocolError => 0,
+        TradingViewError::CriticalError | TradingViewError::ReplayError => 1,
+        TradingViewError::SymbolError => 2,
+        TradingViewError::SeriesError | TradingViewError::StudyError => 3,
+        TradingViewError::QuoteDataStatusError(status) => return Some(status.as_str()),
+        _ => return None,
+    };
+    payload.get(index)?.as_str()
+}
+
+/// Kind of the known server error codes, spelled with spaces or underscores.
+fn code_kind(message: &str) -> Option<ErrorKind> {
+    let code = message.trim().to_lowercase().replace([' ', '-'], "_");
+    match code.as_str() {
+        "permission_denied"
+        | "no_access"
+        | "not_entitled"
+        | "resolution_not_entitled"
+        | "symbol_not_entitled" => Some(ErrorKind::PermissionDenied),
+        "invalid_symbol" | "no_such_symbol" | "unknown_symbol" | "symbol_not_found" => {
+            Some(ErrorKind::SymbolNotFound)
+        }
+        "invalid_resolution" | "invalid_interval" | "unsupported_resolution" => {
+            Some(ErrorKind::InvalidResolution)
+        }
+        _ => None,
+    }
+}
+
 // Implement From traits for common error types
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
@@ -123,6 +276,7 @@ impl From<regex::Error> for Error {
     }
 }
 
+#[cfg(feature = "native")]
 impl From<tokio_tungstenite::tungstenite::Error> for Error {
     fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
         Error::WebSocket(err.to_string().into())
@@ -147,6 +301,7 @@ impl From<base64::DecodeError> for Error {
     }
 }
 
+#[cfg(feature = "pine")]
 impl From<zip::result::ZipError> for Error {
     fn from(err: zip::result::ZipError) -> Self {
         Error::Zip(err.to_string().into())
@@ -171,6 +326,48 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[cfg(feature = "keyring")]
+impl From<keyring::Error> for Error {
+    fn from(err: keyring::Error) -> Self {
+        Error::Io(err.to_string().into())
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for Error {
+    fn from(err: arrow::error::ArrowError) -> Self {
+        Error::TypeConversion(err.to_string().into())
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        Error::Database(err.to_string().into())
+    }
+}
+
+#[cfg(feature = "redis")]
+impl From<redis::RedisError> for Error {
+    fn from(err: redis::RedisError) -> Self {
+        Error::Database(err.to_string().into())
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        Error::Io(err.to_string().into())
+    }
+}
+
+#[cfg(feature = "polars")]
+impl From<polars::error::PolarsError> for Error {
+    fn from(err: polars::error::PolarsError) -> Self {
+        Error::TypeConversion(err.to_string().into())
+    }
+}
+
 impl From<TradingViewError> for Error {
     fn from(err: TradingViewError) -> Self {
         Error::TradingView { source: err }
@@ -199,6 +396,10 @@ pub enum TradingViewError {
     MissingSymbol,
     #[error("Invalid session ID or signature")]
     InvalidSessionId,
+    #[error("Alert request failed: {0}")]
+    AlertError(Ustr),
+    #[error("Broker request failed: {0}")]
+    BrokerError(Ustr),
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
@@ -227,4 +428,88 @@ pub enum LoginError {
     ParseAuthTokenError,
     #[error("Missing auth token")]
     MissingAuthToken,
+    #[error("Login challenge required but no resolver was provided")]
+    ChallengeRequired,
+    #[error("Login challenge was not accepted")]
+    ChallengeFailed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_kind() {
+        let symbol = Error::from(TradingViewError::SymbolError);
+        assert_eq!(
+            symbol.kind(&[json!("cs_1"), json!("sds_sym_1"), json!("invalid symbol")]),
+            ErrorKind::SymbolNotFound
+        );
+        assert_eq!(
+            symbol.kind(&[
+                json!("cs_1"),
+                json!("sds_sym_1"),
+                json!("permission denied")
+            ]),
+            ErrorKind::PermissionDenied
+        );
+
+        let series = Error::from(TradingViewError::SeriesError);
+        let kind = series.kind(&[
+            json!("cs_1"),
+            json!("sds_1"),
+            json!("s1"),
+            json!("invalid resolution"),
+        ]);
+        assert_eq!(kind, ErrorKind::InvalidResolution);
+        assert!(!kind.is_recoverable());
+        assert_eq!(series.kind(&[]), ErrorKind::Series);
+
+        let critical = Error::from(TradingViewError::CriticalError);
+        let payload = [
+            json!("cs_1"),
+            json!("invalid parameters"),
+            json!("create_series"),
+        ];
+        assert_eq!(critical.kind(&payload), ErrorKind::Critical);
+        assert!(
+            Error::from(TradingViewError::ProtocolError)
+                .kind(&[])
+                .is_recoverable()
+        );
+
+        let quote = Error::from(TradingViewError::QuoteDataStatusError(Ustr::from("error")));
+        assert_eq!(
+            quote.kind(&[json!({ "n": "X:Y", "s": "error" })]),
+            ErrorKind::Quote
+        );
+        assert_eq!(Error::Cancelled.kind(&[]), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_error_kind_reads_only_the_message() {
+        // Words in ids, symbols or free text do not count as codes
+        let symbol = Error::from(TradingViewError::SymbolError);
+        let payload = [
+            json!("cs_1"),
+            json!("sds_sym_resolution"),
+            json!("upgrade to premium for this symbol"),
+        ];
+        assert_eq!(symbol.kind(&payload), ErrorKind::SymbolNotFound);
+
+        let study = Error::from(TradingViewError::StudyError);
+        let payload = [
+            json!("cs_1"),
+            json!("st1"),
+            json!("s1"),
+            json!("Permission_Denied"),
+            json!({ "resolution": "invalid_resolution" }),
+        ];
+        assert_eq!(study.kind(&payload), ErrorKind::PermissionDenied);
+        assert_eq!(
+            study.kind(&[json!("cs_1"), json!("invalid_symbol")]),
+            ErrorKind::Study
+        );
+    }
 }
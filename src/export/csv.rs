@@ -0,0 +1,240 @@
+use crate::{DataPoint, Error, OHLCV, Result};
+use bon::Builder;
+use chrono::{
+    DateTime, SecondsFormat, Utc,
+    format::{Item, StrftimeItems},
+};
+use std::{borrow::Cow, io::Write};
+use ustr::Ustr;
+
+/// How the `time` column is written.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum TimestampFormat {
+    #[default]
+    UnixSeconds,
+    UnixMillis,
+    /// e.g. `2023-11-14T22:13:20Z`
+    Rfc3339,
+    /// A chrono `strftime` pattern such as `%Y-%m-%d %H:%M`, formatted in UTC.
+    Custom(String),
+}
+
+impl TimestampFormat {
+    /// Reject `Custom` patterns chrono cannot format.
+    pub fn validate(&self) -> Result<()> {
+        if let TimestampFormat::Custom(pattern) = self
+            && StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error))
+        {
+            return Err(Error::Config(Ustr::from(&format!(
+                "invalid strftime pattern: {pattern}"
+            ))));
+        }
+        Ok(())
+    }
+
+    fn format(&self, timestamp: i64) -> String {
+        let datetime = || DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_default();
+        match self {
+            TimestampFormat::UnixSeconds => timestamp.to_string(),
+            TimestampFormat::UnixMillis => (timestamp * 1000).to_string(),
+            TimestampFormat::Rfc3339 => datetime().to_rfc3339_opts(SecondsFormat::Secs, true),
+            TimestampFormat::Custom(pattern) => datetime().format(pattern).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+pub struct CsvOptions {
+    #[builder(default = true)]
+    pub header: bool,
+    #[builder(default = ',')]
+    pub delimiter: char,
+    #[builder(default)]
+    pub timestamp_format: TimestampFormat,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Empty for `NaN` and infinite values.
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::new()
+    }
+}
+
+impl CsvOptions {
+    /// Quote `field` if it contains the delimiter, a quote or a line break.
+    fn escape<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        if field.contains([self.delimiter, '"', '\n', '\r']) {
+            Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+
+    fn write_row<W: Write>(
+        &self,
+        writer: &mut W,
+        fields: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<()> {
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                write!(writer, "{}", self.delimiter)?;
+            }
+            writer.write_all(self.escape(field.as_ref()).as_bytes())?;
+        }
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Write `bars` as `time,open,high,low,close,volume` rows.
+///
+/// `NaN` and infinite values are left empty. Fails before writing anything
+/// if the timestamp format is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{
+///     DataPoint,
+///     export::csv::{CsvOptions, TimestampFormat, write_bars},
+/// };
+///
+/// let bars = vec![DataPoint {
+///     index: 0,
+///     value: vec![1_700_000_000.0, 10.0, 12.0, 9.0, 11.0, 500.0],
+/// }];
+/// let options = CsvOptions::builder()
+///     .timestamp_format(TimestampFormat::Rfc3339)
+///     .build();
+///
+/// let mut out = Vec::new();
+/// write_bars(&mut out, &bars, &options).unwrap();
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "time,open,high,low,close,volume\n2023-11-14T22:13:20Z,10,12,9,11,500\n"
+/// );
+/// ```
+pub fn write_bars<W: Write>(
+    writer: &mut W,
+    bars: &[DataPoint],
+    options: &CsvOptions,
+) -> Result<()> {
+    options.timestamp_format.validate()?;
+    if options.header {
+        options.write_row(writer, ["time", "open", "high", "low", "close", "volume"])?;
+    }
+    for bar in bars {
+        options.write_row(
+            writer,
+            [
+                options.timestamp_format.format(bar.timestamp()),
+                number(bar.open()),
+                number(bar.high()),
+                number(bar.low()),
+                number(bar.close()),
+                number(bar.volume()),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Write study rows, e.g. `StudyResponseData::studies`, as `time` followed by
+/// one column per plot.
+///
+/// Plots are named after `plot_names` in order, extra plots fall back to
+/// `plot_<n>`. Missing, `NaN` and infinite values are left empty.
+pub fn write_study<W: Write>(
+    writer: &mut W,
+    points: &[DataPoint],
+    plot_names: &[&str],
+    options: &CsvOptions,
+) -> Result<()> {
+    options.timestamp_format.validate()?;
+    let plots = points
+        .iter()
+        .map(|p| p.value.len().saturating_sub(1))
+        .max()
+        .unwrap_or(0)
+        .max(plot_names.len());
+    if options.header {
+        let names = (0..plots).map(|i| match plot_names.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("plot_{i}"),
+        });
+        options.write_row(writer, std::iter::once("time".to_string()).chain(names))?;
+    }
+    for point in points {
+        let values =
+            (1..=plots).map(|i| point.value.get(i).map_or_else(String::new, |v| number(*v)));
+        let time = options.timestamp_format.format(point.timestamp());
+        options.write_row(writer, std::iter::once(time).chain(values))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_study_with_names() {
+        let points = vec![
+            DataPoint {
+                index: 0,
+                value: vec![1_700_000_000.0, 1.5, 2.0],
+            },
+            DataPoint {
+                index: 1,
+                value: vec![1_700_000_060.0, f64::NAN],
+            },
+        ];
+        let options = CsvOptions::builder()
+            .delimiter(';')
+            .timestamp_format(TimestampFormat::Custom("%H:%M".into()))
+            .build();
+
+        let mut out = Vec::new();
+        write_study(&mut out, &points, &["basis; upper"], &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "time;\"basis; upper\";plot_1\n22:13;1.5;2\n22:14;;\n"
+        );
+    }
+
+    #[test]
+    fn test_write_bars_skips_non_finite() {
+        let bars = vec![DataPoint {
+            index: 0,
+            value: vec![1_700_000_000.0, 10.0, f64::INFINITY, 9.0, 11.0, f64::NAN],
+        }];
+        let options = CsvOptions::builder().header(false).build();
+
+        let mut out = Vec::new();
+        write_bars(&mut out, &bars, &options).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "1700000000,10,,9,11,\n");
+    }
+
+    #[test]
+    fn test_invalid_custom_format() {
+        let options = CsvOptions::builder()
+            .timestamp_format(TimestampFormat::Custom("%Y-%Q".into()))
+            .build();
+
+        let mut out = Vec::new();
+        assert!(matches!(
+            write_bars(&mut out, &[], &options),
+            Err(Error::Config(_))
+        ));
+        assert!(write_study(&mut out, &[], &[], &options).is_err());
+        assert!(out.is_empty());
+    }
+}
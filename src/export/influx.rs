@@ -0,0 +1,428 @@
+use crate::{
+    DataPoint, Error, OHLCV, Result,
+    client::retry::RetryPolicy,
+    export::sink::{DataSink, series_symbol},
+    quote::state::QuoteSnapshot,
+    utils,
+    websocket::SeriesInfo,
+};
+use bon::bon;
+use futures_util::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use std::{
+    fmt::Write,
+    mem,
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+use ustr::Ustr;
+
+fn escape(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// One line protocol point, `None` if no field is finite.
+fn line(
+    measurement: &str,
+    tags: &[(&str, &str)],
+    fields: &[(&str, Option<f64>)],
+    timestamp_ns: i64,
+) -> Option<String> {
+    let mut line = escape(measurement, &[',', ' ']);
+    for (key, value) in tags.iter().filter(|(_, value)| !value.is_empty()) {
+        let _ = write!(
+            line,
+            ",{}={}",
+            escape(key, &[',', '=', ' ']),
+            escape(value, &[',', '=', ' '])
+        );
+    }
+    let mut separator = ' ';
+    for (key, value) in fields {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            let _ = write!(line, "{separator}{}={value}", escape(key, &[',', '=', ' ']));
+            separator = ',';
+        }
+    }
+    (separator == ',').then(|| format!("{line} {timestamp_ns}"))
+}
+
+/// [`DataSink`] writing bars and quote snapshots as InfluxDB line protocol,
+/// e.g. to InfluxDB or VictoriaMetrics.
+///
+/// Points are buffered and written once `batch_size` lines are pending, and
+/// by a background task every `flush_interval`, with failed writes retried
+/// according to `retry`. Call [`InfluxSink::flush`] before shutting down to
+/// write what is left. Requests go through the configured proxy and TLS
+/// roots. Timestamps use nanosecond precision, the default of both the v1
+/// `/write` and the v2 `/api/v2/write` endpoints.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{export::influx::InfluxSink, live::handler::types::TradingViewHandler};
+///
+/// # fn main() -> tradingview::Result<()> {
+/// let sink = InfluxSink::builder()
+///     .url("http://localhost:8086/api/v2/write?org=home&bucket=market")
+///     .token("secret")
+///     .tags(vec![("source".into(), "tradingview".into())])
+///     .build()?;
+/// let handler = TradingViewHandler::default().with_sink(sink);
+/// # Ok(())
+/// # }
+/// ```
+pub struct InfluxSink {
+    inner: Arc<Inner>,
+    /// Periodic flush, started by the first write so it runs on the caller's runtime.
+    timer: OnceLock<JoinHandle<()>>,
+}
+
+struct Inner {
+    http: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    bars_measurement: String,
+    quotes_measurement: String,
+    split_symbol: bool,
+    tags: Vec<(String, String)>,
+    batch_size: usize,
+    flush_interval: Duration,
+    retry: RetryPolicy,
+    buffer: Mutex<Batch>,
+}
+
+struct Batch {
+    lines: Vec<String>,
+    since: Instant,
+}
+
+#[bon]
+impl InfluxSink {
+    #[builder]
+    pub fn new(
+        /// Full write URL including the database, or the org and bucket.
+        #[builder(into)]
+        url: String,
+        /// Sent as `Authorization: Token <token>`.
+        #[builder(into)]
+        token: Option<String>,
+        #[builder(into, default = "tv_bars".to_string())] bars_measurement: String,
+        #[builder(into, default = "tv_quotes".to_string())] quotes_measurement: String,
+        /// Tag `exchange` and `symbol` separately instead of one `ticker` tag
+        /// such as `BINANCE:BTCUSDT`.
+        #[builder(default = true)]
+        split_symbol: bool,
+        /// Static tags added to every point.
+        #[builder(default)]
+        tags: Vec<(String, String)>,
+        #[builder(default = 5_000)] batch_size: usize,
+        #[builder(default = Duration::from_secs(1))] flush_interval: Duration,
+        #[builder(default)] retry: RetryPolicy,
+    ) -> Result<Self> {
+        let inner = Inner {
+            http: utils::client_builder(HeaderMap::new())?.build()?,
+            url,
+            token,
+            bars_measurement,
+            quotes_measurement,
+            split_symbol,
+            tags,
+            batch_size,
+            flush_interval,
+            retry,
+            buffer: Mutex::new(Batch {
+                lines: Vec::new(),
+                since: Instant::now(),
+            }),
+        };
+        Ok(Self {
+            inner: Arc::new(inner),
+            timer: OnceLock::new(),
+        })
+    }
+}
+
+impl InfluxSink {
+    /// Write every buffered point now.
+    pub async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn push(&self, lines: Vec<String>) -> Result<()> {
+        self.timer
+            .get_or_init(|| spawn_timer(Arc::downgrade(&self.inner)));
+        self.inner.push(lines).await
+    }
+}
+
+impl Drop for InfluxSink {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer.get() {
+            timer.abort();
+        }
+    }
+}
+
+/// Flush batches older than `flush_interval` even when no new points arrive.
+fn spawn_timer(inner: Weak<Inner>) -> JoinHandle<()> {
+    let period = inner
+        .upgrade()
+        .map_or(Duration::from_secs(1), |inner| inner.flush_interval);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let Some(inner) = inner.upgrade() else {
+                break;
+            };
+            if inner.is_due()
+                && let Err(e) = inner.flush().await
+            {
+                error!("periodic influx flush failed: {e}");
+            }
+        }
+    })
+}
+
+impl Inner {
+    /// Tags of `ticker` followed by the static tags.
+    fn tags<'a>(
+        &'a self,
+        ticker: &'a str,
+        extra: &[(&'a str, &'a str)],
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut tags = match (self.split_symbol, ticker.split_once(':')) {
+            (true, Some((exchange, symbol))) => vec![("exchange", exchange), ("symbol", symbol)],
+            (true, None) => vec![("symbol", ticker)],
+            (false, _) => vec![("ticker", ticker)],
+        };
+        tags.extend_from_slice(extra);
+        tags.extend(self.tags.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        tags
+    }
+
+    fn bar_lines(&self, series: &SeriesInfo, bars: &[DataPoint]) -> Vec<String> {
+        let ticker = series_symbol(series);
+        let interval = series.options.interval.to_string();
+        let tags = self.tags(&ticker, &[("interval", &interval)]);
+        bars.iter()
+            .filter_map(|bar| {
+                line(
+                    &self.bars_measurement,
+                    &tags,
+                    &[
+                        ("open", Some(bar.open())),
+                        ("high", Some(bar.high())),
+                        ("low", Some(bar.low())),
+                        ("close", Some(bar.close())),
+                        ("volume", Some(bar.volume())),
+                    ],
+                    bar.timestamp() * 1_000_000_000,
+                )
+            })
+            .collect()
+    }
+
+    fn quote_lines(&self, quotes: &[QuoteSnapshot], now_ns: i64) -> Vec<String> {
+        quotes
+            .iter()
+            .filter_map(|quote| {
+                let value = &quote.value;
+                let timestamp = value
+                    .timestamp
+                    .map_or(now_ns, |t| (t * 1_000_000_000.0) as i64);
+                line(
+                    &self.quotes_measurement,
+                    &self.tags(&quote.symbol, &[]),
+                    &[
+                        ("price", value.price),
+                        ("bid", value.bid),
+                        ("ask", value.ask),
+                        ("bid_size", value.bid_size),
+                        ("ask_size", value.ask_size),
+                        ("volume", value.volume),
+                        ("change", value.change),
+                        ("change_percent", value.change_percent),
+                    ],
+                    timestamp,
+                )
+            })
+            .collect()
+    }
+
+    fn batch(&self) -> std::sync::MutexGuard<'_, Batch> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether the batch is full, or non-empty and older than `flush_interval`.
+    fn is_due(&self) -> bool {
+        let batch = self.batch();
+        batch.lines.len() >= self.batch_size
+            || (!batch.lines.is_empty() && batch.since.elapsed() >= self.flush_interval)
+    }
+
+    async fn push(&self, lines: Vec<String>) -> Result<()> {
+        self.batch().lines.extend(lines);
+        if self.is_due() {
+            self.flush().await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let lines = {
+            let mut batch = self.batch();
+            batch.since = Instant::now();
+            mem::take(&mut batch.lines)
+        };
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let body = lines.join("\n");
+        let mut attempt = 1;
+        loop {
+            match self.write(&body).await {
+                Err(e)
+                    if attempt < self.retry.max_attempts && self.retry.should_retry(&e, true) =>
+                {
+                    let delay = self.retry.delay(attempt);
+                    warn!("influx write failed ({e}), retrying in {delay:?}");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    warn!("dropping {} points after failed influx write", lines.len());
+                    return Err(e);
+                }
+                Ok(()) => {
+                    debug!("wrote {} points to influx", lines.len());
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    async fn write(&self, body: &str) -> Result<()> {
+        let mut request = self.http.post(&self.url).body(body.to_string());
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("Token {token}"));
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let url = Ustr::from(&self.url);
+        Err(match status.as_u16() {
+            429 => Error::RateLimited(url),
+            code => Error::HttpStatus(code, url),
+        })
+    }
+}
+
+impl DataSink for InfluxSink {
+    fn write_bars<'a>(
+        &'a self,
+        series: &'a SeriesInfo,
+        bars: &'a [DataPoint],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.push(self.inner.bar_lines(series, bars)))
+    }
+
+    fn write_quotes<'a>(&'a self, quotes: &'a [QuoteSnapshot]) -> BoxFuture<'a, Result<()>> {
+        let now_ns = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        Box::pin(self.push(self.inner.quote_lines(quotes, now_ns)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quote::models::QuoteValue;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn test_line_protocol() {
+        let sink = InfluxSink::builder()
+            .url("http://localhost:8086/write?db=market")
+            .tags(vec![("desk".into(), "fx, spot".into())])
+            .build()
+            .unwrap();
+        let quote = QuoteSnapshot {
+            symbol: "FX:EURUSD".into(),
+            value: QuoteValue {
+                price: Some(1.08),
+                bid: Some(f64::NAN),
+                timestamp: Some(1_700_000_000.0),
+                ..Default::default()
+            },
+            updates: 1,
+        };
+
+        assert_eq!(
+            sink.inner.quote_lines(&[quote], 0),
+            vec![
+                r"tv_quotes,exchange=FX,symbol=EURUSD,desk=fx\,\ spot price=1.08 1700000000000000000"
+            ]
+        );
+        assert_eq!(line("m", &[], &[("x", None)], 0), None);
+    }
+
+    #[tokio::test]
+    async fn test_timer_flushes_idle_batch() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/write?db=market", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("tv_bars") {
+                let n = stream.read(&mut chunk).await.unwrap();
+                request.extend_from_slice(&chunk[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let sink = InfluxSink::builder()
+            .url(url)
+            .flush_interval(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let bar = DataPoint {
+            index: 0,
+            value: vec![1_700_000_000.0, 1.0, 2.0, 0.5, 1.5, 10.0],
+        };
+        // Below the batch size and within the interval, so only the timer writes it
+        sink.write_bars(&SeriesInfo::default(), &[bar])
+            .await
+            .unwrap();
+        assert_eq!(sink.inner.batch().lines.len(), 1);
+
+        let request = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("batch was never flushed")
+            .unwrap();
+        assert!(request.starts_with("POST /write?db=market "));
+        assert!(request.contains("open=1,high=2,low=0.5,close=1.5,volume=10 1700000000000000000"));
+    }
+}
@@ -0,0 +1,12 @@
+//! Exporters for chart and study data.
+
+pub mod csv;
+#[cfg(feature = "influxdb")]
+pub mod influx;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sink;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
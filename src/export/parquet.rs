@@ -0,0 +1,322 @@
+use crate::{
+    DataPoint, OHLCV, Result,
+    chart::arrow::{ToRecordBatch, bar_schema},
+};
+use arrow::{
+    array::{Array, ArrayRef, RecordBatch, StringArray, TimestampMillisecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+};
+use bon::bon;
+use chrono::{DateTime, NaiveDate};
+use parquet::{
+    arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder},
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, warn};
+
+const PART_EXTENSION: &str = "parquet";
+const TMP_EXTENSION: &str = "parquet.tmp";
+
+lazy_static::lazy_static! {
+    static ref SCHEMA: SchemaRef = {
+        let mut fields = vec![Arc::new(Field::new("symbol", DataType::Utf8, false))];
+        fields.extend(bar_schema().fields().iter().cloned());
+        Arc::new(Schema::new(fields))
+    };
+}
+
+/// Schema of the files written by [`ParquetSink`]: `symbol` followed by the
+/// columns of [`bar_schema`].
+pub fn parquet_schema() -> SchemaRef {
+    Arc::clone(&SCHEMA)
+}
+
+/// Directory layout of a [`ParquetSink`], using hive style `key=value` directories.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Partitioning {
+    /// All symbols share the files in the root directory.
+    None,
+    /// `symbol=BINANCE_BTCUSDT/part-00000.parquet`
+    #[default]
+    Symbol,
+    /// `symbol=BINANCE_BTCUSDT/date=2024-01-31/part-00000.parquet`, by UTC day.
+    SymbolDate,
+}
+
+/// Writes bars into compressed Parquet files, one open file per partition.
+///
+/// Files are written under a `.parquet.tmp` name and only renamed once
+/// [`ParquetSink::finish`] closes them, so an interrupted download never leaves
+/// truncated files behind. Finished files are kept and new bars go into the
+/// next `part-NNNNN.parquet`, with bars at or before the last archived
+/// timestamp of a symbol skipped so a download can simply be restarted.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{
+///     Interval,
+///     export::parquet::{ParquetSink, Partitioning},
+///     history,
+/// };
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let mut sink = ParquetSink::builder()
+///         .root("archive")
+///         .partitioning(Partitioning::SymbolDate)
+///         .build();
+///
+///     let (_, bars) = history::single::retrieve()
+///         .symbol("BTCUSDT")
+///         .exchange("BINANCE")
+///         .interval(Interval::OneMinute)
+///         .call()
+///         .await?;
+///     let written = sink.write("BINANCE:BTCUSDT", &bars)?;
+///     sink.finish()?;
+///     println!("archived {written} new bars");
+///     Ok(())
+/// }
+/// ```
+pub struct ParquetSink {
+    root: PathBuf,
+    partitioning: Partitioning,
+    properties: WriterProperties,
+    writers: HashMap<PathBuf, ArrowWriter<File>>,
+    last_timestamps: HashMap<String, Option<i64>>,
+}
+
+#[bon]
+impl ParquetSink {
+    #[builder]
+    pub fn new(
+        #[builder(into)] root: PathBuf,
+        #[builder(default)] partitioning: Partitioning,
+        #[builder(default = Compression::ZSTD(ZstdLevel::default()))] compression: Compression,
+        /// Rows buffered before a row group is flushed to disk.
+        #[builder(default = 65_536)]
+        row_group_size: usize,
+    ) -> Self {
+        Self {
+            root,
+            partitioning,
+            properties: WriterProperties::builder()
+                .set_compression(compression)
+                .set_max_row_group_size(row_group_size)
+                .build(),
+            writers: HashMap::new(),
+            last_timestamps: HashMap::new(),
+        }
+    }
+}
+
+impl ParquetSink {
+    /// Latest bar timestamp of `symbol` in unix seconds, including bars
+    /// written since the sink was created.
+    pub fn last_timestamp(&mut self, symbol: &str) -> Result<Option<i64>> {
+        if let Some(last) = self.last_timestamps.get(symbol) {
+            return Ok(*last);
+        }
+        let mut last = None;
+        for path in parts(&self.symbol_dir(symbol), PART_EXTENSION)? {
+            last = last.max(read_last_timestamp(&path, symbol)?);
+        }
+        self.last_timestamps.insert(symbol.to_string(), last);
+        Ok(last)
+    }
+
+    /// Append the bars of `symbol` newer than [`ParquetSink::last_timestamp`],
+    /// returning how many were written.
+    pub fn write(&mut self, symbol: &str, bars: &[DataPoint]) -> Result<usize> {
+        let last = self.last_timestamp(symbol)?;
+        let mut partitions: BTreeMap<PathBuf, Vec<DataPoint>> = BTreeMap::new();
+        for bar in bars
+            .iter()
+            .filter(|bar| last.is_none_or(|t| bar.timestamp() > t))
+        {
+            partitions
+                .entry(self.partition_dir(symbol, bar.timestamp()))
+                .or_default()
+                .push(bar.clone());
+        }
+
+        let mut written = 0;
+        for (dir, bars) in partitions {
+            let batch = bars.to_record_batch()?;
+            let mut columns: Vec<ArrayRef> =
+                vec![Arc::new(StringArray::from(vec![symbol; bars.len()]))];
+            columns.extend(batch.columns().iter().cloned());
+            let batch = RecordBatch::try_new(parquet_schema(), columns)?;
+            self.writer(&dir)?.write(&batch)?;
+            written += bars.len();
+        }
+
+        if let Some(newest) = bars.iter().map(OHLCV::timestamp).max() {
+            self.last_timestamps
+                .insert(symbol.to_string(), last.max(Some(newest)));
+        }
+        debug!("archived {written} bars of {symbol}");
+        Ok(written)
+    }
+
+    /// Close every open file and move it to its final name.
+    pub fn finish(mut self) -> Result<()> {
+        for (tmp, writer) in std::mem::take(&mut self.writers) {
+            writer.close()?;
+            fs::rename(&tmp, tmp.with_extension(""))?;
+        }
+        Ok(())
+    }
+
+    fn writer(&mut self, dir: &Path) -> Result<&mut ArrowWriter<File>> {
+        let open = self
+            .writers
+            .keys()
+            .find(|tmp| tmp.parent() == Some(dir))
+            .cloned();
+        let tmp = match open {
+            Some(tmp) => tmp,
+            None => {
+                fs::create_dir_all(dir)?;
+                for stale in parts(dir, TMP_EXTENSION)? {
+                    warn!("removing unfinished file {}", stale.display());
+                    fs::remove_file(stale)?;
+                }
+                let next = parts(dir, PART_EXTENSION)?.len();
+                let tmp = dir.join(format!("part-{next:05}.{TMP_EXTENSION}"));
+                let writer = ArrowWriter::try_new(
+                    File::create(&tmp)?,
+                    parquet_schema(),
+                    Some(self.properties.clone()),
+                )?;
+                self.writers.insert(tmp.clone(), writer);
+                tmp
+            }
+        };
+        Ok(self
+            .writers
+            .get_mut(&tmp)
+            .expect("writer was just inserted"))
+    }
+
+    fn symbol_dir(&self, symbol: &str) -> PathBuf {
+        match self.partitioning {
+            Partitioning::None => self.root.clone(),
+            Partitioning::Symbol | Partitioning::SymbolDate => self
+                .root
+                .join(format!("symbol={}", symbol.replace([':', '/', '\\'], "_"))),
+        }
+    }
+
+    fn partition_dir(&self, symbol: &str, timestamp: i64) -> PathBuf {
+        let dir = self.symbol_dir(symbol);
+        match self.partitioning {
+            Partitioning::SymbolDate => {
+                let date = DateTime::from_timestamp(timestamp, 0)
+                    .map_or(NaiveDate::MIN, |dt| dt.date_naive());
+                dir.join(format!("date={date}"))
+            }
+            _ => dir,
+        }
+    }
+}
+
+/// Files below `dir` ending in `.{extension}`, sorted by path.
+fn parts(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(parts(&path, extension)?);
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(&format!(".{extension}")))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_last_timestamp(path: &Path, symbol: &str) -> Result<Option<i64>> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+    let mut last = None;
+    for batch in reader {
+        let batch = batch?;
+        let (Some(symbols), Some(times)) = (
+            batch
+                .column_by_name("symbol")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>()),
+            batch
+                .column_by_name("time")
+                .and_then(|c| c.as_any().downcast_ref::<TimestampMillisecondArray>()),
+        ) else {
+            continue;
+        };
+        for i in 0..batch.num_rows() {
+            if symbols.value(i) == symbol && !times.is_null(i) {
+                last = last.max(Some(times.value(i) / 1000));
+            }
+        }
+    }
+    Ok(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(time: i64) -> DataPoint {
+        DataPoint {
+            index: 0,
+            value: vec![time as f64, 1.0, 2.0, 0.5, 1.5, 10.0],
+        }
+    }
+
+    #[test]
+    fn test_resume_skips_archived_bars() {
+        let root = std::env::temp_dir().join(format!("tv-parquet-{}", rand::random::<u64>()));
+        let day = 86_400;
+        let build = || {
+            ParquetSink::builder()
+                .root(&root)
+                .partitioning(Partitioning::SymbolDate)
+                .build()
+        };
+
+        let mut sink = build();
+        assert_eq!(
+            sink.write("BINANCE:BTCUSDT", &[bar(0), bar(60), bar(day)])
+                .unwrap(),
+            3
+        );
+        sink.finish().unwrap();
+
+        let mut sink = build();
+        assert_eq!(sink.last_timestamp("BINANCE:BTCUSDT").unwrap(), Some(day));
+        assert_eq!(
+            sink.write("BINANCE:BTCUSDT", &[bar(day), bar(day + 60)])
+                .unwrap(),
+            1
+        );
+        sink.finish().unwrap();
+
+        let dir = root.join("symbol=BINANCE_BTCUSDT/date=1970-01-02");
+        assert_eq!(parts(&dir, PART_EXTENSION).unwrap().len(), 2);
+        assert!(parts(&root, TMP_EXTENSION).unwrap().is_empty());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    DataPoint, OHLCV, Result, StudyOptions, StudyResponseData,
+    export::sink::{DataSink, finite, series_symbol},
+    quote::state::QuoteSnapshot,
+    websocket::SeriesInfo,
+};
+use bon::bon;
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use tracing::info;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS tv_bars (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    time TIMESTAMPTZ NOT NULL,
+    open DOUBLE PRECISION,
+    high DOUBLE PRECISION,
+    low DOUBLE PRECISION,
+    close DOUBLE PRECISION,
+    volume DOUBLE PRECISION,
+    PRIMARY KEY (symbol, interval, time)
+);
+CREATE TABLE IF NOT EXISTS tv_quotes (
+    symbol TEXT NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL,
+    time TIMESTAMPTZ,
+    price DOUBLE PRECISION,
+    bid DOUBLE PRECISION,
+    ask DOUBLE PRECISION,
+    bid_size DOUBLE PRECISION,
+    ask_size DOUBLE PRECISION,
+    volume DOUBLE PRECISION,
+    change DOUBLE PRECISION,
+    change_percent DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS tv_quotes_symbol ON tv_quotes (symbol, received_at);
+CREATE TABLE IF NOT EXISTS tv_studies (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    script_id TEXT NOT NULL,
+    time TIMESTAMPTZ NOT NULL,
+    plot INTEGER NOT NULL,
+    value DOUBLE PRECISION,
+    PRIMARY KEY (symbol, interval, script_id, time, plot)
+);
+"#;
+
+const HYPERTABLES: &str = r#"
+CREATE EXTENSION IF NOT EXISTS timescaledb;
+SELECT create_hypertable('tv_bars', 'time', if_not_exists => TRUE, migrate_data => TRUE);
+SELECT create_hypertable('tv_quotes', 'received_at', if_not_exists => TRUE, migrate_data => TRUE);
+SELECT create_hypertable('tv_studies', 'time', if_not_exists => TRUE, migrate_data => TRUE);
+"#;
+
+fn datetime(timestamp: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(timestamp, 0)
+}
+
+/// [`DataSink`] storing into the `tv_bars`, `tv_quotes` and `tv_studies`
+/// tables of a Postgres database, optionally as TimescaleDB hypertables.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{export::postgres::PostgresSink, live::handler::types::TradingViewHandler};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let sink = PostgresSink::connect()
+///         .url("postgres://localhost/market")
+///         .timescale(true)
+///         .call()
+///         .await?;
+///     let handler = TradingViewHandler::default().with_sink(sink);
+///     # let _ = handler;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PostgresSink {
+    pool: PgPool,
+}
+
+#[bon]
+impl PostgresSink {
+    /// Connect to `url` and create missing tables.
+    #[builder]
+    pub async fn connect(
+        url: &str,
+        /// Turn the tables into TimescaleDB hypertables.
+        #[builder(default)]
+        timescale: bool,
+        #[builder(default = 4)] max_connections: u32,
+    ) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(url)
+            .await?;
+        Self::from_pool(pool, timescale).await
+    }
+}
+
+impl PostgresSink {
+    pub async fn from_pool(pool: PgPool, timescale: bool) -> Result<Self> {
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+        if timescale {
+            sqlx::raw_sql(HYPERTABLES).execute(&pool).await?;
+            info!("stored tables are TimescaleDB hypertables");
+        }
+        Ok(Self { pool })
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+impl DataSink for PostgresSink {
+    fn write_bars<'a>(
+        &'a self,
+        series: &'a SeriesInfo,
+        bars: &'a [DataPoint],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let symbol = series_symbol(series);
+            let interval = series.options.interval.to_string();
+            let mut tx = self.pool.begin().await?;
+            for bar in bars {
+                sqlx::query(
+                    "INSERT INTO tv_bars (symbol, interval, time, open, high, low, close, volume)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT (symbol, interval, time) DO UPDATE SET
+                        open = excluded.open, high = excluded.high, low = excluded.low,
+                        close = excluded.close, volume = excluded.volume",
+                )
+                .bind(&symbol)
+                .bind(&interval)
+                .bind(datetime(bar.timestamp()))
+                .bind(finite(bar.open()))
+                .bind(finite(bar.high()))
+                .bind(finite(bar.low()))
+                .bind(finite(bar.close()))
+                .bind(finite(bar.volume()))
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+
+    fn write_quotes<'a>(&'a self, quotes: &'a [QuoteSnapshot]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let received_at = Utc::now();
+            let mut tx = self.pool.begin().await?;
+            for quote in quotes {
+                let value = &quote.value;
+                sqlx::query(
+                    "INSERT INTO tv_quotes (symbol, received_at, time, price, bid, ask, bid_size,
+                        ask_size, volume, change, change_percent)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                )
+                .bind(quote.symbol.as_str())
+                .bind(received_at)
+                .bind(value.timestamp.and_then(|t| datetime(t as i64)))
+                .bind(value.price)
+                .bind(value.bid)
+                .bind(value.ask)
+                .bind(value.bid_size)
+                .bind(value.ask_size)
+                .bind(value.volume)
+                .bind(value.change)
+                .bind(value.change_percent)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+
+    fn write_study<'a>(
+        &'a self,
+        study: &'a StudyOptions,
+        data: &'a StudyResponseData,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let interval = data.interval.to_string();
+            let mut tx = self.pool.begin().await?;
+            for point in &data.studies {
+                for (plot, value) in point.value.iter().enumerate().skip(1) {
+                    sqlx::query(
+                        "INSERT INTO tv_studies (symbol, interval, script_id, time, plot, value)
+                         VALUES ($1, $2, $3, $4, $5, $6)
+                         ON CONFLICT (symbol, interval, script_id, time, plot)
+                         DO UPDATE SET value = excluded.value",
+                    )
+                    .bind(data.symbol.as_str())
+                    .bind(&interval)
+                    .bind(study.script_id.as_str())
+                    .bind(datetime(point.timestamp()))
+                    .bind(plot as i32 - 1)
+                    .bind(finite(*value))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+}
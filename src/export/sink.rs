@@ -0,0 +1,121 @@
+use crate::{
+    DataPoint, Result, StudyOptions, StudyResponseData, live::handler::types::TradingViewHandler,
+    quote::state::QuoteSnapshot, websocket::SeriesInfo,
+};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use tracing::error;
+
+/// Storage backend for a live feed, attached with [`TradingViewHandler::with_sink`].
+///
+/// Bars of the same series are sent again while they are still forming, so
+/// implementations should upsert them by symbol, interval and time.
+pub trait DataSink: Send + Sync {
+    fn write_bars<'a>(
+        &'a self,
+        series: &'a SeriesInfo,
+        bars: &'a [DataPoint],
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// Only called when [`crate::quote::options::QuoteSessionOptions::emit_snapshots`] is set.
+    fn write_quotes<'a>(&'a self, _quotes: &'a [QuoteSnapshot]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// `data` carries the symbol and interval of the series the study runs on.
+    fn write_study<'a>(
+        &'a self,
+        _study: &'a StudyOptions,
+        _data: &'a StudyResponseData,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// `NaN` and infinite values are stored as `NULL`.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn finite(value: f64) -> Option<f64> {
+    value.is_finite().then_some(value)
+}
+
+/// `EXCHANGE:SYMBOL` of a chart series, as stored by the bundled sinks.
+pub fn series_symbol(series: &SeriesInfo) -> String {
+    format!("{}:{}", series.options.exchange, series.options.symbol)
+}
+
+/// A handler writing chart, quote snapshot and study events into `sink`.
+///
+/// Writes are awaited before the next event is processed, failures are logged.
+pub fn sink_handler(sink: impl DataSink + 'static) -> TradingViewHandler {
+    let sink: Arc<dyn DataSink> = Arc::new(sink);
+    let bars = Arc::clone(&sink);
+    let quotes = Arc::clone(&sink);
+    TradingViewHandler::default()
+        .on_chart_data_async(move |_, (series, data)| {
+            let sink = Arc::clone(&bars);
+            async move {
+                if let Err(e) = sink.write_bars(&series, &data).await {
+                    error!("failed to store bars of {}: {e}", series_symbol(&series));
+                }
+            }
+        })
+        .on_quote_snapshot_async(move |_, snapshot| {
+            let sink = Arc::clone(&quotes);
+            async move {
+                if let Err(e) = sink.write_quotes(&[snapshot]).await {
+                    error!("failed to store quote of {}: {e}", snapshot.symbol);
+                }
+            }
+        })
+        .on_study_data_async(move |_, (study, data)| {
+            let sink = Arc::clone(&sink);
+            async move {
+                if let Err(e) = sink.write_study(&study, &data).await {
+                    error!("failed to store study {}: {e}", study.script_id);
+                }
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live::handler::types::dispatch;
+    use std::sync::Mutex;
+
+    struct MemorySink(Arc<Mutex<Vec<(String, usize)>>>);
+
+    impl DataSink for MemorySink {
+        fn write_bars<'a>(
+            &'a self,
+            series: &'a SeriesInfo,
+            bars: &'a [DataPoint],
+        ) -> BoxFuture<'a, Result<()>> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((series_symbol(series), bars.len()));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_handler_writes_bars() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let handler = TradingViewHandler::default().with_sink(MemorySink(Arc::clone(&written)));
+
+        let mut series = SeriesInfo::default();
+        series.options.exchange = "BINANCE".into();
+        series.options.symbol = "BTCUSDT".into();
+        dispatch(
+            &handler.on_chart_data,
+            (series, vec![DataPoint::default(); 2].into()),
+        )
+        .await;
+
+        assert_eq!(
+            *written.lock().unwrap(),
+            vec![("BINANCE:BTCUSDT".to_string(), 2)]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    DataPoint, OHLCV, Result, StudyOptions, StudyResponseData,
+    export::sink::{DataSink, finite, series_symbol},
+    quote::state::QuoteSnapshot,
+    websocket::SeriesInfo,
+};
+use futures_util::future::BoxFuture;
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::str::FromStr;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS tv_bars (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    open REAL,
+    high REAL,
+    low REAL,
+    close REAL,
+    volume REAL,
+    PRIMARY KEY (symbol, interval, time)
+);
+CREATE TABLE IF NOT EXISTS tv_quotes (
+    symbol TEXT NOT NULL,
+    received_at INTEGER NOT NULL,
+    time REAL,
+    price REAL,
+    bid REAL,
+    ask REAL,
+    bid_size REAL,
+    ask_size REAL,
+    volume REAL,
+    change REAL,
+    change_percent REAL
+);
+CREATE INDEX IF NOT EXISTS tv_quotes_symbol ON tv_quotes (symbol, received_at);
+CREATE TABLE IF NOT EXISTS tv_studies (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    script_id TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    plot INTEGER NOT NULL,
+    value REAL,
+    PRIMARY KEY (symbol, interval, script_id, time, plot)
+);
+"#;
+
+/// [`DataSink`] storing into the `tv_bars`, `tv_quotes` and `tv_studies`
+/// tables of a SQLite database, with times in unix seconds.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{export::sqlite::SqliteSink, live::handler::types::TradingViewHandler};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let handler = TradingViewHandler::default()
+///         .with_sink(SqliteSink::connect("sqlite://feed.db").await?);
+///     # let _ = handler;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteSink {
+    pool: SqlitePool,
+}
+
+impl SqliteSink {
+    /// Open or create the database at `url` and create missing tables.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        // A single connection keeps `sqlite::memory:` databases shared and
+        // writes serialized
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+        Self::from_pool(pool).await
+    }
+
+    pub async fn from_pool(pool: SqlitePool) -> Result<Self> {
+        sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+impl DataSink for SqliteSink {
+    fn write_bars<'a>(
+        &'a self,
+        series: &'a SeriesInfo,
+        bars: &'a [DataPoint],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let symbol = series_symbol(series);
+            let interval = series.options.interval.to_string();
+            let mut tx = self.pool.begin().await?;
+            for bar in bars {
+                sqlx::query(
+                    "INSERT INTO tv_bars (symbol, interval, time, open, high, low, close, volume)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT (symbol, interval, time) DO UPDATE SET
+                        open = excluded.open, high = excluded.high, low = excluded.low,
+                        close = excluded.close, volume = excluded.volume",
+                )
+                .bind(&symbol)
+                .bind(&interval)
+                .bind(bar.timestamp())
+                .bind(finite(bar.open()))
+                .bind(finite(bar.high()))
+                .bind(finite(bar.low()))
+                .bind(finite(bar.close()))
+                .bind(finite(bar.volume()))
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+
+    fn write_quotes<'a>(&'a self, quotes: &'a [QuoteSnapshot]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let received_at = chrono::Utc::now().timestamp();
+            let mut tx = self.pool.begin().await?;
+            for quote in quotes {
+                let value = &quote.value;
+                sqlx::query(
+                    "INSERT INTO tv_quotes (symbol, received_at, time, price, bid, ask, bid_size,
+                        ask_size, volume, change, change_percent)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(quote.symbol.as_str())
+                .bind(received_at)
+                .bind(value.timestamp)
+                .bind(value.price)
+                .bind(value.bid)
+                .bind(value.ask)
+                .bind(value.bid_size)
+                .bind(value.ask_size)
+                .bind(value.volume)
+                .bind(value.change)
+                .bind(value.change_percent)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+
+    fn write_study<'a>(
+        &'a self,
+        study: &'a StudyOptions,
+        data: &'a StudyResponseData,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let interval = data.interval.to_string();
+            let mut tx = self.pool.begin().await?;
+            for point in &data.studies {
+                for (plot, value) in point.value.iter().enumerate().skip(1) {
+                    sqlx::query(
+                        "INSERT INTO tv_studies (symbol, interval, script_id, time, plot, value)
+                         VALUES (?, ?, ?, ?, ?, ?)
+                         ON CONFLICT (symbol, interval, script_id, time, plot)
+                         DO UPDATE SET value = excluded.value",
+                    )
+                    .bind(data.symbol.as_str())
+                    .bind(&interval)
+                    .bind(study.script_id.as_str())
+                    .bind(point.timestamp())
+                    .bind(plot as i64 - 1)
+                    .bind(finite(*value))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Interval, chart::GraphicDataResponse};
+
+    #[tokio::test]
+    async fn test_write_bars_upserts() {
+        let sink = SqliteSink::connect("sqlite::memory:").await.unwrap();
+        let series = SeriesInfo::default();
+        let bar = |close: f64| DataPoint {
+            index: 0,
+            value: vec![1_700_000_000.0, 1.0, 2.0, 0.5, close],
+        };
+
+        sink.write_bars(&series, &[bar(1.5)]).await.unwrap();
+        sink.write_bars(&series, &[bar(1.75)]).await.unwrap();
+
+        let rows: Vec<(f64, Option<f64>)> = sqlx::query_as("SELECT close, volume FROM tv_bars")
+            .fetch_all(sink.pool())
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![(1.75, None)]);
+    }
+
+    #[tokio::test]
+    async fn test_write_study_per_series() {
+        let sink = SqliteSink::connect("sqlite::memory:").await.unwrap();
+        let study = StudyOptions::default();
+        let data = |symbol: &str, value: f64| StudyResponseData {
+            study_id: "st1".into(),
+            symbol: symbol.into(),
+            interval: Interval::OneDay,
+            node: None,
+            studies: vec![DataPoint {
+                index: 0,
+                value: vec![1_700_000_000.0, value],
+            }],
+            raw_graphics: GraphicDataResponse {
+                d: "".into(),
+                indexes: serde_json::Value::Null,
+            },
+        };
+
+        sink.write_study(&study, &data("NASDAQ:AAPL", 1.0))
+            .await
+            .unwrap();
+        sink.write_study(&study, &data("NASDAQ:MSFT", 2.0))
+            .await
+            .unwrap();
+        sink.write_study(&study, &data("NASDAQ:AAPL", 3.0))
+            .await
+            .unwrap();
+
+        let rows: Vec<(String, f64)> =
+            sqlx::query_as("SELECT symbol, value FROM tv_studies ORDER BY symbol")
+                .fetch_all(sink.pool())
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("NASDAQ:AAPL".to_string(), 3.0),
+                ("NASDAQ:MSFT".to_string(), 2.0)
+            ]
+        );
+    }
+}
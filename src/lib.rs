@@ -1,9 +1,56 @@
+//! TradingView datafeed client.
+//!
+//! The supported surface is:
+//!
+//! - REST helpers re-exported at the crate root (symbol search, indicators), the news,
+//!   alerts, watchlist, chart layout and sparks APIs in [`client`], and [`history`] for one-shot
+//!   historical downloads, with [`fetch_ohlcv`] as the shortest way to get bars.
+//! - [`websocket::WebSocketClient`] together with [`live::handler::command::CommandRunner`],
+//!   the [`live::handler::message::Command`] / [`live::handler::message::TradingViewResponse`]
+//!   messages, [`live::handler::types::TradingViewHandler`] callbacks and the
+//!   [`live::handler::stream`] adapters, with [`live::handler::channel`] for bounded response
+//!   queues, [`live::session::ChartSessionManager`] for keyed multi-symbol charts and
+//!   [`live::pool::ConnectionPool`] for spreading subscriptions over several connections.
+//! - The data models in [`models`], [`chart`] and [`quote`], plus [`Config`] and [`Error`].
+//! - [`broker::paper`] for simulated order execution on TradingView's Paper Trading account.
+//! - [`screener`] for filtered, sorted and paginated market scans.
+//! - [`option_chain`] for option chains of an underlying.
+//! - [`export`] for writing bars and study outputs as CSV, and [`export::sink::DataSink`] for
+//!   persisting a live feed.
+//! - [`blocking`] with synchronous wrappers for scripts and CLI tools, behind the `blocking`
+//!   feature.
+//! - [`transport`] for plugging another HTTP client or websocket connector into the clients.
+//! - [`server`] with an HTTP gateway serving bars, indicators and the live event stream as
+//!   JSON over REST, server-sent events and websockets, behind the `server` feature.
+//! - The websocket subsystems sit behind the `chart`, `quote`, `pine` and `replay` features,
+//!   all enabled by the default `live` feature.
+//! - [`telemetry`] with the names of the message rate, latency, queue depth and reconnect
+//!   metrics emitted through the `metrics` crate behind the `metrics` feature.
+//!
+//! - With the `wasm` feature on `wasm32-unknown-unknown`, [`websocket::WasmWebSocketClient`] speaks
+//!   the same protocol over the browser's WebSocket and feeds the same handler callbacks. The
+//!   tokio based transports, [`history`] and the command runner need the default `native` feature.
+//!
+//! Protocol internals such as frame parsing and raw session plumbing are crate-private or
+//! `#[doc(hidden)]`. Hidden items are not covered by semver and may change in any release.
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod broker;
 pub mod chart;
 pub mod client;
+pub mod config;
 pub mod error;
+pub mod export;
 pub mod models;
+pub mod option_chain;
 pub mod prelude;
 pub mod quote;
+pub mod screener;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod telemetry;
+pub mod transport;
 
 #[cfg(feature = "user")]
 pub mod user;
@@ -12,10 +59,16 @@ mod utils;
 static UA: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/138.0.0.0 Safari/537.36";
 
 pub use crate::client::misc::*;
+pub use crate::client::paginator::{Page, Paginator};
 
-pub use chart::history;
+#[cfg(all(feature = "native", feature = "chart"))]
+pub use chart::history::{self, fetch_ohlcv};
 
 pub mod websocket {
+    pub use crate::live::metadata::SeriesInfo;
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    pub use crate::live::wasm::*;
+    #[cfg(feature = "native")]
     pub use crate::live::websocket::*;
 }
 
@@ -24,9 +77,11 @@ pub use crate::models::*;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+pub use config::Config;
 pub use error::Error;
 
 // Re-exporting some commonly used types
 pub use iso_currency::{Country, Currency, CurrencySymbol};
+pub use tokio_util::sync::CancellationToken;
 
 pub mod live;
@@ -0,0 +1,361 @@
+//! Client side of permessage-deflate (RFC 7692) below `tungstenite`, which
+//! rejects frames with the compression bit set.
+//!
+//! [`InflateStream`] passes the handshake response through, and once the
+//! server accepted the extension, rewrites every compressed message into
+//! plain frames before `tungstenite` parses them. Messages sent by the client
+//! stay uncompressed, which the extension allows.
+
+use flate2::{Decompress, FlushDecompress};
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Extension offered in `Sec-WebSocket-Extensions`.
+pub(crate) const OFFER: &str = "permessage-deflate";
+
+/// Inflated size above which a message is refused, as a guard against
+/// compression bombs.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// Tail the sender strips from every compressed message.
+const SYNC_TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Reading the HTTP response of the handshake.
+    Handshake,
+    /// The server accepted the extension.
+    Inflate,
+    /// The server declined it, frames pass unchanged.
+    Passthrough,
+}
+
+/// Wraps the socket of a websocket client that offered [`OFFER`].
+pub(crate) struct InflateStream<S> {
+    inner: S,
+    state: State,
+    /// Bytes read from `inner` that do not form a complete frame yet.
+    input: Vec<u8>,
+    /// Rewritten bytes waiting to be read, from `output_pos` on.
+    output: Vec<u8>,
+    output_pos: usize,
+    /// The data frames being received belong to a compressed message.
+    compressed: bool,
+    /// Inflated size of the message being received.
+    message_size: usize,
+    /// Kept across messages, the server may refer to earlier ones.
+    inflater: Decompress,
+}
+
+impl<S> InflateStream<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            state: State::Handshake,
+            input: Vec::new(),
+            output: Vec::new(),
+            output_pos: 0,
+            compressed: false,
+            message_size: 0,
+            inflater: Decompress::new(false),
+        }
+    }
+
+    /// Move what can be rewritten from `input` to `output`, returns whether
+    /// anything was moved.
+    fn process(&mut self) -> io::Result<bool> {
+        match self.state {
+            State::Handshake => {
+                let Some(end) = self.input.windows(4).position(|w| w == b"\r\n\r\n") else {
+                    return Ok(false);
+                };
+                let response: Vec<u8> = self.input.drain(..end + 4).collect();
+                self.state = if accepts_deflate(&response) {
+                    State::Inflate
+                } else {
+                    State::Passthrough
+                };
+                self.output.extend_from_slice(&response);
+                Ok(true)
+            }
+            State::Passthrough if self.input.is_empty() => Ok(false),
+            State::Passthrough => {
+                self.output.append(&mut self.input);
+                Ok(true)
+            }
+            State::Inflate => {
+                let Some(frame) = FrameHeader::parse(&self.input) else {
+                    return Ok(false);
+                };
+                if frame.payload_len > MAX_MESSAGE_SIZE {
+                    return Err(too_large());
+                }
+                let end = frame.header_len + frame.payload_len;
+                if self.input.len() < end {
+                    return Ok(false);
+                }
+                let is_data = frame.opcode < 0x8;
+                if frame.opcode == 0x1 || frame.opcode == 0x2 {
+                    self.compressed = frame.rsv1;
+                    self.message_size = 0;
+                }
+                if !is_data || !self.compressed || frame.masked {
+                    self.output.extend(self.input.drain(..end));
+                    return Ok(true);
+                }
+
+                let raw: Vec<u8> = self.input.drain(..end).collect();
+                let mut payload = Vec::with_capacity(frame.payload_len * 2);
+                self.inflate(&raw[frame.header_len..], &mut payload)?;
+                if frame.fin {
+                    self.inflate(&SYNC_TAIL, &mut payload)?;
+                    self.compressed = false;
+                }
+                self.message_size += payload.len();
+                // Same frame without the compression bit
+                self.output.push(raw[0] & !0x40);
+                write_len(&mut self.output, payload.len());
+                self.output.extend_from_slice(&payload);
+                Ok(true)
+            }
+        }
+    }
+
+    fn inflate(&mut self, mut input: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        loop {
+            if out.capacity() - out.len() < 4096 {
+                out.reserve(input.len().max(4096));
+            }
+            let (total_in, total_out) = (self.inflater.total_in(), self.inflater.total_out());
+            self.inflater
+                .decompress_vec(input, out, FlushDecompress::Sync)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let consumed = (self.inflater.total_in() - total_in) as usize;
+            let produced = self.inflater.total_out() - total_out;
+            input = &input[consumed..];
+            if self.message_size + out.len() > MAX_MESSAGE_SIZE {
+                return Err(too_large());
+            }
+            // Done once the input is used up and the output was not cut short
+            if (input.is_empty() && out.len() < out.capacity()) || (consumed == 0 && produced == 0)
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "websocket message too large")
+}
+
+/// `true` if the handshake `response` accepted [`OFFER`].
+fn accepts_deflate(response: &[u8]) -> bool {
+    String::from_utf8_lossy(response).lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("sec-websocket-extensions")
+                && value
+                    .split(',')
+                    .any(|extension| extension.trim().starts_with(OFFER))
+        })
+    })
+}
+
+/// The fields of a frame header needed to rewrite it.
+struct FrameHeader {
+    fin: bool,
+    rsv1: bool,
+    opcode: u8,
+    masked: bool,
+    header_len: usize,
+    payload_len: usize,
+}
+
+impl FrameHeader {
+    /// `None` until `input` holds the whole header.
+    fn parse(input: &[u8]) -> Option<Self> {
+        let (&first, &second) = (input.first()?, input.get(1)?);
+        let masked = second & 0x80 != 0;
+        let (len_bytes, payload_len) = match second & 0x7f {
+            126 => (
+                2,
+                u16::from_be_bytes(input.get(2..4)?.try_into().ok()?) as usize,
+            ),
+            127 => (
+                8,
+                u64::from_be_bytes(input.get(2..10)?.try_into().ok()?) as usize,
+            ),
+            len => (0, len as usize),
+        };
+        let header_len = 2 + len_bytes + if masked { 4 } else { 0 };
+        (input.len() >= header_len).then_some(Self {
+            fin: first & 0x80 != 0,
+            rsv1: first & 0x40 != 0,
+            opcode: first & 0x0f,
+            masked,
+            header_len,
+            payload_len,
+        })
+    }
+}
+
+/// Append the unmasked payload length of a frame header.
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    if len < 126 {
+        out.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(126);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(127);
+        out.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for InflateStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.output_pos < this.output.len() {
+                let n = buf.remaining().min(this.output.len() - this.output_pos);
+                buf.put_slice(&this.output[this.output_pos..this.output_pos + n]);
+                this.output_pos += n;
+                if this.output_pos == this.output.len() {
+                    this.output.clear();
+                    this.output_pos = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if this.process()? {
+                continue;
+            }
+
+            let mut chunk = [0u8; 8192];
+            let mut read = ReadBuf::new(&mut chunk);
+            match Pin::new(&mut this.inner).poll_read(cx, &mut read) {
+                Poll::Ready(Ok(())) if read.filled().is_empty() => {
+                    // Leave incomplete frames to tungstenite to report
+                    if this.input.is_empty() {
+                        return Poll::Ready(Ok(()));
+                    }
+                    this.output.append(&mut this.input);
+                }
+                Poll::Ready(Ok(())) => this.input.extend_from_slice(read.filled()),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for InflateStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compress, Compression, FlushCompress};
+    use tokio::io::AsyncReadExt;
+
+    /// `text` compressed the way a server does, without the sync tail.
+    fn deflate(compressor: &mut Compress, text: &str) -> Vec<u8> {
+        let mut out = Vec::with_capacity(text.len() + 64);
+        compressor
+            .compress_vec(text.as_bytes(), &mut out, FlushCompress::Sync)
+            .unwrap();
+        assert!(out.ends_with(&SYNC_TAIL));
+        out.truncate(out.len() - SYNC_TAIL.len());
+        out
+    }
+
+    fn frame(first: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![first];
+        write_len(&mut out, payload.len());
+        out.extend_from_slice(payload);
+        out
+    }
+
+    async fn rewrite(response: &str, frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = response.as_bytes().to_vec();
+        frames.iter().for_each(|f| bytes.extend_from_slice(f));
+        // One byte per read to split headers and frames everywhere
+        let (mut server, client) = tokio::io::duplex(1);
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            server.write_all(&bytes).await.unwrap();
+        });
+        let mut out = Vec::new();
+        InflateStream::new(client)
+            .read_to_end(&mut out)
+            .await
+            .unwrap();
+        out
+    }
+
+    #[tokio::test]
+    async fn test_inflates_compressed_messages() {
+        let accepted = "HTTP/1.1 101 Switching Protocols\r\n\
+                        Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n";
+        let mut compressor = Compress::new(Compression::default(), false);
+        let first = deflate(&mut compressor, "~m~5~m~hello");
+        // The second message refers back to the first one
+        let second = deflate(&mut compressor, "~m~5~m~hello");
+        let (part, rest) = second.split_at(second.len() / 2);
+        let frames = [
+            frame(0x80 | 0x40 | 0x1, &first),
+            frame(0x80 | 0x9, b"ping"),
+            frame(0x40 | 0x1, part),
+            frame(0x80, rest),
+            frame(0x80 | 0x1, b"plain"),
+        ];
+
+        let mut expected = accepted.as_bytes().to_vec();
+        expected.extend(frame(0x80 | 0x1, b"~m~5~m~hello"));
+        expected.extend(frame(0x80 | 0x9, b"ping"));
+        let out = rewrite(accepted, &frames).await;
+        assert!(out.starts_with(&expected));
+        // A fragmented message keeps its fragments, inflated
+        let fragments = &out[expected.len()..];
+        let (first_fragment, tail) = fragments.split_at(2 + fragments[1] as usize);
+        assert_eq!(first_fragment[0], 0x1);
+        let (last_fragment, tail) = tail.split_at(2 + tail[1] as usize);
+        assert_eq!(last_fragment[0], 0x80);
+        assert_eq!(
+            [&first_fragment[2..], &last_fragment[2..]].concat(),
+            b"~m~5~m~hello"
+        );
+        assert_eq!(tail, frame(0x80 | 0x1, b"plain"));
+    }
+
+    #[tokio::test]
+    async fn test_passes_frames_when_declined() {
+        let declined = "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n";
+        let frames = [frame(0x80 | 0x1, b"~m~5~m~hello")];
+        let mut expected = declined.as_bytes().to_vec();
+        expected.extend(&frames[0]);
+        assert_eq!(rewrite(declined, &frames).await, expected);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ustr::Ustr;
+
+/// One price level of an order book side.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: f64,
+    /// Size resting at `price`, `0` in an incremental update removes the level.
+    pub size: f64,
+}
+
+impl DepthLevel {
+    /// Parse `[price, size]` or `{"p": price, "v": size}`.
+    fn parse(value: &Value) -> Option<Self> {
+        let (price, size) = match value {
+            Value::Array(level) => (level.first()?, level.get(1)?),
+            Value::Object(level) => (level.get("p")?, level.get("v").or(level.get("s"))?),
+            _ => return None,
+        };
+        Some(Self {
+            price: price.as_f64()?,
+            size: size.as_f64().unwrap_or_default(),
+        })
+    }
+}
+
+/// Order book event of a depth session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthUpdate {
+    pub session: Ustr,
+    /// Symbol the session was subscribed to, empty if it is unknown.
+    pub symbol: Ustr,
+    /// The levels replace the whole book rather than update it.
+    pub snapshot: bool,
+    /// Best bid first.
+    pub bids: Vec<DepthLevel>,
+    /// Best ask first.
+    pub asks: Vec<DepthLevel>,
+}
+
+impl DepthUpdate {
+    /// Parse the payload of a `dpd` snapshot or `dpu` update event.
+    pub fn new(messages: &[Value], symbol: Ustr, snapshot: bool) -> Result<Self> {
+        let session = messages
+            .first()
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Internal(Ustr::from("depth message has no session")))?;
+        let data = messages.get(1).unwrap_or(&Value::Null);
+        // Levels are sent either at the top level or nested under `dom`
+        let book = data.get("dom").unwrap_or(data);
+        let side = |name: &str| -> Vec<DepthLevel> {
+            book.get(name)
+                .and_then(Value::as_array)
+                .map(|levels| levels.iter().filter_map(DepthLevel::parse).collect())
+                .unwrap_or_default()
+        };
+        let mut update = Self {
+            session: Ustr::from(session),
+            symbol,
+            snapshot,
+            bids: side("bids"),
+            asks: side("asks"),
+        };
+        update.bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        update.asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+        Ok(update)
+    }
+}
+
+/// Order book rebuilt from the [`DepthUpdate`]s of one depth session.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::live::depth::{DepthLevel, DepthUpdate, OrderBook};
+///
+/// let level = |price, size| DepthLevel { price, size };
+/// let mut book = OrderBook::default();
+/// book.apply(&DepthUpdate {
+///     session: "dp_1".into(),
+///     symbol: "CME_MINI:ES1!".into(),
+///     snapshot: true,
+///     bids: vec![level(99.75, 5.0), level(99.5, 8.0)],
+///     asks: vec![level(100.0, 3.0)],
+/// });
+///
+/// assert_eq!(book.spread(), Some(0.25));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    /// Best bid first.
+    pub bids: Vec<DepthLevel>,
+    /// Best ask first.
+    pub asks: Vec<DepthLevel>,
+}
+
+impl OrderBook {
+    pub fn apply(&mut self, update: &DepthUpdate) {
+        if update.snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+        for level in &update.bids {
+            Self::upsert(&mut self.bids, *level, |a, b| b.total_cmp(&a));
+        }
+        for level in &update.asks {
+            Self::upsert(&mut self.asks, *level, |a, b| a.total_cmp(&b));
+        }
+    }
+
+    fn upsert(
+        side: &mut Vec<DepthLevel>,
+        level: DepthLevel,
+        order: impl Fn(f64, f64) -> std::cmp::Ordering,
+    ) {
+        match side.binary_search_by(|l| order(l.price, level.price)) {
+            Ok(i) if level.size == 0.0 => {
+                side.remove(i);
+            }
+            Ok(i) => side[i] = level,
+            Err(_) if level.size == 0.0 => {}
+            Err(i) => side.insert(i, level),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<DepthLevel> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<DepthLevel> {
+        self.asks.first().copied()
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_depth_update_into_book() {
+        let snapshot = DepthUpdate::new(
+            &[
+                json!("dp_abc"),
+                json!({"dom": {"bids": [[99.5, 8], [99.75, 5]], "asks": [{"p": 100.0, "v": 3}]}}),
+            ],
+            Ustr::from("CME_MINI:ES1!"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(snapshot.bids[0].price, 99.75);
+
+        let mut book = OrderBook::default();
+        book.apply(&snapshot);
+        let update = DepthUpdate::new(
+            &[
+                json!("dp_abc"),
+                json!({"bids": [[99.75, 0], [99.6, 2]], "asks": [[100.25, 4]]}),
+            ],
+            Ustr::from("CME_MINI:ES1!"),
+            false,
+        )
+        .unwrap();
+        book.apply(&update);
+
+        let prices = |side: &[DepthLevel]| side.iter().map(|l| l.price).collect::<Vec<_>>();
+        assert_eq!(prices(&book.bids), vec![99.6, 99.5]);
+        assert_eq!(prices(&book.asks), vec![100.0, 100.25]);
+        assert!((book.spread().unwrap() - 0.4).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::Result;
+use futures_util::future::BoxFuture;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+use tracing::error;
+
+/// Ids remembered per symbol, enough to cover several pages of a feed.
+const SEEN_CAPACITY: usize = 1024;
+
+type FetchFn<T> = Box<dyn Fn(String) -> BoxFuture<'static, Result<Vec<T>>> + Send + Sync>;
+
+/// Ids of the items already seen for one symbol, the oldest are forgotten
+/// once `capacity` is reached.
+#[derive(Debug)]
+struct SeenIds<K> {
+    ids: HashSet<K>,
+    order: VecDeque<K>,
+    capacity: usize,
+}
+
+impl<K: Eq + Hash + Clone> SeenIds<K> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns `true` if `id` was not seen before.
+    fn insert(&mut self, id: K) -> bool {
+        if !self.ids.insert(id.clone()) {
+            return false;
+        }
+        self.order.push_back(id);
+        if self.order.len() > self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.ids.remove(&oldest);
+        }
+        true
+    }
+}
+
+/// Polls a per-symbol feed and returns the items that were not seen before.
+///
+/// The first successful poll of a symbol only records what is already
+/// published, so a stream never replays the backlog of a symbol, even when
+/// its first polls fail.
+pub(crate) struct FeedPoller<T, K> {
+    symbols: Vec<String>,
+    fetch: FetchFn<T>,
+    key: fn(&T) -> K,
+    seen: HashMap<String, SeenIds<K>>,
+    capacity: usize,
+}
+
+impl<T, K: Eq + Hash + Clone> FeedPoller<T, K> {
+    pub(crate) fn new<F>(symbols: &[&str], key: fn(&T) -> K, fetch: F) -> Self
+    where
+        F: Fn(String) -> BoxFuture<'static, Result<Vec<T>>> + Send + Sync + 'static,
+    {
+        Self {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            fetch: Box::new(fetch),
+            key,
+            seen: HashMap::new(),
+            capacity: SEEN_CAPACITY,
+        }
+    }
+
+    /// Fetch every symbol once and return the new items.
+    pub(crate) async fn poll(&mut self) -> Vec<T> {
+        let mut fresh = Vec::new();
+        for symbol in &self.symbols {
+            let items = match (self.fetch)(symbol.clone()).await {
+                Ok(items) => items,
+                Err(e) => {
+                    error!("failed to poll feed for {symbol}: {e}");
+                    continue;
+                }
+            };
+            let seeded = self.seen.contains_key(symbol);
+            let seen = self
+                .seen
+                .entry(symbol.clone())
+                .or_insert_with(|| SeenIds::new(self.capacity));
+            for item in items {
+                if seen.insert((self.key)(&item)) && seeded {
+                    fresh.push(item);
+                }
+            }
+        }
+        fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use futures_util::FutureExt;
+    use std::sync::{Arc, Mutex};
+    use ustr::Ustr;
+
+    /// Feed serving the queued responses of each symbol in order.
+    fn poller(responses: Vec<(&'static str, Result<Vec<u32>>)>) -> FeedPoller<u32, u32> {
+        let mut symbols: Vec<&str> = Vec::new();
+        for (symbol, _) in &responses {
+            if !symbols.contains(symbol) {
+                symbols.push(symbol);
+            }
+        }
+        let responses = Arc::new(Mutex::new(responses));
+        FeedPoller::new(
+            &symbols,
+            |id| *id,
+            move |symbol| {
+                let mut responses = responses.lock().unwrap_or_else(|e| e.into_inner());
+                let i = responses
+                    .iter()
+                    .position(|(s, _)| *s == symbol)
+                    .expect("no response queued");
+                let (_, response) = responses.remove(i);
+                async move { response }.boxed()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_seeds_each_symbol_on_first_success() {
+        let failed = Err(Error::Internal(Ustr::from("unavailable")));
+        let mut feed = poller(vec![
+            ("AAPL", Ok(vec![1, 2])),
+            ("TSLA", failed),
+            ("AAPL", Ok(vec![1, 2, 3])),
+            ("TSLA", Ok(vec![10, 11])),
+            ("AAPL", Ok(vec![3, 4])),
+            ("TSLA", Ok(vec![11, 12])),
+        ]);
+
+        assert!(feed.poll().await.is_empty());
+        // TSLA's backlog is recorded, not replayed, on its first success
+        assert_eq!(feed.poll().await, vec![3]);
+        assert_eq!(feed.poll().await, vec![4, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_seen_ids_are_bounded() {
+        let mut feed = poller(vec![
+            ("AAPL", Ok(vec![1, 2])),
+            ("AAPL", Ok(vec![3])),
+            ("AAPL", Ok(vec![1, 3])),
+        ]);
+        feed.capacity = 2;
+
+        assert!(feed.poll().await.is_empty());
+        assert_eq!(feed.poll().await, vec![3]);
+        // 1 was evicted to make room for 3
+        assert_eq!(feed.poll().await, vec![1]);
+        assert_eq!(feed.seen["AAPL"].ids.len(), 2);
+    }
+}
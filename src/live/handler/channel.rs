@@ -0,0 +1,297 @@
+use crate::{live::handler::message::TradingViewResponse, telemetry};
+use arc_swap::{ArcSwapOption, Guard};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+use tokio::sync::{
+    broadcast,
+    mpsc::{self, UnboundedSender, error::TrySendError},
+};
+use tracing::Instrument;
+
+/// What a bounded response channel does when the consumer falls behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BackpressurePolicy {
+    /// Wait for free capacity, which pauses reading from the websocket.
+    #[default]
+    Block,
+    /// Discard the oldest queued response to make room.
+    DropOldest,
+    /// Discard the response that did not fit.
+    DropNewest,
+}
+
+/// Snapshot of a response channel's counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueMetrics {
+    /// Responses currently queued, `None` for unbounded channels.
+    pub depth: Option<usize>,
+    /// Maximum queue length, `None` for unbounded channels.
+    pub capacity: Option<usize>,
+    /// Highest depth observed so far.
+    pub high_water_mark: usize,
+    pub sent: u64,
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    high_water_mark: AtomicUsize,
+}
+
+impl Counters {
+    fn record_sent(&self, depth: Option<usize>) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        if let Some(depth) = depth {
+            self.high_water_mark.fetch_max(depth, Ordering::Relaxed);
+            telemetry::queue_depth(depth);
+        }
+    }
+
+    fn record_dropped(&self, count: u64) {
+        self.dropped.fetch_add(count, Ordering::Relaxed);
+        telemetry::queue_dropped(count);
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Sender {
+    Unbounded(UnboundedSender<TradingViewResponse>),
+    Bounded {
+        tx: mpsc::Sender<TradingViewResponse>,
+        block: bool,
+    },
+    Ring(broadcast::Sender<TradingViewResponse>, usize),
+}
+
+/// Sending half of the response channel handed to the websocket client.
+///
+/// Created from an unbounded sender via [`From`], or with [`bounded_channel`].
+#[derive(Debug, Clone)]
+pub struct DataTx {
+    /// Shared by every clone, `None` once [`DataTx::close`] dropped the sender.
+    sender: Arc<ArcSwapOption<Sender>>,
+    counters: Arc<Counters>,
+}
+
+impl From<UnboundedSender<TradingViewResponse>> for DataTx {
+    fn from(tx: UnboundedSender<TradingViewResponse>) -> Self {
+        Self::new(Sender::Unbounded(tx), Arc::default())
+    }
+}
+
+impl DataTx {
+    fn new(sender: Sender, counters: Arc<Counters>) -> Self {
+        Self {
+            sender: Arc::new(ArcSwapOption::from_pointee(sender)),
+            counters,
+        }
+    }
+
+    fn sender(&self) -> Guard<Option<Arc<Sender>>> {
+        self.sender.load()
+    }
+
+    fn depth(&self) -> Option<usize> {
+        Self::depth_of(self.sender().as_deref()?)
+    }
+
+    fn depth_of(sender: &Sender) -> Option<usize> {
+        match sender {
+            Sender::Unbounded(_) => None,
+            Sender::Bounded { tx, .. } => Some(tx.max_capacity() - tx.capacity()),
+            Sender::Ring(tx, _) => Some(tx.len()),
+        }
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        match self.sender().as_deref()? {
+            Sender::Unbounded(_) => None,
+            Sender::Bounded { tx, .. } => Some(tx.max_capacity()),
+            Sender::Ring(_, capacity) => Some(*capacity),
+        }
+    }
+
+    /// Drop the sender shared by every clone, so the receiver completes once
+    /// it has drained the queued responses. Later responses are discarded.
+    pub(crate) fn close(&self) {
+        self.sender.store(None);
+    }
+
+    pub fn metrics(&self) -> QueueMetrics {
+        QueueMetrics {
+            depth: self.depth(),
+            capacity: self.capacity(),
+            high_water_mark: self.counters.high_water_mark.load(Ordering::Relaxed),
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Queue `response`, returning a future to await only when a blocking
+    /// channel is full.
+    #[tracing::instrument(name = "forward", level = "trace", skip_all)]
+    pub(crate) fn forward(&self, response: TradingViewResponse) -> Option<BoxFuture<'static, ()>> {
+        let closed = || tracing::error!("Failed to forward response: receiver dropped");
+        let sender = self.sender();
+        let Some(sender) = sender.as_deref() else {
+            tracing::debug!("Discarding response after the data channel was closed");
+            return None;
+        };
+        match sender {
+            Sender::Unbounded(tx) => match tx.send(response) {
+                Ok(()) => self.counters.record_sent(None),
+                Err(_) => closed(),
+            },
+            Sender::Bounded { tx, block } => match tx.try_send(response) {
+                Ok(()) => self.counters.record_sent(Self::depth_of(sender)),
+                Err(TrySendError::Full(response)) if *block => {
+                    let tx = tx.clone();
+                    let counters = Arc::clone(&self.counters);
+                    return Some(Box::pin(
+                        async move {
+                            match tx.send(response).await {
+                                Ok(()) => counters.record_sent(Some(tx.max_capacity())),
+                                Err(_) => {
+                                    tracing::error!("Failed to forward response: receiver dropped")
+                                }
+                            }
+                        }
+                        .in_current_span(),
+                    ));
+                }
+                Err(TrySendError::Full(_)) => self.counters.record_dropped(1),
+                Err(TrySendError::Closed(_)) => closed(),
+            },
+            Sender::Ring(tx, _) => match tx.send(response) {
+                Ok(_) => self.counters.record_sent(Self::depth_of(sender)),
+                Err(_) => closed(),
+            },
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+enum Receiver {
+    Bounded(mpsc::Receiver<TradingViewResponse>),
+    Ring(broadcast::Receiver<TradingViewResponse>),
+}
+
+/// Receiving half of a [`bounded_channel`].
+#[derive(Debug)]
+pub struct BoundedDataRx {
+    receiver: Receiver,
+    counters: Arc<Counters>,
+}
+
+impl BoundedDataRx {
+    /// Receive the next response, or `None` once every [`DataTx`] was dropped.
+    pub async fn recv(&mut self) -> Option<TradingViewResponse> {
+        match &mut self.receiver {
+            Receiver::Bounded(rx) => rx.recv().await,
+            Receiver::Ring(rx) => loop {
+                match rx.recv().await {
+                    Ok(response) => return Some(response),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        self.counters.record_dropped(skipped)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        }
+    }
+}
+
+/// Create a response channel holding at most `capacity` responses.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn bounded_channel(capacity: usize, policy: BackpressurePolicy) -> (DataTx, BoundedDataRx) {
+    let counters = Arc::<Counters>::default();
+    let (sender, receiver) = match policy {
+        BackpressurePolicy::Block | BackpressurePolicy::DropNewest => {
+            let (tx, rx) = mpsc::channel(capacity);
+            let block = policy == BackpressurePolicy::Block;
+            (Sender::Bounded { tx, block }, Receiver::Bounded(rx))
+        }
+        BackpressurePolicy::DropOldest => {
+            let (tx, rx) = broadcast::channel(capacity);
+            (Sender::Ring(tx, capacity), Receiver::Ring(rx))
+        }
+    };
+    (
+        DataTx::new(sender, Arc::clone(&counters)),
+        BoundedDataRx { receiver, counters },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ustr::Ustr;
+
+    fn response(session: &str) -> TradingViewResponse {
+        TradingViewResponse::UnknownEvent(Ustr::from(session), Vec::new())
+    }
+
+    fn session(response: Option<TradingViewResponse>) -> Ustr {
+        match response {
+            Some(TradingViewResponse::UnknownEvent(session, _)) => session,
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_discards_overflow() {
+        let (tx, mut rx) = bounded_channel(1, BackpressurePolicy::DropNewest);
+        assert!(tx.forward(response("a")).is_none());
+        assert!(tx.forward(response("b")).is_none());
+
+        let metrics = tx.metrics();
+        assert_eq!(metrics.depth, Some(1));
+        assert_eq!(metrics.dropped, 1);
+        assert_eq!(session(rx.recv().await), Ustr::from("a"));
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_latest() {
+        let (tx, mut rx) = bounded_channel(2, BackpressurePolicy::DropOldest);
+        for s in ["a", "b", "c"] {
+            assert!(tx.forward(response(s)).is_none());
+        }
+
+        assert_eq!(session(rx.recv().await), Ustr::from("b"));
+        assert_eq!(tx.metrics().dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_capacity() {
+        let (tx, mut rx) = bounded_channel(1, BackpressurePolicy::Block);
+        assert!(tx.forward(response("a")).is_none());
+        let pending = tx.forward(response("b")).expect("channel is full");
+
+        assert_eq!(session(rx.recv().await), Ustr::from("a"));
+        pending.await;
+        assert_eq!(session(rx.recv().await), Ustr::from("b"));
+        assert_eq!(tx.metrics().dropped, 0);
+    }
+
+    #[tokio::test]
+    async fn test_close_completes_receiver() {
+        let (tx, mut rx) = bounded_channel(4, BackpressurePolicy::DropNewest);
+        let clone = tx.clone();
+        assert!(tx.forward(response("a")).is_none());
+        clone.close();
+        assert!(tx.forward(response("b")).is_none());
+
+        assert_eq!(session(rx.recv().await), Ustr::from("a"));
+        assert!(rx.recv().await.is_none());
+    }
+}
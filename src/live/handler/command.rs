@@ -1,4 +1,9 @@
-use crate::{Error, Result, error::TradingViewError, live::handler::message::Command};
+use crate::{
+    Error, Result,
+    config::{Config, ReconnectConfig, ThrottleConfig},
+    error::TradingViewError,
+    live::handler::message::Command,
+};
 use std::{collections::VecDeque, sync::Arc};
 use tokio::{
     select,
@@ -64,7 +69,7 @@ enum ErrorSeverity {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct ExponentialBackoff {
+pub(crate) struct ExponentialBackoff {
     config: BackoffConfig,
     current_delay: Duration,
     attempts: usize,
@@ -72,11 +77,11 @@ struct ExponentialBackoff {
 
 #[derive(Debug, Clone, Copy)]
 pub struct BackoffConfig {
-    initial_delay: Duration,
-    max_delay: Duration,
-    max_attempts: usize,
-    multiplier: f64,
-    jitter_percent: f64,
+    pub(crate) initial_delay: Duration,
+    pub(crate) max_delay: Duration,
+    pub(crate) max_attempts: usize,
+    pub(crate) multiplier: f64,
+    pub(crate) jitter_percent: f64,
 }
 
 impl Default for BackoffConfig {
@@ -92,7 +97,7 @@ impl Default for BackoffConfig {
 }
 
 impl ExponentialBackoff {
-    fn new(config: BackoffConfig) -> Self {
+    pub(crate) fn new(config: BackoffConfig) -> Self {
         Self {
             current_delay: config.initial_delay,
             config,
@@ -100,7 +105,7 @@ impl ExponentialBackoff {
         }
     }
 
-    fn next_backoff(&mut self) -> Option<Duration> {
+    pub(crate) fn next_backoff(&mut self) -> Option<Duration> {
         if self.attempts >= self.config.max_attempts {
             return None;
         }
@@ -167,14 +172,14 @@ impl CommandQueue {
             let max_normal_capacity = self.max_size / 2;
 
             // Drop the oldest normal command if queue is full
-            if self.normal_queue.len() >= max_normal_capacity {
-                if let Some(_dropped) = self.normal_queue.pop_front() {
-                    self.dropped_count += 1;
-                    warn!(
-                        "Dropped normal command due to queue overflow (total dropped: {})",
-                        self.dropped_count
-                    );
-                }
+            if self.normal_queue.len() >= max_normal_capacity
+                && let Some(_dropped) = self.normal_queue.pop_front()
+            {
+                self.dropped_count += 1;
+                warn!(
+                    "Dropped normal command due to queue overflow (total dropped: {})",
+                    self.dropped_count
+                );
             }
 
             self.normal_queue.push_back(cmd);
@@ -227,6 +232,7 @@ impl CommandQueue {
                 | Command::Delete
                 | Command::Ping
                 | Command::CreateQuoteSession
+                | Command::CreateQuoteSessionWith { .. }
                 | Command::CreateChartSession { .. }
         )
     }
@@ -348,6 +354,10 @@ pub struct CommandRunnerConfig {
     pub max_queue_size: usize,
     pub backoff_config: BackoffConfig,
     pub health_check_timeout: Duration,
+    /// Applied to the client when the runner is created, `None` keeps the
+    /// throttle the client was built with. Converting a [`Config`] whose
+    /// throttle is the default gives `None`.
+    pub throttle: Option<ThrottleConfig>,
 }
 
 impl Default for CommandRunnerConfig {
@@ -360,6 +370,33 @@ impl Default for CommandRunnerConfig {
             max_queue_size: 100,
             backoff_config: BackoffConfig::default(),
             health_check_timeout: Duration::from_secs(60),
+            throttle: None,
+        }
+    }
+}
+
+impl From<&ReconnectConfig> for BackoffConfig {
+    fn from(config: &ReconnectConfig) -> Self {
+        Self {
+            initial_delay: Duration::from_millis(config.initial_delay_ms),
+            max_delay: Duration::from_secs(config.max_delay_secs),
+            max_attempts: config.max_attempts,
+            multiplier: config.multiplier,
+            jitter_percent: config.jitter_percent,
+        }
+    }
+}
+
+impl From<&Config> for CommandRunnerConfig {
+    fn from(config: &Config) -> Self {
+        Self {
+            heartbeat_interval: config.keepalive.ping_interval(),
+            command_timeout: config.timeouts.command(),
+            reconnect_timeout: config.timeouts.reconnect(),
+            backoff_config: BackoffConfig::from(&config.reconnect),
+            // A default config has no throttle of its own to apply
+            throttle: (config.throttle != ThrottleConfig::default()).then_some(config.throttle),
+            ..Default::default()
         }
     }
 }
@@ -386,6 +423,9 @@ impl CommandRunner {
         ws: Arc<WebSocketClient>,
         config: CommandRunnerConfig,
     ) -> Self {
+        if let Some(throttle) = config.throttle {
+            ws.set_throttle(throttle);
+        }
         Self {
             rx,
             ws,
@@ -399,6 +439,14 @@ impl CommandRunner {
         }
     }
 
+    /// Tie the runner to an external token, cancelling it shuts the runner down.
+    ///
+    /// The runner uses a child of `token`, so its own shutdown never cancels the caller's token.
+    pub fn with_cancellation_token(mut self, token: &CancellationToken) -> Self {
+        self.shutdown = token.child_token();
+        self
+    }
+
     // #[instrument(skip(self), fields(runner_id = %std::ptr::addr_of!(*self) as usize))]
     pub async fn run(mut self) -> Result<()> {
         let mut hb = interval(self.config.heartbeat_interval);
@@ -425,6 +473,10 @@ impl CommandRunner {
                 },
 
                 cmd = self.rx.recv() => match cmd {
+                    Some(Command::Shutdown) => {
+                        self.drain().await;
+                        break;
+                    },
                     Some(cmd) => {
                         let start = Instant::now();
                         match self.handle_command(cmd, &mut backoff).await {
@@ -474,11 +526,11 @@ impl CommandRunner {
             }
 
             // Handle disconnection state
-            if self.state.status == ConnectionStatus::Disconnected {
-                if let Err(e) = self.handle_reconnection(&mut backoff).await {
-                    error!("Reconnection failed: {}", e);
-                    break;
-                }
+            if self.state.status == ConnectionStatus::Disconnected
+                && let Err(e) = self.handle_reconnection(&mut backoff).await
+            {
+                error!("Reconnection failed: {}", e);
+                break;
             }
         }
 
@@ -495,12 +547,12 @@ impl CommandRunner {
         }
 
         // Check if connection has been unhealthy for too long
-        if let Some(time_since_success) = self.state.time_since_last_success() {
-            if time_since_success > self.config.health_check_timeout {
-                return Err(Error::Internal(
-                    format!("No successful operations for {time_since_success:?}").into(),
-                ));
-            }
+        if let Some(time_since_success) = self.state.time_since_last_success()
+            && time_since_success > self.config.health_check_timeout
+        {
+            return Err(Error::Internal(
+                format!("No successful operations for {time_since_success:?}").into(),
+            ));
         }
 
         // Actively test the connection with a ping
@@ -540,6 +592,7 @@ impl CommandRunner {
         }
 
         // Create a quote session if not already created
+        #[cfg(feature = "quote")]
         if self.ws.quote_session.read().await.is_empty() {
             info!("Creating initial quote session");
             self.ws.create_quote_session().await?;
@@ -588,6 +641,11 @@ impl CommandRunner {
                                 },
                                 Err(e) => {
                                     error!("WebSocket reader task failed: {}", e);
+                                    // A closed socket is reopened by the runner, which starts a new reader
+                                    if ws.is_closed().await {
+                                        info!("WebSocket closed, stopping reader task");
+                                        break;
+                                    }
                                     // Small delay before retrying
                                     sleep(Duration::from_secs(1)).await;
                                 }
@@ -651,6 +709,8 @@ impl CommandRunner {
                     self.cleanup().await;
                     Ok(())
                 }
+                // Drained by the run loop, a queued duplicate has nothing left to do
+                Shutdown => Ok(()),
                 SetAuthToken { auth_token } => {
                     self.ws.set_auth_token(&auth_token).await?;
                     Ok(())
@@ -663,45 +723,65 @@ impl CommandRunner {
                     self.ws.set_data_quality(&quality).await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 SetTimeZone { session, timezone } => {
                     self.ws.set_timezone(&session, timezone).await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
                 CreateQuoteSession => {
                     self.ws.create_quote_session().await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
+                CreateQuoteSessionWith { options } => {
+                    self.ws.create_quote_session_with(options).await?;
+                    Ok(())
+                }
+                #[cfg(feature = "quote")]
                 DeleteQuoteSession => {
                     self.ws.delete_quote_session().await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
                 SetQuoteFields => {
                     self.ws.set_fields().await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
                 FastSymbols { symbols } => {
                     let symbols: Vec<_> = symbols.into_iter().map(|s| s.as_str()).collect();
                     self.ws.fast_symbols(&symbols).await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
                 AddSymbols { symbols } => {
                     let symbols: Vec<_> = symbols.into_iter().map(|s| s.as_str()).collect();
                     self.ws.add_symbols(&symbols).await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
                 RemoveSymbols { symbols } => {
                     let symbols: Vec<_> = symbols.into_iter().map(|s| s.as_str()).collect();
                     self.ws.remove_symbols(&symbols).await?;
                     Ok(())
                 }
+                #[cfg(feature = "quote")]
+                RemoveQuoteSymbol { symbol } => {
+                    self.ws.remove_symbols(&[symbol.as_str()]).await?;
+                    Ok(())
+                }
+                #[cfg(feature = "chart")]
                 CreateChartSession { session } => {
                     self.ws.create_chart_session(&session).await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 DeleteChartSession { session } => {
                     self.ws.delete_chart_session(&session).await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 RequestMoreData {
                     session,
                     series_id,
@@ -712,6 +792,7 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 RequestMoreTickMarks {
                     session,
                     series_id,
@@ -722,37 +803,43 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "pine")]
                 CreateStudy {
                     session,
                     study_id,
                     series_id,
                     indicator,
+                    inputs,
                 } => {
                     self.ws
-                        .create_study(&session, &study_id, &series_id, indicator)
+                        .create_study_with(&session, &study_id, &series_id, indicator, &inputs)
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "pine")]
                 ModifyStudy {
                     session,
                     study_id,
                     series_id,
                     indicator,
+                    inputs,
                 } => {
                     self.ws
-                        .modify_study(&session, &study_id, &series_id, indicator)
+                        .modify_study_with(&session, &study_id, &series_id, indicator, &inputs)
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "pine")]
                 RemoveStudy {
                     session,
                     study_id,
-                    series_id,
+                    series_id: _,
                 } => {
-                    let id = format!("{series_id}_{study_id}");
-                    self.ws.remove_study(&session, &id).await?;
+                    // Studies are created under their bare id, see `create_study`
+                    self.ws.remove_study(&session, &study_id).await?;
                     Ok(())
                 }
+                #[cfg(feature = "pine")]
                 SetStudy {
                     study_options,
                     session,
@@ -763,6 +850,7 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 CreateSeries {
                     session,
                     series_id,
@@ -781,36 +869,45 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 ModifySeries {
-                    session,
                     series_id,
-                    series_version,
-                    series_symbol_id,
-                    config,
+                    interval,
+                    bar_count,
                 } => {
                     self.ws
-                        .modify_series(
-                            &session,
-                            &series_id,
-                            &series_version,
-                            &series_symbol_id,
-                            config,
-                        )
+                        .set_series_interval(&series_id, interval, bar_count)
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 RemoveSeries { session, series_id } => {
                     self.ws.remove_series(&session, &series_id).await?;
                     Ok(())
                 }
+                CreateDepthSession { session } => {
+                    self.ws.create_depth_session(&session).await?;
+                    Ok(())
+                }
+                DeleteDepthSession { session } => {
+                    self.ws.delete_depth_session(&session).await?;
+                    Ok(())
+                }
+                SetDepthSymbol { session, symbol } => {
+                    self.ws.set_depth_symbol(&session, &symbol).await?;
+                    Ok(())
+                }
+                #[cfg(feature = "replay")]
                 CreateReplaySession { session } => {
                     self.ws.create_replay_session(&session).await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 DeleteReplaySession { session } => {
                     self.ws.delete_replay_session(&session).await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 ResolveSymbol {
                     session,
                     symbol,
@@ -829,6 +926,7 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 SetReplayStep {
                     session,
                     series_id,
@@ -837,6 +935,7 @@ impl CommandRunner {
                     self.ws.replay_step(&session, &series_id, step).await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 StartReplay {
                     session,
                     series_id,
@@ -845,10 +944,12 @@ impl CommandRunner {
                     self.ws.replay_start(&session, &series_id, interval).await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 StopReplay { session, series_id } => {
                     self.ws.replay_stop(&session, &series_id).await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 ResetReplay {
                     session,
                     series_id,
@@ -859,6 +960,7 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "replay")]
                 SetReplay {
                     symbol,
                     options,
@@ -870,10 +972,20 @@ impl CommandRunner {
                         .await?;
                     Ok(())
                 }
+                #[cfg(feature = "chart")]
                 SetMarket { options } => {
                     self.ws.set_market(options).await?;
                     Ok(())
                 }
+                #[cfg(not(all(
+                    feature = "chart",
+                    feature = "quote",
+                    feature = "pine",
+                    feature = "replay"
+                )))]
+                cmd => Err(Error::Config(ustr(&format!(
+                    "{cmd:?} needs a disabled cargo feature"
+                )))),
             }
         })
         .await;
@@ -887,6 +999,7 @@ impl CommandRunner {
             Command::SetAuthToken { .. }
                 | Command::Delete
                 | Command::CreateQuoteSession
+                | Command::CreateQuoteSessionWith { .. }
                 | Command::CreateChartSession { .. }
         )
     }
@@ -914,7 +1027,10 @@ impl CommandRunner {
                 backoff.remaining_attempts()
             );
 
-            sleep(delay).await;
+            select! {
+                _ = sleep(delay) => {},
+                _ = self.shutdown.cancelled() => {},
+            }
 
             if self.shutdown.is_cancelled() {
                 self.state.transition_to(ConnectionStatus::Shutdown);
@@ -1069,6 +1185,43 @@ impl CommandRunner {
         info!("CommandRunner final stats: {:?}", self.stats);
     }
 
+    /// Graceful part of [`Command::Shutdown`], `cleanup` runs after it.
+    #[instrument(skip(self))]
+    async fn drain(&mut self) {
+        info!("Graceful shutdown requested, draining");
+        // Senders fail from now on, commands already buffered are still flushed
+        self.rx.close();
+        let mut pending = self.command_queue.drain();
+        while let Ok(cmd) = self.rx.try_recv() {
+            pending.push(cmd);
+        }
+
+        if self.state.status == ConnectionStatus::Connected {
+            for cmd in pending {
+                if let Err(e) = self.process_command(cmd).await {
+                    warn!("Failed to flush command during shutdown: {}", e);
+                }
+            }
+        } else if !pending.is_empty() {
+            warn!(
+                "Dropping {} pending commands, the connection is down",
+                pending.len()
+            );
+        }
+        self.state.transition_to(ConnectionStatus::Shutdown);
+
+        if let Err(e) = timeout(Duration::from_secs(5), self.ws.delete()).await {
+            warn!("WebSocket cleanup timeout: {:?}", e);
+        }
+        // The reader ends on the close frame, after forwarding everything before it
+        if let Some(handle) = self.reader_handle.take()
+            && timeout(Duration::from_secs(5), handle).await.is_err()
+        {
+            warn!("Reader task did not finish after the socket was closed");
+        }
+        self.ws.close_data_channel();
+    }
+
     #[instrument(skip(self))]
     async fn cleanup(&mut self) {
         info!("Cleaning up CommandRunner");
@@ -1088,7 +1241,10 @@ impl CommandRunner {
             self.command_queue.clear();
         }
 
-        // Cleanup WebSocket with timeout
+        // Cleanup WebSocket with timeout, unless a drain already closed it
+        if self.ws.is_closed().await {
+            return;
+        }
         if let Err(e) = timeout(Duration::from_secs(5), self.ws.delete()).await {
             warn!("WebSocket cleanup timeout: {:?}", e);
         }
@@ -1115,3 +1271,220 @@ impl CommandRunner {
         self.command_queue.stats()
     }
 }
+
+#[cfg(all(test, feature = "chart", feature = "quote"))]
+mod tests {
+    use super::*;
+    use crate::{
+        ChartOptions, DataPoint, Interval,
+        live::{
+            handler::{
+                message::TradingViewResponse,
+                types::{CommandTx, DataRx},
+            },
+            mock::{MockFrame, MockScript, MockServer},
+        },
+        websocket::WebSocketClient,
+    };
+    use serde_json::json;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    async fn start_runner(server: &MockServer) -> (CommandTx, DataRx, JoinHandle<Result<()>>) {
+        let (data_tx, data_rx) = unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(data_tx)
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+        let config = CommandRunnerConfig {
+            health_check_interval: Duration::from_millis(50),
+            backoff_config: BackoffConfig {
+                initial_delay: Duration::from_millis(10),
+                max_attempts: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (cmd_tx, cmd_rx) = unbounded_channel();
+        let runner = CommandRunner::with_config(cmd_rx, ws, config);
+        (cmd_tx, data_rx, tokio::spawn(runner.run()))
+    }
+
+    async fn wait_for<T>(
+        rx: &mut DataRx,
+        mut pick: impl FnMut(TradingViewResponse) -> Option<T>,
+    ) -> T {
+        timeout(Duration::from_secs(5), async {
+            while let Some(response) = rx.recv().await {
+                if let Some(value) = pick(response) {
+                    return value;
+                }
+            }
+            panic!("data channel closed");
+        })
+        .await
+        .expect("timed out waiting for a response")
+    }
+
+    #[test]
+    fn test_config_keeps_the_client_throttle() {
+        assert_eq!(CommandRunnerConfig::from(&Config::default()).throttle, None);
+        let throttle = ThrottleConfig {
+            quotes_per_second: 4,
+            chart_per_second: 1,
+        };
+        let config = Config::builder().throttle(throttle).build();
+        assert_eq!(CommandRunnerConfig::from(&config).throttle, Some(throttle));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_restores_subscriptions() {
+        let script = MockScript::new()
+            .quotes(vec![("NASDAQ:AAPL", json!({ "lp": 190.5 }))])
+            .chart(
+                json!({ "name": "AAPL", "exchange": "NASDAQ", "pro_name": "NASDAQ:AAPL" }),
+                vec![DataPoint {
+                    index: 0,
+                    value: vec![1.7e9, 1.0, 2.0, 0.5, 1.5, 100.0],
+                }],
+            );
+        let server = MockServer::start(script).await.unwrap();
+        let (cmd_tx, mut rx, runner) = start_runner(&server).await;
+
+        cmd_tx.send(Command::add_symbol("NASDAQ:AAPL")).unwrap();
+        cmd_tx
+            .send(Command::set_market(ChartOptions::new_with(
+                "AAPL",
+                "NASDAQ",
+                Interval::OneDay,
+            )))
+            .unwrap();
+        wait_for(&mut rx, |r| match r {
+            TradingViewResponse::SeriesCompleted(_) => Some(()),
+            _ => None,
+        })
+        .await;
+
+        let before = server.received().await.len();
+        server.disconnect();
+
+        let reconnected = wait_for(&mut rx, |r| match r {
+            TradingViewResponse::Reconnected(msg) => Some(msg),
+            _ => None,
+        })
+        .await;
+        assert_eq!(reconnected.symbols, 1);
+        assert_eq!(reconnected.series, 1);
+
+        // The replayed series streams again on the new connection, and the
+        // server answers frames in order, so everything before it was received
+        wait_for(&mut rx, |r| match r {
+            TradingViewResponse::SeriesCompleted(_) => Some(()),
+            _ => None,
+        })
+        .await;
+        let replayed: Vec<MockFrame> = server.received().await.split_off(before);
+        let sent = |method: &str| replayed.iter().any(|f| f.method.as_str() == method);
+        assert!(sent("set_auth_token"));
+        assert!(sent("quote_create_session"));
+        assert!(sent("chart_create_session"));
+        assert!(sent("create_series"));
+        assert!(replayed.iter().any(|f| {
+            f.method.as_str() == "quote_add_symbols" && f.params[1] == json!("NASDAQ:AAPL")
+        }));
+
+        runner.abort();
+    }
+
+    #[tokio::test]
+    async fn test_modify_series_keeps_the_series_ids() {
+        let script = MockScript::new().chart(
+            json!({ "name": "AAPL", "exchange": "NASDAQ", "pro_name": "NASDAQ:AAPL" }),
+            vec![DataPoint {
+                index: 0,
+                value: vec![1.7e9, 1.0, 2.0, 0.5, 1.5, 100.0],
+            }],
+        );
+        let server = MockServer::start(script).await.unwrap();
+        let (cmd_tx, mut rx, runner) = start_runner(&server).await;
+
+        cmd_tx
+            .send(Command::set_market(ChartOptions::new_with(
+                "AAPL",
+                "NASDAQ",
+                Interval::OneDay,
+            )))
+            .unwrap();
+        let completed = wait_for(&mut rx, |r| match r {
+            TradingViewResponse::SeriesCompleted(msg) => Some(msg),
+            _ => None,
+        })
+        .await;
+        let created = server
+            .received()
+            .await
+            .into_iter()
+            .find(|f| f.method.as_str() == "create_series")
+            .unwrap();
+
+        cmd_tx
+            .send(Command::modify_series(
+                completed.series_id,
+                Interval::OneWeek,
+                None,
+            ))
+            .unwrap();
+        let modified = timeout(Duration::from_secs(5), async {
+            loop {
+                let received = server.received().await;
+                if let Some(frame) = received
+                    .into_iter()
+                    .find(|f| f.method.as_str() == "modify_series")
+                {
+                    return frame;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("modify_series was not sent");
+        // Same session, series, version and symbol ids, only the interval changes
+        assert_eq!(modified.params[..4], created.params[..4]);
+        assert_eq!(modified.params[4], json!("1W"));
+
+        runner.abort();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_and_completes_the_data_channel() {
+        let script = MockScript::new().quotes(vec![("NASDAQ:AAPL", json!({ "lp": 190.5 }))]);
+        let server = MockServer::start(script).await.unwrap();
+        let (cmd_tx, mut rx, runner) = start_runner(&server).await;
+
+        // Both are buffered before the runner gets to them
+        cmd_tx.send(Command::add_symbol("NASDAQ:AAPL")).unwrap();
+        cmd_tx.send(Command::Shutdown).unwrap();
+
+        timeout(Duration::from_secs(5), runner)
+            .await
+            .expect("runner did not stop")
+            .unwrap()
+            .unwrap();
+        assert!(cmd_tx.send(Command::add_symbol("NASDAQ:MSFT")).is_err());
+
+        // The receiver completes instead of waiting for a sender that never sends
+        let mut quoted = false;
+        timeout(Duration::from_secs(5), async {
+            while let Some(response) = rx.recv().await {
+                quoted |= matches!(response, TradingViewResponse::QuoteData(_));
+            }
+        })
+        .await
+        .expect("data channel was not closed");
+        assert!(quoted);
+        assert!(server.received().await.iter().any(|f| {
+            f.method.as_str() == "quote_add_symbols" && f.params[1] == json!("NASDAQ:AAPL")
+        }));
+    }
+}
@@ -1,105 +1,464 @@
+use dashmap::DashMap;
 use serde::Deserialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tracing::{debug, error, info, warn};
 use ustr::Ustr;
 
+#[cfg(feature = "replay")]
+use crate::live::handler::message::{
+    ReplayDataEnd, ReplayInstanceId, ReplayOk, ReplayPoint, ReplayResolutions,
+};
 use crate::{
-    ChartResponseData, Error, QuoteData, QuoteValue, Result, StudyOptions, StudyResponseData,
-    SymbolInfo,
+    ChartResponseData, DataPoint, Error, OHLCV, QuoteData, QuoteValue, Result, SymbolInfo,
+    config::ThrottleConfig,
     error::TradingViewError,
     live::{
-        handler::types::{DataTx, TradingViewHandler, create_handler},
+        depth::DepthUpdate,
+        handler::{
+            message::{BarMsg, SeriesCompletedMsg, StudyCompletedMsg},
+            types::{DataTx, EventMask, TradingViewHandler, create_handler, dispatch},
+        },
+        metadata::{Metadata, SeriesInfo},
         models::TradingViewDataEvent,
     },
-    quote::utils::merge_quotes,
-    websocket::Metadata,
+    quote::state::QuoteSnapshot,
+    telemetry::EventTimer,
 };
+#[cfg(feature = "pine")]
+use crate::{StudyOptions, StudyResponseData, chart::strategy::StrategyReport};
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct DataHandler {
     pub(crate) metadata: Metadata,
     pub(crate) handler: TradingViewHandler,
+    pub(crate) res_tx: Arc<DataTx>,
+    quote_conflation: Arc<QuoteConflation>,
+    throttle: Arc<UpdateThrottle>,
+    /// Bits of the [`EventMask`] of classes to parse and dispatch.
+    event_mask: Arc<AtomicU32>,
+    emit_quote_snapshots: Arc<AtomicBool>,
+    /// Forming bar of every series, to tell bar updates from bar closes.
+    last_bars: Arc<DashMap<Ustr, DataPoint>>,
+    /// Plain symbol of every `=`-prefixed quote name, parsed once per name.
+    quote_names: Arc<DashMap<Ustr, Ustr>>,
+}
+
+/// Per-symbol coalescing of quote callbacks, disabled while the window is zero.
+///
+/// The first update of a window is emitted right away, later ones are merged
+/// and flushed once the window ends.
+#[derive(Default)]
+struct QuoteConflation {
+    window_nanos: AtomicU64,
+    flush_trades: AtomicBool,
+    symbols: DashMap<Ustr, ConflationState>,
+}
+
+#[derive(Default)]
+struct ConflationState {
+    last_emit: Option<Instant>,
+    /// A flush is scheduled for the end of the window.
+    pending: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Conflate {
+    Emit,
+    /// Schedule a flush after the given delay.
+    Flush(Duration),
+    /// Already covered by a scheduled flush.
+    Merge,
+}
+
+impl QuoteConflation {
+    fn window(&self) -> Option<Duration> {
+        match self.window_nanos.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    fn on_update(
+        &self,
+        name: Ustr,
+        value: &QuoteValue,
+        window: Duration,
+        now: Instant,
+    ) -> Conflate {
+        let trade = value.price.is_some() && self.flush_trades.load(Ordering::Relaxed);
+        let mut state = self.symbols.entry(name).or_default();
+        let elapsed = state
+            .last_emit
+            .map_or(window, |last| now.saturating_duration_since(last));
+        if trade || (!state.pending && elapsed >= window) {
+            state.last_emit = Some(now);
+            state.pending = false;
+            Conflate::Emit
+        } else if state.pending {
+            Conflate::Merge
+        } else {
+            state.pending = true;
+            Conflate::Flush(window - elapsed)
+        }
+    }
+
+    /// Returns `true` if the scheduled flush for `name` is still due.
+    fn take_pending(&self, name: Ustr, now: Instant) -> bool {
+        match self.symbols.get_mut(&name) {
+            Some(mut state) if state.pending => {
+                state.last_emit = Some(now);
+                state.pending = false;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Per-symbol cap on quote and chart updates, see [`ThrottleConfig`].
+///
+/// The last update dropped in a window is flushed once the window ends, so
+/// the newest state is always delivered. The clock is only read while a cap
+/// is set.
+#[derive(Default)]
+struct UpdateThrottle {
+    quotes_per_second: AtomicU32,
+    chart_per_second: AtomicU32,
+    quotes: DashMap<Ustr, RateWindow>,
+    /// Keyed by series id.
+    chart: DashMap<Ustr, RateWindow>,
+    /// Newest dropped chart update of every series awaiting its flush.
+    pending_chart: DashMap<Ustr, (SeriesInfo, Arc<[DataPoint]>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RateWindow {
+    start: Instant,
+    count: u32,
+    /// A flush is scheduled for the end of the window.
+    pending: bool,
+}
+
+impl UpdateThrottle {
+    fn set(&self, config: ThrottleConfig) {
+        self.quotes_per_second
+            .store(config.quotes_per_second, Ordering::Relaxed);
+        self.chart_per_second
+            .store(config.chart_per_second, Ordering::Relaxed);
+        self.quotes.clear();
+        self.chart.clear();
+        self.pending_chart.clear();
+    }
+
+    fn limits_quotes(&self) -> bool {
+        self.quotes_per_second.load(Ordering::Relaxed) > 0
+    }
+
+    fn allow_quote(&self, symbol: Ustr, now: impl FnOnce() -> Instant) -> Conflate {
+        let limit = self.quotes_per_second.load(Ordering::Relaxed);
+        Self::allow(&self.quotes, limit, symbol, now)
+    }
+
+    fn allow_chart(&self, series_id: Ustr, now: impl FnOnce() -> Instant) -> Conflate {
+        let limit = self.chart_per_second.load(Ordering::Relaxed);
+        Self::allow(&self.chart, limit, series_id, now)
+    }
+
+    /// Count an update against the one second window of `key`. The first
+    /// update over `limit` schedules a flush for the end of the window.
+    fn allow(
+        windows: &DashMap<Ustr, RateWindow>,
+        limit: u32,
+        key: Ustr,
+        now: impl FnOnce() -> Instant,
+    ) -> Conflate {
+        if limit == 0 {
+            return Conflate::Emit;
+        }
+        let now = now();
+        let mut window = windows.entry(key).or_insert(RateWindow {
+            start: now,
+            count: 0,
+            pending: false,
+        });
+        let elapsed = now.saturating_duration_since(window.start);
+        if elapsed >= Duration::from_secs(1) {
+            *window = RateWindow {
+                start: now,
+                count: 0,
+                pending: false,
+            };
+        }
+        window.count += 1;
+        if window.count <= limit {
+            Conflate::Emit
+        } else if window.pending {
+            Conflate::Merge
+        } else {
+            window.pending = true;
+            Conflate::Flush(Duration::from_secs(1).saturating_sub(elapsed))
+        }
+    }
+
+    /// Returns `true` if the scheduled flush of `key` is still due, the
+    /// flushed update opens the next window.
+    fn take_pending(windows: &DashMap<Ustr, RateWindow>, key: Ustr, now: Instant) -> bool {
+        match windows.get_mut(&key) {
+            Some(mut window) if window.pending => {
+                *window = RateWindow {
+                    start: now,
+                    count: 1,
+                    pending: false,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn take_pending_quote(&self, symbol: Ustr, now: Instant) -> bool {
+        Self::take_pending(&self.quotes, symbol, now)
+    }
+
+    fn take_pending_chart(
+        &self,
+        series_id: Ustr,
+        now: Instant,
+    ) -> Option<(SeriesInfo, Arc<[DataPoint]>)> {
+        if !Self::take_pending(&self.chart, series_id, now) {
+            return None;
+        }
+        self.pending_chart
+            .remove(&series_id)
+            .map(|(_, update)| update)
+    }
+}
+
+/// Bars of a chart update that closed since `previous` was the forming bar,
+/// oldest first, and the bar forming now. Nothing closes on the first update,
+/// which is the history load, and updates older than `previous` are ignored.
+fn track_bars(
+    previous: Option<&DataPoint>,
+    update: &[DataPoint],
+) -> (Vec<DataPoint>, Option<DataPoint>) {
+    let Some(forming) = update.iter().max_by_key(|bar| bar.timestamp()) else {
+        return (Vec::new(), None);
+    };
+    let Some(previous) = previous else {
+        return (Vec::new(), Some(forming.clone()));
+    };
+    let (start, end) = (previous.timestamp(), forming.timestamp());
+    if end < start {
+        return (Vec::new(), None);
+    }
+
+    let mut closed: Vec<DataPoint> = update
+        .iter()
+        .filter(|bar| (start..end).contains(&bar.timestamp()))
+        .cloned()
+        .collect();
+    closed.sort_by_key(|bar| bar.timestamp());
+    if end > start && closed.first().is_none_or(|bar| bar.timestamp() != start) {
+        closed.insert(0, previous.clone());
+    }
+    (closed, Some(forming.clone()))
+}
+
+/// Plain symbol of a quote, whose name echoes the symbol init string for
+/// symbols added with a trading session.
+fn quote_symbol(name: Ustr) -> Ustr {
+    if let Some(init) = name.strip_prefix('=')
+        && let Ok(init) = serde_json::from_str::<Value>(init)
+        && let Some(symbol) = init["symbol"].as_str()
+    {
+        return Ustr::from(symbol);
+    }
+    name
 }
 
 #[bon::bon]
 impl DataHandler {
     #[builder]
-    pub fn new(res_tx: DataTx) -> Self {
+    pub fn new(
+        res_tx: DataTx,
+        handler: Option<TradingViewHandler>,
+        #[builder(default)] throttle: ThrottleConfig,
+        #[builder(default)] event_mask: EventMask,
+    ) -> Self {
         let res_tx = Arc::new(res_tx);
-        let handler: TradingViewHandler = create_handler(res_tx);
+        let forward = create_handler(Arc::clone(&res_tx));
+        let handler = match handler {
+            Some(handler) => forward.chain(handler),
+            None => forward,
+        };
+        let update_throttle = Arc::<UpdateThrottle>::default();
+        update_throttle.set(throttle);
         Self {
             metadata: Metadata::default(),
             handler,
+            res_tx,
+            quote_conflation: Arc::default(),
+            throttle: update_throttle,
+            event_mask: Arc::new(AtomicU32::new(event_mask.bits())),
+            emit_quote_snapshots: Arc::default(),
+            last_bars: Arc::default(),
+            quote_names: Arc::default(),
         }
     }
 
+    #[cfg(feature = "quote")]
+    pub(crate) fn set_quote_conflation(&self, window: Option<Duration>, flush_trades: bool) {
+        let nanos = window.map_or(0, |w| w.as_nanos().min(u64::MAX as u128) as u64);
+        self.quote_conflation
+            .window_nanos
+            .store(nanos, Ordering::Relaxed);
+        self.quote_conflation
+            .flush_trades
+            .store(flush_trades, Ordering::Relaxed);
+        self.quote_conflation.symbols.clear();
+    }
+
+    pub(crate) fn set_throttle(&self, throttle: ThrottleConfig) {
+        self.throttle.set(throttle);
+    }
+
+    pub(crate) fn event_mask(&self) -> EventMask {
+        EventMask::from_bits_truncate(self.event_mask.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set_event_mask(&self, mask: EventMask) {
+        self.event_mask.store(mask.bits(), Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "quote")]
+    pub(crate) fn set_emit_quote_snapshots(&self, enabled: bool) {
+        self.emit_quote_snapshots.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Drop the forming bar of a removed or modified series, so a series
+    /// created later under the same id, or switched to another interval,
+    /// starts from its own history.
+    #[cfg(feature = "chart")]
+    pub(crate) fn forget_bars(&self, series_id: &Ustr) {
+        self.last_bars.remove(series_id);
+    }
+
+    #[tracing::instrument(name = "event", level = "debug", skip_all, fields(event = event.name()))]
     pub(crate) async fn handle_events(&self, event: TradingViewDataEvent, message: &[Value]) {
-        if let Err(e) = self.process_event(event, message).await {
+        let timer = EventTimer::start();
+        let result = self.process_event(event, message).await;
+        let failed = result.is_err();
+        if let Err(e) = result {
             error!("Event processing error: {:?}", e);
-            self.notify_error(e, message);
+            self.notify_error(e, message).await;
         }
+        timer.finish(event.name(), failed);
     }
 
     async fn process_event(&self, event: TradingViewDataEvent, message: &[Value]) -> Result<()> {
+        if !self.event_mask().intersects(event.class()) {
+            return Ok(());
+        }
         match event {
-            TradingViewDataEvent::OnChartData | TradingViewDataEvent::OnChartDataUpdate => {
+            TradingViewDataEvent::OnChartData => {
                 tracing::trace!("received raw chart data: {:?}", message);
-                self.handle_chart_data(message).await
+                self.handle_chart_data(message, true).await
+            }
+            TradingViewDataEvent::OnChartDataUpdate => {
+                tracing::trace!("received raw chart data: {:?}", message);
+                self.handle_chart_data(message, false).await
             }
             TradingViewDataEvent::OnQuoteData => {
                 self.handle_quote_data(message).await;
                 Ok(())
             }
+            TradingViewDataEvent::OnDepthData => self.handle_depth_data(message, true).await,
+            TradingViewDataEvent::OnDepthUpdate => self.handle_depth_data(message, false).await,
             TradingViewDataEvent::OnSymbolResolved => self.handle_symbol_resolved(message).await,
             TradingViewDataEvent::OnSeriesCompleted => {
                 debug!("series completed: {:?}", message);
-                (self.handler.on_series_completed)(message.to_vec());
+                let completed = SeriesCompletedMsg::new(message)?;
+                self.metadata.backfill.remove(&completed.series_id);
+                dispatch(&self.handler.on_series_completed, completed).await;
                 Ok(())
             }
             TradingViewDataEvent::OnSeriesLoading => {
                 debug!("series loading: {:?}", message);
-                (self.handler.on_series_loading)(message.to_vec());
+                dispatch(&self.handler.on_series_loading, message.to_vec()).await;
                 Ok(())
             }
             TradingViewDataEvent::OnQuoteCompleted => {
                 debug!("quote completed: {:?}", message);
-                (self.handler.on_quote_completed)(message.to_vec());
+                dispatch(&self.handler.on_quote_completed, message.to_vec()).await;
                 Ok(())
             }
+            #[cfg(feature = "replay")]
             TradingViewDataEvent::OnReplayOk => {
                 debug!("replay ok: {:?}", message);
-                (self.handler.on_replay_ok)(message.to_vec());
+                dispatch(&self.handler.on_replay_ok, ReplayOk::new(message)?).await;
                 Ok(())
             }
+            #[cfg(feature = "replay")]
             TradingViewDataEvent::OnReplayPoint => {
                 debug!("replay point: {:?}", message);
-                (self.handler.on_replay_point)(message.to_vec());
+                dispatch(&self.handler.on_replay_point, ReplayPoint::new(message)?).await;
                 Ok(())
             }
+            #[cfg(feature = "replay")]
             TradingViewDataEvent::OnReplayInstanceId => {
                 debug!("replay instance id: {:?}", message);
-                (self.handler.on_replay_instance_id)(message.to_vec());
+                dispatch(
+                    &self.handler.on_replay_instance_id,
+                    ReplayInstanceId::new(message)?,
+                )
+                .await;
                 Ok(())
             }
+            #[cfg(feature = "replay")]
             TradingViewDataEvent::OnReplayResolutions => {
                 debug!("replay resolutions: {:?}", message);
-                (self.handler.on_replay_resolutions)(message.to_vec());
+                dispatch(
+                    &self.handler.on_replay_resolutions,
+                    ReplayResolutions::new(message)?,
+                )
+                .await;
                 Ok(())
             }
+            #[cfg(feature = "replay")]
             TradingViewDataEvent::OnReplayDataEnd => {
                 debug!("replay data end: {:?}", message);
-                (self.handler.on_replay_data_end)(message.to_vec());
+                dispatch(
+                    &self.handler.on_replay_data_end,
+                    ReplayDataEnd::new(message)?,
+                )
+                .await;
                 Ok(())
             }
+            #[cfg(not(feature = "replay"))]
+            TradingViewDataEvent::OnReplayOk
+            | TradingViewDataEvent::OnReplayPoint
+            | TradingViewDataEvent::OnReplayInstanceId
+            | TradingViewDataEvent::OnReplayResolutions
+            | TradingViewDataEvent::OnReplayDataEnd => Ok(()),
             TradingViewDataEvent::OnStudyLoading => {
                 debug!("study loading: {:?}", message);
-                (self.handler.on_study_loading)(message.to_vec());
+                dispatch(&self.handler.on_study_loading, message.to_vec()).await;
                 Ok(())
             }
             TradingViewDataEvent::OnStudyCompleted => {
                 debug!("study completed: {:?}", message);
-                (self.handler.on_study_completed)(message.to_vec());
+                dispatch(
+                    &self.handler.on_study_completed,
+                    StudyCompletedMsg::new(message)?,
+                )
+                .await;
                 Ok(())
             }
             TradingViewDataEvent::OnError(tradingview_error) => {
@@ -107,12 +466,12 @@ impl DataHandler {
                 let error = Error::TradingView {
                     source: tradingview_error,
                 };
-                self.notify_error(error, message);
+                self.notify_error(error, message).await;
                 Ok(())
             }
             TradingViewDataEvent::UnknownEvent(event) => {
                 warn!("unknown event: {:?}", event);
-                (self.handler.on_unknown_event)((event, message.to_vec()));
+                dispatch(&self.handler.on_unknown_event, (event, message.to_vec())).await;
                 Ok(())
             }
         }
@@ -123,9 +482,22 @@ impl DataHandler {
             return Ok(());
         }
 
-        let symbol_info = SymbolInfo::deserialize(&message[2])
+        let mut symbol_info = SymbolInfo::deserialize(&message[2])
             .map_err(|e| Error::JsonParse(Ustr::from(&e.to_string())))?;
 
+        // The resolved symbol doesn't echo the adjustment it was requested with
+        if let Some(session) = message[0].as_str()
+            && let Some(series) = self
+                .metadata
+                .series
+                .iter()
+                .find(|series| series.chart_session.as_str() == session)
+        {
+            symbol_info.adjustment = series.options.adjustment;
+            symbol_info.backadjustment = series.options.backadjustment;
+            symbol_info.conversion_currency = series.options.currency;
+        }
+
         debug!("receive symbol info: {:?}", symbol_info);
 
         // Update chart state with shorter lock scope
@@ -134,11 +506,17 @@ impl DataHandler {
             chart_state.symbol_info.replace(symbol_info.clone());
         }
 
-        (self.handler.on_symbol_info)(symbol_info);
+        dispatch(&self.handler.on_symbol_info, symbol_info).await;
         Ok(())
     }
 
-    async fn handle_study_data(&self, options: &StudyOptions, message_data: &Value) -> Result<()> {
+    #[cfg(feature = "pine")]
+    async fn handle_study_data(
+        &self,
+        series: &SeriesInfo,
+        options: &StudyOptions,
+        message_data: &Value,
+    ) -> Result<()> {
         // Pre-allocate vector if we know the capacity
         let studies_len = self.metadata.studies.len();
         if studies_len == 0 {
@@ -152,14 +530,76 @@ impl DataHandler {
                 if tracing::enabled!(tracing::Level::DEBUG) {
                     debug!("study data received: {} - {:?}", k, resp_data);
                 }
-                let data = StudyResponseData::deserialize(resp_data)?;
-                (self.handler.on_study_data)((*options, data));
+                self.dispatch_study(*v, series, options, resp_data).await?;
             }
         }
         Ok(())
     }
 
-    async fn handle_chart_data(&self, message: &[Value]) -> Result<()> {
+    /// Route updates of the studies added with `create_study` on `session`
+    /// by the id they were created under.
+    #[cfg(feature = "pine")]
+    async fn handle_created_studies(&self, session: &str, message_data: &Value) -> Result<()> {
+        let studies: Vec<(Ustr, StudyOptions)> = self
+            .metadata
+            .created_studies
+            .iter()
+            .filter(|study| {
+                study.chart_session.as_str() == session
+                    && message_data.get(study.study_id.as_str()).is_some()
+            })
+            .map(|study| (study.study_id, study.study_options()))
+            .collect();
+        if studies.is_empty() {
+            return Ok(());
+        }
+        let series = self
+            .metadata
+            .series
+            .iter()
+            .find(|series| series.chart_session.as_str() == session)
+            .map(|series| series.value().clone())
+            .unwrap_or_default();
+        for (study_id, options) in &studies {
+            self.dispatch_study(
+                *study_id,
+                &series,
+                options,
+                &message_data[study_id.as_str()],
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "pine")]
+    async fn dispatch_study(
+        &self,
+        study_id: Ustr,
+        series: &SeriesInfo,
+        options: &StudyOptions,
+        resp_data: &Value,
+    ) -> Result<()> {
+        if let Some(report) = StrategyReport::from_study_data(resp_data)? {
+            dispatch(&self.handler.on_strategy_report, (*options, report)).await;
+        }
+        // report-only updates carry no plot values or graphics
+        if resp_data.get("st").is_some() || resp_data.get("ns").is_some() {
+            let mut data = StudyResponseData::deserialize(resp_data)?;
+            data.study_id = study_id;
+            data.symbol = Ustr::from(&format!(
+                "{}:{}",
+                series.options.exchange, series.options.symbol
+            ));
+            data.interval = series.options.interval;
+            dispatch(&self.handler.on_study_data, (*options, data)).await;
+        }
+        Ok(())
+    }
+
+    /// `timescale_update` carries both the initial load and `request_more_data`
+    /// pages, `du` only carries realtime updates.
+    async fn handle_chart_data(&self, message: &[Value], timescale_update: bool) -> Result<()> {
         if message.len() < 2 {
             return Ok(());
         }
@@ -176,31 +616,162 @@ impl DataHandler {
             let (id, series_info) = series_entry.pair();
 
             if let Some(resp_data) = message_data.get(id.as_str()) {
-                let chart_response = ChartResponseData::deserialize(resp_data)?;
-                let data = chart_response.series;
-
-                // Clone series_info once outside the lock
-                let series_info_clone = series_info.clone();
-                let data_clone = data.clone();
-
-                // Update chart state with minimal lock scope
-                let chart_data = {
-                    let mut chart_state = self.metadata.chart_state.write().await;
-                    chart_state
-                        .chart
-                        .replace((series_info_clone.clone(), data_clone.clone()));
-                    (series_info_clone, data_clone)
-                };
+                self.handle_series_data(
+                    *id,
+                    series_info,
+                    resp_data,
+                    message_data,
+                    timescale_update,
+                )
+                .await?;
+            }
+        }
 
-                (self.handler.on_chart_data)(chart_data);
+        #[cfg(feature = "pine")]
+        if self.event_mask().contains(EventMask::STUDY)
+            && let Some(session) = message[0].as_str()
+        {
+            self.handle_created_studies(session, message_data).await?;
+        }
+        Ok(())
+    }
 
-                // Handle study data if present
-                if let Some(study_options) = &series_info.options.study_config {
-                    self.handle_study_data(study_options, message_data).await?;
-                }
+    #[tracing::instrument(
+        name = "series",
+        level = "debug",
+        skip_all,
+        fields(series_id = %id, symbol = %series_info.options.symbol)
+    )]
+    async fn handle_series_data(
+        &self,
+        id: Ustr,
+        series_info: &SeriesInfo,
+        resp_data: &Value,
+        message_data: &Value,
+        timescale_update: bool,
+    ) -> Result<()> {
+        if !self.event_mask().contains(EventMask::CHART) {
+            return self.handle_series_studies(series_info, message_data).await;
+        }
+        let chart_response = ChartResponseData::deserialize(resp_data)?;
+        // Shared by the chart state and every callback instead of cloned per consumer
+        let data: Arc<[DataPoint]> = chart_response.series.into();
+
+        let mut info = series_info.clone();
+        info.backfill = timescale_update && self.metadata.backfill.contains(&id);
+        let backfill = info.backfill;
+        self.metadata
+            .chart_state
+            .write()
+            .await
+            .chart
+            .replace((info.clone(), Arc::clone(&data)));
+
+        // Only realtime updates are throttled, history loads always go through
+        let throttled = if timescale_update {
+            Conflate::Emit
+        } else {
+            self.throttle.allow_chart(id, Instant::now)
+        };
+        let emit = throttled == Conflate::Emit;
+        if emit {
+            dispatch(&self.handler.on_chart_data, (info, Arc::clone(&data))).await;
+        } else {
+            self.throttle
+                .pending_chart
+                .insert(id, (info, Arc::clone(&data)));
+            if let Conflate::Flush(delay) = throttled {
+                self.flush_chart_later(id, delay);
             }
         }
+        if !backfill {
+            self.handle_bars(id, series_info, &data, emit).await;
+        }
+        self.handle_series_studies(series_info, message_data).await
+    }
+
+    /// Deliver the newest dropped update of `series_id` once its throttle
+    /// window ends.
+    fn flush_chart_later(&self, series_id: Ustr, delay: Duration) {
+        let handler = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let Some((info, data)) = handler
+                .throttle
+                .take_pending_chart(series_id, Instant::now())
+            else {
+                return;
+            };
+            let forming = handler.last_bars.get(&series_id).map(|bar| bar.clone());
+            dispatch(&handler.handler.on_chart_data, (info.clone(), data)).await;
+            if let Some(bar) = forming {
+                let msg = BarMsg {
+                    series_id,
+                    series: info,
+                    bar,
+                };
+                dispatch(&handler.handler.on_bar_update, msg).await;
+            }
+        });
+    }
 
+    /// Studies are only created, and their data only parsed, with the `pine` feature.
+    #[cfg_attr(not(feature = "pine"), allow(unused_variables))]
+    async fn handle_series_studies(
+        &self,
+        series_info: &SeriesInfo,
+        message_data: &Value,
+    ) -> Result<()> {
+        #[cfg(feature = "pine")]
+        if self.event_mask().contains(EventMask::STUDY)
+            && let Some(study_options) = &series_info.options.study_config
+        {
+            self.handle_study_data(series_info, study_options, message_data)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Bar closes are dispatched even if the forming bar update is throttled.
+    async fn handle_bars(
+        &self,
+        series_id: Ustr,
+        series: &SeriesInfo,
+        update: &[DataPoint],
+        emit_update: bool,
+    ) {
+        let (closed, forming) = {
+            let previous = self.last_bars.get(&series_id);
+            track_bars(previous.as_deref(), update)
+        };
+        let Some(forming) = forming else {
+            return;
+        };
+        self.last_bars.insert(series_id, forming.clone());
+
+        let bar_msg = |bar| BarMsg {
+            series_id,
+            series: series.clone(),
+            bar,
+        };
+        for bar in closed {
+            dispatch(&self.handler.on_bar_close, bar_msg(bar)).await;
+        }
+        if emit_update {
+            dispatch(&self.handler.on_bar_update, bar_msg(forming)).await;
+        }
+    }
+
+    #[tracing::instrument(name = "depth", level = "debug", skip_all)]
+    async fn handle_depth_data(&self, message: &[Value], snapshot: bool) -> Result<()> {
+        let symbol = message
+            .first()
+            .and_then(Value::as_str)
+            .and_then(|session| self.metadata.depth_sessions.get(&Ustr::from(session)))
+            .map(|symbol| *symbol)
+            .unwrap_or_default();
+        let update = DepthUpdate::new(message, symbol, snapshot)?;
+        dispatch(&self.handler.on_depth_data, update).await;
         Ok(())
     }
 
@@ -226,14 +797,30 @@ impl DataHandler {
             self.notify_error(
                 Error::JsonParse(Ustr::from("Failed to parse quote data")),
                 message,
-            );
+            )
+            .await;
+        }
+    }
+
+    /// Handle a `qsd` update that was parsed straight from the frame.
+    pub(crate) async fn handle_typed_quote(&self, qsd: QuoteData) {
+        if !self.event_mask().contains(EventMask::QUOTE) {
+            return;
+        }
+        if let Err(e) = self.apply_quote_data(qsd).await {
+            error!("Quote data error: {:?}", e);
+            self.notify_error(e, &[]).await;
         }
     }
 
     async fn try_parse_quote_data(&self, data: &Value) -> Result<()> {
         let qsd = QuoteData::deserialize(data)
             .map_err(|e| Error::JsonParse(Ustr::from(&e.to_string())))?;
+        self.apply_quote_data(qsd).await
+    }
 
+    #[tracing::instrument(name = "quote", level = "debug", skip_all, fields(symbol = %qsd.name))]
+    async fn apply_quote_data(&self, qsd: QuoteData) -> Result<()> {
         // Reduce info logging overhead in hot paths
         if tracing::enabled!(tracing::Level::INFO) {
             info!("Successfully parsed quote data: {:?}", qsd);
@@ -245,23 +832,82 @@ impl DataHandler {
             });
         }
 
-        // Optimize quote storage update with entry API
-        let name = qsd.name;
+        let name = self.quote_symbol(qsd.name);
         let value = qsd.value;
+        let snapshot = self.metadata.quotes.update(name, &value);
 
-        match self.metadata.quotes.get_mut(&name) {
-            Some(mut prev_quote) => {
-                *prev_quote = merge_quotes(&prev_quote, &value);
+        let Some(window) = self.quote_conflation.window() else {
+            if self.throttle.limits_quotes() {
+                // Emit the merged snapshot so fields of dropped updates are not lost
+                self.emit_merged_quote(snapshot).await;
+            } else {
+                dispatch(&self.handler.on_quote_data, value).await;
+                self.emit_quote_snapshot(snapshot).await;
             }
-            None => {
-                self.metadata.quotes.insert(name, value);
+            return Ok(());
+        };
+        // Emit the merged snapshot so fields from coalesced updates are not lost
+        match self
+            .quote_conflation
+            .on_update(name, &value, window, Instant::now())
+        {
+            Conflate::Emit => self.emit_merged_quote(snapshot).await,
+            Conflate::Flush(delay) => {
+                let handler = self.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if handler.quote_conflation.take_pending(name, Instant::now())
+                        && let Some(snapshot) = handler.metadata.quotes.get(&name)
+                    {
+                        handler.emit_merged_quote(snapshot).await;
+                    }
+                });
             }
+            Conflate::Merge => {}
         }
-
-        (self.handler.on_quote_data)(value);
         Ok(())
     }
 
+    fn quote_symbol(&self, name: Ustr) -> Ustr {
+        if !name.starts_with('=') {
+            return name;
+        }
+        *self
+            .quote_names
+            .entry(name)
+            .or_insert_with(|| quote_symbol(name))
+    }
+
+    async fn emit_merged_quote(&self, snapshot: QuoteSnapshot) {
+        let symbol = snapshot.symbol;
+        match self.throttle.allow_quote(symbol, Instant::now) {
+            Conflate::Emit => self.emit_quote(snapshot).await,
+            Conflate::Flush(delay) => {
+                let handler = self.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if handler.throttle.take_pending_quote(symbol, Instant::now())
+                        && let Some(snapshot) = handler.metadata.quotes.get(&symbol)
+                    {
+                        handler.emit_quote(snapshot).await;
+                    }
+                });
+            }
+            Conflate::Merge => {}
+        }
+    }
+
+    async fn emit_quote(&self, snapshot: QuoteSnapshot) {
+        dispatch(&self.handler.on_quote_data, snapshot.value).await;
+        self.emit_quote_snapshot(snapshot).await;
+    }
+
+    async fn emit_quote_snapshot(&self, snapshot: QuoteSnapshot) {
+        if self.emit_quote_snapshots.load(Ordering::Relaxed) {
+            dispatch(&self.handler.on_quote_snapshot, snapshot).await;
+        }
+    }
+
     async fn try_parse_direct_quote(&self, data: &Value) -> Result<()> {
         let direct_quote = QuoteValue::deserialize(data)
             .map_err(|e| Error::JsonParse(Ustr::from(&e.to_string())))?;
@@ -269,16 +915,262 @@ impl DataHandler {
         if tracing::enabled!(tracing::Level::INFO) {
             info!("Parsed as direct QuoteValue: {:?}", direct_quote);
         }
-        (self.handler.on_quote_data)(direct_quote);
+        dispatch(&self.handler.on_quote_data, direct_quote).await;
         Ok(())
     }
 
-    fn notify_error(&self, error: Error, message: &[Value]) {
-        (self.handler.on_error)((error, message.to_vec()));
+    async fn notify_error(&self, error: Error, message: &[Value]) {
+        dispatch(&self.handler.on_error, (error, message.to_vec())).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live::handler::message::TradingViewResponse;
+
+    #[test]
+    fn test_conflation_coalesces_until_window_ends() {
+        let conflation = QuoteConflation::default();
+        let name = Ustr::from("BINANCE:BTCUSDT");
+        let window = Duration::from_millis(100);
+        let bid = QuoteValue {
+            bid: Some(1.0),
+            ..Default::default()
+        };
+        let trade = QuoteValue {
+            price: Some(1.0),
+            ..Default::default()
+        };
+        let start = Instant::now();
+
+        assert_eq!(
+            conflation.on_update(name, &bid, window, start),
+            Conflate::Emit
+        );
+        let later = start + Duration::from_millis(40);
+        assert_eq!(
+            conflation.on_update(name, &bid, window, later),
+            Conflate::Flush(Duration::from_millis(60))
+        );
+        assert_eq!(
+            conflation.on_update(name, &trade, window, later),
+            Conflate::Merge
+        );
+
+        conflation.flush_trades.store(true, Ordering::Relaxed);
+        assert_eq!(
+            conflation.on_update(name, &trade, window, later),
+            Conflate::Emit
+        );
+        assert!(!conflation.take_pending(name, start + window));
+    }
+
+    #[test]
+    fn test_throttle_caps_updates_per_second() {
+        let throttle = UpdateThrottle::default();
+        let (aapl, msft) = (Ustr::from("NASDAQ:AAPL"), Ustr::from("NASDAQ:MSFT"));
+        let start = Instant::now();
+        // Without a cap the clock is not read
+        let unread = || -> Instant { panic!("clock read without a cap") };
+        assert_eq!(throttle.allow_quote(aapl, unread), Conflate::Emit);
+        assert!(!throttle.limits_quotes());
+
+        throttle.set(ThrottleConfig {
+            quotes_per_second: 2,
+            chart_per_second: 1,
+        });
+        let at = |millis| move || start + Duration::from_millis(millis);
+        assert_eq!(throttle.allow_quote(aapl, at(0)), Conflate::Emit);
+        assert_eq!(throttle.allow_quote(aapl, at(10)), Conflate::Emit);
+        assert_eq!(
+            throttle.allow_quote(aapl, at(20)),
+            Conflate::Flush(Duration::from_millis(980))
+        );
+        assert_eq!(throttle.allow_quote(aapl, at(30)), Conflate::Merge);
+        // Every symbol has its own window
+        assert_eq!(throttle.allow_quote(msft, at(20)), Conflate::Emit);
+        // The flush opens the next window
+        assert!(throttle.take_pending_quote(aapl, start + Duration::from_secs(1)));
+        assert!(!throttle.take_pending_quote(aapl, start + Duration::from_secs(1)));
+        assert_eq!(throttle.allow_quote(aapl, at(1010)), Conflate::Emit);
+        assert_eq!(
+            throttle.allow_quote(aapl, at(1020)),
+            Conflate::Flush(Duration::from_millis(980))
+        );
+
+        let series = Ustr::from("sds_1");
+        assert_eq!(throttle.allow_chart(series, at(0)), Conflate::Emit);
+        assert_eq!(
+            throttle.allow_chart(series, at(500)),
+            Conflate::Flush(Duration::from_millis(500))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_throttle_flushes_the_last_dropped_quote() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler = DataHandler::builder()
+            .res_tx(tx.into())
+            .throttle(ThrottleConfig {
+                quotes_per_second: 1,
+                chart_per_second: 0,
+            })
+            .build();
+        let quote = |price: f64| QuoteData {
+            name: Ustr::from("NASDAQ:AAPL"),
+            status: Ustr::from("ok"),
+            value: QuoteValue {
+                price: Some(price),
+                ..Default::default()
+            },
+        };
+        for price in [1.0, 2.0, 3.0] {
+            handler.apply_quote_data(quote(price)).await.unwrap();
+        }
+
+        let mut prices = Vec::new();
+        while prices.len() < 2 {
+            let response = tokio::time::timeout(Duration::from_secs(3), rx.recv())
+                .await
+                .expect("the dropped quote was not flushed")
+                .unwrap();
+            if let TradingViewResponse::QuoteData(value) = response {
+                prices.push(value.price);
+            }
+        }
+        assert_eq!(prices, vec![Some(1.0), Some(3.0)]);
+    }
+
+    #[test]
+    fn test_quote_symbol_strips_session() {
+        let extended = Ustr::from(r#"={"session":"extended","symbol":"NASDAQ:AAPL"}"#);
+        assert_eq!(quote_symbol(extended), "NASDAQ:AAPL");
+        assert_eq!(quote_symbol(Ustr::from("NASDAQ:AAPL")), "NASDAQ:AAPL");
+    }
+
+    #[test]
+    fn test_track_bars() {
+        let bar = |time: f64, close: f64| DataPoint {
+            index: 0,
+            value: vec![time, close, close, close, close, 0.0],
+        };
+
+        // The history load only sets the forming bar
+        let (closed, forming) = track_bars(None, &[bar(60.0, 1.0), bar(120.0, 2.0)]);
+        assert!(closed.is_empty());
+        assert_eq!(forming, Some(bar(120.0, 2.0)));
+
+        let (closed, forming) = track_bars(Some(&bar(120.0, 2.0)), &[bar(120.0, 2.5)]);
+        assert!(closed.is_empty());
+        assert_eq!(forming, Some(bar(120.0, 2.5)));
+
+        // The final update of a bar may arrive along with the next one
+        let (closed, _) = track_bars(Some(&bar(120.0, 2.5)), &[bar(120.0, 3.0), bar(180.0, 3.1)]);
+        assert_eq!(closed, vec![bar(120.0, 3.0)]);
+
+        let (closed, forming) = track_bars(Some(&bar(180.0, 3.1)), &[bar(240.0, 4.0)]);
+        assert_eq!(closed, vec![bar(180.0, 3.1)]);
+        assert_eq!(forming, Some(bar(240.0, 4.0)));
+
+        assert_eq!(
+            track_bars(Some(&bar(240.0, 4.0)), &[bar(60.0, 1.0)]),
+            (vec![], None)
+        );
     }
 
-    pub fn set_handler(mut self, handler: TradingViewHandler) -> Self {
-        self.handler = handler;
-        self
+    #[cfg(feature = "pine")]
+    #[tokio::test]
+    async fn test_created_studies_are_routed_by_study_id() {
+        use crate::{chart::StudyOptions, live::snapshot::StudySnapshot};
+        use serde_json::json;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler = DataHandler::builder().res_tx(tx.into()).build();
+        let session = Ustr::from("cs_1");
+        handler.metadata.series.insert(
+            Ustr::from("sds_1"),
+            SeriesInfo {
+                chart_session: session,
+                ..Default::default()
+            },
+        );
+        // Two studies on the series and a third one computed on the first
+        for (series_id, study_id, script_id) in [
+            ("sds_1", "st1", "STD;RSI"),
+            ("sds_1", "st2", "STD;MACD"),
+            ("st1", "st3", "STD;SMA"),
+        ] {
+            let study_id = Ustr::from(study_id);
+            handler.metadata.created_studies.insert(
+                (session, study_id),
+                StudySnapshot {
+                    chart_session: session,
+                    series_id: Ustr::from(series_id),
+                    study_id,
+                    script_id: Ustr::from(script_id),
+                    script_version: Ustr::from("1"),
+                    script_type: Default::default(),
+                    inputs: Default::default(),
+                    volume_profile: None,
+                },
+            );
+        }
+        let study = |value: f64| {
+            json!({
+                "st": [{"i": 0, "v": [60.0, value]}],
+                "ns": {"d": "", "indexes": []}
+            })
+        };
+        let message = [
+            json!("cs_1"),
+            json!({"st1": study(1.0), "st2": study(2.0), "st3": study(3.0)}),
+        ];
+        handler
+            .handle_events(TradingViewDataEvent::OnChartDataUpdate, &message)
+            .await;
+
+        let mut studies: Vec<(Ustr, Ustr, f64)> = Vec::new();
+        while let Ok(response) = rx.try_recv() {
+            if let TradingViewResponse::StudyData(StudyOptions { script_id, .. }, data) = response {
+                studies.push((data.study_id, script_id, data.studies[0].value[1]));
+            }
+        }
+        studies.sort_by_key(|(study_id, ..)| *study_id);
+        assert_eq!(
+            studies,
+            vec![
+                (Ustr::from("st1"), Ustr::from("STD;RSI"), 1.0),
+                (Ustr::from("st2"), Ustr::from("STD;MACD"), 2.0),
+                (Ustr::from("st3"), Ustr::from("STD;SMA"), 3.0),
+            ]
+        );
+    }
+
+    #[cfg(feature = "chart")]
+    #[tokio::test]
+    async fn test_forget_bars() {
+        let bar = |time: f64| DataPoint {
+            index: 0,
+            value: vec![time, 1.0, 1.0, 1.0, 1.0, 0.0],
+        };
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler = DataHandler::builder().res_tx(tx.into()).build();
+        let (series_id, series) = (Ustr::from("sds_1"), SeriesInfo::default());
+
+        handler
+            .handle_bars(series_id, &series, &[bar(240.0)], false)
+            .await;
+        // Older bars are ignored while the forming bar is remembered
+        handler
+            .handle_bars(series_id, &series, &[bar(60.0)], false)
+            .await;
+        assert_eq!(*handler.last_bars.get(&series_id).unwrap(), bar(240.0));
+
+        handler.forget_bars(&series_id);
+        handler
+            .handle_bars(series_id, &series, &[bar(60.0)], false)
+            .await;
+        assert_eq!(*handler.last_bars.get(&series_id).unwrap(), bar(60.0));
     }
 }
@@ -1,35 +1,63 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use ustr::{Ustr, ustr};
 
+#[cfg(feature = "pine")]
+use crate::chart::strategy::StrategyReport;
 use crate::{
-    ChartOptions, DataPoint, Error, Interval, QuoteValue, Result, StudyOptions, StudyResponseData,
-    SymbolInfo, Timezone, pine_indicator::PineIndicator, websocket::SeriesInfo,
+    ChartOptions, DataPoint, Error, Interval, News, QuoteValue, Result, SocialPost, StudyOptions,
+    StudyResponseData, SymbolInfo, Timezone,
+    live::depth::DepthUpdate,
+    pine_indicator::PineIndicator,
+    quote::{options::QuoteSessionOptions, state::QuoteSnapshot},
+    websocket::SeriesInfo,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TradingViewResponse {
-    ChartData(SeriesInfo, Vec<DataPoint>),
+    /// Bars of one update, shared with every callback that saw it.
+    ChartData(SeriesInfo, Arc<[DataPoint]>),
+    BarUpdate(BarMsg),
+    BarClose(BarMsg),
     QuoteData(QuoteValue),
     StudyData(StudyOptions, StudyResponseData),
+    #[cfg(feature = "pine")]
+    StrategyReport(StudyOptions, Box<StrategyReport>),
     Error(Error, Vec<Value>),
     SymbolInfo(SymbolInfo),
-    SeriesCompleted(Vec<Value>),
+    SeriesCompleted(SeriesCompletedMsg),
     SeriesLoading(LoadingMsg),
     QuoteCompleted(Vec<Value>),
-    ReplayOk(Vec<Value>),
-    ReplayPoint(Vec<Value>),
-    ReplayInstanceId(Vec<Value>),
-    ReplayResolutions(Vec<Value>),
-    ReplayDataEnd(Vec<Value>),
+    QuoteSnapshot(QuoteSnapshot),
+    DepthData(DepthUpdate),
+    News(News),
+    SocialPost(SocialPost),
+    #[cfg(feature = "replay")]
+    ReplayOk(ReplayOk),
+    #[cfg(feature = "replay")]
+    ReplayPoint(ReplayPoint),
+    #[cfg(feature = "replay")]
+    ReplayInstanceId(ReplayInstanceId),
+    #[cfg(feature = "replay")]
+    ReplayResolutions(ReplayResolutions),
+    #[cfg(feature = "replay")]
+    ReplayDataEnd(ReplayDataEnd),
     StudyLoading(LoadingMsg),
-    StudyCompleted(Vec<Value>),
+    StudyCompleted(StudyCompletedMsg),
     UnknownEvent(Ustr, Vec<Value>),
+    Reconnected(ReconnectedMsg),
+    ConnectionStale(ConnectionStaleMsg),
+    SessionRefreshed(SessionRefreshedMsg),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Command {
     Delete,
+    /// Stop accepting commands, send the ones already queued, delete every
+    /// session, close the socket and complete the response channel once the
+    /// responses read so far were forwarded. The runner exits afterwards.
+    Shutdown,
     Ping,
     SetAuthToken {
         auth_token: Ustr,
@@ -46,6 +74,9 @@ pub enum Command {
         timezone: Timezone,
     },
     CreateQuoteSession,
+    CreateQuoteSessionWith {
+        options: QuoteSessionOptions,
+    },
     DeleteQuoteSession,
     SetQuoteFields,
     FastSymbols {
@@ -57,6 +88,9 @@ pub enum Command {
     RemoveSymbols {
         symbols: Vec<Ustr>,
     },
+    RemoveQuoteSymbol {
+        symbol: Ustr,
+    },
 
     CreateChartSession {
         session: Ustr,
@@ -75,17 +109,24 @@ pub enum Command {
         bar_count: u64,
     },
 
+    /// Attach `indicator` under `study_id` to the series or study `series_id`.
     CreateStudy {
         session: Ustr,
         study_id: Ustr,
         series_id: Ustr,
         indicator: PineIndicator,
+        /// Input values replacing the indicator defaults, keyed by input id or name.
+        #[serde(default)]
+        inputs: HashMap<Ustr, Value>,
     },
     ModifyStudy {
         session: Ustr,
         study_id: Ustr,
         series_id: Ustr,
         indicator: PineIndicator,
+        /// Input values replacing the indicator defaults, keyed by input id or name.
+        #[serde(default)]
+        inputs: HashMap<Ustr, Value>,
     },
     RemoveStudy {
         session: Ustr,
@@ -104,17 +145,28 @@ pub enum Command {
         series_symbol_id: Ustr,
         config: ChartOptions,
     },
+    /// Switch a series to another resolution, keeping its studies.
     ModifySeries {
-        session: Ustr,
         series_id: Ustr,
-        series_version: Ustr,
-        series_symbol_id: Ustr,
-        config: ChartOptions,
+        interval: Interval,
+        /// Bars to load at the new resolution, `None` keeps the current count.
+        #[serde(default)]
+        bar_count: Option<u64>,
     },
     RemoveSeries {
         session: Ustr,
         series_id: Ustr,
     },
+    CreateDepthSession {
+        session: Ustr,
+    },
+    DeleteDepthSession {
+        session: Ustr,
+    },
+    SetDepthSymbol {
+        session: Ustr,
+        symbol: Ustr,
+    },
     CreateReplaySession {
         session: Ustr,
     },
@@ -187,20 +239,103 @@ impl Command {
         }
     }
 
+    /// Create RemoveQuoteSymbol command, dropping one symbol and its quote state
+    pub fn remove_quote_symbol<S: AsRef<str>>(symbol: S) -> Self {
+        Self::RemoveQuoteSymbol {
+            symbol: ustr(symbol.as_ref()),
+        }
+    }
+
+    /// Create RemoveSeries command, stopping chart data for `series_id`
+    pub fn remove_series<S: AsRef<str>>(session: S, series_id: S) -> Self {
+        Self::RemoveSeries {
+            session: ustr(session.as_ref()),
+            series_id: ustr(series_id.as_ref()),
+        }
+    }
+
+    /// Create ModifyStudy command, updating the inputs of a live study
+    pub fn modify_study<S: AsRef<str>>(
+        session: S,
+        study_id: S,
+        series_id: S,
+        indicator: PineIndicator,
+        inputs: HashMap<Ustr, Value>,
+    ) -> Self {
+        Self::ModifyStudy {
+            session: ustr(session.as_ref()),
+            study_id: ustr(study_id.as_ref()),
+            series_id: ustr(series_id.as_ref()),
+            indicator,
+            inputs,
+        }
+    }
+
+    /// Create RemoveStudy command, stopping study data for `study_id`
+    pub fn remove_study<S: AsRef<str>>(session: S, study_id: S, series_id: S) -> Self {
+        Self::RemoveStudy {
+            session: ustr(session.as_ref()),
+            study_id: ustr(study_id.as_ref()),
+            series_id: ustr(series_id.as_ref()),
+        }
+    }
+
     /// Create SetMarket command with builder pattern
     pub fn set_market(options: ChartOptions) -> Self {
         Self::SetMarket { options }
     }
 
+    /// Create ModifySeries command, switching `series_id` to `interval`
+    pub fn modify_series<S: AsRef<str>>(
+        series_id: S,
+        interval: Interval,
+        bar_count: Option<u64>,
+    ) -> Self {
+        Self::ModifySeries {
+            series_id: ustr(series_id.as_ref()),
+            interval,
+            bar_count,
+        }
+    }
+
+    /// Create RequestMoreData command, paging `bar_count` older bars into `series_id`
+    pub fn request_more_data<S: AsRef<str>>(session: S, series_id: S, bar_count: u64) -> Self {
+        Self::RequestMoreData {
+            session: ustr(session.as_ref()),
+            series_id: ustr(series_id.as_ref()),
+            bar_count,
+        }
+    }
+
     /// Create Delete command
     pub fn delete() -> Self {
         Self::Delete
     }
 
+    /// Create CreateQuoteSessionWith command from quote session options
+    pub fn create_quote_session_with(options: QuoteSessionOptions) -> Self {
+        Self::CreateQuoteSessionWith { options }
+    }
+
     /// Create DeleteQuoteSession command
     pub fn delete_quote_session() -> Self {
         Self::DeleteQuoteSession
     }
+
+    /// Create CreateDepthSession command for a new session id such as `dp_0123456789ab`
+    pub fn create_depth_session<S: AsRef<str>>(session: S) -> Self {
+        Self::CreateDepthSession {
+            session: ustr(session.as_ref()),
+        }
+    }
+
+    /// Create SetDepthSymbol command, streaming the order book of `symbol` into `session`
+    pub fn set_depth_symbol<S: AsRef<str>>(session: S, symbol: S) -> Self {
+        Self::SetDepthSymbol {
+            session: ustr(session.as_ref()),
+            symbol: ustr(symbol.as_ref()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
@@ -300,3 +435,307 @@ impl LoadingMsg {
         matches!(self, Self::Study(_))
     }
 }
+
+/// The forming bar of a realtime series changed, or a bar was finalized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarMsg {
+    pub series_id: Ustr,
+    pub series: SeriesInfo,
+    pub bar: DataPoint,
+}
+
+/// Emitted once the websocket has reconnected and replayed its subscriptions.
+///
+/// Events that arrived while disconnected are lost, and chart sessions and
+/// series ids are regenerated, so consumers should refetch anything they need.
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ReconnectedMsg {
+    /// Number of chart series that were recreated.
+    pub series: usize,
+    /// Number of quote symbols that were re-added.
+    pub symbols: usize,
+}
+
+/// Emitted after [`crate::client::session::SessionGuard`] replaced an expired
+/// session with fresh cookies.
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct SessionRefreshedMsg {
+    pub user_id: u32,
+    pub username: Ustr,
+}
+
+/// Emitted when no frame arrived within the keepalive silence window.
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionStaleMsg {
+    /// Time since the last frame was received.
+    pub silence: Duration,
+}
+
+/// Payload of a `series_completed` event.
+///
+/// `update_mode` is `data_completed` once historical data has been delivered and
+/// `streaming` (or `delayed_streaming_<secs>`) while realtime updates follow.
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct SeriesCompletedMsg {
+    pub session: Ustr,
+    pub series_id: Ustr,
+    pub update_mode: Ustr,
+    pub turnaround: Ustr,
+    /// Realtime update period in seconds, when reported.
+    pub rt_update_period: Option<u64>,
+}
+
+impl SeriesCompletedMsg {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        const REQUIRED_FIELDS: usize = 4;
+
+        if messages.len() < REQUIRED_FIELDS {
+            return Err(Error::Internal(Ustr::from(&format!(
+                "Series completed message requires {} fields, got {}",
+                REQUIRED_FIELDS,
+                messages.len()
+            ))));
+        }
+
+        Ok(Self {
+            session: Ustr::deserialize(&messages[0])?,
+            series_id: Ustr::deserialize(&messages[1])?,
+            update_mode: Ustr::deserialize(&messages[2])?,
+            turnaround: Ustr::deserialize(&messages[3])?,
+            rt_update_period: messages
+                .get(4)
+                .and_then(|v| v.get("rt_update_period"))
+                .and_then(Value::as_u64),
+        })
+    }
+
+    pub fn is_data_completed(&self) -> bool {
+        self.update_mode.as_str() == "data_completed"
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.update_mode.starts_with("streaming")
+            || self.update_mode.starts_with("delayed_streaming")
+    }
+}
+
+/// Payload of a `study_completed` event.
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct StudyCompletedMsg {
+    pub session: Ustr,
+    pub study_id: Ustr,
+    pub turnaround: Ustr,
+}
+
+impl StudyCompletedMsg {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        const REQUIRED_FIELDS: usize = 3;
+
+        if messages.len() < REQUIRED_FIELDS {
+            return Err(Error::Internal(Ustr::from(&format!(
+                "Study completed message requires {} fields, got {}",
+                REQUIRED_FIELDS,
+                messages.len()
+            ))));
+        }
+
+        Ok(Self {
+            session: Ustr::deserialize(&messages[0])?,
+            study_id: Ustr::deserialize(&messages[1])?,
+            turnaround: Ustr::deserialize(&messages[2])?,
+        })
+    }
+}
+
+#[cfg(feature = "replay")]
+fn replay_session(messages: &[Value], event: &str) -> Result<Ustr> {
+    match messages.first() {
+        Some(session) => Ok(Ustr::deserialize(session)?),
+        None => Err(Error::Internal(Ustr::from(&format!(
+            "{event} message is missing the replay session"
+        )))),
+    }
+}
+
+/// Acknowledgement of a replay session request.
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ReplayOk {
+    pub session: Ustr,
+    pub request_id: Option<Ustr>,
+}
+
+#[cfg(feature = "replay")]
+impl ReplayOk {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        Ok(Self {
+            session: replay_session(messages, "replay_ok")?,
+            request_id: messages.get(1).and_then(|v| v.as_str()).map(Ustr::from),
+        })
+    }
+}
+
+/// Current position of the replay cursor.
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ReplayPoint {
+    pub session: Ustr,
+    /// Unix timestamp in seconds of the bar the replay is positioned at.
+    pub timestamp: i64,
+}
+
+#[cfg(feature = "replay")]
+impl ReplayPoint {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        let session = replay_session(messages, "replay_point")?;
+        // The timestamp is the last numeric field, after an optional request id
+        let timestamp = messages[1..]
+            .iter()
+            .rev()
+            .find_map(Value::as_f64)
+            .ok_or_else(|| Error::Internal(Ustr::from("replay_point message has no timestamp")))?;
+        Ok(Self {
+            session,
+            timestamp: timestamp as i64,
+        })
+    }
+}
+
+/// Identifier TradingView assigned to a replay session.
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ReplayInstanceId {
+    pub session: Ustr,
+    pub instance_id: Ustr,
+}
+
+#[cfg(feature = "replay")]
+impl ReplayInstanceId {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        let session = replay_session(messages, "replay_instance_id")?;
+        let instance_id = messages
+            .get(1)
+            .and_then(Value::as_str)
+            .map(Ustr::from)
+            .ok_or_else(|| {
+                Error::Internal(Ustr::from("replay_instance_id message has no instance id"))
+            })?;
+        Ok(Self {
+            session,
+            instance_id,
+        })
+    }
+}
+
+/// Resolutions available for replaying a symbol.
+///
+/// Resolutions without a matching [`Interval`] are skipped.
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ReplayResolutions {
+    pub session: Ustr,
+    pub resolutions: Vec<Interval>,
+}
+
+#[cfg(feature = "replay")]
+impl ReplayResolutions {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        let session = replay_session(messages, "replay_resolutions")?;
+        let resolutions = messages[1..]
+            .iter()
+            .find_map(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|resolution| resolution.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            session,
+            resolutions,
+        })
+    }
+}
+
+/// Notification that the replay has reached the end of the available data.
+#[cfg(feature = "replay")]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Hash)]
+pub struct ReplayDataEnd {
+    pub session: Ustr,
+}
+
+#[cfg(feature = "replay")]
+impl ReplayDataEnd {
+    pub fn new(messages: &[Value]) -> Result<Self> {
+        Ok(Self {
+            session: replay_session(messages, "replay_data_end")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_series_completed_update_mode() {
+        let msg = SeriesCompletedMsg::new(&[
+            json!("cs_1lMtxZTkwtYN"),
+            json!("sds_5"),
+            json!("delayed_streaming_900"),
+            json!("s1"),
+            json!({"rt_update_period": 5}),
+        ])
+        .unwrap();
+        assert_eq!(msg.series_id, Ustr::from("sds_5"));
+        assert_eq!(msg.rt_update_period, Some(5));
+        assert!(msg.is_streaming());
+        assert!(!msg.is_data_completed());
+    }
+
+    #[test]
+    fn test_modify_series_keeps_bar_count_by_default() {
+        let cmd: Command = serde_json::from_value(json!({
+            "ModifySeries": {"series_id": "sds_1", "interval": "OneHour"}
+        }))
+        .unwrap();
+        assert!(matches!(
+            cmd,
+            Command::ModifySeries {
+                interval: Interval::OneHour,
+                bar_count: None,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_point_takes_trailing_timestamp() {
+        let point =
+            ReplayPoint::new(&[json!("rs_abc"), json!("req_3"), json!(1685633880.0)]).unwrap();
+        assert_eq!(point.session, Ustr::from("rs_abc"));
+        assert_eq!(point.timestamp, 1685633880);
+        assert!(ReplayPoint::new(&[json!("rs_abc")]).is_err());
+    }
+
+    #[cfg(feature = "replay")]
+    #[test]
+    fn test_replay_resolutions_maps_minute_counts() {
+        let resolutions =
+            ReplayResolutions::new(&[json!("rs_abc"), json!(["1", "60", "240", "1D", "7"])])
+                .unwrap();
+        assert_eq!(
+            resolutions.resolutions,
+            vec![
+                Interval::OneMinute,
+                Interval::OneHour,
+                Interval::FourHours,
+                Interval::OneDay
+            ]
+        );
+    }
+}
@@ -1,4 +1,8 @@
+pub mod channel;
+#[cfg(feature = "native")]
 pub mod command;
-pub mod data;
+pub(crate) mod data;
 pub mod message;
+#[cfg(feature = "native")]
+pub mod stream;
 pub mod types;
@@ -0,0 +1,167 @@
+use crate::{
+    Error, Result,
+    live::{
+        handler::{
+            command::CommandRunner,
+            message::{Command, TradingViewResponse},
+            types::{CommandTx, DataRx, DataTx, TradingViewHandler},
+        },
+        models::DataServer,
+        websocket::WebSocketClient,
+    },
+};
+use futures_util::{Sink, Stream};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+
+/// [`Stream`] of responses received from the websocket.
+///
+/// Ends once every [`DataTx`] feeding it has been dropped.
+#[derive(Debug)]
+pub struct ResponseStream {
+    rx: DataRx,
+}
+
+impl ResponseStream {
+    pub fn new(rx: DataRx) -> Self {
+        Self { rx }
+    }
+
+    pub fn into_inner(self) -> DataRx {
+        self.rx
+    }
+}
+
+impl Stream for ResponseStream {
+    type Item = TradingViewResponse;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// [`Sink`] forwarding commands to a [`CommandRunner`].
+#[derive(Debug, Clone)]
+pub struct CommandSink {
+    tx: CommandTx,
+}
+
+impl CommandSink {
+    pub fn new(tx: CommandTx) -> Self {
+        Self { tx }
+    }
+
+    pub fn into_inner(self) -> CommandTx {
+        self.tx
+    }
+}
+
+impl Sink<Command> for CommandSink {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.tx.is_closed() {
+            return Poll::Ready(Err(Error::Cancelled));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Command) -> Result<()> {
+        self.tx.send(item).map_err(|_| Error::Cancelled)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Create a response channel whose receiving half is a [`ResponseStream`].
+pub fn create_stream() -> (DataTx, ResponseStream) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (tx.into(), ResponseStream::new(rx))
+}
+
+/// Connect to `server` and spawn a [`CommandRunner`], returning the typed
+/// event stream and command sink.
+///
+/// `handler` callbacks still run for every event before it reaches the stream.
+#[bon::builder]
+pub async fn connect(
+    auth_token: Option<&str>,
+    #[builder(default = DataServer::ProData)] server: DataServer,
+    handler: Option<TradingViewHandler>,
+    /// Cancelling this token shuts down the websocket and the command runner.
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<(ResponseStream, CommandSink)> {
+    let (data_tx, events) = create_stream();
+    let (command_tx, command_rx) = mpsc::unbounded_channel();
+
+    let ws = WebSocketClient::builder()
+        .maybe_auth_token(auth_token)
+        .server(server)
+        .data_tx(data_tx)
+        .maybe_handler(handler)
+        .maybe_cancellation_token(cancellation_token)
+        .build()
+        .await?;
+
+    let mut runner = CommandRunner::new(command_rx, Arc::clone(&ws));
+    if let Some(token) = cancellation_token {
+        runner = runner.with_cancellation_token(token);
+    }
+    tokio::spawn(async move {
+        if let Err(e) = runner.run().await {
+            error!("Command runner failed: {}", e);
+        }
+    });
+
+    Ok((events, CommandSink::new(command_tx)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use ustr::Ustr;
+
+    #[tokio::test]
+    async fn test_stream_yields_until_senders_dropped() {
+        let (tx, stream) = create_stream();
+        tx.forward(TradingViewResponse::QuoteCompleted(Vec::new()));
+        tx.forward(TradingViewResponse::UnknownEvent(
+            Ustr::from("replay_data_end"),
+            Vec::new(),
+        ));
+        assert_eq!(tx.metrics().sent, 2);
+        drop(tx);
+
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], TradingViewResponse::UnknownEvent(..)));
+    }
+
+    #[tokio::test]
+    async fn test_sink_errors_once_runner_is_gone() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut sink = CommandSink::new(tx);
+
+        sink.send(Command::Ping).await.unwrap();
+        assert!(matches!(rx.recv().await, Some(Command::Ping)));
+
+        drop(rx);
+        assert!(matches!(
+            sink.send(Command::Ping).await,
+            Err(Error::Cancelled)
+        ));
+    }
+}
@@ -1,262 +1,594 @@
+#[cfg(feature = "pine")]
+use crate::chart::strategy::StrategyReport;
+#[cfg(feature = "replay")]
+use crate::live::handler::message::{
+    ReplayDataEnd, ReplayInstanceId, ReplayOk, ReplayPoint, ReplayResolutions,
+};
 use crate::{
-    Error,
+    Error, News, SocialPost,
     chart::{DataPoint, StudyOptions, StudyResponseData, SymbolInfo},
-    live::handler::message::{Command, LoadingMsg, TradingViewResponse},
-    quote::models::QuoteValue,
+    export::sink::{DataSink, sink_handler},
+    live::depth::DepthUpdate,
+    live::handler::message::{
+        BarMsg, Command, ConnectionStaleMsg, LoadingMsg, ReconnectedMsg, SeriesCompletedMsg,
+        SessionRefreshedMsg, StudyCompletedMsg, TradingViewResponse,
+    },
+    quote::{models::QuoteValue, state::QuoteSnapshot},
     websocket::SeriesInfo,
 };
+use bitflags::bitflags;
 use bon::Builder;
+use futures_util::future::BoxFuture;
 use serde_json::Value;
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use ustr::Ustr;
 
-pub type DataTx = UnboundedSender<TradingViewResponse>;
+pub use super::channel::{
+    BackpressurePolicy, BoundedDataRx, DataTx, QueueMetrics, bounded_channel,
+};
 pub type DataRx = UnboundedReceiver<TradingViewResponse>;
 
 pub type CommandTx = UnboundedSender<Command>;
 pub type CommandRx = UnboundedReceiver<Command>;
 
-pub type CallbackFn<T> = Box<dyn Fn(T) + Send + Sync + 'static>;
+/// Series and bars of one chart data update.
+pub type ChartData = (SeriesInfo, Arc<[DataPoint]>);
+
+/// Callback invoked with the handler's shared context and the event payload.
+///
+/// Synchronous callbacks return `None`; async callbacks return the future to
+/// be awaited before the next event is dispatched.
+pub type CallbackFn<T, Ctx = ()> =
+    Box<dyn Fn(&Ctx, T) -> Option<BoxFuture<'static, ()>> + Send + Sync + 'static>;
+
+/// Wrap a synchronous closure into a [`CallbackFn`].
+pub fn sync_callback<T, Ctx>(
+    f: impl Fn(&Ctx, T) + Send + Sync + 'static,
+) -> Arc<CallbackFn<T, Ctx>> {
+    Arc::new(Box::new(move |ctx: &Ctx, data: T| {
+        f(ctx, data);
+        None
+    }))
+}
+
+/// Wrap an async closure into a [`CallbackFn`] that owns a handle to `context`.
+pub fn async_callback<T, Ctx, Fut>(
+    context: &Arc<Ctx>,
+    f: impl Fn(Arc<Ctx>, T) -> Fut + Send + Sync + 'static,
+) -> Arc<CallbackFn<T, Ctx>>
+where
+    Ctx: Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let context = Arc::clone(context);
+    Arc::new(Box::new(move |_, data| {
+        let fut: BoxFuture<'static, ()> = Box::pin(f(Arc::clone(&context), data));
+        Some(fut)
+    }))
+}
+
+/// Invoke `callback` and await its future if it was registered as async.
+#[tracing::instrument(name = "dispatch", level = "trace", skip_all)]
+pub(crate) async fn dispatch<T>(callback: &CallbackFn<T>, data: T) {
+    if let Some(fut) = callback(&(), data) {
+        fut.await;
+    }
+}
+
+bitflags! {
+    /// Event classes a connection parses and dispatches.
+    ///
+    /// Frames of a masked class are skipped before they are deserialized, so
+    /// their callbacks never run and nothing reaches the data channel.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tradingview::live::handler::types::EventMask;
+    ///
+    /// let mask = EventMask::all() - EventMask::REPLAY - EventMask::STUDY;
+    /// assert!(mask.contains(EventMask::QUOTE));
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EventMask: u32 {
+        /// Chart data, bar updates and closes, series status and symbol info.
+        const CHART = 1;
+        /// Study data, strategy reports and study status.
+        const STUDY = 1 << 1;
+        const QUOTE = 1 << 2;
+        const DEPTH = 1 << 3;
+        const REPLAY = 1 << 4;
+        const ERROR = 1 << 5;
+        /// Events the crate does not model.
+        const UNKNOWN = 1 << 6;
+    }
+}
+
+impl Default for EventMask {
+    fn default() -> Self {
+        Self::all()
+    }
+}
 
-fn default_callback<T: std::fmt::Debug>(name: &'static str) -> Arc<CallbackFn<T>> {
-    Arc::new(Box::new(move |data| {
+fn default_callback<T: std::fmt::Debug, Ctx>(name: &'static str) -> Arc<CallbackFn<T, Ctx>> {
+    sync_callback(move |_, data| {
         tracing::trace!("Callback trigger on {}: {:?}", name, data);
+    })
+}
+
+fn erase_callback<T: 'static, Ctx: Send + Sync + 'static>(
+    context: &Arc<Ctx>,
+    f: Arc<CallbackFn<T, Ctx>>,
+) -> Arc<CallbackFn<T>> {
+    let context = Arc::clone(context);
+    Arc::new(Box::new(move |_, data| f(&context, data)))
+}
+
+fn chain_callback<T: Clone + Send + 'static>(
+    first: Arc<CallbackFn<T>>,
+    second: Arc<CallbackFn<T>>,
+) -> Arc<CallbackFn<T>> {
+    Arc::new(Box::new(move |ctx, data: T| {
+        match first(ctx, data.clone()) {
+            None => second(ctx, data),
+            // Defer `second` until `first` has finished to preserve ordering
+            Some(fut) => {
+                let second = Arc::clone(&second);
+                let chained: BoxFuture<'static, ()> = Box::pin(async move {
+                    fut.await;
+                    if let Some(fut) = second(&(), data) {
+                        fut.await;
+                    }
+                });
+                Some(chained)
+            }
+        }
     }))
 }
 
-// Macro to generate setter methods assuming method name and field name are the same
+// Macro to generate the sync and async setter methods for a callback field
 macro_rules! event_setter {
-    ($name:ident, $param_type:ty) => {
-        pub fn $name(mut self, f: impl Fn($param_type) + Send + Sync + 'static) -> Self {
-            self.$name = Arc::new(Box::new(f));
+    ($name:ident, $async_name:ident, $param_type:ty) => {
+        pub fn $name(mut self, f: impl Fn(&Ctx, $param_type) + Send + Sync + 'static) -> Self {
+            self.$name = sync_callback(f);
             self
         }
-    };
-    // Variant for tupled parameters
-    ($name:ident, ($($param_type_tuple:ty),+)) => {
-        pub fn $name(mut self, f: impl Fn(($($param_type_tuple),+)) + Send + Sync + 'static) -> Self {
-            self.$name = Arc::new(Box::new(f));
+
+        pub fn $async_name<Fut>(
+            mut self,
+            f: impl Fn(Arc<Ctx>, $param_type) -> Fut + Send + Sync + 'static,
+        ) -> Self
+        where
+            Ctx: Send + Sync + 'static,
+            Fut: Future<Output = ()> + Send + 'static,
+        {
+            self.$name = async_callback(&self.context, f);
             self
         }
     };
 }
 
-#[derive(Clone, Builder)]
-pub struct TradingViewHandler {
-    #[builder(default= default_callback::<SymbolInfo>("ON_SYMBOL_INFO"))]
-    pub on_symbol_info: Arc<CallbackFn<SymbolInfo>>,
+// Invoke `$m!` with the name of every callback field
+macro_rules! with_callback_fields {
+    ($m:ident) => {
+        $m!(
+            on_symbol_info,
+            on_series_loading,
+            on_chart_data,
+            on_bar_update,
+            on_bar_close,
+            on_series_completed,
+            on_study_loading,
+            on_study_data,
+            #[cfg(feature = "pine")]
+            on_strategy_report,
+            on_study_completed,
+            on_quote_data,
+            on_quote_completed,
+            on_quote_snapshot,
+            on_depth_data,
+            on_news,
+            on_social_post,
+            #[cfg(feature = "replay")]
+            on_replay_ok,
+            #[cfg(feature = "replay")]
+            on_replay_point,
+            #[cfg(feature = "replay")]
+            on_replay_instance_id,
+            #[cfg(feature = "replay")]
+            on_replay_resolutions,
+            #[cfg(feature = "replay")]
+            on_replay_data_end,
+            on_error,
+            on_unknown_event,
+            on_reconnected,
+            on_connection_stale,
+            on_session_refreshed
+        )
+    };
+}
+
+/// Set of event callbacks sharing an application context.
+///
+/// Every callback receives `&Ctx` alongside the event payload, so shared state
+/// can be passed explicitly instead of being captured by each closure.
+///
+/// Each setter has an `_async` variant taking `Arc<Ctx>` and returning a
+/// future. The future is awaited before the next event is processed, so slow
+/// async callbacks apply backpressure to the websocket reader.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use tradingview::live::handler::types::TradingViewHandler;
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     quotes: AtomicUsize,
+/// }
+///
+/// let handler = TradingViewHandler::with_context(Stats::default())
+///     .on_quote_data(|stats: &Stats, _quote| {
+///         stats.quotes.fetch_add(1, Ordering::Relaxed);
+///     });
+/// assert_eq!(handler.context.quotes.load(Ordering::Relaxed), 0);
+/// ```
+#[derive(Builder)]
+pub struct TradingViewHandler<Ctx = ()> {
+    pub context: Arc<Ctx>,
+
+    #[builder(default= default_callback::<SymbolInfo, Ctx>("ON_SYMBOL_INFO"))]
+    pub on_symbol_info: Arc<CallbackFn<SymbolInfo, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_SERIES_LOADING"))]
-    pub on_series_loading: Arc<CallbackFn<Vec<Value>>>,
+    #[builder(default= default_callback::<Vec<Value>, Ctx>("ON_SERIES_LOADING"))]
+    pub on_series_loading: Arc<CallbackFn<Vec<Value>, Ctx>>,
 
-    #[builder(default= default_callback::<(SeriesInfo, Vec<DataPoint>)>("ON_CHART_DATA"))]
-    pub on_chart_data: Arc<CallbackFn<(SeriesInfo, Vec<DataPoint>)>>,
+    #[builder(default= default_callback::<ChartData, Ctx>("ON_CHART_DATA"))]
+    pub on_chart_data: Arc<CallbackFn<ChartData, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_SERIES_COMPLETED"))]
-    pub on_series_completed: Arc<CallbackFn<Vec<Value>>>,
+    /// Latest version of the bar still forming, once per chart update.
+    #[builder(default= default_callback::<BarMsg, Ctx>("ON_BAR_UPDATE"))]
+    pub on_bar_update: Arc<CallbackFn<BarMsg, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_STUDY_LOADING"))]
-    pub on_study_loading: Arc<CallbackFn<Vec<Value>>>,
+    /// Final version of a bar, exactly once after the next bar has started.
+    #[builder(default= default_callback::<BarMsg, Ctx>("ON_BAR_CLOSE"))]
+    pub on_bar_close: Arc<CallbackFn<BarMsg, Ctx>>,
 
-    #[builder(default= default_callback::<(StudyOptions, StudyResponseData)>("ON_STUDY_DATA"))]
-    pub on_study_data: Arc<CallbackFn<(StudyOptions, StudyResponseData)>>,
+    #[builder(default= default_callback::<SeriesCompletedMsg, Ctx>("ON_SERIES_COMPLETED"))]
+    pub on_series_completed: Arc<CallbackFn<SeriesCompletedMsg, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_STUDY_COMPLETED"))]
-    pub on_study_completed: Arc<CallbackFn<Vec<Value>>>,
+    #[builder(default= default_callback::<Vec<Value>, Ctx>("ON_STUDY_LOADING"))]
+    pub on_study_loading: Arc<CallbackFn<Vec<Value>, Ctx>>,
 
-    #[builder(default= default_callback::<QuoteValue>("ON_QUOTE_DATA"))]
-    pub on_quote_data: Arc<CallbackFn<QuoteValue>>,
+    #[builder(default= default_callback::<(StudyOptions, StudyResponseData), Ctx>("ON_STUDY_DATA"))]
+    pub on_study_data: Arc<CallbackFn<(StudyOptions, StudyResponseData), Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_QUOTE_COMPLETED"))]
-    pub on_quote_completed: Arc<CallbackFn<Vec<Value>>>,
+    /// Backtest report of studies attached as [`crate::pine_indicator::ScriptType::StrategyScript`].
+    #[cfg(feature = "pine")]
+    #[builder(default= default_callback::<(StudyOptions, StrategyReport), Ctx>("ON_STRATEGY_REPORT"))]
+    pub on_strategy_report: Arc<CallbackFn<(StudyOptions, StrategyReport), Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_REPLAY_OK"))]
-    pub on_replay_ok: Arc<CallbackFn<Vec<Value>>>,
+    #[builder(default= default_callback::<StudyCompletedMsg, Ctx>("ON_STUDY_COMPLETED"))]
+    pub on_study_completed: Arc<CallbackFn<StudyCompletedMsg, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_REPLAY_POINT"))]
-    pub on_replay_point: Arc<CallbackFn<Vec<Value>>>,
+    #[builder(default= default_callback::<QuoteValue, Ctx>("ON_QUOTE_DATA"))]
+    pub on_quote_data: Arc<CallbackFn<QuoteValue, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_REPLAY_INSTANCE_ID"))]
-    pub on_replay_instance_id: Arc<CallbackFn<Vec<Value>>>,
+    #[builder(default= default_callback::<Vec<Value>, Ctx>("ON_QUOTE_COMPLETED"))]
+    pub on_quote_completed: Arc<CallbackFn<Vec<Value>, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_REPLAY_RESOLUTIONS"))]
-    pub on_replay_resolutions: Arc<CallbackFn<Vec<Value>>>,
+    /// Only called when [`crate::quote::options::QuoteSessionOptions::emit_snapshots`] is set.
+    #[builder(default= default_callback::<QuoteSnapshot, Ctx>("ON_QUOTE_SNAPSHOT"))]
+    pub on_quote_snapshot: Arc<CallbackFn<QuoteSnapshot, Ctx>>,
 
-    #[builder(default= default_callback::<Vec<Value>>("ON_REPLAY_DATA_END"))]
-    pub on_replay_data_end: Arc<CallbackFn<Vec<Value>>>,
+    /// Order book snapshots and updates of depth sessions, see
+    /// [`crate::websocket::WebSocketClient::add_depth`].
+    #[builder(default= default_callback::<DepthUpdate, Ctx>("ON_DEPTH_DATA"))]
+    pub on_depth_data: Arc<CallbackFn<DepthUpdate, Ctx>>,
 
-    #[builder(default= default_callback::<(Error, Vec<Value>)>("ON_ERROR"))]
-    pub on_error: Arc<CallbackFn<(Error, Vec<Value>)>>,
+    /// Headlines polled by [`crate::websocket::WebSocketClient::stream_news`].
+    #[builder(default= default_callback::<News, Ctx>("ON_NEWS"))]
+    pub on_news: Arc<CallbackFn<News, Ctx>>,
 
-    #[builder(default= default_callback::<(Ustr, Vec<Value>)>("ON_UNKNOWN_EVENT"))]
-    pub on_unknown_event: Arc<CallbackFn<(Ustr, Vec<Value>)>>,
+    /// Ideas and Minds posts polled by [`crate::websocket::WebSocketClient::stream_social`].
+    #[builder(default= default_callback::<SocialPost, Ctx>("ON_SOCIAL_POST"))]
+    pub on_social_post: Arc<CallbackFn<SocialPost, Ctx>>,
+
+    #[cfg(feature = "replay")]
+    #[builder(default= default_callback::<ReplayOk, Ctx>("ON_REPLAY_OK"))]
+    pub on_replay_ok: Arc<CallbackFn<ReplayOk, Ctx>>,
+
+    #[cfg(feature = "replay")]
+    #[builder(default= default_callback::<ReplayPoint, Ctx>("ON_REPLAY_POINT"))]
+    pub on_replay_point: Arc<CallbackFn<ReplayPoint, Ctx>>,
+
+    #[cfg(feature = "replay")]
+    #[builder(default= default_callback::<ReplayInstanceId, Ctx>("ON_REPLAY_INSTANCE_ID"))]
+    pub on_replay_instance_id: Arc<CallbackFn<ReplayInstanceId, Ctx>>,
+
+    #[cfg(feature = "replay")]
+    #[builder(default= default_callback::<ReplayResolutions, Ctx>("ON_REPLAY_RESOLUTIONS"))]
+    pub on_replay_resolutions: Arc<CallbackFn<ReplayResolutions, Ctx>>,
+
+    #[cfg(feature = "replay")]
+    #[builder(default= default_callback::<ReplayDataEnd, Ctx>("ON_REPLAY_DATA_END"))]
+    pub on_replay_data_end: Arc<CallbackFn<ReplayDataEnd, Ctx>>,
+
+    #[builder(default= default_callback::<(Error, Vec<Value>), Ctx>("ON_ERROR"))]
+    pub on_error: Arc<CallbackFn<(Error, Vec<Value>), Ctx>>,
+
+    #[builder(default= default_callback::<(Ustr, Vec<Value>), Ctx>("ON_UNKNOWN_EVENT"))]
+    pub on_unknown_event: Arc<CallbackFn<(Ustr, Vec<Value>), Ctx>>,
+
+    #[builder(default= default_callback::<ReconnectedMsg, Ctx>("ON_RECONNECTED"))]
+    pub on_reconnected: Arc<CallbackFn<ReconnectedMsg, Ctx>>,
+
+    #[builder(default= default_callback::<ConnectionStaleMsg, Ctx>("ON_CONNECTION_STALE"))]
+    pub on_connection_stale: Arc<CallbackFn<ConnectionStaleMsg, Ctx>>,
+
+    /// Emitted by [`crate::client::session::SessionGuard`] after re-authenticating.
+    #[builder(default= default_callback::<SessionRefreshedMsg, Ctx>("ON_SESSION_REFRESHED"))]
+    pub on_session_refreshed: Arc<CallbackFn<SessionRefreshedMsg, Ctx>>,
+}
+
+impl<Ctx> Clone for TradingViewHandler<Ctx> {
+    fn clone(&self) -> Self {
+        macro_rules! build {
+            ($($(#[$meta:meta])* $field:ident),+) => {
+                TradingViewHandler {
+                    context: Arc::clone(&self.context),
+                    $($(#[$meta])* $field: Arc::clone(&self.$field)),+
+                }
+            };
+        }
+        with_callback_fields!(build)
+    }
 }
 
-impl Default for TradingViewHandler {
+impl<Ctx: Default> Default for TradingViewHandler<Ctx> {
     fn default() -> Self {
-        TradingViewHandler::builder().build()
+        Self::with_context(Ctx::default())
+    }
+}
+
+impl<Ctx> TradingViewHandler<Ctx> {
+    /// Create a handler with no-op callbacks around `context`.
+    pub fn with_context(context: Ctx) -> Self {
+        TradingViewHandler::builder()
+            .context(Arc::new(context))
+            .build()
+    }
+
+    event_setter!(on_chart_data, on_chart_data_async, ChartData);
+    event_setter!(on_bar_update, on_bar_update_async, BarMsg);
+    event_setter!(on_bar_close, on_bar_close_async, BarMsg);
+    event_setter!(on_quote_data, on_quote_data_async, QuoteValue);
+    event_setter!(
+        on_study_data,
+        on_study_data_async,
+        (StudyOptions, StudyResponseData)
+    );
+    #[cfg(feature = "pine")]
+    event_setter!(
+        on_strategy_report,
+        on_strategy_report_async,
+        (StudyOptions, StrategyReport)
+    );
+    event_setter!(on_error, on_error_async, (Error, Vec<Value>));
+    event_setter!(on_symbol_info, on_symbol_info_async, SymbolInfo);
+    event_setter!(
+        on_series_completed,
+        on_series_completed_async,
+        SeriesCompletedMsg
+    );
+    event_setter!(on_series_loading, on_series_loading_async, Vec<Value>);
+    event_setter!(on_quote_completed, on_quote_completed_async, Vec<Value>);
+    event_setter!(on_quote_snapshot, on_quote_snapshot_async, QuoteSnapshot);
+    event_setter!(on_depth_data, on_depth_data_async, DepthUpdate);
+    event_setter!(on_news, on_news_async, News);
+    event_setter!(on_social_post, on_social_post_async, SocialPost);
+    #[cfg(feature = "replay")]
+    event_setter!(on_replay_ok, on_replay_ok_async, ReplayOk);
+    #[cfg(feature = "replay")]
+    event_setter!(on_replay_point, on_replay_point_async, ReplayPoint);
+    #[cfg(feature = "replay")]
+    event_setter!(
+        on_replay_instance_id,
+        on_replay_instance_id_async,
+        ReplayInstanceId
+    );
+    #[cfg(feature = "replay")]
+    event_setter!(
+        on_replay_resolutions,
+        on_replay_resolutions_async,
+        ReplayResolutions
+    );
+    #[cfg(feature = "replay")]
+    event_setter!(on_replay_data_end, on_replay_data_end_async, ReplayDataEnd);
+    event_setter!(on_study_loading, on_study_loading_async, Vec<Value>);
+    event_setter!(
+        on_study_completed,
+        on_study_completed_async,
+        StudyCompletedMsg
+    );
+    event_setter!(on_unknown_event, on_unknown_event_async, (Ustr, Vec<Value>));
+    event_setter!(on_reconnected, on_reconnected_async, ReconnectedMsg);
+    event_setter!(
+        on_connection_stale,
+        on_connection_stale_async,
+        ConnectionStaleMsg
+    );
+    event_setter!(
+        on_session_refreshed,
+        on_session_refreshed_async,
+        SessionRefreshedMsg
+    );
+}
+
+impl<Ctx: Send + Sync + 'static> TradingViewHandler<Ctx> {
+    /// Bind the context into every callback, producing a context-free handler
+    /// that can be attached to a [`crate::websocket::WebSocketClient`].
+    pub fn erase(self) -> TradingViewHandler {
+        macro_rules! build {
+            ($($(#[$meta:meta])* $field:ident),+) => {
+                TradingViewHandler {
+                    context: Arc::new(()),
+                    $($(#[$meta])* $field: erase_callback(&self.context, self.$field)),+
+                }
+            };
+        }
+        with_callback_fields!(build)
     }
 }
 
 impl TradingViewHandler {
-    event_setter!(on_chart_data, (SeriesInfo, Vec<DataPoint>));
-    event_setter!(on_quote_data, QuoteValue);
-    event_setter!(on_study_data, (StudyOptions, StudyResponseData));
-    event_setter!(on_error, (Error, Vec<Value>));
-    event_setter!(on_symbol_info, SymbolInfo);
-    event_setter!(on_series_completed, Vec<Value>);
-    event_setter!(on_series_loading, Vec<Value>);
-    event_setter!(on_quote_completed, Vec<Value>);
-    event_setter!(on_replay_ok, Vec<Value>);
-    event_setter!(on_replay_point, Vec<Value>);
-    event_setter!(on_replay_instance_id, Vec<Value>);
-    event_setter!(on_replay_resolutions, Vec<Value>);
-    event_setter!(on_replay_data_end, Vec<Value>);
-    event_setter!(on_study_loading, Vec<Value>);
-    event_setter!(on_study_completed, Vec<Value>);
-    event_setter!(on_unknown_event, (Ustr, Vec<Value>));
+    /// Also persist chart, quote snapshot and study events into `sink`.
+    pub fn with_sink(self, sink: impl DataSink + 'static) -> TradingViewHandler {
+        self.chain(sink_handler(sink))
+    }
+
+    /// Run every callback of `self` followed by the matching callback of `next`.
+    pub fn chain(self, next: TradingViewHandler) -> TradingViewHandler {
+        macro_rules! build {
+            ($($(#[$meta:meta])* $field:ident),+) => {
+                TradingViewHandler {
+                    context: self.context,
+                    $($(#[$meta])* $field: chain_callback(self.$field, next.$field)),+
+                }
+            };
+        }
+        with_callback_fields!(build)
+    }
+}
+
+/// Forward every event into `tx`, applying its backpressure policy.
+fn forward_callback<T: 'static>(
+    tx: &Arc<DataTx>,
+    wrap: fn(T) -> TradingViewResponse,
+) -> Arc<CallbackFn<T>> {
+    let tx = Arc::clone(tx);
+    Arc::new(Box::new(move |_: &(), data| tx.forward(wrap(data))))
 }
 
 pub fn create_handler(tx: Arc<DataTx>) -> TradingViewHandler {
-    TradingViewHandler::builder()
-        .on_symbol_info({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                let _ = tx.send(TradingViewResponse::SymbolInfo(data));
-            }))
-        })
+    #[allow(unused_mut)]
+    let mut handler = TradingViewHandler::builder()
+        .context(Arc::new(()))
+        .on_symbol_info(forward_callback(&tx, TradingViewResponse::SymbolInfo))
         .on_series_loading({
             let tx = tx.clone();
-            Arc::new(Box::new(move |data: Vec<Value>| {
-                let msg = if let Ok(msg) = LoadingMsg::new(&data) {
-                    msg
-                } else {
+            Arc::new(Box::new(move |_: &(), data: Vec<Value>| {
+                let Ok(msg) = LoadingMsg::new(&data) else {
                     tracing::error!("Failed to parse LoadingMsg from data: {:?}", data);
-                    return;
+                    return None;
                 };
-                if let Err(e) = tx.send(TradingViewResponse::SeriesLoading(msg)) {
-                    tracing::error!("Failed to send SeriesLoading response: {}", e);
-                }
+                tx.forward(TradingViewResponse::SeriesLoading(msg))
             }))
         })
         .on_chart_data({
             let tx = tx.clone();
-            Arc::new(Box::new(move |(series_info, data_points)| {
-                if let Err(e) = tx.send(TradingViewResponse::ChartData(series_info, data_points)) {
-                    tracing::error!("Failed to send ChartData response: {}", e);
-                }
-            }))
-        })
-        .on_series_completed({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::SeriesCompleted(data)) {
-                    tracing::error!("Failed to send SeriesCompleted response: {}", e);
-                }
+            Arc::new(Box::new(move |_: &(), (series_info, data_points)| {
+                tx.forward(TradingViewResponse::ChartData(series_info, data_points))
             }))
         })
+        .on_bar_update(forward_callback(&tx, TradingViewResponse::BarUpdate))
+        .on_bar_close(forward_callback(&tx, TradingViewResponse::BarClose))
+        .on_series_completed(forward_callback(&tx, TradingViewResponse::SeriesCompleted))
         .on_study_loading({
             let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                let msg = if let Ok(msg) = LoadingMsg::new(&data) {
-                    msg
-                } else {
+            Arc::new(Box::new(move |_: &(), data: Vec<Value>| {
+                let Ok(msg) = LoadingMsg::new(&data) else {
                     tracing::error!("Failed to parse LoadingMsg from data: {:?}", data);
-                    return;
+                    return None;
                 };
-                if let Err(e) = tx.send(TradingViewResponse::StudyLoading(msg)) {
-                    tracing::error!("Failed to send StudyLoading response: {}", e);
-                }
+                tx.forward(TradingViewResponse::StudyLoading(msg))
             }))
         })
         .on_study_data({
             let tx = tx.clone();
-            Arc::new(Box::new(move |(study_options, study_data)| {
-                if let Err(e) = tx.send(TradingViewResponse::StudyData(study_options, study_data)) {
-                    tracing::error!("Failed to send StudyData response: {}", e);
-                }
-            }))
-        })
-        .on_study_completed({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::StudyCompleted(data)) {
-                    tracing::error!("Failed to send StudyCompleted response: {}", e);
-                }
-            }))
-        })
-        .on_quote_data({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::QuoteData(data)) {
-                    tracing::error!("Failed to send QuoteData response: {}", e);
-                }
-            }))
-        })
-        .on_quote_completed({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::QuoteCompleted(data)) {
-                    tracing::error!("Failed to send QuoteCompleted response: {}", e);
-                }
-            }))
-        })
-        .on_replay_ok({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::ReplayOk(data)) {
-                    tracing::error!("Failed to send ReplayOk response: {}", e);
-                }
-            }))
-        })
-        .on_replay_point({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::ReplayPoint(data)) {
-                    tracing::error!("Failed to send ReplayPoint response: {}", e);
-                }
-            }))
-        })
-        .on_replay_instance_id({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::ReplayInstanceId(data)) {
-                    tracing::error!("Failed to send ReplayInstanceId response: {}", e);
-                }
-            }))
-        })
-        .on_replay_resolutions({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::ReplayResolutions(data)) {
-                    tracing::error!("Failed to send ReplayResolutions response: {}", e);
-                }
-            }))
-        })
-        .on_replay_data_end({
-            let tx = tx.clone();
-            Arc::new(Box::new(move |data| {
-                if let Err(e) = tx.send(TradingViewResponse::ReplayDataEnd(data)) {
-                    tracing::error!("Failed to send ReplayDataEnd response: {}", e);
-                }
+            Arc::new(Box::new(move |_: &(), (study_options, study_data)| {
+                tx.forward(TradingViewResponse::StudyData(study_options, study_data))
             }))
         })
+        .on_study_completed(forward_callback(&tx, TradingViewResponse::StudyCompleted))
+        .on_quote_data(forward_callback(&tx, TradingViewResponse::QuoteData))
+        .on_quote_completed(forward_callback(&tx, TradingViewResponse::QuoteCompleted))
+        .on_quote_snapshot(forward_callback(&tx, TradingViewResponse::QuoteSnapshot))
+        .on_depth_data(forward_callback(&tx, TradingViewResponse::DepthData))
+        .on_news(forward_callback(&tx, TradingViewResponse::News))
+        .on_social_post(forward_callback(&tx, TradingViewResponse::SocialPost))
         .on_error({
             let tx = tx.clone();
-            Arc::new(Box::new(move |(error, values)| {
-                if let Err(e) = tx.send(TradingViewResponse::Error(error, values)) {
-                    tracing::error!("Failed to send Error response: {}", e);
-                }
+            Arc::new(Box::new(move |_: &(), (error, values)| {
+                tx.forward(TradingViewResponse::Error(error, values))
             }))
         })
         .on_unknown_event({
             let tx = tx.clone();
-            Arc::new(Box::new(move |(event, values): (Ustr, Vec<Value>)| {
-                if let Err(e) = tx.send(TradingViewResponse::UnknownEvent(event.into(), values)) {
-                    tracing::error!("Failed to send UnknownEvent response: {}", e);
-                }
-            }))
+            Arc::new(Box::new(
+                move |_: &(), (event, values): (Ustr, Vec<Value>)| {
+                    tx.forward(TradingViewResponse::UnknownEvent(event, values))
+                },
+            ))
         })
-        .build()
+        .on_reconnected(forward_callback(&tx, TradingViewResponse::Reconnected))
+        .on_connection_stale(forward_callback(&tx, TradingViewResponse::ConnectionStale))
+        .on_session_refreshed(forward_callback(&tx, TradingViewResponse::SessionRefreshed))
+        .build();
+    #[cfg(feature = "pine")]
+    {
+        let tx = tx.clone();
+        handler.on_strategy_report = Arc::new(Box::new(move |_: &(), (study_options, report)| {
+            tx.forward(TradingViewResponse::StrategyReport(
+                study_options,
+                Box::new(report),
+            ))
+        }));
+    }
+    #[cfg(feature = "replay")]
+    {
+        handler.on_replay_ok = forward_callback(&tx, TradingViewResponse::ReplayOk);
+        handler.on_replay_point = forward_callback(&tx, TradingViewResponse::ReplayPoint);
+        handler.on_replay_instance_id =
+            forward_callback(&tx, TradingViewResponse::ReplayInstanceId);
+        handler.on_replay_resolutions =
+            forward_callback(&tx, TradingViewResponse::ReplayResolutions);
+        handler.on_replay_data_end = forward_callback(&tx, TradingViewResponse::ReplayDataEnd);
+    }
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    #[tokio::test]
+    async fn test_chain_awaits_async_callback_in_order() {
+        let log: Log = Arc::default();
+
+        let first = TradingViewHandler::with_context(Arc::clone(&log)).on_unknown_event_async(
+            |log: Arc<Log>, _| async move {
+                tokio::task::yield_now().await;
+                log.lock().unwrap().push("async");
+            },
+        );
+        let second =
+            TradingViewHandler::with_context(Arc::clone(&log)).on_unknown_event(|log: &Log, _| {
+                log.lock().unwrap().push("sync");
+            });
+
+        let handler = first.erase().chain(second.erase());
+        dispatch(
+            &handler.on_unknown_event,
+            (Ustr::from("replay_ok"), Vec::new()),
+        )
+        .await;
+
+        assert_eq!(*log.lock().unwrap(), vec!["async", "sync"]);
+    }
 }
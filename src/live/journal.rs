@@ -0,0 +1,183 @@
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use ustr::Ustr;
+
+const SESSION_PREFIXES: [&str; 3] = ["cs_", "qs_", "rs_"];
+/// Methods whose payload carries credentials and is never stored.
+pub(crate) const REDACTED_METHODS: [&str; 1] = ["set_auth_token"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JournalDirection {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Monotonic sequence number, unique within a journal.
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub direction: JournalDirection,
+    pub method: Ustr,
+    /// Chart, quote or replay session the frame belongs to, if any.
+    pub correlation_id: Option<Ustr>,
+    pub payload: Vec<Value>,
+}
+
+/// Opt-in ring buffer of every frame sent and received by a [`crate::websocket::WebSocketClient`].
+///
+/// Attach one with `WebSocketClient::builder().journal(...)` and dump it with
+/// [`EventJournal::to_json`] when reporting protocol issues.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::live::journal::{EventJournal, JournalDirection};
+///
+/// let journal = EventJournal::new(1024);
+/// journal.record(JournalDirection::Sent, "chart_create_session", &["cs_abc".into()]);
+///
+/// assert_eq!(journal.by_correlation_id("cs_abc").len(), 1);
+/// println!("{}", journal.to_json().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct EventJournal {
+    capacity: usize,
+    seq: AtomicU64,
+    entries: Mutex<VecDeque<JournalEntry>>,
+}
+
+impl Default for EventJournal {
+    fn default() -> Self {
+        Self::new(4096)
+    }
+}
+
+impl EventJournal {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seq: AtomicU64::new(0),
+            entries: Mutex::new(VecDeque::with_capacity(capacity.clamp(1, 4096))),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Record a frame, evicting the oldest entry once the journal is full.
+    pub fn record(&self, direction: JournalDirection, method: &str, payload: &[Value]) {
+        let payload = if REDACTED_METHODS.contains(&method) {
+            vec![Value::from("<redacted>")]
+        } else {
+            payload.to_vec()
+        };
+        let entry = JournalEntry {
+            seq: self.seq.fetch_add(1, Ordering::Relaxed),
+            timestamp: Utc::now(),
+            direction,
+            method: Ustr::from(method),
+            correlation_id: correlation_id(&payload),
+            payload,
+        };
+
+        let mut entries = self.lock();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Snapshot of all entries, oldest first.
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.lock().iter().cloned().collect()
+    }
+
+    pub fn query(&self, filter: impl Fn(&JournalEntry) -> bool) -> Vec<JournalEntry> {
+        self.lock().iter().filter(|e| filter(e)).cloned().collect()
+    }
+
+    pub fn by_correlation_id(&self, id: &str) -> Vec<JournalEntry> {
+        self.query(|e| e.correlation_id.is_some_and(|c| c == id))
+    }
+
+    pub fn by_method(&self, method: &str) -> Vec<JournalEntry> {
+        self.query(|e| e.method == method)
+    }
+
+    pub fn since(&self, timestamp: DateTime<Utc>) -> Vec<JournalEntry> {
+        self.query(|e| e.timestamp >= timestamp)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&*self.lock())?)
+    }
+
+    /// Write the journal as pretty-printed JSON to `path`.
+    pub fn dump(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<JournalEntry>> {
+        // A panic while holding the lock cannot leave the buffer half-written
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn correlation_id(payload: &[Value]) -> Option<Ustr> {
+    payload
+        .first()
+        .and_then(Value::as_str)
+        .filter(|s| SESSION_PREFIXES.iter().any(|p| s.starts_with(p)))
+        .map(Ustr::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_eviction() {
+        let journal = EventJournal::new(2);
+        journal.record(JournalDirection::Sent, "a", &[]);
+        journal.record(JournalDirection::Received, "b", &[]);
+        journal.record(JournalDirection::Received, "c", &["qs_123".into()]);
+
+        let entries = journal.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].method, "b");
+        assert_eq!(entries[1].seq, 2);
+        assert_eq!(entries[1].correlation_id, Some(Ustr::from("qs_123")));
+    }
+
+    #[test]
+    fn test_redacts_auth_token() {
+        let journal = EventJournal::default();
+        journal.record(JournalDirection::Sent, "set_auth_token", &["secret".into()]);
+        assert!(!journal.to_json().unwrap().contains("secret"));
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(feature = "pine")]
+use crate::live::snapshot::StudySnapshot;
+use crate::{
+    DataPoint,
+    chart::{ChartOptions, SymbolInfo},
+    quote::state::QuoteStateTracker,
+};
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use ustr::Ustr;
+
+/// Sessions opened by a client, shared with its data handler.
+#[derive(Default, Clone)]
+pub(crate) struct Metadata {
+    pub(crate) series: Arc<DashMap<Ustr, SeriesInfo>>,
+    pub(crate) studies: Arc<DashMap<Ustr, Ustr>>,
+    /// Studies added with `create_study`, keyed by chart session and study
+    /// id, replayed after a reconnect.
+    #[cfg(feature = "pine")]
+    pub(crate) created_studies: Arc<DashMap<(Ustr, Ustr), StudySnapshot>>,
+    pub(crate) quotes: Arc<QuoteStateTracker>,
+    /// Symbols added to the quote session, replayed after a reconnect.
+    pub(crate) quote_symbols: Arc<DashSet<Ustr>>,
+    /// Symbol of every depth session, replayed after a reconnect.
+    pub(crate) depth_sessions: Arc<DashMap<Ustr, Ustr>>,
+    /// Series with a `request_more_data` in flight, cleared on `series_completed`.
+    pub(crate) backfill: Arc<DashSet<Ustr>>,
+    pub(crate) chart_state: Arc<RwLock<ChartState>>,
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct ChartState {
+    pub(crate) chart: Option<(SeriesInfo, Arc<[DataPoint]>)>,
+    pub(crate) symbol_info: Option<SymbolInfo>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SeriesInfo {
+    pub chart_session: Ustr,
+    pub options: ChartOptions,
+    /// Id of the resolved symbol the series was created on, e.g. `sds_sym_1`.
+    #[serde(default)]
+    pub symbol_series_id: Ustr,
+    /// Version the series was created with, e.g. `s1`.
+    #[serde(default)]
+    pub series_version: Ustr,
+    /// Set on chart data answering a `request_more_data`, i.e. older bars
+    /// prepended to the series rather than new ones.
+    #[serde(default)]
+    pub backfill: bool,
+}
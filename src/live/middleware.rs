@@ -0,0 +1,115 @@
+use serde_json::Value;
+use std::{borrow::Cow, sync::Arc};
+use ustr::Ustr;
+
+/// Command about to be sent by a [`crate::websocket::WebSocketClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboundMessage {
+    pub method: Ustr,
+    pub params: Vec<Value>,
+}
+
+/// Hook around the websocket of a [`crate::websocket::WebSocketClient`],
+/// for logging, protocol experiments or rewriting messages the crate does
+/// not model yet.
+///
+/// Both methods pass their input on unchanged by default. Returning `None`
+/// drops the frame, and later middleware never sees it. Custom commands are
+/// injected with `WebSocketClient::send`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use tradingview::live::middleware::FrameMiddleware;
+///
+/// /// Swallow quote updates before they are parsed.
+/// struct NoQuotes;
+///
+/// impl FrameMiddleware for NoQuotes {
+///     fn inbound<'a>(&self, frame: Cow<'a, str>) -> Option<Cow<'a, str>> {
+///         (!frame.contains(r#""m":"qsd""#)).then_some(frame)
+///     }
+/// }
+/// ```
+pub trait FrameMiddleware: Send + Sync {
+    /// Raw `~m~` framed text received, before it is parsed.
+    fn inbound<'a>(&self, frame: Cow<'a, str>) -> Option<Cow<'a, str>> {
+        Some(frame)
+    }
+
+    /// Command about to be framed and sent.
+    fn outbound(&self, message: OutboundMessage) -> Option<OutboundMessage> {
+        Some(message)
+    }
+}
+
+/// Middleware of a client, run in the order they were added.
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareChain(Vec<Arc<dyn FrameMiddleware>>);
+
+impl MiddlewareChain {
+    pub(crate) fn new(middleware: Vec<Arc<dyn FrameMiddleware>>) -> Self {
+        Self(middleware)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn inbound<'a>(&self, frame: &'a str) -> Option<Cow<'a, str>> {
+        self.0
+            .iter()
+            .try_fold(Cow::Borrowed(frame), |frame, m| m.inbound(frame))
+    }
+
+    pub(crate) fn outbound(&self, method: &str, params: &[Value]) -> Option<OutboundMessage> {
+        let message = OutboundMessage {
+            method: Ustr::from(method),
+            params: params.to_vec(),
+        };
+        self.0
+            .iter()
+            .try_fold(message, |message, m| m.outbound(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DropQuotes;
+
+    impl FrameMiddleware for DropQuotes {
+        fn inbound<'a>(&self, frame: Cow<'a, str>) -> Option<Cow<'a, str>> {
+            (!frame.contains("qsd")).then_some(frame)
+        }
+    }
+
+    struct Rename;
+
+    impl FrameMiddleware for Rename {
+        fn outbound(&self, mut message: OutboundMessage) -> Option<OutboundMessage> {
+            if message.method == "quote_fast_symbols" {
+                message.method = Ustr::from("quote_add_symbols");
+            }
+            Some(message)
+        }
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let middleware: Vec<Arc<dyn FrameMiddleware>> =
+            vec![Arc::new(DropQuotes), Arc::new(Rename)];
+        let chain = MiddlewareChain::new(middleware);
+
+        assert!(chain.inbound(r#"~m~20~m~{"m":"qsd","p":[]}"#).is_none());
+        assert_eq!(chain.inbound("~m~4~m~~h~1").as_deref(), Some("~m~4~m~~h~1"));
+
+        let message = chain
+            .outbound("quote_fast_symbols", &["qs_1".into()])
+            .unwrap();
+        assert_eq!(message.method, "quote_add_symbols");
+        assert_eq!(message.params, vec![Value::from("qs_1")]);
+    }
+}
@@ -0,0 +1,512 @@
+//! Local websocket server speaking the TradingView frame format, for
+//! integration tests that must not reach TradingView.
+//!
+//! A [`MockScript`] maps each client method to the frames sent back, and
+//! [`MockServer::url`] plugs into the `endpoint` of
+//! [`crate::websocket::WebSocketClient`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use serde_json::json;
+//! use tradingview::{live::mock::{MockScript, MockServer}, websocket::WebSocketClient};
+//!
+//! # async fn run() -> tradingview::Result<()> {
+//! let script = MockScript::new().quotes(vec![("NASDAQ:AAPL", json!({ "lp": 190.5 }))]);
+//! let server = MockServer::start(script).await?;
+//! let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+//! let _ws = WebSocketClient::builder()
+//!     .data_tx(tx)
+//!     .endpoint(server.url())
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    DataPoint, Result,
+    live::{
+        handler::{
+            channel::DataTx,
+            data::DataHandler,
+            types::{EventMask, TradingViewHandler},
+        },
+        models::{SocketMessage, SocketMessageDe},
+        protocol,
+    },
+    utils::{format_frame, parse_packet, parse_packet_into},
+};
+use futures_util::{Sink, SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, broadcast},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{accept_async, tungstenite::protocol::Message};
+use tracing::{debug, warn};
+use url::Url;
+use ustr::Ustr;
+
+/// Builds the frames answering one client message from its parameters.
+pub type Responder = Arc<dyn Fn(&[Value]) -> Vec<MockFrame> + Send + Sync>;
+
+/// One `{"m": method, "p": params}` message, in either direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockFrame {
+    pub method: Ustr,
+    pub params: Vec<Value>,
+}
+
+impl MockFrame {
+    pub fn new(method: &str, params: Vec<Value>) -> Self {
+        Self {
+            method: Ustr::from(method),
+            params,
+        }
+    }
+}
+
+/// Canned responses of a [`MockServer`], keyed by client method.
+///
+/// Methods without a responder are recorded and left unanswered.
+#[derive(Clone, Default)]
+pub struct MockScript {
+    responders: HashMap<Ustr, Responder>,
+}
+
+impl MockScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer every `method` message with the frames built by `responder`.
+    pub fn on(
+        mut self,
+        method: &str,
+        responder: impl Fn(&[Value]) -> Vec<MockFrame> + Send + Sync + 'static,
+    ) -> Self {
+        self.responders
+            .insert(Ustr::from(method), Arc::new(responder));
+        self
+    }
+
+    /// Resolve every symbol to `symbol_info` and fill every series with `bars`.
+    pub fn chart(self, symbol_info: Value, bars: Vec<DataPoint>) -> Self {
+        let bars = data_points(&bars);
+        self.on("resolve_symbol", move |p| {
+            vec![MockFrame::new(
+                "symbol_resolved",
+                vec![param(p, 0), param(p, 1), symbol_info.clone()],
+            )]
+        })
+        .on("create_series", move |p| {
+            let series_id = param(p, 1);
+            let key = series_id.as_str().unwrap_or_default();
+            vec![
+                MockFrame::new(
+                    "timescale_update",
+                    vec![param(p, 0), json!({ key: { "node": "mock", "s": bars } })],
+                ),
+                MockFrame::new(
+                    "series_completed",
+                    vec![param(p, 0), series_id, json!("streaming"), json!("s1")],
+                ),
+            ]
+        })
+    }
+
+    /// Answer `quote_add_symbols` with the quote values of each known symbol,
+    /// e.g. `("NASDAQ:AAPL", json!({ "lp": 190.5 }))`.
+    pub fn quotes(self, quotes: Vec<(&str, Value)>) -> Self {
+        let quotes: HashMap<Ustr, Value> = quotes
+            .into_iter()
+            .map(|(symbol, values)| (Ustr::from(symbol), values))
+            .collect();
+        self.on("quote_add_symbols", move |p| {
+            let session = param(p, 0);
+            p.iter()
+                .skip(1)
+                .filter_map(|name| {
+                    let values = quotes.get(&requested_symbol(name)?)?;
+                    Some([
+                        MockFrame::new(
+                            "qsd",
+                            vec![
+                                session.clone(),
+                                json!({ "n": name, "s": "ok", "v": values }),
+                            ],
+                        ),
+                        MockFrame::new("quote_completed", vec![session.clone(), name.clone()]),
+                    ])
+                })
+                .flatten()
+                .collect()
+        })
+    }
+
+    /// Fill every study with `points`.
+    pub fn study(self, points: Vec<DataPoint>) -> Self {
+        let points = data_points(&points);
+        self.on("create_study", move |p| {
+            let study_id = param(p, 1);
+            let key = study_id.as_str().unwrap_or_default();
+            vec![
+                MockFrame::new(
+                    "timescale_update",
+                    vec![
+                        param(p, 0),
+                        json!({ key: {
+                            "node": "mock",
+                            "st": points,
+                            "ns": { "d": "", "indexes": [] },
+                        } }),
+                    ],
+                ),
+                MockFrame::new("study_completed", vec![param(p, 0), study_id, json!("s1")]),
+            ]
+        })
+    }
+}
+
+/// Websocket server on `127.0.0.1` answering with a [`MockScript`].
+///
+/// Every connection is greeted like TradingView does and served
+/// independently, so reconnects see the same script. The server stops when
+/// dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    received: Arc<Mutex<Vec<MockFrame>>>,
+    push_tx: broadcast::Sender<MockFrame>,
+    disconnect_tx: broadcast::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Bind a free local port and start accepting connections.
+    pub async fn start(script: MockScript) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (push_tx, _) = broadcast::channel(256);
+        let (disconnect_tx, _) = broadcast::channel(1);
+
+        let script = Arc::new(script);
+        let task = tokio::spawn({
+            let received = Arc::clone(&received);
+            let push_tx = push_tx.clone();
+            let disconnect_tx = disconnect_tx.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(
+                        stream,
+                        Arc::clone(&script),
+                        Arc::clone(&received),
+                        push_tx.subscribe(),
+                        disconnect_tx.subscribe(),
+                    ));
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            received,
+            push_tx,
+            disconnect_tx,
+            task,
+        })
+    }
+
+    /// Endpoint to pass to the client builder.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("ws://{}/socket.io/websocket", self.addr))
+            .expect("mock server address is a valid url")
+    }
+
+    /// Every message received so far, across all connections.
+    pub async fn received(&self) -> Vec<MockFrame> {
+        self.received.lock().await.clone()
+    }
+
+    /// Send `frame` unprompted to every open connection, e.g. a `du` update.
+    pub fn push(&self, frame: MockFrame) {
+        // No receivers only means no client is connected
+        let _ = self.push_tx.send(frame);
+    }
+
+    /// Drop every open connection without a close frame, like a network
+    /// failure would. The server keeps accepting new connections.
+    pub fn disconnect(&self) {
+        let _ = self.disconnect_tx.send(());
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Runs websocket messages through the parser and handler of a
+/// [`crate::websocket::WebSocketClient`] without a socket, e.g. to measure
+/// dispatch on its own.
+pub struct MockFeed {
+    handler: DataHandler,
+    parse_buf: Vec<SocketMessage<SocketMessageDe>>,
+}
+
+#[bon::bon]
+impl MockFeed {
+    #[builder]
+    pub fn new(
+        /// Receives every parsed response, as the client's `data_tx`.
+        #[builder(into)]
+        data_tx: DataTx,
+        /// Extra callbacks run after each event has been forwarded to `data_tx`.
+        handler: Option<TradingViewHandler>,
+    ) -> Self {
+        Self {
+            handler: DataHandler::builder()
+                .res_tx(data_tx)
+                .maybe_handler(handler)
+                .build(),
+            parse_buf: Vec::new(),
+        }
+    }
+
+    /// Parse `message`, e.g. one built with [`encode`], and dispatch its
+    /// frames the way the reader task does.
+    pub async fn feed(&mut self, message: &str) {
+        parse_packet_into(
+            message,
+            true,
+            self.handler.event_mask(),
+            &mut self.parse_buf,
+        );
+        for frame in self.parse_buf.drain(..) {
+            protocol::dispatch(&self.handler, frame).await;
+        }
+    }
+}
+
+/// `frames` as one websocket message, the way the server batches them.
+pub fn encode(frames: Vec<MockFrame>) -> Result<String> {
+    frames
+        .into_iter()
+        .map(|frame| format_frame(frame_value(frame)))
+        .collect()
+}
+
+async fn serve(
+    stream: TcpStream,
+    script: Arc<MockScript>,
+    received: Arc<Mutex<Vec<MockFrame>>>,
+    mut push_rx: broadcast::Receiver<MockFrame>,
+    mut disconnect_rx: broadcast::Receiver<()>,
+) {
+    let socket = match accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("Mock handshake failed: {}", e);
+            return;
+        }
+    };
+    let (mut write, mut read) = socket.split();
+
+    let greeting = json!({
+        "session_id": "mock",
+        "timestamp": 0,
+        "timestamp_ms": 0,
+        "release": "mock",
+        "studies_metadata_hash": "",
+        "auth_scheme_vsn": 2,
+        "protocol": "json",
+        "via": "127.0.0.1",
+        "sjavastudies": [],
+    });
+    if send_frames(&mut write, [greeting]).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            frame = read.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) => {
+                        // Complete the closing handshake so the client's reader ends
+                        let _ = write.flush().await;
+                        break;
+                    }
+                    None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+                let mut replies = Vec::new();
+                for message in parse_packet(&text, false, EventMask::all()) {
+                    let SocketMessage::SocketMessage(message) = message else {
+                        continue;
+                    };
+                    debug!("Mock received: {} {:?}", message.m, message.p);
+                    if let Some(responder) = script.responders.get(&message.m) {
+                        replies.extend(responder(&message.p).into_iter().map(frame_value));
+                    }
+                    received
+                        .lock()
+                        .await
+                        .push(MockFrame { method: message.m, params: message.p });
+                }
+                if send_frames(&mut write, replies).await.is_err() {
+                    break;
+                }
+            }
+            pushed = push_rx.recv() => match pushed {
+                Ok(frame) => {
+                    if send_frames(&mut write, [frame_value(frame)]).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = disconnect_rx.recv() => break,
+        }
+    }
+}
+
+async fn send_frames<S>(write: &mut S, frames: impl IntoIterator<Item = Value>) -> Result<()>
+where
+    S: Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    for frame in frames {
+        let text = format_frame(frame)?;
+        write.send(Message::Text(text.into())).await?;
+    }
+    Ok(())
+}
+
+fn frame_value(frame: MockFrame) -> Value {
+    json!({ "m": frame.method.as_str(), "p": frame.params })
+}
+
+fn param(params: &[Value], index: usize) -> Value {
+    params.get(index).cloned().unwrap_or(Value::Null)
+}
+
+fn data_points(points: &[DataPoint]) -> Vec<Value> {
+    points
+        .iter()
+        .map(|p| json!({ "i": p.index, "v": p.value }))
+        .collect()
+}
+
+/// Plain symbol of a quote request, which is either the symbol itself or a
+/// `={"symbol": ..}` init string.
+fn requested_symbol(name: &Value) -> Option<Ustr> {
+    let name = name.as_str()?;
+    match name.strip_prefix('=') {
+        Some(init) => {
+            let init: Value = serde_json::from_str(init).ok()?;
+            init.get("symbol")?.as_str().map(Ustr::from)
+        }
+        None => Some(Ustr::from(name)),
+    }
+}
+
+#[cfg(all(test, feature = "quote"))]
+mod tests {
+    use super::*;
+    use crate::{
+        live::handler::message::TradingViewResponse,
+        quote::options::{QuoteMode, QuoteSessionOptions},
+        websocket::WebSocketClient,
+    };
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_client_against_mock_server() {
+        let script = MockScript::new().quotes(vec![("NASDAQ:AAPL", json!({ "lp": 190.5 }))]);
+        let server = MockServer::start(script).await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(tx)
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+        Arc::clone(&ws).spawn_reader_task();
+        ws.set_auth_token("mock_token").await.unwrap();
+        ws.create_quote_session_with(
+            QuoteSessionOptions::builder()
+                .symbols(vec![Ustr::from("NASDAQ:AAPL")])
+                .build(),
+        )
+        .await
+        .unwrap();
+
+        let completed = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(response) = rx.recv().await {
+                if let TradingViewResponse::QuoteCompleted(message) = response {
+                    return message;
+                }
+            }
+            panic!("data channel closed");
+        })
+        .await
+        .unwrap();
+        assert_eq!(completed[1], json!("NASDAQ:AAPL"));
+
+        let received = server.received().await;
+        assert!(
+            received
+                .iter()
+                .any(|f| f.method.as_str() == "set_auth_token")
+        );
+        assert!(
+            received
+                .iter()
+                .any(|f| f.method.as_str() == "quote_add_symbols")
+        );
+        ws.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fast_mode_marks_new_symbols() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(tx)
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+        ws.create_quote_session_with(QuoteSessionOptions::builder().mode(QuoteMode::Fast).build())
+            .await
+            .unwrap();
+        ws.add_symbols(&["NASDAQ:AAPL"]).await.unwrap();
+        ws.add_symbols(&["NASDAQ:MSFT"]).await.unwrap();
+
+        let fast = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let fast: Vec<MockFrame> = server
+                    .received()
+                    .await
+                    .into_iter()
+                    .filter(|f| f.method.as_str() == "quote_fast_symbols")
+                    .collect();
+                if fast.len() == 2 {
+                    return fast;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(fast[0].params[1..], [json!("NASDAQ:AAPL")]);
+        assert_eq!(fast[1].params[1..], [json!("NASDAQ:MSFT")]);
+        ws.close().await.unwrap();
+    }
+}
@@ -1,3 +1,32 @@
+#[cfg(feature = "deflate")]
+pub(crate) mod deflate;
+pub mod depth;
+#[cfg(feature = "native")]
+pub(crate) mod feed;
 pub mod handler;
+pub mod journal;
+pub mod metadata;
+#[cfg(feature = "native")]
+pub mod middleware;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod models;
+#[cfg(all(feature = "native", feature = "chart", feature = "quote"))]
+pub mod pool;
+#[cfg(any(feature = "native", all(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) mod protocol;
+#[cfg(feature = "native")]
+pub(crate) mod proxy;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(all(feature = "native", feature = "replay"))]
+pub mod replay;
+#[cfg(all(feature = "native", feature = "chart"))]
+pub mod session;
+pub mod snapshot;
+#[cfg(feature = "native")]
+pub mod transcript;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
+#[cfg(feature = "native")]
 pub mod websocket;
@@ -1,22 +1,28 @@
 use core::fmt;
 
-use futures_util::stream::SplitStream;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
 use serde_json::Value;
-use tokio::{net::TcpStream, sync::MutexGuard};
-use tokio_tungstenite::{
-    MaybeTlsStream, WebSocketStream,
-    tungstenite::{
-        http::{HeaderMap, HeaderValue},
-        protocol::Message,
-    },
-};
 use ustr::Ustr;
 
-use crate::{Result, UA, error::Error, error::TradingViewError, utils::format_packet};
+#[cfg(feature = "native")]
+use crate::transport::FrameStream;
+use crate::{
+    QuoteData, Result, error::TradingViewError, live::handler::types::EventMask,
+    utils::format_frame,
+};
+#[cfg(feature = "native")]
+use crate::{UA, error::Error, utils::format_packet};
+#[cfg(feature = "native")]
+use tokio::sync::MutexGuard;
+#[cfg(feature = "native")]
+use tokio_tungstenite::tungstenite::{
+    http::{HeaderMap, HeaderValue},
+    protocol::Message,
+};
 
+#[cfg(feature = "native")]
 lazy_static::lazy_static! {
-    pub static ref WEBSOCKET_HEADERS: HeaderMap<HeaderValue> = {
+    pub(crate) static ref WEBSOCKET_HEADERS: HeaderMap<HeaderValue> = {
         let mut headers = HeaderMap::new();
         headers.insert("Origin", "https://www.tradingview.com/".parse().unwrap());
         headers.insert("User-Agent", UA.parse().unwrap());
@@ -25,11 +31,13 @@ lazy_static::lazy_static! {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
-pub enum TradingViewDataEvent {
+pub(crate) enum TradingViewDataEvent {
     OnChartData,
     OnChartDataUpdate,
     OnQuoteData,
     OnQuoteCompleted,
+    OnDepthData,
+    OnDepthUpdate,
     OnSeriesLoading,
     OnSeriesCompleted,
     OnSymbolResolved,
@@ -46,13 +54,22 @@ pub enum TradingViewDataEvent {
 
 impl From<String> for TradingViewDataEvent {
     fn from(s: String) -> Self {
-        match s.as_str() {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<&str> for TradingViewDataEvent {
+    fn from(s: &str) -> Self {
+        match s {
             "timescale_update" => TradingViewDataEvent::OnChartData,
             "du" => TradingViewDataEvent::OnChartDataUpdate,
 
             "qsd" => TradingViewDataEvent::OnQuoteData,
             "quote_completed" => TradingViewDataEvent::OnQuoteCompleted,
 
+            "dpd" => TradingViewDataEvent::OnDepthData,
+            "dpu" => TradingViewDataEvent::OnDepthUpdate,
+
             "series_loading" => TradingViewDataEvent::OnSeriesLoading,
             "series_completed" => TradingViewDataEvent::OnSeriesCompleted,
 
@@ -64,7 +81,7 @@ impl From<String> for TradingViewDataEvent {
             "replay_resolutions" => TradingViewDataEvent::OnReplayResolutions,
             "replay_data_end" => TradingViewDataEvent::OnReplayDataEnd,
 
-            "study_loading" => TradingViewDataEvent::OnSeriesLoading,
+            "study_loading" => TradingViewDataEvent::OnStudyLoading,
             "study_completed" => TradingViewDataEvent::OnStudyCompleted,
 
             "symbol_error" => TradingViewDataEvent::OnError(TradingViewError::SymbolError),
@@ -79,14 +96,69 @@ impl From<String> for TradingViewDataEvent {
     }
 }
 
+impl TradingViewDataEvent {
+    /// Class of the event for an [`EventMask`].
+    pub(crate) fn class(&self) -> EventMask {
+        match self {
+            // Study data arrives along with the chart data
+            TradingViewDataEvent::OnChartData | TradingViewDataEvent::OnChartDataUpdate => {
+                EventMask::CHART | EventMask::STUDY
+            }
+            TradingViewDataEvent::OnSeriesLoading
+            | TradingViewDataEvent::OnSeriesCompleted
+            | TradingViewDataEvent::OnSymbolResolved => EventMask::CHART,
+            TradingViewDataEvent::OnQuoteData | TradingViewDataEvent::OnQuoteCompleted => {
+                EventMask::QUOTE
+            }
+            TradingViewDataEvent::OnDepthData | TradingViewDataEvent::OnDepthUpdate => {
+                EventMask::DEPTH
+            }
+            TradingViewDataEvent::OnReplayOk
+            | TradingViewDataEvent::OnReplayPoint
+            | TradingViewDataEvent::OnReplayInstanceId
+            | TradingViewDataEvent::OnReplayResolutions
+            | TradingViewDataEvent::OnReplayDataEnd => EventMask::REPLAY,
+            TradingViewDataEvent::OnStudyLoading | TradingViewDataEvent::OnStudyCompleted => {
+                EventMask::STUDY
+            }
+            TradingViewDataEvent::OnError(_) => EventMask::ERROR,
+            TradingViewDataEvent::UnknownEvent(_) => EventMask::UNKNOWN,
+        }
+    }
+
+    /// Wire method of the event, e.g. `timescale_update`.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TradingViewDataEvent::OnChartData => "timescale_update",
+            TradingViewDataEvent::OnChartDataUpdate => "du",
+            TradingViewDataEvent::OnQuoteData => "qsd",
+            TradingViewDataEvent::OnQuoteCompleted => "quote_completed",
+            TradingViewDataEvent::OnDepthData => "dpd",
+            TradingViewDataEvent::OnDepthUpdate => "dpu",
+            TradingViewDataEvent::OnSeriesLoading => "series_loading",
+            TradingViewDataEvent::OnSeriesCompleted => "series_completed",
+            TradingViewDataEvent::OnSymbolResolved => "symbol_resolved",
+            TradingViewDataEvent::OnReplayOk => "replay_ok",
+            TradingViewDataEvent::OnReplayPoint => "replay_point",
+            TradingViewDataEvent::OnReplayInstanceId => "replay_instance_id",
+            TradingViewDataEvent::OnReplayResolutions => "replay_resolutions",
+            TradingViewDataEvent::OnReplayDataEnd => "replay_data_end",
+            TradingViewDataEvent::OnStudyLoading => "study_loading",
+            TradingViewDataEvent::OnStudyCompleted => "study_completed",
+            TradingViewDataEvent::OnError(_) => "error",
+            TradingViewDataEvent::UnknownEvent(method) => method.as_str(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct SocketMessageSer {
+pub(crate) struct SocketMessageSer {
     pub m: Value,
     pub p: Value,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct SocketMessageDe {
+pub(crate) struct SocketMessageDe {
     pub m: Ustr,
     pub p: Vec<Value>,
 }
@@ -102,6 +174,12 @@ impl SocketMessageSer {
         SocketMessageSer { m, p }
     }
 
+    /// The `~m~` framed text frame of this message.
+    pub fn to_text(&self) -> Result<String> {
+        format_frame(self)
+    }
+
+    #[cfg(feature = "native")]
     pub fn to_message(&self) -> Result<Message> {
         let msg = format_packet(self)?;
         Ok(msg)
@@ -145,13 +223,23 @@ impl fmt::Display for SocketServerInfo {
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
-pub enum SocketMessage<T> {
+#[allow(clippy::enum_variant_names)]
+pub(crate) enum SocketMessage<T> {
     SocketServerInfo(SocketServerInfo),
     SocketMessage(T),
+    /// `qsd` update parsed straight into its typed payload.
+    #[serde(skip)]
+    QuoteData(QuoteData),
     Other(Value),
     Unknown(String),
 }
 
+/// `{"m":"qsd","p":[session, quote]}` frame.
+#[derive(Debug, Deserialize)]
+pub(crate) struct QuoteFrame {
+    pub p: (IgnoredAny, QuoteData),
+}
+
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize, Copy, Eq)]
 pub enum DataServer {
     #[default]
@@ -172,23 +260,15 @@ impl std::fmt::Display for DataServer {
     }
 }
 
-pub trait Socket {
-    fn event_loop(
-        &self,
-        read: MutexGuard<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
-    ) -> impl Future<Output = Result<()>> + Send;
+#[cfg(feature = "native")]
+pub(crate) trait Socket {
+    fn event_loop(&self, read: MutexGuard<FrameStream>) -> impl Future<Output = Result<()>> + Send;
 
     fn handle_raw_messages(&self, raw: Message) -> impl Future<Output = Result<()>> + Send;
 
     fn handle_parsed_messages(
         &self,
-        messages: Vec<SocketMessage<SocketMessageDe>>,
-        raw: &Message,
-    ) -> impl Future<Output = Result<()>> + Send;
-
-    fn handle_message_data(
-        &self,
-        message: SocketMessageDe,
+        messages: &mut Vec<SocketMessage<SocketMessageDe>>,
     ) -> impl Future<Output = Result<()>> + Send;
 
     fn handle_error(&self, error: Error, context: Ustr) -> impl Future<Output = Result<()>> + Send;
@@ -0,0 +1,535 @@
+use crate::{
+    ChartOptions, Error, Result,
+    live::{
+        handler::{
+            command::{BackoffConfig, ExponentialBackoff},
+            types::DataTx,
+        },
+        models::DataServer,
+        websocket::WebSocketClient,
+    },
+};
+use dashmap::DashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+use tokio::{sync::Mutex, time::sleep};
+use tracing::{error, info, warn};
+use url::Url;
+use ustr::Ustr;
+
+/// One websocket of a [`ConnectionPool`] and the subscriptions placed on it.
+struct Slot {
+    ws: Arc<WebSocketClient>,
+    /// `false` while the dead connection is being replaced, its
+    /// subscriptions stay here until they are placed again.
+    alive: bool,
+    quotes: HashSet<Ustr>,
+    /// Series id of the pool, to the chart session and series id on this socket.
+    charts: HashMap<u64, (Ustr, Ustr)>,
+}
+
+impl Slot {
+    fn load(&self) -> usize {
+        self.quotes.len() + self.charts.len()
+    }
+}
+
+/// Spreads quote symbols and chart series over several websockets, for
+/// tracking more symbols than one connection handles well.
+///
+/// Every connection forwards into the same `data_tx`, so the pool is read
+/// through a single `DataRx`. Subscriptions go to the least loaded
+/// connection with room below `max_symbols`, counting each quote symbol and
+/// each chart series once. When a connection dies it is replaced, retrying
+/// with `backoff`, and its subscriptions are moved onto the pool again; a
+/// connection that cannot be replaced is dropped from the pool. Chart series
+/// come back under new chart sessions, so route chart data by its
+/// [`ChartOptions`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::live::pool::ConnectionPool;
+///
+/// # async fn run() -> tradingview::Result<()> {
+/// let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+/// let pool = ConnectionPool::builder()
+///     .data_tx(tx)
+///     .size(4)
+///     .max_symbols(50)
+///     .build()
+///     .await?;
+/// pool.add_symbols(&["NASDAQ:AAPL", "NASDAQ:MSFT", "BINANCE:BTCUSDT"]).await?;
+///
+/// while let Some(response) = rx.recv().await {
+///     println!("{response:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ConnectionPool {
+    auth_token: Option<Ustr>,
+    server: DataServer,
+    endpoint: Option<Url>,
+    data_tx: DataTx,
+    max_symbols: usize,
+    backoff: BackoffConfig,
+    slots: Mutex<Vec<Slot>>,
+    /// Options of every chart series, keyed by the id handed out by [`ConnectionPool::add_market`].
+    charts: DashMap<u64, ChartOptions>,
+    next_chart: AtomicU64,
+    closing: AtomicBool,
+    this: Weak<Self>,
+}
+
+#[bon::bon]
+impl ConnectionPool {
+    /// Open `size` connections up front.
+    #[builder]
+    pub async fn new(
+        auth_token: Option<&str>,
+        #[builder(default = DataServer::ProData)] server: DataServer,
+        /// Receives the responses of every connection.
+        #[builder(into)]
+        data_tx: DataTx,
+        #[builder(default = 4)] size: usize,
+        /// Quote symbols plus chart series per connection.
+        #[builder(default = 100)]
+        max_symbols: usize,
+        /// Retries of replacing a dead connection.
+        #[builder(default)]
+        backoff: BackoffConfig,
+        /// Replaces the url of `server`, see [`WebSocketClient::builder`].
+        endpoint: Option<Url>,
+    ) -> Result<Arc<Self>> {
+        let pool = Arc::new_cyclic(|this| Self {
+            auth_token: auth_token.map(Ustr::from),
+            server,
+            endpoint,
+            data_tx,
+            max_symbols: max_symbols.max(1),
+            backoff,
+            slots: Mutex::new(Vec::with_capacity(size)),
+            charts: DashMap::new(),
+            next_chart: AtomicU64::new(0),
+            closing: AtomicBool::new(false),
+            this: this.clone(),
+        });
+
+        for _ in 0..size.max(1) {
+            let ws = pool.connect().await?;
+            pool.watch(&ws);
+            pool.slots.lock().await.push(Slot {
+                ws,
+                alive: true,
+                quotes: HashSet::new(),
+                charts: HashMap::new(),
+            });
+        }
+        info!("Connection pool opened {} connections", size.max(1));
+        Ok(pool)
+    }
+
+    async fn connect(&self) -> Result<Arc<WebSocketClient>> {
+        let ws = WebSocketClient::builder()
+            .maybe_auth_token(self.auth_token.as_deref())
+            .server(self.server)
+            .maybe_endpoint(self.endpoint.clone())
+            .data_tx(self.data_tx.clone())
+            .build()
+            .await?;
+        Arc::clone(&ws).spawn_reader_task();
+        Ok(ws)
+    }
+
+    /// Rebalance once `ws` dies.
+    fn watch(&self, ws: &Arc<WebSocketClient>) {
+        let pool = self.this.clone();
+        let ws = Arc::clone(ws);
+        tokio::spawn(async move {
+            ws.closed_notifier().await;
+            if let Some(pool) = pool.upgrade()
+                && !pool.closing.load(Ordering::Relaxed)
+            {
+                pool.replace(&ws).await;
+            }
+        });
+    }
+
+    /// Replace the dead connection and place its subscriptions again, or
+    /// drop its slot and spread them over the others once every retry failed.
+    async fn replace(&self, dead: &Arc<WebSocketClient>) {
+        {
+            let mut slots = self.slots.lock().await;
+            let Some(slot) = slots.iter_mut().find(|s| Arc::ptr_eq(&s.ws, dead)) else {
+                return;
+            };
+            warn!("Pooled connection died, replacing it");
+            slot.alive = false;
+        }
+
+        // Reconnect without the lock, the other connections stay usable meanwhile
+        let ws = self.reconnect().await;
+
+        let mut slots = self.slots.lock().await;
+        let Some(index) = slots.iter().position(|s| Arc::ptr_eq(&s.ws, dead)) else {
+            return;
+        };
+        if self.closing.load(Ordering::Relaxed) {
+            if let Some(ws) = ws
+                && let Err(e) = ws.delete().await
+            {
+                warn!("Failed to close pooled connection: {}", e);
+            }
+            return;
+        }
+        let (quotes, charts) = match ws {
+            Some(ws) => {
+                self.watch(&ws);
+                let slot = &mut slots[index];
+                slot.ws = ws;
+                slot.alive = true;
+                let quotes: Vec<Ustr> = slot.quotes.drain().collect();
+                let charts: Vec<u64> = slot.charts.drain().map(|(id, _)| id).collect();
+                (quotes, charts)
+            }
+            None => {
+                error!(
+                    "Dropping pooled connection {} after it could not be replaced",
+                    index
+                );
+                let slot = slots.remove(index);
+                let charts = slot.charts.into_keys().collect();
+                (slot.quotes.into_iter().collect(), charts)
+            }
+        };
+
+        if let Err(e) = self.place_symbols(&mut slots, &quotes).await {
+            error!("Failed to move quote symbols to another connection: {}", e);
+        }
+        for id in charts {
+            let Some(options) = self.charts.get(&id).map(|o| *o) else {
+                continue;
+            };
+            if let Err(e) = self.place_market(&mut slots, id, options).await {
+                error!("Failed to move chart {} to another connection: {}", id, e);
+                self.charts.remove(&id);
+            }
+        }
+    }
+
+    /// Open a connection with `backoff` between the attempts, `None` once
+    /// they are exhausted or the pool is closing.
+    async fn reconnect(&self) -> Option<Arc<WebSocketClient>> {
+        let mut backoff = ExponentialBackoff::new(self.backoff);
+        while let Some(delay) = backoff.next_backoff() {
+            sleep(delay).await;
+            if self.closing.load(Ordering::Relaxed) {
+                return None;
+            }
+            match self.connect().await {
+                Ok(ws) => return Some(ws),
+                Err(e) => warn!("Failed to replace pooled connection: {}", e),
+            }
+        }
+        None
+    }
+
+    /// Subscribe to quotes of `symbols`, skipping ones the pool already tracks.
+    ///
+    /// Symbols beyond the room left on the pool are logged and left out, and
+    /// the call fails, while the others stay subscribed.
+    pub async fn add_symbols(&self, symbols: &[&str]) -> Result<()> {
+        let mut slots = self.slots.lock().await;
+        let symbols: Vec<Ustr> = symbols.iter().map(|s| Ustr::from(s)).collect();
+        self.place_symbols(&mut slots, &symbols).await
+    }
+
+    /// Subscribe `symbols` with one `quote_add_symbols` per connection. The
+    /// symbols left out, for want of room or of a failed send, are logged and
+    /// the first error is returned.
+    async fn place_symbols(&self, slots: &mut [Slot], symbols: &[Ustr]) -> Result<()> {
+        let mut batches: HashMap<usize, Vec<Ustr>> = HashMap::new();
+        let mut unplaced = Vec::new();
+        let mut result = Ok(());
+        for symbol in symbols {
+            if slots.iter().any(|s| s.quotes.contains(symbol))
+                || batches.values().any(|b| b.contains(symbol))
+            {
+                continue;
+            }
+            let loads: Vec<Option<usize>> = slots
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    s.alive
+                        .then(|| s.load() + batches.get(&i).map_or(0, Vec::len))
+                })
+                .collect();
+            match least_loaded(&loads, self.max_symbols) {
+                Some(index) => batches.entry(index).or_default().push(*symbol),
+                None => {
+                    unplaced.push(*symbol);
+                    result = result.and(Err(full()));
+                }
+            }
+        }
+
+        for (index, batch) in batches {
+            let names: Vec<&str> = batch.iter().map(|s| s.as_str()).collect();
+            match slots[index].ws.add_symbols(&names).await {
+                Ok(_) => slots[index].quotes.extend(batch),
+                Err(e) => {
+                    unplaced.extend(batch);
+                    result = result.and(Err(e));
+                }
+            }
+        }
+        if !unplaced.is_empty() {
+            error!(
+                "{} quote symbols were not placed on the pool: {:?}",
+                unplaced.len(),
+                unplaced
+            );
+        }
+        result
+    }
+
+    pub async fn remove_symbols(&self, symbols: &[&str]) -> Result<()> {
+        let mut slots = self.slots.lock().await;
+        for slot in slots.iter_mut() {
+            let owned: Vec<&str> = symbols
+                .iter()
+                .copied()
+                .filter(|s| slot.quotes.contains(&Ustr::from(s)))
+                .collect();
+            if owned.is_empty() {
+                continue;
+            }
+            // Only forget symbols the connection actually dropped
+            if slot.alive {
+                slot.ws.remove_symbols(&owned).await?;
+            }
+            for symbol in &owned {
+                slot.quotes.remove(&Ustr::from(symbol));
+            }
+        }
+        Ok(())
+    }
+
+    /// Open a chart series on the least loaded connection and return its
+    /// id within the pool, which stays valid across rebalancing.
+    pub async fn add_market(&self, options: ChartOptions) -> Result<u64> {
+        let mut slots = self.slots.lock().await;
+        let id = self.next_chart.fetch_add(1, Ordering::Relaxed);
+        self.place_market(&mut slots, id, options).await?;
+        self.charts.insert(id, options);
+        Ok(id)
+    }
+
+    async fn place_market(&self, slots: &mut [Slot], id: u64, options: ChartOptions) -> Result<()> {
+        let loads: Vec<Option<usize>> = slots.iter().map(|s| s.alive.then(|| s.load())).collect();
+        let index = least_loaded(&loads, self.max_symbols).ok_or_else(full)?;
+        let (chart_session, series_id) = slots[index].ws.add_market(options).await?;
+        slots[index].charts.insert(id, (chart_session, series_id));
+        Ok(())
+    }
+
+    /// Close the chart series `id`, returns `false` if the pool has no such series.
+    pub async fn remove_market(&self, id: u64) -> Result<bool> {
+        let mut slots = self.slots.lock().await;
+        if !self.charts.contains_key(&id) {
+            return Ok(false);
+        }
+        if let Some(slot) = slots.iter_mut().find(|s| s.charts.contains_key(&id)) {
+            if slot.alive {
+                let (chart_session, series_id) = slot.charts[&id];
+                slot.ws.remove_series(&chart_session, &series_id).await?;
+                slot.ws.delete_chart_session(&chart_session).await?;
+            }
+            slot.charts.remove(&id);
+        }
+        self.charts.remove(&id);
+        Ok(true)
+    }
+
+    /// Subscriptions on every connection, `None` for one being replaced.
+    pub async fn loads(&self) -> Vec<Option<usize>> {
+        self.slots
+            .lock()
+            .await
+            .iter()
+            .map(|s| s.alive.then(|| s.load()))
+            .collect()
+    }
+
+    /// Delete every session and close all connections.
+    pub async fn close(&self) -> Result<()> {
+        self.closing.store(true, Ordering::Relaxed);
+        let mut slots = self.slots.lock().await;
+        for slot in slots.iter_mut().filter(|s| s.alive) {
+            slot.alive = false;
+            if let Err(e) = slot.ws.delete().await {
+                warn!("Failed to close pooled connection: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn full() -> Error {
+    Error::Internal(Ustr::from("Every pooled connection is at its symbol cap"))
+}
+
+/// Index of the live connection with the fewest subscriptions below `max`.
+fn least_loaded(loads: &[Option<usize>], max: usize) -> Option<usize> {
+    loads
+        .iter()
+        .enumerate()
+        .filter_map(|(i, load)| load.filter(|&l| l < max).map(|l| (i, l)))
+        .min_by_key(|&(_, load)| load)
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live::mock::{MockScript, MockServer};
+    use std::time::Duration;
+    use tokio::{sync::mpsc::unbounded_channel, time::timeout};
+
+    async fn open_pool(
+        server: &MockServer,
+        size: usize,
+        max_symbols: usize,
+    ) -> Arc<ConnectionPool> {
+        let (tx, _rx) = unbounded_channel();
+        ConnectionPool::builder()
+            .data_tx(tx)
+            .size(size)
+            .max_symbols(max_symbols)
+            .backoff(BackoffConfig {
+                initial_delay: Duration::from_millis(10),
+                max_attempts: 2,
+                ..Default::default()
+            })
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    async fn ws_of(pool: &ConnectionPool, index: usize) -> Arc<WebSocketClient> {
+        Arc::clone(&pool.slots.lock().await[index].ws)
+    }
+
+    async fn count(server: &MockServer, method: &str) -> usize {
+        let received = server.received().await;
+        received
+            .iter()
+            .filter(|f| f.method.as_str() == method)
+            .count()
+    }
+
+    #[test]
+    fn test_least_loaded() {
+        assert_eq!(least_loaded(&[Some(3), Some(1), Some(2)], 10), Some(1));
+        // Dead connections and full ones are skipped
+        assert_eq!(least_loaded(&[Some(3), None, Some(10)], 10), Some(0));
+        assert_eq!(least_loaded(&[Some(10), None], 10), None);
+        assert_eq!(least_loaded(&[], 10), None);
+    }
+
+    #[tokio::test]
+    async fn test_spreads_symbols_up_to_the_cap() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let pool = open_pool(&server, 2, 2).await;
+
+        pool.add_symbols(&["NASDAQ:AAPL", "NASDAQ:MSFT", "NASDAQ:AAPL"])
+            .await
+            .unwrap();
+        assert_eq!(pool.loads().await, vec![Some(1), Some(1)]);
+        pool.add_symbols(&["NASDAQ:TSLA", "NASDAQ:NVDA"])
+            .await
+            .unwrap();
+        assert_eq!(pool.loads().await, vec![Some(2), Some(2)]);
+        assert!(pool.add_symbols(&["NASDAQ:AMD"]).await.is_err());
+
+        pool.remove_symbols(&["NASDAQ:AAPL", "NASDAQ:AMD"])
+            .await
+            .unwrap();
+        let loads = pool.loads().await;
+        assert_eq!(loads.iter().flatten().sum::<usize>(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_remove_keeps_the_symbols() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let pool = open_pool(&server, 1, 10).await;
+        pool.add_symbols(&["NASDAQ:AAPL"]).await.unwrap();
+
+        ws_of(&pool, 0).await.close().await.unwrap();
+        assert!(pool.remove_symbols(&["NASDAQ:AAPL"]).await.is_err());
+        assert_eq!(pool.loads().await, vec![Some(1)]);
+    }
+
+    #[tokio::test]
+    async fn test_replaced_connection_takes_its_subscriptions_back() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let pool = open_pool(&server, 2, 10).await;
+        pool.add_symbols(&["NASDAQ:AAPL", "NASDAQ:MSFT", "NASDAQ:TSLA"])
+            .await
+            .unwrap();
+        let loads = pool.loads().await;
+        // Messages received once `n` arrived and the server settled
+        let added = |n: usize| {
+            let server = &server;
+            async move {
+                timeout(Duration::from_secs(5), async {
+                    while count(server, "quote_add_symbols").await < n {
+                        sleep(Duration::from_millis(10)).await;
+                    }
+                })
+                .await
+                .expect("symbols were not subscribed");
+                sleep(Duration::from_millis(50)).await;
+                count(server, "quote_add_symbols").await
+            }
+        };
+        // One message per connection
+        assert_eq!(added(2).await, 2);
+
+        let dead = ws_of(&pool, 0).await;
+        pool.replace(&dead).await;
+
+        assert!(!Arc::ptr_eq(&dead, &ws_of(&pool, 0).await));
+        assert_eq!(pool.loads().await, loads);
+        // The moved symbols are subscribed again on the new connection, in one message
+        assert_eq!(added(3).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_unreplaceable_connections_leave_the_pool() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let pool = open_pool(&server, 2, 10).await;
+        pool.add_symbols(&["NASDAQ:AAPL", "NASDAQ:MSFT"])
+            .await
+            .unwrap();
+
+        // Drops every connection and refuses new ones
+        drop(server);
+        timeout(Duration::from_secs(10), async {
+            while !pool.loads().await.is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("dead connections were kept");
+        assert!(pool.add_symbols(&["NASDAQ:AAPL"]).await.is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Frames of the TradingView websocket protocol, shared by the native
+//! [`crate::websocket::WebSocketClient`] and the wasm client so both send the
+//! same messages and dispatch received frames the same way.
+
+#[cfg(any(feature = "chart", feature = "quote"))]
+use crate::{Result, payload};
+#[cfg(feature = "chart")]
+use crate::{chart::ChartOptions, utils::symbol_init};
+use crate::{
+    live::{
+        handler::data::DataHandler,
+        models::{SocketMessage, SocketMessageDe, TradingViewDataEvent},
+    },
+    telemetry,
+};
+#[cfg(feature = "quote")]
+use crate::{quote::options::QuoteSessionOptions, utils::quote_symbol_payload};
+use serde_json::Value;
+use tracing::{debug, info, trace, warn};
+
+/// Dispatch a parsed frame to `handler`. Returns the reply a heartbeat asks
+/// for, which has to be sent back as is.
+pub(crate) async fn dispatch(
+    handler: &DataHandler,
+    message: SocketMessage<SocketMessageDe>,
+) -> Option<String> {
+    match message {
+        SocketMessage::SocketMessage(message) => {
+            debug!(
+                "Handling message: method={}, params_count={}",
+                message.m,
+                message.p.len()
+            );
+            let event = TradingViewDataEvent::from(message.m.to_owned());
+            handler.handle_events(event, &message.p).await;
+        }
+        SocketMessage::QuoteData(quote) => handler.handle_typed_quote(quote).await,
+        SocketMessage::SocketServerInfo(info) => info!("{}", info),
+        SocketMessage::Other(value) if value.is_number() => {
+            debug!("handling heartbeat message: {:?}", value);
+            return Some(heartbeat(&value));
+        }
+        SocketMessage::Other(value) if value.is_string() => {
+            trace!("Received string message: {:?}", value);
+        }
+        SocketMessage::Other(value) => warn!("Received unrecognized message: {:?}", value),
+        SocketMessage::Unknown(s) => {
+            warn!("unknown message: {:?}", s);
+            telemetry::parse_failed();
+        }
+    }
+    None
+}
+
+/// `~m~<len>~m~~h~<beat>` answering the heartbeat `beat`. Only the heartbeat
+/// is echoed, not the other frames of the message it arrived in.
+fn heartbeat(beat: &Value) -> String {
+    let payload = format!("~h~{beat}");
+    format!("~m~{}~m~{}", payload.len(), payload)
+}
+
+/// Params of `quote_set_fields` for the fields `options` asks for.
+#[cfg(feature = "quote")]
+pub(crate) fn quote_fields(session: &str, options: &QuoteSessionOptions) -> Vec<Value> {
+    let mut params = payload![session];
+    let fields = options.mode.fields(&options.fields);
+    params.extend(fields.iter().map(|f| Value::from(f.as_str())));
+    params
+}
+
+/// Params of `quote_add_symbols`, `quote_fast_symbols` and
+/// `quote_remove_symbols`, with every symbol wrapped into a symbol init
+/// string when [`QuoteSessionOptions::session`] is set.
+#[cfg(feature = "quote")]
+pub(crate) fn quote_symbols(
+    session: &str,
+    symbols: &[&str],
+    options: &QuoteSessionOptions,
+) -> Result<Vec<Value>> {
+    let mut params = payload![session];
+    for symbol in symbols {
+        params.push(quote_symbol_payload(symbol, options.session)?);
+    }
+    Ok(params)
+}
+
+/// Symbol and version ids of the series `sds_<n>`, i.e. `sds_sym_<n>` and `s<n>`.
+#[cfg(feature = "chart")]
+pub(crate) fn series_ids(series_id: &str) -> (String, String) {
+    let number = series_id.trim_start_matches("sds_");
+    (format!("sds_sym_{number}"), format!("s{number}"))
+}
+
+/// Params of `resolve_symbol`, optionally resolving on a replay session.
+#[cfg(feature = "chart")]
+pub(crate) fn resolve_symbol(
+    session: &str,
+    symbol_series_id: &str,
+    symbol: &str,
+    options: &ChartOptions,
+    replay_session: Option<&str>,
+) -> Result<Vec<Value>> {
+    Ok(payload!(
+        session,
+        symbol_series_id,
+        symbol_init(symbol, options, replay_session)?
+    ))
+}
+
+/// Params of `create_series` and `modify_series`, with the range of
+/// `options` as `r,<from>:<to>` or one of `1D`, `5d`, `1M`, .., `ALL`.
+#[cfg(feature = "chart")]
+pub(crate) fn series_params(
+    session: &str,
+    series_id: &str,
+    series_version: &str,
+    symbol_series_id: &str,
+    options: &ChartOptions,
+) -> Vec<Value> {
+    let range = match (&options.range, options.from, options.to) {
+        (Some(range), _, _) => range.to_string(),
+        (None, Some(from), Some(to)) => format!("r,{from}:{to}"),
+        _ => Default::default(),
+    };
+    payload!(
+        session,
+        series_id,
+        series_version,
+        symbol_series_id,
+        options.interval.to_string(),
+        options.bar_count,
+        range
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{live::handler::types::EventMask, utils::parse_packet};
+
+    #[tokio::test]
+    async fn test_heartbeat_echoes_only_the_beat() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler = DataHandler::builder().res_tx(tx.into()).build();
+        let packet = r#"~m~4~m~~h~7~m~27~m~{"m":"quote_completed","p":[]}"#;
+
+        let mut replies = Vec::new();
+        for message in parse_packet(packet, true, EventMask::all()) {
+            replies.extend(dispatch(&handler, message).await);
+        }
+        assert_eq!(replies, vec!["~m~4~m~~h~7".to_string()]);
+    }
+
+    #[cfg(feature = "chart")]
+    #[test]
+    fn test_series_messages() {
+        use crate::Interval;
+
+        assert_eq!(
+            series_ids("sds_3"),
+            ("sds_sym_3".to_string(), "s3".to_string())
+        );
+        let mut options = ChartOptions::new_with("AAPL", "NASDAQ", Interval::OneDay);
+        options.from = Some(1_626_220_800);
+        options.to = Some(1_628_640_000);
+        let params = series_params("cs_1", "sds_3", "s3", "sds_sym_3", &options);
+        assert_eq!(params[4], Value::from("1D"));
+        assert_eq!(params[6], Value::from("r,1626220800:1628640000"));
+    }
+}
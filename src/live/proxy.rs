@@ -0,0 +1,292 @@
+use crate::{Error, Result, config::ProxyConfig};
+use base64::engine::{Engine as _, general_purpose::STANDARD as BASE64};
+use std::net::IpAddr;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use url::Url;
+use ustr::Ustr;
+
+fn proxy_error(msg: impl AsRef<str>) -> Error {
+    Error::WebSocket(Ustr::from(&format!("proxy: {}", msg.as_ref())))
+}
+
+/// Open a TCP tunnel to `host:port` through `proxy`.
+pub(crate) async fn connect(proxy: &ProxyConfig, host: &str, port: u16) -> Result<TcpStream> {
+    let url = proxy.to_url()?;
+    let proxy_host = url.host_str().ok_or_else(|| proxy_error("missing host"))?;
+    let proxy_port = url
+        .port_or_known_default()
+        .unwrap_or(if url.scheme().starts_with("socks") {
+            1080
+        } else {
+            8080
+        });
+
+    let mut stream = TcpStream::connect((proxy_host, proxy_port)).await?;
+    match url.scheme() {
+        "socks5" | "socks5h" => socks5_handshake(&mut stream, &url, host, port).await?,
+        "http" => http_connect(&mut stream, &url, host, port).await?,
+        scheme => return Err(proxy_error(format!("{scheme} proxies are not supported"))),
+    }
+    Ok(stream)
+}
+
+async fn http_connect(stream: &mut TcpStream, url: &Url, host: &str, port: u16) -> Result<()> {
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !url.username().is_empty() {
+        let username =
+            urlencoding::decode(url.username()).map_err(|e| proxy_error(e.to_string()))?;
+        let password = urlencoding::decode(url.password().unwrap_or_default())
+            .map_err(|e| proxy_error(e.to_string()))?;
+        let credentials = BASE64.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response head byte by byte so nothing past it is consumed
+    let mut reader = BufReader::with_capacity(1, stream);
+    let mut status = String::new();
+    reader.read_line(&mut status).await?;
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(proxy_error(format!("CONNECT failed: {}", status.trim())));
+    }
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line == "\r\n" {
+            break;
+        }
+    }
+    Ok(())
+}
+
+async fn socks5_handshake(stream: &mut TcpStream, url: &Url, host: &str, port: u16) -> Result<()> {
+    let has_auth = !url.username().is_empty();
+    let method = if has_auth { 0x02 } else { 0x00 };
+    stream.write_all(&[0x05, 0x01, method]).await?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != 0x05 || reply[1] != method {
+        return Err(proxy_error("SOCKS5 authentication method rejected"));
+    }
+
+    if has_auth {
+        let username =
+            urlencoding::decode(url.username()).map_err(|e| proxy_error(e.to_string()))?;
+        let password = urlencoding::decode(url.password().unwrap_or_default())
+            .map_err(|e| proxy_error(e.to_string()))?;
+        let (username, password) = (username.as_bytes(), password.as_bytes());
+        if username.len() > 255 || password.len() > 255 {
+            return Err(proxy_error("SOCKS5 credentials are too long"));
+        }
+        let mut auth = vec![0x01, username.len() as u8];
+        auth.extend_from_slice(username);
+        auth.push(password.len() as u8);
+        auth.extend_from_slice(password);
+        stream.write_all(&auth).await?;
+
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0x00 {
+            return Err(proxy_error("SOCKS5 authentication failed"));
+        }
+    }
+
+    let mut request = vec![0x05, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(0x01);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(0x04);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.len() > 255 {
+                return Err(proxy_error("SOCKS5 host name is too long"));
+            }
+            request.push(0x03);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut head = [0u8; 4];
+    stream.read_exact(&mut head).await?;
+    if head[1] != 0x00 {
+        return Err(proxy_error(format!(
+            "SOCKS5 connect failed with code {}",
+            head[1]
+        )));
+    }
+    // Skip the bound address, its length depends on the address type
+    let addr_len = match head[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => stream.read_u8().await? as usize,
+        other => return Err(proxy_error(format!("SOCKS5 unknown address type {other}"))),
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{net::TcpListener, task::JoinHandle};
+
+    /// Accept one connection on a local port and run `serve` on it.
+    async fn listen<F, Fut>(serve: F) -> (u16, JoinHandle<Vec<u8>>)
+    where
+        F: FnOnce(TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = Vec<u8>> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve(stream).await
+        });
+        (port, task)
+    }
+
+    fn proxy(scheme: &str, port: u16) -> ProxyConfig {
+        ProxyConfig::builder()
+            .url(format!("{scheme}://127.0.0.1:{port}"))
+            .username("user")
+            .password("p@ss")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_http_connect() {
+        let (port, server) = listen(|mut stream| async move {
+            let mut request = vec![0u8; 1024];
+            let n = stream.read(&mut request).await.unwrap();
+            request.truncate(n);
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\nVia: test\r\n\r\nhello")
+                .await
+                .unwrap();
+            request
+        })
+        .await;
+
+        let mut stream = connect(&proxy("http", port), "data.tradingview.com", 443)
+            .await
+            .unwrap();
+        // Bytes after the response head belong to the tunnel
+        let mut tunneled = [0u8; 5];
+        stream.read_exact(&mut tunneled).await.unwrap();
+        assert_eq!(&tunneled, b"hello");
+
+        let request = String::from_utf8(server.await.unwrap()).unwrap();
+        assert!(request.starts_with("CONNECT data.tradingview.com:443 HTTP/1.1\r\n"));
+        // base64("user:p@ss")
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwQHNz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_http_connect_rejected() {
+        let (port, _server) = listen(|mut stream| async move {
+            let mut request = vec![0u8; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await
+                .unwrap();
+            Vec::new()
+        })
+        .await;
+
+        let err = connect(&proxy("http", port), "data.tradingview.com", 443)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("407"));
+    }
+
+    #[tokio::test]
+    async fn test_socks5_handshake() {
+        let (port, server) = listen(|mut stream| async move {
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [0x05, 0x01, 0x02]);
+            stream.write_all(&[0x05, 0x02]).await.unwrap();
+
+            let mut auth = vec![0u8; 2 + 4 + 1 + 4];
+            stream.read_exact(&mut auth).await.unwrap();
+            stream.write_all(&[0x01, 0x00]).await.unwrap();
+
+            let mut head = [0u8; 5];
+            stream.read_exact(&mut head).await.unwrap();
+            let mut rest = vec![0u8; head[4] as usize + 2];
+            stream.read_exact(&mut rest).await.unwrap();
+            stream
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x1f, 0x90])
+                .await
+                .unwrap();
+            stream.write_all(b"hello").await.unwrap();
+
+            [auth, head.to_vec(), rest].concat()
+        })
+        .await;
+
+        let mut stream = connect(&proxy("socks5h", port), "data.tradingview.com", 443)
+            .await
+            .unwrap();
+        let mut tunneled = [0u8; 5];
+        stream.read_exact(&mut tunneled).await.unwrap();
+        assert_eq!(&tunneled, b"hello");
+
+        let mut expected = vec![0x01, 4];
+        expected.extend_from_slice(b"user");
+        expected.push(4);
+        expected.extend_from_slice(b"p@ss");
+        expected.extend_from_slice(&[0x05, 0x01, 0x00, 0x03, 20]);
+        expected.extend_from_slice(b"data.tradingview.com");
+        expected.extend_from_slice(&443u16.to_be_bytes());
+        assert_eq!(server.await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_socks5_connect_refused() {
+        let (port, _server) = listen(|mut stream| async move {
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            stream.write_all(&[0x05, 0x00]).await.unwrap();
+            let mut request = [0u8; 10];
+            stream.read_exact(&mut request).await.unwrap();
+            // Connection refused by the destination host
+            stream
+                .write_all(&[0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            Vec::new()
+        })
+        .await;
+
+        let err = connect(
+            &ProxyConfig::new(format!("socks5://127.0.0.1:{port}")),
+            "10.0.0.1",
+            443,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("code 5"));
+    }
+
+    #[tokio::test]
+    async fn test_https_proxy_rejected() {
+        assert!(
+            connect(&ProxyConfig::new("https://127.0.0.1:1"), "example.com", 443)
+                .await
+                .is_err()
+        );
+    }
+}
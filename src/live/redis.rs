@@ -0,0 +1,155 @@
+use crate::{
+    Error, Result,
+    export::sink::series_symbol,
+    live::handler::message::{BarMsg, TradingViewResponse},
+};
+use bon::bon;
+use futures_util::{Stream, StreamExt};
+use redis::{AsyncCommands, aio::ConnectionManager};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+use ustr::Ustr;
+
+/// Payload format of messages published by [`RedisBridge`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BridgeEncoding {
+    #[default]
+    Json,
+    /// MessagePack with named fields, decodable into the same structure as the JSON.
+    MessagePack,
+}
+
+impl BridgeEncoding {
+    /// Serialize `response` as an externally tagged enum, e.g. `{"QuoteSnapshot": {..}}`.
+    pub fn encode(self, response: &TradingViewResponse) -> Result<Vec<u8>> {
+        match self {
+            BridgeEncoding::Json => Ok(serde_json::to_vec(response)?),
+            BridgeEncoding::MessagePack => rmp_serde::to_vec_named(response)
+                .map_err(|e| Error::TypeConversion(Ustr::from(&e.to_string()))),
+        }
+    }
+}
+
+/// Channel `response` is published on:
+///
+/// - `{prefix}:chart:{EXCHANGE:SYMBOL}` for chart data and bar events
+/// - `{prefix}:quote:{EXCHANGE:SYMBOL}` for quote data and snapshots
+/// - `{prefix}:depth:{EXCHANGE:SYMBOL}` for order book updates
+/// - `{prefix}:study:{script_id}` for study data and strategy reports
+/// - `{prefix}:events` for everything else
+pub fn channel_name(prefix: &str, response: &TradingViewResponse) -> String {
+    match response {
+        TradingViewResponse::ChartData(series, _)
+        | TradingViewResponse::BarUpdate(BarMsg { series, .. })
+        | TradingViewResponse::BarClose(BarMsg { series, .. }) => {
+            format!("{prefix}:chart:{}", series_symbol(series))
+        }
+        TradingViewResponse::QuoteSnapshot(snapshot) => {
+            format!("{prefix}:quote:{}", snapshot.symbol)
+        }
+        TradingViewResponse::QuoteData(quote) => match (quote.exchange, quote.symbol) {
+            (Some(exchange), Some(symbol)) => format!("{prefix}:quote:{exchange}:{symbol}"),
+            _ => format!("{prefix}:quote"),
+        },
+        TradingViewResponse::DepthData(depth) => format!("{prefix}:depth:{}", depth.symbol),
+        TradingViewResponse::StudyData(study, _)
+        | TradingViewResponse::StrategyReport(study, _) => {
+            format!("{prefix}:study:{}", study.script_id)
+        }
+        _ => format!("{prefix}:events"),
+    }
+}
+
+/// Republishes live events onto Redis pub/sub channels, see [`channel_name`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::live::{handler::stream::connect, redis::{BridgeEncoding, RedisBridge}};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let (events, _commands) = connect().call().await?;
+///     let bridge = RedisBridge::connect()
+///         .url("redis://127.0.0.1/")
+///         .encoding(BridgeEncoding::MessagePack)
+///         .call()
+///         .await?;
+///     bridge.run(events).await;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RedisBridge {
+    connection: ConnectionManager,
+    prefix: String,
+    encoding: BridgeEncoding,
+}
+
+#[bon]
+impl RedisBridge {
+    #[builder]
+    pub async fn connect(
+        url: &str,
+        /// Channel prefix, `tv` by default.
+        prefix: Option<&str>,
+        #[builder(default)] encoding: BridgeEncoding,
+    ) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        Ok(Self {
+            connection: ConnectionManager::new(client).await?,
+            prefix: prefix.unwrap_or("tv").to_string(),
+            encoding,
+        })
+    }
+}
+
+impl RedisBridge {
+    pub async fn publish(&self, response: &TradingViewResponse) -> Result<()> {
+        let channel = channel_name(&self.prefix, response);
+        let payload = self.encoding.encode(response)?;
+        debug!("publishing {} bytes on {channel}", payload.len());
+        self.connection
+            .clone()
+            .publish::<_, _, ()>(channel, payload)
+            .await?;
+        Ok(())
+    }
+
+    /// Publish every event of `events` until it ends, logging failed publishes.
+    pub async fn run(&self, events: impl Stream<Item = TradingViewResponse>) {
+        let mut events = std::pin::pin!(events);
+        while let Some(response) = events.next().await {
+            if let Err(e) = self.publish(&response).await {
+                error!("failed to publish event to redis: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quote::state::QuoteSnapshot, websocket::SeriesInfo};
+
+    #[test]
+    fn test_channel_and_encoding() {
+        let mut series = SeriesInfo::default();
+        series.options.exchange = "BINANCE".into();
+        series.options.symbol = "BTCUSDT".into();
+        let chart = TradingViewResponse::ChartData(series, Vec::new().into());
+        assert_eq!(channel_name("tv", &chart), "tv:chart:BINANCE:BTCUSDT");
+
+        let quote = TradingViewResponse::QuoteSnapshot(QuoteSnapshot {
+            symbol: "NASDAQ:AAPL".into(),
+            ..Default::default()
+        });
+        assert_eq!(channel_name("md", &quote), "md:quote:NASDAQ:AAPL");
+
+        let json = BridgeEncoding::Json.encode(&quote).unwrap();
+        assert!(json.starts_with(br#"{"QuoteSnapshot":"#));
+        let msgpack = BridgeEncoding::MessagePack.encode(&quote).unwrap();
+        let decoded: serde_json::Value = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(decoded["QuoteSnapshot"]["symbol"], "NASDAQ:AAPL");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    ChartOptions, DataPoint, Interval, Result,
+    live::{handler::message::TradingViewResponse, websocket::WebSocketClient},
+    utils::{gen_id, gen_session_id},
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use ustr::Ustr;
+
+/// Replay event belonging to one [`ReplaySession`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayEvent {
+    /// Bars revealed by a seek, step or autoplay tick.
+    Bars(Arc<[DataPoint]>),
+    /// The replay cursor moved to this unix timestamp in seconds.
+    Point(i64),
+    /// A request was acknowledged, carries the id returned by the request method.
+    Ok(Option<Ustr>),
+    Resolutions(Vec<Interval>),
+    InstanceId(Ustr),
+    /// No more bars are available.
+    DataEnd,
+}
+
+/// Bar-by-bar replay of a symbol on its own chart session.
+///
+/// Every control method returns the request id TradingView echoes back in
+/// [`ReplayEvent::Ok`]. Replay sessions are not restored after a reconnect.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn run(
+/// #     ws: std::sync::Arc<tradingview::websocket::WebSocketClient>,
+/// #     mut rx: tradingview::live::handler::types::DataRx,
+/// # ) -> tradingview::Result<()> {
+/// use tradingview::{
+///     ChartOptions, Interval,
+///     live::replay::{ReplayEvent, ReplaySession},
+/// };
+///
+/// let replay = ReplaySession::builder()
+///     .ws(ws)
+///     .options(ChartOptions::new_with("BTCUSDT", "BINANCE", Interval::OneHour))
+///     .from(1_700_000_000)
+///     .build()
+///     .await?;
+///
+/// replay.step(1).await?;
+/// while let Some(response) = rx.recv().await {
+///     match replay.event(&response) {
+///         Some(ReplayEvent::Bars(bars)) => println!("{} bars", bars.len()),
+///         Some(ReplayEvent::DataEnd) => break,
+///         _ => {}
+///     }
+/// }
+/// replay.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplaySession {
+    ws: Arc<WebSocketClient>,
+    session: Ustr,
+    series_id: Ustr,
+    chart_session: Ustr,
+    chart_series_id: Ustr,
+    requests: AtomicU64,
+}
+
+#[bon::bon]
+impl ReplaySession {
+    /// Create the replay session, position it at `from` and open a chart
+    /// session following it.
+    #[builder]
+    pub async fn new(
+        ws: Arc<WebSocketClient>,
+        options: ChartOptions,
+        /// Unix timestamp in seconds to start at, defaults to `options.replay_from`.
+        from: Option<i64>,
+    ) -> Result<Self> {
+        let session = Ustr::from(&gen_session_id("rs"));
+        let series_id = Ustr::from(&gen_id());
+        let symbol = format!("{}:{}", options.exchange, options.symbol);
+
+        ws.create_replay_session(&session).await?;
+        ws.add_replay_series(&session, &series_id, &symbol, options)
+            .await?;
+
+        let mut replay = Self {
+            ws: Arc::clone(&ws),
+            session,
+            series_id,
+            chart_session: Ustr::default(),
+            chart_series_id: Ustr::default(),
+            requests: AtomicU64::new(0),
+        };
+        replay.seek(from.unwrap_or(options.replay_from)).await?;
+
+        let (chart_session, chart_series_id) = ws
+            .add_market(ChartOptions {
+                replay_mode: true,
+                replay_session: Some(session),
+                ..options
+            })
+            .await?;
+        replay.chart_session = chart_session;
+        replay.chart_series_id = chart_series_id;
+        Ok(replay)
+    }
+}
+
+impl ReplaySession {
+    fn next_request_id(&self) -> Ustr {
+        let seq = self.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        Ustr::from(&format!("req_{seq}"))
+    }
+
+    pub fn session(&self) -> Ustr {
+        self.session
+    }
+
+    pub fn series_id(&self) -> Ustr {
+        self.series_id
+    }
+
+    /// Chart session receiving the replayed bars.
+    pub fn chart_session(&self) -> Ustr {
+        self.chart_session
+    }
+
+    /// Move the replay cursor to `timestamp`, in unix seconds.
+    pub async fn seek(&self, timestamp: i64) -> Result<Ustr> {
+        let request_id = self.next_request_id();
+        self.ws
+            .replay_reset(&self.session, &request_id, timestamp)
+            .await?;
+        Ok(request_id)
+    }
+
+    /// Reveal the next `bars` bars.
+    pub async fn step(&self, bars: u64) -> Result<Ustr> {
+        let request_id = self.next_request_id();
+        self.ws
+            .replay_step(&self.session, &request_id, bars)
+            .await?;
+        Ok(request_id)
+    }
+
+    /// Start autoplay, revealing one bar every `delay`.
+    pub async fn play(&self, delay: Duration) -> Result<Ustr> {
+        let request_id = self.next_request_id();
+        self.ws
+            .replay_autoplay(&self.session, &request_id, delay)
+            .await?;
+        Ok(request_id)
+    }
+
+    /// Stop autoplay.
+    pub async fn pause(&self) -> Result<Ustr> {
+        let request_id = self.next_request_id();
+        self.ws.replay_stop(&self.session, &request_id).await?;
+        Ok(request_id)
+    }
+
+    /// Tear down the chart and replay sessions.
+    pub async fn close(self) -> Result<()> {
+        self.ws
+            .remove_series(&self.chart_session, &self.chart_series_id)
+            .await?;
+        self.ws.delete_chart_session(&self.chart_session).await?;
+        self.ws.delete_replay_session(&self.session).await
+    }
+
+    /// Map `response` to a [`ReplayEvent`] if it belongs to this session.
+    pub fn event(&self, response: &TradingViewResponse) -> Option<ReplayEvent> {
+        let event = match response {
+            TradingViewResponse::ChartData(info, bars)
+                if info.chart_session == self.chart_session =>
+            {
+                ReplayEvent::Bars(Arc::clone(bars))
+            }
+            TradingViewResponse::ReplayOk(msg) if msg.session == self.session => {
+                ReplayEvent::Ok(msg.request_id)
+            }
+            TradingViewResponse::ReplayPoint(msg) if msg.session == self.session => {
+                ReplayEvent::Point(msg.timestamp)
+            }
+            TradingViewResponse::ReplayResolutions(msg) if msg.session == self.session => {
+                ReplayEvent::Resolutions(msg.resolutions.clone())
+            }
+            TradingViewResponse::ReplayInstanceId(msg) if msg.session == self.session => {
+                ReplayEvent::InstanceId(msg.instance_id)
+            }
+            TradingViewResponse::ReplayDataEnd(msg) if msg.session == self.session => {
+                ReplayEvent::DataEnd
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+}
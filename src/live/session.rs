@@ -0,0 +1,236 @@
+use crate::{
+    ChartOptions, DataPoint, Result,
+    live::{
+        handler::message::TradingViewResponse,
+        websocket::{SeriesInfo, WebSocketClient},
+    },
+};
+use dashmap::DashMap;
+use std::{hash::Hash, sync::Arc};
+use ustr::Ustr;
+
+/// Server-side identifiers of a series opened by a [`ChartSessionManager`].
+#[derive(Debug, Clone, Copy)]
+pub struct ManagedSeries {
+    pub chart_session: Ustr,
+    pub series_id: Ustr,
+    pub options: ChartOptions,
+}
+
+/// Opens one chart session per symbol and maps its events back to a caller
+/// chosen key.
+///
+/// Chart sessions and series ids survive reconnects, so keys keep routing
+/// after [`TradingViewResponse::Reconnected`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn run(
+/// #     ws: std::sync::Arc<tradingview::websocket::WebSocketClient>,
+/// #     mut rx: tradingview::live::handler::types::DataRx,
+/// # ) -> tradingview::Result<()> {
+/// use tradingview::{ChartOptions, Interval, live::session::ChartSessionManager};
+///
+/// let charts = ChartSessionManager::new(ws);
+/// charts
+///     .add("btc", ChartOptions::new_with("BTCUSDT", "BINANCE", Interval::OneHour))
+///     .await?;
+///
+/// while let Some(response) = rx.recv().await {
+///     if let Some((key, _info, bars)) = charts.route(&response) {
+///         println!("{key}: {} bars", bars.len());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ChartSessionManager<K> {
+    ws: Arc<WebSocketClient>,
+    series: DashMap<K, ManagedSeries>,
+    keys: DashMap<Ustr, K>,
+}
+
+impl<K: Eq + Hash + Clone> ChartSessionManager<K> {
+    pub fn new(ws: Arc<WebSocketClient>) -> Self {
+        Self {
+            ws,
+            series: DashMap::new(),
+            keys: DashMap::new(),
+        }
+    }
+
+    /// Open a chart session for `options` under `key`, replacing any series
+    /// already registered with that key.
+    pub async fn add(&self, key: K, options: ChartOptions) -> Result<ManagedSeries> {
+        self.remove(&key).await?;
+
+        let (chart_session, series_id) = self.ws.add_market(options).await?;
+        let managed = ManagedSeries {
+            chart_session,
+            series_id,
+            options,
+        };
+        self.keys.insert(chart_session, key.clone());
+        self.series.insert(key, managed);
+        Ok(managed)
+    }
+
+    /// Close the chart session registered under `key`, returns `false` if there was none.
+    pub async fn remove(&self, key: &K) -> Result<bool> {
+        let Some(managed) = self.get(key) else {
+            return Ok(false);
+        };
+
+        // Keep routing the key until the server side is gone, so a failed
+        // send can be retried
+        self.ws
+            .remove_series(&managed.chart_session, &managed.series_id)
+            .await?;
+        self.ws.delete_chart_session(&managed.chart_session).await?;
+
+        self.series.remove(key);
+        self.keys.remove(&managed.chart_session);
+        Ok(true)
+    }
+
+    /// Close every chart session opened by this manager.
+    pub async fn clear(&self) -> Result<()> {
+        for key in self.keys() {
+            self.remove(&key).await?;
+        }
+        Ok(())
+    }
+
+    /// Page `bar_count` older bars into the series registered under `key`.
+    pub async fn request_more_data(&self, key: &K, bar_count: u64) -> Result<bool> {
+        let Some(managed) = self.get(key) else {
+            return Ok(false);
+        };
+        self.ws
+            .request_more_data(&managed.chart_session, &managed.series_id, bar_count)
+            .await?;
+        Ok(true)
+    }
+
+    pub fn get(&self, key: &K) -> Option<ManagedSeries> {
+        self.series.get(key).map(|entry| *entry)
+    }
+
+    pub fn keys(&self) -> Vec<K> {
+        self.series
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    /// Key of the series `info` was emitted for, if this manager opened it.
+    pub fn key_for(&self, info: &SeriesInfo) -> Option<K> {
+        self.keys
+            .get(&info.chart_session)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Tag a [`TradingViewResponse::ChartData`] with the key of its series.
+    ///
+    /// Returns `None` for other responses and for series opened elsewhere.
+    pub fn route<'a>(
+        &self,
+        response: &'a TradingViewResponse,
+    ) -> Option<(K, &'a SeriesInfo, &'a [DataPoint])> {
+        match response {
+            TradingViewResponse::ChartData(info, bars) => {
+                self.key_for(info).map(|key| (key, info, &bars[..]))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "chart"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Interval,
+        live::mock::{MockFrame, MockScript, MockServer},
+    };
+    use std::time::Duration;
+    use tokio::{
+        sync::mpsc::unbounded_channel,
+        time::{sleep, timeout},
+    };
+
+    async fn manager(server: &MockServer) -> ChartSessionManager<&'static str> {
+        let (data_tx, _data_rx) = unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(data_tx)
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+        ChartSessionManager::new(ws)
+    }
+
+    fn options(symbol: &str) -> ChartOptions {
+        ChartOptions::new_with(symbol, "BINANCE", Interval::OneHour)
+    }
+
+    #[tokio::test]
+    async fn test_remove_closes_session() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let charts = manager(&server).await;
+
+        let btc = charts.add("btc", options("BTCUSDT")).await.unwrap();
+        charts.add("eth", options("ETHUSDT")).await.unwrap();
+        assert_eq!(charts.len(), 2);
+
+        assert!(charts.remove(&"btc").await.unwrap());
+        assert!(!charts.remove(&"btc").await.unwrap());
+        assert!(charts.get(&"btc").is_none());
+        assert!(charts.get(&"eth").is_some());
+
+        let session = btc.chart_session.as_str();
+        let closed = |method: &str, frames: &[MockFrame]| {
+            frames
+                .iter()
+                .any(|frame| frame.method == method && frame.params[0] == session)
+        };
+        timeout(Duration::from_secs(5), async {
+            loop {
+                let received = server.received().await;
+                if closed("remove_series", &received) && closed("chart_delete_session", &received) {
+                    break;
+                }
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("server never saw the session closed");
+    }
+
+    #[tokio::test]
+    async fn test_failed_remove_keeps_key() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let charts = manager(&server).await;
+
+        let btc = charts.add("btc", options("BTCUSDT")).await.unwrap();
+        charts.ws.close().await.unwrap();
+
+        assert!(charts.remove(&"btc").await.is_err());
+        assert_eq!(charts.keys(), vec!["btc"]);
+        let info = SeriesInfo {
+            chart_session: btc.chart_session,
+            options: btc.options,
+            ..Default::default()
+        };
+        assert_eq!(charts.key_for(&info), Some("btc"));
+    }
+}
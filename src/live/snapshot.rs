@@ -0,0 +1,221 @@
+#[cfg(feature = "pine")]
+use crate::VolumeProfileOptions;
+use crate::{
+    ChartOptions, Error, Result, StudyOptions, live::models::DataServer,
+    pine_indicator::ScriptType, quote::options::QuoteSessionOptions,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+use ustr::Ustr;
+
+/// Version written by [`SessionSnapshot::to_json`].
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// A chart series and the ids it was opened under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesSnapshot {
+    pub chart_session: Ustr,
+    pub series_id: Ustr,
+    pub options: ChartOptions,
+}
+
+/// A study added with `create_study` and the inputs it was last modified with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudySnapshot {
+    pub chart_session: Ustr,
+    /// Series the study is attached to, or the study it is computed on.
+    pub series_id: Ustr,
+    pub study_id: Ustr,
+    pub script_id: Ustr,
+    pub script_version: Ustr,
+    pub script_type: ScriptType,
+    /// Inputs overriding the script defaults, empty for the defaults.
+    #[serde(default)]
+    pub inputs: HashMap<Ustr, Value>,
+    /// Set for studies added with `add_volume_profile`, which have no script.
+    #[cfg(feature = "pine")]
+    #[serde(default)]
+    pub volume_profile: Option<VolumeProfileOptions>,
+}
+
+impl StudySnapshot {
+    pub fn study_options(&self) -> StudyOptions {
+        StudyOptions {
+            script_id: self.script_id,
+            script_version: self.script_version,
+            script_type: self.script_type,
+        }
+    }
+}
+
+/// Order `studies` so each comes after the study it is computed on, keeping
+/// the order otherwise.
+#[cfg(feature = "pine")]
+pub(crate) fn parents_first(mut studies: Vec<StudySnapshot>) -> Vec<StudySnapshot> {
+    let mut ordered = Vec::with_capacity(studies.len());
+    while !studies.is_empty() {
+        let (ready, waiting): (Vec<_>, Vec<_>) = studies.iter().cloned().partition(|study| {
+            !studies.iter().any(|parent| {
+                parent.chart_session == study.chart_session && parent.study_id == study.series_id
+            })
+        });
+        // A cycle can never be created, keep whatever is left as is
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            break;
+        }
+        ordered.extend(ready);
+        studies = waiting;
+    }
+    ordered
+}
+
+/// Every subscription of a websocket client at one point in time, to resume
+/// identical coverage on a fresh connection after a crash or an upgrade.
+///
+/// Taken with `WebSocketClient::snapshot` and re-established with
+/// `WebSocketClient::resume_from_snapshot`. Replay series are left out, and
+/// studies of [`ChartOptions::study_config`] come back with their defaults.
+/// The auth token is never stored.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{live::snapshot::SessionSnapshot, websocket::WebSocketClient};
+///
+/// # async fn run(ws: &WebSocketClient) -> tradingview::Result<()> {
+/// ws.snapshot().await.save_to_file("session.json")?;
+///
+/// // After a restart
+/// let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+/// let snapshot = SessionSnapshot::load_from_file("session.json")?;
+/// let ws = WebSocketClient::resume_from_snapshot()
+///     .snapshot(&snapshot)
+///     .data_tx(tx)
+///     .call()
+///     .await?;
+/// ws.spawn_reader_task();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub saved_at: DateTime<Utc>,
+    pub server: DataServer,
+    /// Options of the quote session with every symbol it tracked, `None` if
+    /// the client had no quote session.
+    pub quote_options: Option<QuoteSessionOptions>,
+    #[serde(default)]
+    pub series: Vec<SeriesSnapshot>,
+    #[serde(default)]
+    pub studies: Vec<StudySnapshot>,
+    /// Symbols with an order book subscription.
+    #[serde(default)]
+    pub depth_symbols: Vec<Ustr>,
+}
+
+impl SessionSnapshot {
+    pub(crate) fn new(server: DataServer) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            saved_at: Utc::now(),
+            server,
+            quote_options: None,
+            series: Vec::new(),
+            studies: Vec::new(),
+            depth_symbols: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a snapshot, rejecting ones written by a newer release.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(json)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(Error::Internal(Ustr::from(&format!(
+                "Unsupported session snapshot version {}",
+                snapshot.version
+            ))));
+        }
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot to `path`, replacing any older one atomically.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.to_json()?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interval;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut snapshot = SessionSnapshot::new(DataServer::ProData);
+        snapshot.quote_options = Some(QuoteSessionOptions {
+            symbols: vec![Ustr::from("NASDAQ:AAPL")],
+            ..Default::default()
+        });
+        snapshot.series.push(SeriesSnapshot {
+            chart_session: Ustr::from("cs_abc"),
+            series_id: Ustr::from("sds_3"),
+            options: ChartOptions::new_with("BTCUSDT", "BINANCE", Interval::OneHour),
+        });
+        snapshot.studies.push(StudySnapshot {
+            chart_session: Ustr::from("cs_abc"),
+            series_id: Ustr::from("sds_3"),
+            study_id: Ustr::from("st1"),
+            script_id: Ustr::from("STD;RSI"),
+            script_version: Ustr::from("31.0"),
+            script_type: ScriptType::IntervalScript,
+            inputs: [(Ustr::from("in_0"), json!(21))].into_iter().collect(),
+            #[cfg(feature = "pine")]
+            volume_profile: None,
+        });
+
+        let restored = SessionSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(restored.quote_options, snapshot.quote_options);
+        assert_eq!(restored.series[0].series_id, Ustr::from("sds_3"));
+        assert_eq!(restored.series[0].options.interval, Interval::OneHour);
+        assert_eq!(restored.studies, snapshot.studies);
+        assert_eq!(
+            restored.studies[0].study_options().script_id,
+            Ustr::from("STD;RSI")
+        );
+
+        #[cfg(feature = "pine")]
+        {
+            let child = StudySnapshot {
+                series_id: Ustr::from("st1"),
+                study_id: Ustr::from("ema_of_rsi"),
+                ..snapshot.studies[0].clone()
+            };
+            let ordered = parents_first(vec![child, snapshot.studies[0].clone()]);
+            assert_eq!(ordered[0].study_id, Ustr::from("st1"));
+            assert_eq!(ordered[1].study_id, Ustr::from("ema_of_rsi"));
+        }
+
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        assert!(SessionSnapshot::from_json(&snapshot.to_json().unwrap()).is_err());
+    }
+}
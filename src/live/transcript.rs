@@ -0,0 +1,382 @@
+use crate::{
+    Error, Result, StudyOptions,
+    chart::ChartOptions,
+    live::{
+        handler::{
+            data::DataHandler,
+            types::{DataTx, EventMask, TradingViewHandler},
+        },
+        journal::{JournalDirection, REDACTED_METHODS},
+        metadata::{Metadata, SeriesInfo},
+        models::{SocketMessage, SocketMessageSer, TradingViewDataEvent},
+    },
+    payload,
+    utils::parse_packet,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::Path,
+    sync::mpsc,
+    thread::JoinHandle,
+};
+use tracing::{debug, warn};
+use ustr::Ustr;
+
+/// One raw websocket frame of a [`Transcript`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptFrame {
+    pub timestamp: DateTime<Utc>,
+    pub direction: JournalDirection,
+    /// The `~m~` framed text exactly as it went over the wire.
+    pub frame: String,
+}
+
+/// Appends every raw frame of a [`crate::websocket::WebSocketClient`] to a
+/// file, one JSON encoded [`TranscriptFrame`] per line.
+///
+/// Attach one with `WebSocketClient::builder().recorder(...)`. Auth tokens
+/// are redacted like in the [`crate::live::journal::EventJournal`]. Frames
+/// are written on a thread of their own, so recording never blocks the
+/// reader; dropping the recorder waits for the queued frames.
+#[derive(Debug)]
+pub struct TranscriptRecorder {
+    tx: Option<mpsc::Sender<TranscriptFrame>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl TranscriptRecorder {
+    /// Record into `path`, replacing any previous transcript there.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = LineWriter::new(File::create(path)?);
+        let (tx, rx) = mpsc::channel::<TranscriptFrame>();
+        let writer = std::thread::spawn(move || {
+            for frame in rx {
+                let written = serde_json::to_writer(&mut file, &frame)
+                    .map_err(Error::from)
+                    .and_then(|()| Ok(file.write_all(b"\n")?));
+                if let Err(e) = written {
+                    warn!("Failed to record frame: {}", e);
+                }
+            }
+        });
+        Ok(Self {
+            tx: Some(tx),
+            writer: Some(writer),
+        })
+    }
+
+    pub fn record(&self, direction: JournalDirection, frame: &str) {
+        let frame = TranscriptFrame {
+            timestamp: Utc::now(),
+            direction,
+            frame: frame.to_owned(),
+        };
+        if let Some(tx) = &self.tx
+            && tx.send(frame).is_err()
+        {
+            warn!("Transcript writer stopped, frame not recorded");
+        }
+    }
+
+    /// Record an outgoing message, with credentials redacted.
+    pub(crate) fn record_message(&self, m: &str, p: &[Value]) {
+        let frame = if REDACTED_METHODS.contains(&m) {
+            SocketMessageSer::new(m, payload!("<redacted>")).to_text()
+        } else {
+            SocketMessageSer::new(m, p).to_text()
+        };
+        match frame {
+            Ok(frame) => self.record(JournalDirection::Sent, &frame),
+            Err(e) => warn!("Failed to record frame: {}", e),
+        }
+    }
+}
+
+impl Drop for TranscriptRecorder {
+    fn drop(&mut self) {
+        // Closing the channel ends the writer once it has drained the queue
+        self.tx.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Frames captured by a [`TranscriptRecorder`], replayable without a
+/// connection for deterministic tests and offline debugging.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::live::transcript::Transcript;
+///
+/// # async fn run() -> tradingview::Result<()> {
+/// let transcript = Transcript::load("session.jsonl")?;
+/// let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+/// tokio::spawn(async move {
+///     while let Some(response) = rx.recv().await {
+///         println!("{response:?}");
+///     }
+/// });
+/// // Ten times faster than it was recorded
+/// transcript.play().data_tx(tx).speed(10.0).call().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub frames: Vec<TranscriptFrame>,
+}
+
+#[bon::bon]
+impl Transcript {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let mut frames = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                frames.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { frames })
+    }
+
+    /// Feed the received frames through a fresh data handler, waiting out
+    /// the recorded gaps divided by `speed`.
+    ///
+    /// Sent frames are not replayed but register their charts and studies,
+    /// so chart data reaches the handler as it did live. `f64::INFINITY`
+    /// plays back without any delay.
+    #[builder]
+    pub async fn play(
+        &self,
+        #[builder(into)] data_tx: DataTx,
+        handler: Option<TradingViewHandler>,
+        #[builder(default = 1.0)] speed: f64,
+    ) -> Result<()> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(Error::Internal(Ustr::from(&format!(
+                "Playback speed must be positive, got {speed}"
+            ))));
+        }
+        let data_handler = DataHandler::builder()
+            .res_tx(data_tx)
+            .maybe_handler(handler)
+            .build();
+        let mut symbols: HashMap<Ustr, ChartOptions> = HashMap::new();
+        let mut previous: Option<DateTime<Utc>> = None;
+
+        for frame in &self.frames {
+            if let Some(previous) = previous {
+                let gap = (frame.timestamp - previous).to_std().unwrap_or_default();
+                let delay = gap.div_f64(speed);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            previous = Some(frame.timestamp);
+
+            match frame.direction {
+                JournalDirection::Sent => {
+                    for message in parse_packet(&frame.frame, false, EventMask::all()) {
+                        if let SocketMessage::SocketMessage(msg) = message {
+                            track_sent(&data_handler.metadata, &mut symbols, &msg.m, &msg.p);
+                        }
+                    }
+                }
+                JournalDirection::Received => {
+                    for message in parse_packet(&frame.frame, true, data_handler.event_mask()) {
+                        match message {
+                            SocketMessage::SocketMessage(msg) => {
+                                let event = TradingViewDataEvent::from(msg.m.to_owned());
+                                data_handler.handle_events(event, &msg.p).await;
+                            }
+                            SocketMessage::QuoteData(quote) => {
+                                data_handler.handle_typed_quote(quote).await;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        debug!("Played back {} frames", self.frames.len());
+        Ok(())
+    }
+}
+
+/// Mirror the metadata the client registered alongside a sent message.
+fn track_sent(
+    metadata: &Metadata,
+    symbols: &mut HashMap<Ustr, ChartOptions>,
+    m: &str,
+    p: &[Value],
+) {
+    let param = |i: usize| p.get(i).and_then(Value::as_str).map(Ustr::from);
+    match m {
+        "resolve_symbol" => {
+            if let (Some(symbol_series_id), Some(init)) = (param(1), param(2)) {
+                symbols.insert(symbol_series_id, symbol_options(&init));
+            }
+        }
+        "create_series" | "modify_series" => {
+            let (Some(chart_session), Some(series_id)) = (param(0), param(1)) else {
+                return;
+            };
+            let mut options = param(3)
+                .and_then(|id| symbols.get(&id).copied())
+                .unwrap_or_default();
+            if let Some(interval) = param(4).and_then(|i| i.parse().ok()) {
+                options.interval = interval;
+            }
+            if let Some(mut series) = metadata.series.get_mut(&series_id) {
+                // A modified series keeps its study
+                options.study_config = series.options.study_config;
+                series.options = options;
+            } else {
+                metadata.series.insert(
+                    series_id,
+                    SeriesInfo {
+                        chart_session,
+                        options,
+                        symbol_series_id: param(3).unwrap_or_default(),
+                        series_version: param(2).unwrap_or_default(),
+                        backfill: false,
+                    },
+                );
+            }
+        }
+        "create_study" => {
+            let (Some(study_id), Some(series_id)) = (param(1), param(3)) else {
+                return;
+            };
+            let input = |key: &str| {
+                p.get(5)
+                    .and_then(|inputs| inputs.get(key))
+                    .and_then(Value::as_str)
+                    .map(Ustr::from)
+            };
+            let study = StudyOptions {
+                script_id: input("pineId").unwrap_or(study_id),
+                script_version: input("pineVersion").unwrap_or_default(),
+                ..Default::default()
+            };
+            metadata.studies.insert(study.script_id, study_id);
+            if let Some(mut series) = metadata.series.get_mut(&series_id) {
+                series.options.study_config = Some(study);
+            }
+        }
+        "request_more_data" => {
+            if let Some(series_id) = param(1) {
+                metadata.backfill.insert(series_id);
+            }
+        }
+        "remove_series" => {
+            if let Some(series_id) = param(1) {
+                metadata.series.remove(&series_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Symbol and exchange of a `={"symbol": "EXCHANGE:SYMBOL", ..}` init string.
+fn symbol_options(init: &str) -> ChartOptions {
+    let init: Value = serde_json::from_str(init.trim_start_matches('=')).unwrap_or_default();
+    // Alternative chart styles nest the symbol init one level deeper
+    let symbol = match &init["symbol"] {
+        Value::Object(_) => init["symbol"]["symbol"].as_str(),
+        symbol => symbol.as_str(),
+    };
+    let (exchange, symbol) = symbol.and_then(|s| s.split_once(':')).unwrap_or_default();
+    ChartOptions::builder()
+        .symbol(Ustr::from(symbol))
+        .exchange(Ustr::from(exchange))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Interval, live::handler::message::TradingViewResponse};
+    use chrono::TimeDelta;
+
+    #[tokio::test]
+    async fn test_play_registers_series_from_sent_frames() {
+        let start = Utc::now();
+        let frame = |ms: i64, direction, m: &str, p: Vec<Value>| TranscriptFrame {
+            timestamp: start + TimeDelta::milliseconds(ms),
+            direction,
+            frame: SocketMessageSer::new(m, p).to_text().unwrap(),
+        };
+        let transcript = Transcript {
+            frames: vec![
+                frame(
+                    0,
+                    JournalDirection::Sent,
+                    "resolve_symbol",
+                    payload!("cs_1", "sds_sym_1", r#"={"symbol":"NASDAQ:AAPL"}"#),
+                ),
+                frame(
+                    1,
+                    JournalDirection::Sent,
+                    "create_series",
+                    payload!("cs_1", "sds_1", "s1", "sds_sym_1", "1H", 10, ""),
+                ),
+                frame(
+                    5,
+                    JournalDirection::Received,
+                    "timescale_update",
+                    vec![
+                        Value::from("cs_1"),
+                        serde_json::json!({ "sds_1": { "s": [{ "i": 0, "v": [1.0, 2.0, 3.0, 0.5, 2.5, 100.0] }] } }),
+                    ],
+                ),
+            ],
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        transcript
+            .play()
+            .data_tx(tx)
+            .speed(f64::INFINITY)
+            .call()
+            .await
+            .unwrap();
+
+        let mut chart = None;
+        while let Ok(response) = rx.try_recv() {
+            if let TradingViewResponse::ChartData(series, bars) = response {
+                chart = Some((series, bars));
+            }
+        }
+        let (series, bars) = chart.expect("chart data was played back");
+        assert_eq!(series.options.symbol, "AAPL");
+        assert_eq!(series.options.exchange, "NASDAQ");
+        assert_eq!(series.options.interval, Interval::OneHour);
+        assert_eq!(bars.len(), 1);
+    }
+
+    #[test]
+    fn test_recorder_writes_in_order() {
+        let path =
+            std::env::temp_dir().join(format!("tv_transcript_{}.jsonl", rand::random::<u64>()));
+        let recorder = TranscriptRecorder::create(&path).unwrap();
+        recorder.record_message("set_auth_token", &payload!("secret"));
+        for i in 0..100 {
+            recorder.record(JournalDirection::Received, &format!("~m~1~m~{i}"));
+        }
+        drop(recorder);
+
+        let transcript = Transcript::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(transcript.frames.len(), 101);
+        assert!(!transcript.frames[0].frame.contains("secret"));
+        assert_eq!(transcript.frames[100].frame, "~m~1~m~99");
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{
+    Error, Result,
+    chart::ChartOptions,
+    live::{
+        handler::{
+            data::DataHandler,
+            types::{DataTx, EventMask, TradingViewHandler},
+        },
+        metadata::SeriesInfo,
+        models::{DataServer, SocketMessageSer},
+        protocol,
+    },
+    payload,
+    quote::options::QuoteSessionOptions,
+    utils::{gen_session_id, parse_packet},
+};
+use futures_util::{SinkExt, StreamExt, stream::SplitSink};
+use gloo_net::websocket::{Message, futures::WebSocket};
+use serde_json::Value;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicU16, Ordering},
+};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info, warn};
+use ustr::{Ustr, ustr};
+
+/// Websocket client for `wasm32-unknown-unknown` on top of the browser's
+/// WebSocket, for browser and Tauri frontends.
+///
+/// Responses are parsed by the same data handler as the native client, so
+/// `data_tx` and the [`TradingViewHandler`] callbacks see identical events.
+/// It covers charts and quotes; reconnects, studies, replay and the command
+/// runner remain native only. Quote conflation relies on `std::time::Instant`
+/// and must stay disabled.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use tradingview::{Interval, chart::ChartOptions, websocket::WasmWebSocketClient};
+///
+/// let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+/// let ws = WasmWebSocketClient::builder().data_tx(tx).build().await?;
+/// ws.add_market(ChartOptions::new_with("AAPL", "NASDAQ", Interval::OneDay)).await?;
+/// while let Some(response) = rx.recv().await {
+///     web_sys::console::log_1(&format!("{response:?}").into());
+/// }
+/// ```
+pub struct WasmWebSocketClient {
+    pub server: DataServer,
+    quote_session: RwLock<Ustr>,
+    quote_options: RwLock<QuoteSessionOptions>,
+    data_handler: DataHandler,
+    is_closed: Arc<AtomicBool>,
+    series_count: AtomicU16,
+    write: Mutex<SplitSink<WebSocket, Message>>,
+}
+
+#[bon::bon]
+impl WasmWebSocketClient {
+    /// Connect and read frames on the browser's event loop until the socket closes.
+    #[builder]
+    pub async fn new(
+        auth_token: Option<&str>,
+        #[builder(default = DataServer::ProData)] server: DataServer,
+        /// Receives every parsed response, see [`crate::live::handler::types::bounded_channel`].
+        #[builder(into)]
+        data_tx: DataTx,
+        /// Extra callbacks run after each event has been forwarded to `data_tx`.
+        handler: Option<TradingViewHandler>,
+        /// Event classes to parse and dispatch, see [`EventMask`].
+        #[builder(default)]
+        event_mask: EventMask,
+    ) -> Result<Arc<Self>> {
+        let url = format!("wss://{server}.tradingview.com/socket.io/websocket");
+        let socket = WebSocket::open(&url).map_err(|e| Error::WebSocket(ustr(&e.to_string())))?;
+        info!("WebSocket connected to {}", url);
+        let (write, mut read) = socket.split();
+
+        let client = Arc::new(Self {
+            server,
+            quote_session: RwLock::new(ustr("")),
+            quote_options: RwLock::new(QuoteSessionOptions::default()),
+            data_handler: DataHandler::builder()
+                .res_tx(data_tx)
+                .maybe_handler(handler)
+                .event_mask(event_mask)
+                .build(),
+            is_closed: Arc::new(AtomicBool::new(false)),
+            series_count: AtomicU16::new(0),
+            write: Mutex::new(write),
+        });
+
+        let reader = Arc::clone(&client);
+        wasm_bindgen_futures::spawn_local(async move {
+            while let Some(frame) = read.next().await {
+                match frame {
+                    Ok(Message::Text(text)) => reader.handle_frame(&text).await,
+                    Ok(Message::Bytes(bytes)) => {
+                        debug!("Received binary message of {} bytes", bytes.len());
+                    }
+                    Err(e) => {
+                        error!("Error reading message: {}", e);
+                        break;
+                    }
+                }
+            }
+            reader.is_closed.store(true, Ordering::Relaxed);
+            info!("WebSocket event loop ended");
+        });
+
+        client
+            .set_auth_token(auth_token.unwrap_or("unauthorized_user_token"))
+            .await?;
+        Ok(client)
+    }
+
+    #[tracing::instrument(name = "frame", level = "debug", skip_all, fields(bytes = text.len()))]
+    async fn handle_frame(&self, text: &str) {
+        for message in parse_packet(text, true, self.data_handler.event_mask()) {
+            if let Some(heartbeat) = protocol::dispatch(&self.data_handler, message).await
+                && let Err(e) = self.send_raw(heartbeat).await
+            {
+                warn!("Failed to answer heartbeat: {}", e);
+            }
+        }
+    }
+
+    async fn send_raw(&self, text: String) -> Result<()> {
+        self.write
+            .lock()
+            .await
+            .send(Message::Text(text))
+            .await
+            .map_err(|e| Error::WebSocket(ustr(&e.to_string())))
+    }
+
+    #[doc(hidden)]
+    pub async fn send(&self, m: &str, p: &[Value]) -> Result<()> {
+        if self.is_closed.load(Ordering::Relaxed) {
+            return Err(Error::Internal("WebSocket is closed".into()));
+        }
+        debug!("Sending message: {} with payload: {:?}", m, p);
+        self.send_raw(SocketMessageSer::new(m, p).to_text()?).await
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.is_closed.load(Ordering::Relaxed)
+    }
+
+    pub async fn close(&self) -> Result<()> {
+        self.is_closed.store(true, Ordering::Relaxed);
+        self.write
+            .lock()
+            .await
+            .close()
+            .await
+            .map_err(|e| Error::WebSocket(ustr(&e.to_string())))
+    }
+
+    pub async fn set_auth_token(&self, auth_token: &str) -> Result<()> {
+        self.send("set_auth_token", &payload!(auth_token)).await
+    }
+
+    // Begin TradingView WebSocket Quote methods
+    /// Create the quote session configured by `options`, replacing the
+    /// options used by later [`WasmWebSocketClient::add_symbols`] calls.
+    pub async fn create_quote_session(&self, options: QuoteSessionOptions) -> Result<()> {
+        self.data_handler
+            .set_emit_quote_snapshots(options.emit_snapshots);
+        let session = ustr(&gen_session_id(options.kind.session_prefix()));
+        self.send("quote_create_session", &payload!(session.as_str()))
+            .await?;
+
+        self.send(
+            "quote_set_fields",
+            &protocol::quote_fields(&session, &options),
+        )
+        .await?;
+
+        *self.quote_session.write().await = session;
+        let symbols = options.symbols.clone();
+        *self.quote_options.write().await = options;
+        if !symbols.is_empty() {
+            let symbols: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+            self.add_symbols(&symbols).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn add_symbols(&self, symbols: &[&str]) -> Result<()> {
+        if self.quote_session.read().await.is_empty() {
+            self.create_quote_session(QuoteSessionOptions::default())
+                .await?;
+        }
+        let payloads = self.quote_payloads(symbols).await?;
+        self.send("quote_add_symbols", &payloads).await?;
+        for symbol in symbols {
+            self.data_handler
+                .metadata
+                .quote_symbols
+                .insert(ustr(symbol));
+        }
+
+        if self.quote_options.read().await.mode.is_fast() {
+            self.send("quote_fast_symbols", &payloads).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove_symbols(&self, symbols: &[&str]) -> Result<()> {
+        let payloads = self.quote_payloads(symbols).await?;
+        self.send("quote_remove_symbols", &payloads).await?;
+        for symbol in symbols {
+            self.data_handler
+                .metadata
+                .quote_symbols
+                .remove(&ustr(symbol));
+            self.data_handler.metadata.quotes.remove(symbol);
+        }
+        Ok(())
+    }
+
+    async fn quote_payloads(&self, symbols: &[&str]) -> Result<Vec<Value>> {
+        let session = self.quote_session.read().await.to_string();
+        protocol::quote_symbols(&session, symbols, &*self.quote_options.read().await)
+    }
+    // End TradingView WebSocket Quote methods
+
+    // Begin TradingView WebSocket Chart methods
+    /// Open a chart session streaming the series described by `options` and
+    /// return the chart session and series id it was created under.
+    pub async fn add_market(&self, options: ChartOptions) -> Result<(Ustr, Ustr)> {
+        let series_count = self.series_count.fetch_add(1, Ordering::SeqCst) + 1;
+        let series_id = ustr(&format!("sds_{series_count}"));
+        let (symbol_series_id, series_version) = protocol::series_ids(&series_id);
+        let chart_session = ustr(&gen_session_id("cs"));
+        let symbol = format!("{}:{}", options.exchange, options.symbol);
+
+        // Registered up front so the resolved symbol can be matched to its options
+        self.data_handler.metadata.series.insert(
+            series_id,
+            SeriesInfo {
+                chart_session,
+                options,
+                symbol_series_id: ustr(&symbol_series_id),
+                series_version: ustr(&series_version),
+                backfill: false,
+            },
+        );
+
+        let opened = async {
+            self.send("chart_create_session", &payload!(chart_session.as_str()))
+                .await?;
+            let params = protocol::resolve_symbol(
+                &chart_session,
+                &symbol_series_id,
+                &symbol,
+                &options,
+                None,
+            )?;
+            self.send("resolve_symbol", &params).await?;
+            let params = protocol::series_params(
+                &chart_session,
+                &series_id,
+                &series_version,
+                &symbol_series_id,
+                &options,
+            );
+            self.send("create_series", &params).await
+        };
+        if let Err(e) = opened.await {
+            self.data_handler.metadata.series.remove(&series_id);
+            return Err(e);
+        }
+        Ok((chart_session, series_id))
+    }
+
+    pub async fn remove_market(&self, chart_session: &str, series_id: &str) -> Result<()> {
+        self.send("remove_series", &payload!(chart_session, series_id))
+            .await?;
+        self.send("chart_delete_session", &payload!(chart_session))
+            .await?;
+        self.data_handler
+            .metadata
+            .series
+            .remove(&Ustr::from(series_id));
+        Ok(())
+    }
+    // End TradingView WebSocket Chart methods
+}
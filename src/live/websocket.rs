@@ -1,27 +1,51 @@
+#[cfg(feature = "quote")]
+use crate::quote::options::QuoteSessionOptions;
+#[cfg(feature = "replay")]
+use crate::utils::{gen_id, symbol_init};
+#[cfg(feature = "chart")]
+use crate::{ChartOptions, Interval, Timezone, live::snapshot::SeriesSnapshot};
 use crate::{
-    DataPoint, Error, Interval, Result, SocketServerInfo, Timezone,
-    chart::{ChartOptions, StudyOptions, SymbolInfo},
+    Error, News, Result, SocialPost, UserCookies,
+    client::{
+        news::get_news,
+        social::{get_ideas, get_minds},
+    },
+    config::{KeepaliveConfig, ProxyConfig, ThrottleConfig, TlsConfig},
     live::{
-        handler::{data::DataHandler, types::DataTx},
+        feed::FeedPoller,
+        handler::{
+            data::DataHandler,
+            message::{ConnectionStaleMsg, ReconnectedMsg, SessionRefreshedMsg},
+            types::{DataTx, EventMask, QueueMetrics, TradingViewHandler, dispatch},
+        },
+        journal::{EventJournal, JournalDirection},
+        middleware::{FrameMiddleware, MiddlewareChain},
         models::{
-            DataServer, Socket, SocketMessage, SocketMessageDe, SocketMessageSer,
-            TradingViewDataEvent, WEBSOCKET_HEADERS,
+            DataServer, Socket, SocketMessage, SocketMessageDe, SocketMessageSer, WEBSOCKET_HEADERS,
         },
+        protocol,
+        snapshot::SessionSnapshot,
+        transcript::TranscriptRecorder,
     },
     payload,
-    pine_indicator::PineIndicator,
-    quote::{ALL_QUOTE_FIELDS, models::QuoteValue},
-    utils::{gen_id, gen_session_id, parse_packet, symbol_init},
+    quote::state::QuoteSnapshot,
+    telemetry,
+    transport::{FrameSink, FrameStream, TokioTransport, WebSocketTransport},
+    utils::{gen_session_id, parse_packet_into},
 };
-
-use dashmap::DashMap;
-use futures_util::{
-    SinkExt, StreamExt,
-    future::join_all,
-    stream::{SplitSink, SplitStream},
+#[cfg(feature = "pine")]
+use crate::{
+    chart::{StudyOptions, VolumeProfileOptions},
+    live::snapshot::{StudySnapshot, parents_first},
+    pine_indicator::{PineIndicator, ScriptType},
 };
-use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "chart")]
+use futures_util::future::join_all;
+use futures_util::{FutureExt, SinkExt, StreamExt};
 use serde_json::Value;
+#[cfg(feature = "pine")]
+use std::collections::HashMap;
 use std::{
     fmt::Debug,
     sync::{
@@ -31,22 +55,19 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio::{
-    net::TcpStream,
+    select,
     sync::{Mutex, MutexGuard, RwLock},
+    task::JoinHandle,
     time::timeout,
 };
-use tokio_tungstenite::{
-    MaybeTlsStream, WebSocketStream, connect_async_with_config,
-    tungstenite::{
-        client::IntoClientRequest,
-        protocol::{Message, WebSocketConfig},
-    },
-};
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, protocol::Message};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 use url::Url;
 use ustr::{Ustr, ustr};
 
+pub use crate::live::metadata::SeriesInfo;
+
 // Error recovery configuration
 #[derive(Debug, Clone, Copy)]
 struct ErrorRecoveryConfig {
@@ -102,40 +123,36 @@ impl ErrorStats {
     }
 }
 
-#[derive(Default, Clone)]
-pub(crate) struct Metadata {
-    pub(crate) series: Arc<DashMap<Ustr, SeriesInfo>>,
-    pub(crate) studies: Arc<DashMap<Ustr, Ustr>>,
-    pub(crate) quotes: Arc<DashMap<Ustr, QuoteValue>>,
-    pub(crate) chart_state: Arc<RwLock<ChartState>>,
-}
-
-#[derive(Default, Clone)]
-pub(crate) struct ChartState {
-    pub(crate) chart: Option<(SeriesInfo, Vec<DataPoint>)>,
-    pub(crate) symbol_info: Option<SymbolInfo>,
-}
-
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct SeriesInfo {
-    pub chart_session: Ustr,
-    pub options: ChartOptions,
-}
-
 pub struct WebSocketClient {
     pub server: DataServer,
     pub(crate) auth_token: Arc<RwLock<Ustr>>,
+    /// Cookies of the account, used to fetch the metadata of private scripts.
+    user: Arc<RwLock<Option<UserCookies>>>,
+    #[cfg(feature = "quote")]
     pub(crate) quote_session: Arc<RwLock<Ustr>>,
+    #[cfg(feature = "quote")]
+    quote_options: Arc<RwLock<QuoteSessionOptions>>,
+    journal: Option<Arc<EventJournal>>,
+    recorder: Option<Arc<TranscriptRecorder>>,
+    middleware: MiddlewareChain,
+    keepalive: KeepaliveConfig,
+    last_frame: Arc<RwLock<Instant>>,
+    proxy: Option<ProxyConfig>,
+    endpoint: Option<Url>,
 
     data_handler: DataHandler,
     closed: CancellationToken,
+    cancel: CancellationToken,
     // The following fields are used for the WebSocket connection
     is_closed: Arc<AtomicBool>,
     series_count: Arc<AtomicU16>,
     studies_count: Arc<AtomicU16>,
 
-    read: Arc<Mutex<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>>,
-    write: Arc<Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>>,
+    transport: Arc<dyn WebSocketTransport>,
+    read: Arc<Mutex<FrameStream>>,
+    write: Arc<Mutex<FrameSink>>,
+    /// Parsed frames of the message being handled, kept to reuse its capacity.
+    parse_buf: std::sync::Mutex<Vec<SocketMessage<SocketMessageDe>>>,
 
     // Error handling and recovery
     error_stats: ErrorStats,
@@ -158,33 +175,107 @@ impl WebSocketClient {
     #[builder]
     pub async fn new(
         auth_token: Option<&str>,
+        /// Account cookies, their auth token is used when `auth_token` is not
+        /// given. Needed for studies of private and invite-only scripts.
+        user: Option<UserCookies>,
         #[builder(default = DataServer::ProData)] server: DataServer,
+        /// Receives every parsed response, see [`crate::live::handler::types::bounded_channel`].
+        #[builder(into)]
         data_tx: DataTx,
+        cancellation_token: Option<&CancellationToken>,
+        /// Extra callbacks run after each event has been forwarded to `data_tx`.
+        handler: Option<TradingViewHandler>,
+        /// Records every frame sent and received, see [`EventJournal`].
+        journal: Option<Arc<EventJournal>>,
+        /// Writes every raw frame to a transcript file, see [`TranscriptRecorder`].
+        recorder: Option<Arc<TranscriptRecorder>>,
+        /// Sees every frame received and command sent, in order, see [`FrameMiddleware`].
+        #[builder(default)]
+        middleware: Vec<Arc<dyn FrameMiddleware>>,
+        /// Ping interval and stale-connection detection, see [`KeepaliveConfig`].
+        #[builder(default)]
+        keepalive: KeepaliveConfig,
+        /// Caps quote and chart updates per symbol, see [`ThrottleConfig`].
+        #[builder(default)]
+        throttle: ThrottleConfig,
+        /// Event classes to parse and dispatch, see [`EventMask`].
+        #[builder(default)]
+        event_mask: EventMask,
+        /// Proxy for the websocket connection, defaults to [`crate::config::proxy`].
+        proxy: Option<ProxyConfig>,
+        /// Roots and client certificate for the default transport, defaults to
+        /// [`crate::config::tls`]. Ignored when a `transport` is given.
+        tls: Option<TlsConfig>,
+        /// Offer permessage-deflate so the server compresses its frames, see
+        /// [`TokioTransport::with_compression`]. Needs the `deflate` cargo
+        /// feature and is ignored when a `transport` is given.
+        #[builder(default)]
+        compression: bool,
+        /// Opens the socket, defaults to [`TokioTransport`].
+        transport: Option<Arc<dyn WebSocketTransport>>,
+        /// Replaces `wss://{server}.tradingview.com/socket.io/websocket`, e.g.
+        /// with the url of a `test-util` `MockServer`.
+        endpoint: Option<Url>,
     ) -> Result<Arc<Self>> {
-        let auth_token = Ustr::from(auth_token.unwrap_or("unauthorized_user_token"));
+        let auth_token = auth_token
+            .or(user.as_ref().map(|user| user.auth_token.as_str()))
+            .unwrap_or("unauthorized_user_token");
+        let auth_token = Ustr::from(auth_token);
+        let proxy = proxy.or_else(crate::config::proxy);
+
+        let transport = match transport {
+            Some(transport) => transport,
+            None => Self::default_transport(tls, compression)?,
+        };
 
-        let (write, read) = Self::connect(server).await?;
+        let (write, read) = Self::connect(
+            transport.as_ref(),
+            server,
+            endpoint.as_ref(),
+            proxy.as_ref(),
+        )
+        .await?;
 
-        let data_handler = DataHandler::builder().res_tx(data_tx).build();
+        let data_handler = DataHandler::builder()
+            .res_tx(data_tx)
+            .maybe_handler(handler)
+            .throttle(throttle)
+            .event_mask(event_mask)
+            .build();
         let is_closed = Arc::new(AtomicBool::new(false));
         let series_count = Arc::new(AtomicU16::new(0));
         let studies_count = Arc::new(AtomicU16::new(0));
         let auth_token = Arc::new(RwLock::new(auth_token));
         let write = Arc::new(Mutex::new(write));
         let read = Arc::new(Mutex::new(read));
-        let quote_session = Arc::new(RwLock::new(ustr("")));
 
         let client = Arc::new(Self {
             data_handler,
             server,
+            transport,
             read,
             write,
+            parse_buf: std::sync::Mutex::default(),
             auth_token,
+            user: Arc::new(RwLock::new(user)),
             is_closed,
-            quote_session,
+            #[cfg(feature = "quote")]
+            quote_session: Arc::new(RwLock::new(ustr(""))),
+            #[cfg(feature = "quote")]
+            quote_options: Arc::default(),
+            journal,
+            recorder,
+            middleware: MiddlewareChain::new(middleware),
+            keepalive,
+            last_frame: Arc::new(RwLock::new(Instant::now())),
+            proxy,
+            endpoint,
             series_count,
             studies_count,
             closed: CancellationToken::new(),
+            cancel: cancellation_token
+                .map(CancellationToken::child_token)
+                .unwrap_or_default(),
             error_stats: ErrorStats::default(),
             error_config: ErrorRecoveryConfig::default(),
         });
@@ -192,6 +283,33 @@ impl WebSocketClient {
         Ok(client)
     }
 
+    /// Connect to the server of `snapshot` and [`WebSocketClient::restore`]
+    /// it, resuming the coverage of a previous process in one call. Spawn the
+    /// reader task afterwards as for a new client.
+    #[builder]
+    pub async fn resume_from_snapshot(
+        snapshot: &SessionSnapshot,
+        auth_token: Option<&str>,
+        /// Account cookies, needed to restore studies of private scripts.
+        user: Option<UserCookies>,
+        #[builder(into)] data_tx: DataTx,
+        cancellation_token: Option<&CancellationToken>,
+        handler: Option<TradingViewHandler>,
+    ) -> Result<Arc<Self>> {
+        let client = Self::builder()
+            .maybe_auth_token(auth_token)
+            .maybe_user(user)
+            .server(snapshot.server)
+            .data_tx(data_tx)
+            .maybe_cancellation_token(cancellation_token)
+            .maybe_handler(handler)
+            .build()
+            .await?;
+        client.restore(snapshot).await?;
+        Ok(client)
+    }
+
+    #[doc(hidden)]
     pub fn spawn_reader_task(self: Arc<Self>) {
         tokio::spawn(async move {
             if let Err(e) = self.subscribe().await {
@@ -200,33 +318,59 @@ impl WebSocketClient {
         });
     }
 
+    /// The tokio transport on `tls`, or on the configuration installed with
+    /// [`crate::config::set_tls`].
+    fn default_transport(
+        tls: Option<TlsConfig>,
+        compression: bool,
+    ) -> Result<Arc<dyn WebSocketTransport>> {
+        #[cfg(feature = "rustls-tls")]
+        let client_config = match tls {
+            Some(tls) => Some(tls.client_config()?),
+            None => crate::config::tls_client_config(),
+        };
+        #[cfg(feature = "rustls-tls")]
+        let transport = match client_config {
+            Some(config) => TokioTransport::with_rustls(config),
+            None => TokioTransport::default(),
+        };
+        #[cfg(not(feature = "rustls-tls"))]
+        let transport = match tls.or_else(crate::config::tls) {
+            Some(_) => {
+                return Err(Error::Config(ustr(
+                    "a TLS config needs the `rustls-tls` cargo feature",
+                )));
+            }
+            None => TokioTransport::default(),
+        };
+        #[cfg(feature = "deflate")]
+        let transport = transport.with_compression(compression);
+        #[cfg(not(feature = "deflate"))]
+        if compression {
+            return Err(Error::Config(ustr(
+                "websocket compression needs the `deflate` cargo feature",
+            )));
+        }
+        Ok(Arc::new(transport))
+    }
+
     async fn connect(
+        transport: &dyn WebSocketTransport,
         server: DataServer,
-    ) -> Result<(
-        SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-        SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-    )> {
-        let url = Url::parse(&format!(
-            "wss://{server}.tradingview.com/socket.io/websocket"
-        ))?;
+        endpoint: Option<&Url>,
+        proxy: Option<&ProxyConfig>,
+    ) -> Result<(FrameSink, FrameStream)> {
+        let url = match endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => Url::parse(&format!(
+                "wss://{server}.tradingview.com/socket.io/websocket"
+            ))?,
+        };
 
         let mut request = url.into_client_request()?;
-        request
-            .headers_mut()
-            .extend(WEBSOCKET_HEADERS.clone().into_iter());
-
-        // Configure WebSocket with larger message size limits
-        let conf = WebSocketConfig::default()
-            .read_buffer_size(1024 * 1024)
-            .write_buffer_size(1024 * 1024);
+        request.headers_mut().extend(WEBSOCKET_HEADERS.clone());
 
-        let (socket, response) = connect_async_with_config(request, Some(conf), false).await?;
-
-        info!("WebSocket connected with status: {}", response.status());
-
-        let (write, read) = socket.split();
-
-        Ok((write, read))
+        transport.connect(request, proxy).await
     }
 
     /// Classify error severity for appropriate response
@@ -372,7 +516,7 @@ impl WebSocketClient {
         })];
 
         // Notify through the error callback
-        (self.data_handler.handler.on_error)((*error, error_context));
+        dispatch(&self.data_handler.handler.on_error, (*error, error_context)).await;
     }
 
     /// Log error with appropriate level based on severity
@@ -408,25 +552,328 @@ impl WebSocketClient {
         }
     }
 
+    /// Depth and drop counters of the response channel.
+    pub fn queue_metrics(&self) -> QueueMetrics {
+        self.data_handler.res_tx.metrics()
+    }
+
+    /// Replace the per-symbol update caps, see [`ThrottleConfig`].
+    pub fn set_throttle(&self, throttle: ThrottleConfig) {
+        self.data_handler.set_throttle(throttle);
+    }
+
+    /// Replace the event classes to parse and dispatch, see [`EventMask`].
+    pub fn set_event_mask(&self, mask: EventMask) {
+        self.data_handler.set_event_mask(mask);
+    }
+
+    /// Merged quote state of `symbol`, if any update for it was received.
+    pub fn quote_snapshot(&self, symbol: &str) -> Option<QuoteSnapshot> {
+        self.data_handler.metadata.quotes.get(symbol)
+    }
+
+    /// Merged quote state of every symbol in the quote session.
+    pub fn quote_snapshots(&self) -> Vec<QuoteSnapshot> {
+        self.data_handler.metadata.quotes.snapshots()
+    }
+
     pub async fn is_closed(&self) -> bool {
         self.is_closed.load(Ordering::Relaxed)
     }
 
+    /// Complete the receiver of `data_tx` once it drained the queued responses.
+    pub(crate) fn close_data_channel(&self) {
+        self.data_handler.res_tx.close();
+    }
+
+    /// Reopen the socket, re-authenticate and replay every active quote, chart
+    /// and study subscription, then emit [`TradingViewResponse::Reconnected`].
+    ///
+    /// [`TradingViewResponse::Reconnected`]: crate::live::handler::message::TradingViewResponse::Reconnected
     pub async fn reconnect(&self) -> Result<()> {
-        let auth_token = self.auth_token.read().await;
-        let (write, read) = Self::connect(self.server).await?;
-        let mut write_guard = self.write.lock().await;
-        let mut read_guard = self.read.lock().await;
-        *write_guard = write;
-        *read_guard = read;
+        if self.cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        let result = self.reopen().await;
+        telemetry::reconnected(result.is_ok());
+        result
+    }
+
+    async fn reopen(&self) -> Result<()> {
+        let auth_token = *self.auth_token.read().await;
+        {
+            let (write, read) = Self::connect(
+                self.transport.as_ref(),
+                self.server,
+                self.endpoint.as_ref(),
+                self.proxy.as_ref(),
+            )
+            .await?;
+            let mut write_guard = self.write.lock().await;
+            let mut read_guard = self.read.lock().await;
+            *write_guard = write;
+            *read_guard = read;
+        }
         self.is_closed.store(false, Ordering::Relaxed);
+        *self.last_frame.write().await = Instant::now();
         self.set_auth_token(&auth_token).await?;
+
+        let msg = self.resubscribe().await?;
+        info!(
+            "Resubscribed {} series and {} quote symbols after reconnect",
+            msg.series, msg.symbols
+        );
+        dispatch(&self.data_handler.handler.on_reconnected, msg).await;
         Ok(())
     }
 
+    /// Recreate the sessions tracked in the metadata on a fresh connection.
+    async fn resubscribe(&self) -> Result<ReconnectedMsg> {
+        #[cfg(feature = "quote")]
+        let symbols = self.resubscribe_quotes().await?;
+        #[cfg(not(feature = "quote"))]
+        let symbols = 0;
+        #[cfg(feature = "chart")]
+        let series = self.resubscribe_series().await?;
+        #[cfg(not(feature = "chart"))]
+        let series = 0;
+
+        let depth_sessions: Vec<(Ustr, Ustr)> = self
+            .data_handler
+            .metadata
+            .depth_sessions
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        for (session, symbol) in depth_sessions {
+            self.create_depth_session(&session).await?;
+            self.set_depth_symbol(&session, &symbol).await?;
+        }
+
+        Ok(ReconnectedMsg { series, symbols })
+    }
+
+    /// Recreate the quote session, returning how many symbols it tracks.
+    #[cfg(feature = "quote")]
+    async fn resubscribe_quotes(&self) -> Result<usize> {
+        let had_quote_session = !self.quote_session.read().await.is_empty();
+        let symbols: Vec<Ustr> = self
+            .data_handler
+            .metadata
+            .quote_symbols
+            .iter()
+            .map(|s| *s)
+            .collect();
+
+        if had_quote_session || !symbols.is_empty() {
+            self.create_quote_session().await?;
+            self.set_fields().await?;
+        }
+        if !symbols.is_empty() {
+            let symbols: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+            self.add_symbols(&symbols).await?;
+        }
+        let (mode, fast_symbols) = {
+            let options = self.quote_options.read().await;
+            (options.mode, options.fast_symbols.clone())
+        };
+        // Fast modes already marked every symbol in `add_symbols`
+        if !fast_symbols.is_empty() && !mode.is_fast() {
+            let symbols: Vec<&str> = fast_symbols.iter().map(|s| s.as_str()).collect();
+            self.fast_symbols(&symbols).await?;
+        }
+        Ok(symbols.len())
+    }
+
+    /// Recreate every chart series and study, returning how many series were
+    /// reopened.
+    #[cfg(feature = "chart")]
+    async fn resubscribe_series(&self) -> Result<usize> {
+        // Reopen every series under its old chart session and series id so
+        // callers keyed on them keep working, studies are recreated from scratch
+        let series: Vec<(Ustr, SeriesInfo)> = self
+            .data_handler
+            .metadata
+            .series
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        self.data_handler.metadata.series.clear();
+        self.data_handler.metadata.studies.clear();
+        self.data_handler.metadata.backfill.clear();
+        self.studies_count.store(0, Ordering::SeqCst);
+
+        for (series_id, info) in &series {
+            self.open_market(info.chart_session, *series_id, info.options)
+                .await?;
+        }
+        #[cfg(feature = "pine")]
+        {
+            let studies = parents_first(
+                self.data_handler
+                    .metadata
+                    .created_studies
+                    .iter()
+                    .map(|entry| entry.value().clone())
+                    .collect(),
+            );
+            for study in &studies {
+                self.restore_study(study).await?;
+            }
+        }
+        Ok(series.len())
+    }
+
+    /// Metadata of a script, fetched with the client's cookies.
+    #[cfg(feature = "pine")]
+    async fn fetch_indicator(
+        &self,
+        script_id: &str,
+        script_version: &str,
+        script_type: ScriptType,
+    ) -> Result<PineIndicator> {
+        let mut builder = PineIndicator::build();
+        if let Some(user) = self.user.read().await.clone() {
+            builder.user(user);
+        }
+        builder.fetch(script_id, script_version, script_type).await
+    }
+
+    /// Recreate a study of a snapshot under its old ids, with its inputs.
+    #[cfg(feature = "pine")]
+    async fn restore_study(&self, study: &StudySnapshot) -> Result<()> {
+        if let Some(options) = study.volume_profile {
+            return self
+                .add_volume_profile(
+                    &study.chart_session,
+                    &study.study_id,
+                    &study.series_id,
+                    options,
+                )
+                .await;
+        }
+        let indicator = self
+            .fetch_indicator(&study.script_id, &study.script_version, study.script_type)
+            .await?;
+        self.create_study_with(
+            &study.chart_session,
+            &study.study_id,
+            &study.series_id,
+            indicator,
+            &study.inputs,
+        )
+        .await
+    }
+
+    /// The quote symbols, chart series, studies and order books this client
+    /// is subscribed to, see [`SessionSnapshot`].
+    pub async fn snapshot(&self) -> SessionSnapshot {
+        let metadata = &self.data_handler.metadata;
+        let mut snapshot = SessionSnapshot::new(self.server);
+
+        #[cfg(feature = "quote")]
+        if !self.quote_session.read().await.is_empty() {
+            let mut options = self.quote_options.read().await.clone();
+            options.symbols = metadata.quote_symbols.iter().map(|s| *s).collect();
+            options.symbols.sort();
+            snapshot.quote_options = Some(options);
+        }
+        #[cfg(feature = "chart")]
+        {
+            snapshot.series = metadata
+                .series
+                .iter()
+                .filter(|entry| !entry.options.replay_mode)
+                .map(|entry| SeriesSnapshot {
+                    chart_session: entry.chart_session,
+                    series_id: *entry.key(),
+                    options: entry.options,
+                })
+                .collect();
+            snapshot
+                .series
+                .sort_by_key(|series| series_number(&series.series_id));
+        }
+        #[cfg(feature = "pine")]
+        {
+            let mut studies: Vec<StudySnapshot> = metadata
+                .created_studies
+                .iter()
+                .map(|entry| entry.value().clone())
+                .collect();
+            studies.sort_by_key(|study| (series_number(&study.series_id), study.study_id));
+            snapshot.studies = parents_first(studies);
+        }
+        snapshot.depth_symbols = metadata
+            .depth_sessions
+            .iter()
+            .map(|entry| *entry.value())
+            .collect();
+        snapshot
+    }
+
+    /// Subscribe to everything in `snapshot` on this connection. Series and
+    /// studies keep the chart session and ids they had when it was taken.
+    ///
+    /// Parts of the snapshot whose feature is disabled are skipped.
+    pub async fn restore(&self, snapshot: &SessionSnapshot) -> Result<()> {
+        #[cfg(feature = "quote")]
+        if let Some(options) = &snapshot.quote_options {
+            self.create_quote_session_with(options.clone()).await?;
+        }
+        #[cfg(feature = "chart")]
+        for series in &snapshot.series {
+            // New series must not reuse the ids of restored ones
+            if let Some(number) = series_number(&series.series_id) {
+                self.series_count.fetch_max(number, Ordering::SeqCst);
+            }
+            self.open_market(series.chart_session, series.series_id, series.options)
+                .await?;
+        }
+        #[cfg(feature = "pine")]
+        for study in &parents_first(snapshot.studies.clone()) {
+            self.restore_study(study).await?;
+        }
+        for symbol in &snapshot.depth_symbols {
+            self.add_depth(symbol).await?;
+        }
+        info!(
+            "Restored {} series and {} studies from a session snapshot",
+            snapshot.series.len(),
+            snapshot.studies.len()
+        );
+        Ok(())
+    }
+
+    /// Create a quote session configured by `options`, then set its fields and
+    /// add the initial symbols. Later sessions created on reconnect reuse them.
+    #[cfg(feature = "quote")]
+    pub async fn create_quote_session_with(&self, options: QuoteSessionOptions) -> Result<()> {
+        self.data_handler
+            .set_quote_conflation(options.conflation, options.flush_trades);
+        self.data_handler
+            .set_emit_quote_snapshots(options.emit_snapshots);
+        *self.quote_options.write().await = options.clone();
+
+        self.create_quote_session().await?;
+        self.set_fields().await?;
+
+        if !options.symbols.is_empty() {
+            let symbols: Vec<&str> = options.symbols.iter().map(|s| s.as_str()).collect();
+            self.add_symbols(&symbols).await?;
+        }
+        if !options.fast_symbols.is_empty() && !options.mode.is_fast() {
+            let symbols: Vec<&str> = options.fast_symbols.iter().map(|s| s.as_str()).collect();
+            self.fast_symbols(&symbols).await?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "quote")]
     pub async fn create_quote_session(&self) -> Result<()> {
         // Generate a new session ID for the quote session
-        let session_id = gen_session_id("qs");
+        let kind = self.quote_options.read().await.kind;
+        let session_id = gen_session_id(kind.session_prefix());
         self.send("quote_create_session", &payload!(session_id.clone()))
             .await?;
         let mut quote_session = self.quote_session.write().await;
@@ -434,6 +881,43 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Stream the order book of `symbol` through `on_depth_data` on a new
+    /// depth session, whose id is returned. Only symbols with a DOM feed on
+    /// TradingView send any data.
+    pub async fn add_depth(&self, symbol: &str) -> Result<Ustr> {
+        let session = ustr(&gen_session_id("dp"));
+        self.create_depth_session(&session).await?;
+        self.set_depth_symbol(&session, symbol).await?;
+        Ok(session)
+    }
+
+    pub async fn create_depth_session(&self, session: &str) -> Result<()> {
+        self.send("depth_create_session", &payload!(session))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_depth_symbol(&self, session: &str, symbol: &str) -> Result<()> {
+        self.send("depth_set_symbol", &payload!(session, symbol))
+            .await?;
+        self.data_handler
+            .metadata
+            .depth_sessions
+            .insert(ustr(session), ustr(symbol));
+        Ok(())
+    }
+
+    pub async fn delete_depth_session(&self, session: &str) -> Result<()> {
+        self.send("depth_delete_session", &payload!(session))
+            .await?;
+        self.data_handler
+            .metadata
+            .depth_sessions
+            .remove(&ustr(session));
+        Ok(())
+    }
+
+    #[cfg(feature = "quote")]
     pub async fn delete_quote_session(&self) -> Result<()> {
         let quote_session = self.quote_session.read().await;
         self.send("quote_delete_session", &payload!(quote_session.to_string()))
@@ -441,32 +925,45 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "quote")]
     pub async fn set_fields(&self) -> Result<()> {
         let quote_session = self.quote_session.read().await.to_string();
-
-        let mut quote_fields = payload![quote_session];
-        quote_fields.extend(ALL_QUOTE_FIELDS.clone().into_iter().map(Value::from));
-
+        let quote_fields =
+            protocol::quote_fields(&quote_session, &*self.quote_options.read().await);
         self.send("quote_set_fields", &quote_fields).await?;
 
         Ok(())
     }
 
+    #[cfg(feature = "quote")]
     pub async fn add_symbols(&self, symbols: &[&str]) -> Result<()> {
         // Ensure quote session exists first
         self.ensure_quote_session().await?;
 
-        let quote_session = self.quote_session.read().await.to_string();
-
-        let mut payloads = payload![quote_session];
-        payloads.extend(symbols.iter().map(|s| Value::from(*s)));
-
+        let payloads = self.quote_symbols(symbols).await?;
         self.send("quote_add_symbols", &payloads).await?;
+        for symbol in symbols {
+            self.data_handler
+                .metadata
+                .quote_symbols
+                .insert(ustr(symbol));
+        }
+
+        if self.quote_options.read().await.mode.is_fast() {
+            self.fast_symbols(symbols).await?;
+        }
 
         info!("Added {} symbols to quote session", symbols.len());
         Ok(())
     }
 
+    /// Params adding, marking or removing `symbols` on the quote session.
+    #[cfg(feature = "quote")]
+    async fn quote_symbols(&self, symbols: &[&str]) -> Result<Vec<Value>> {
+        let quote_session = self.quote_session.read().await.to_string();
+        protocol::quote_symbols(&quote_session, symbols, &*self.quote_options.read().await)
+    }
+
     pub async fn set_auth_token(&self, auth_token: &str) -> Result<()> {
         let mut auth_token_ = self.auth_token.write().await;
         *auth_token_ = Ustr::from(auth_token);
@@ -474,11 +971,48 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Switch to the auth token of a refreshed session and emit
+    /// [`TradingViewResponse::SessionRefreshed`].
+    ///
+    /// [`TradingViewResponse::SessionRefreshed`]: crate::live::handler::message::TradingViewResponse::SessionRefreshed
+    pub async fn refresh_session(&self, cookies: &UserCookies) -> Result<()> {
+        self.set_auth_token(&cookies.auth_token).await?;
+        *self.user.write().await = Some(cookies.clone());
+        let msg = SessionRefreshedMsg {
+            user_id: cookies.id,
+            username: Ustr::from(&cookies.username),
+        };
+        dispatch(&self.data_handler.handler.on_session_refreshed, msg).await;
+        Ok(())
+    }
+
+    #[doc(hidden)]
     pub async fn send(&self, m: &str, p: &[Value]) -> Result<()> {
         if self.is_closed.load(Ordering::Relaxed) {
             return Err(Error::Internal("WebSocket is closed".into()));
         }
+        self.write_message(m, p).await
+    }
+
+    async fn write_message(&self, m: &str, p: &[Value]) -> Result<()> {
+        let intercepted;
+        let (m, p) = if self.middleware.is_empty() {
+            (m, p)
+        } else {
+            let Some(message) = self.middleware.outbound(m, p) else {
+                debug!("Middleware dropped message: {}", m);
+                return Ok(());
+            };
+            intercepted = message;
+            (intercepted.method.as_str(), intercepted.params.as_slice())
+        };
         debug!("Sending message: {} with payload: {:?}", m, p);
+        if let Some(journal) = &self.journal {
+            journal.record(JournalDirection::Sent, m, p);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.record_message(m, p);
+        }
         let mut write_guard = self.write.lock().await;
         write_guard
             .send(SocketMessageSer::new(m, p).to_message()?)
@@ -488,7 +1022,11 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[doc(hidden)]
     pub async fn ping(&self, ping: &Message) -> Result<()> {
+        if let (Some(recorder), Message::Text(text)) = (&self.recorder, ping) {
+            recorder.record(JournalDirection::Sent, text);
+        }
         let mut write_guard = self.write.lock().await;
         write_guard.send(ping.clone()).await?;
         trace!("sent ping message {}", ping);
@@ -507,36 +1045,33 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[doc(hidden)]
     pub async fn update_auth_token(&self, auth_token: &str) -> Result<()> {
-        let mut write_guard = self.write.lock().await;
-        write_guard
-            .send(SocketMessageSer::new("set_auth_token", payload!(auth_token)).to_message()?)
-            .await?;
-        trace!("updated auth token to: {}", auth_token);
-        drop(write_guard); // Explicitly drop the lock to avoid deadlocks
-        Ok(())
+        self.write_message("set_auth_token", &payload!(auth_token))
+            .await
     }
 
+    #[cfg(feature = "quote")]
     pub async fn fast_symbols(&self, symbols: &[&str]) -> Result<()> {
         self.ensure_quote_session().await?;
 
-        let quote_session = self.quote_session.read().await.to_string();
-
-        let mut payloads = payload![quote_session];
-        payloads.extend(symbols.iter().map(|s| Value::from(*s)));
-
+        let payloads = self.quote_symbols(symbols).await?;
         self.send("quote_fast_symbols", &payloads).await?;
 
         Ok(())
     }
 
+    #[cfg(feature = "quote")]
     pub async fn remove_symbols(&self, symbols: &[&str]) -> Result<()> {
-        let quote_session = self.quote_session.read().await.to_string();
-
-        let mut payloads = payload![quote_session];
-        payloads.extend(symbols.iter().map(|s| Value::from(*s)));
-
+        let payloads = self.quote_symbols(symbols).await?;
         self.send("quote_remove_symbols", &payloads).await?;
+        for symbol in symbols {
+            self.data_handler
+                .metadata
+                .quote_symbols
+                .remove(&ustr(symbol));
+            self.data_handler.metadata.quotes.remove(symbol);
+        }
 
         Ok(())
     }
@@ -556,6 +1091,7 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn set_timezone(&self, session: &str, timezone: Timezone) -> Result<()> {
         self.send("switch_timezone", &payload!(session, timezone.to_string()))
             .await?;
@@ -563,18 +1099,21 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn create_chart_session(&self, session: &str) -> Result<()> {
         self.send("chart_create_session", &payload!(session))
             .await?;
         Ok(())
     }
 
+    #[cfg(feature = "replay")]
     pub async fn create_replay_session(&self, session: &str) -> Result<()> {
         self.send("replay_create_session", &payload!(session))
             .await?;
         Ok(())
     }
 
+    #[cfg(feature = "replay")]
     pub async fn add_replay_series(
         &self,
         session: &str,
@@ -587,13 +1126,7 @@ impl WebSocketClient {
             &payload!(
                 session,
                 series_id,
-                symbol_init(
-                    symbol,
-                    config.adjustment,
-                    config.currency,
-                    config.session_type,
-                    None
-                )?,
+                symbol_init(symbol, &config, None)?,
                 config.interval.to_string()
             ),
         )
@@ -601,24 +1134,55 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn delete_chart_session(&self, session: &str) -> Result<()> {
         self.send("chart_delete_session", &payload!(session))
             .await?;
+        let metadata = &self.data_handler.metadata;
+        metadata.series.retain(|id, info| {
+            let keep = info.chart_session.as_str() != session;
+            if !keep {
+                metadata.backfill.remove(id);
+                self.data_handler.forget_bars(id);
+            }
+            keep
+        });
+        #[cfg(feature = "pine")]
+        metadata
+            .created_studies
+            .retain(|(chart_session, _), _| chart_session.as_str() != session);
+        self.clear_chart_state(session).await;
         Ok(())
     }
 
+    /// Forget the last chart payload if it belongs to `session`.
+    #[cfg(feature = "chart")]
+    async fn clear_chart_state(&self, session: &str) {
+        let mut chart_state = self.data_handler.metadata.chart_state.write().await;
+        if chart_state
+            .chart
+            .as_ref()
+            .is_some_and(|(info, _)| info.chart_session.as_str() == session)
+        {
+            chart_state.chart = None;
+        }
+    }
+
+    #[cfg(feature = "replay")]
     pub async fn delete_replay_session(&self, session: &str) -> Result<()> {
         self.send("replay_delete_session", &payload!(session))
             .await?;
         Ok(())
     }
 
+    #[cfg(feature = "replay")]
     pub async fn replay_step(&self, session: &str, series_id: &str, step: u64) -> Result<()> {
         self.send("replay_step", &payload!(session, series_id, step))
             .await?;
         Ok(())
     }
 
+    #[cfg(feature = "replay")]
     pub async fn replay_start(
         &self,
         session: &str,
@@ -633,24 +1197,50 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "replay")]
     pub async fn replay_stop(&self, session: &str, series_id: &str) -> Result<()> {
         self.send("replay_stop", &payload!(session, series_id))
             .await?;
         Ok(())
     }
 
+    /// Start autoplay, advancing one bar every `delay`.
+    #[cfg(feature = "replay")]
+    pub async fn replay_autoplay(
+        &self,
+        session: &str,
+        request_id: &str,
+        delay: Duration,
+    ) -> Result<()> {
+        let delay_ms = delay.as_millis().min(u64::MAX as u128) as u64;
+        self.send("replay_start", &payload!(session, request_id, delay_ms))
+            .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "replay")]
     pub async fn replay_reset(&self, session: &str, series_id: &str, timestamp: i64) -> Result<()> {
         self.send("replay_reset", &payload!(session, series_id, timestamp))
             .await?;
         Ok(())
     }
 
+    /// Request `num` bars older than the ones already loaded for `series_id`.
+    ///
+    /// They arrive through `on_chart_data` with [`SeriesInfo::backfill`] set,
+    /// followed by `on_series_completed`.
+    #[cfg(feature = "chart")]
     pub async fn request_more_data(&self, session: &str, series_id: &str, num: u64) -> Result<()> {
+        self.data_handler
+            .metadata
+            .backfill
+            .insert(Ustr::from(series_id));
         self.send("request_more_data", &payload!(session, series_id, num))
             .await?;
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn request_more_tickmarks(
         &self,
         session: &str,
@@ -662,6 +1252,9 @@ impl WebSocketClient {
         Ok(())
     }
 
+    /// Add `indicator` to the series as `study_id`. The study is part of
+    /// [`WebSocketClient::snapshot`] and recreated after a reconnect.
+    #[cfg(feature = "pine")]
     pub async fn create_study(
         &self,
         session: &str,
@@ -669,12 +1262,102 @@ impl WebSocketClient {
         series_id: &str,
         indicator: PineIndicator,
     ) -> Result<()> {
-        let inputs = indicator.to_study_inputs()?;
+        self.create_study_with(session, study_id, series_id, indicator, &HashMap::new())
+            .await
+    }
+
+    /// Like [`WebSocketClient::create_study`], with `inputs` overriding the
+    /// indicator's default values.
+    ///
+    /// Any number of studies can share a series, each under its own
+    /// `study_id`, which [`StudyResponseData::study_id`] carries back. Passing
+    /// another study's id as `parent_id` and feeding a source input from one
+    /// of its plots, see [`StudyInputs::source_study`], computes the study on
+    /// that study's output.
+    ///
+    /// [`StudyResponseData::study_id`]: crate::StudyResponseData::study_id
+    /// [`StudyInputs::source_study`]: crate::pine_indicator::StudyInputs::source_study
+    #[cfg(feature = "pine")]
+    pub async fn create_study_with(
+        &self,
+        session: &str,
+        study_id: &str,
+        parent_id: &str,
+        indicator: PineIndicator,
+        inputs: &HashMap<Ustr, Value>,
+    ) -> Result<()> {
+        let study = StudySnapshot {
+            chart_session: Ustr::from(session),
+            series_id: Ustr::from(parent_id),
+            study_id: Ustr::from(study_id),
+            script_id: indicator.script_id,
+            script_version: indicator.script_version,
+            script_type: indicator.script_type,
+            inputs: inputs.clone(),
+            volume_profile: None,
+        };
+        self.send_create_study(session, study_id, parent_id, indicator, inputs)
+            .await?;
+        self.data_handler
+            .metadata
+            .created_studies
+            .insert((study.chart_session, study.study_id), study);
+        Ok(())
+    }
+
+    /// Add a volume-by-price study as `study_id` to the series. Its updates
+    /// come through `on_study_data`, parse them with
+    /// [`StudyResponseData::volume_profiles`](crate::StudyResponseData::volume_profiles).
+    #[cfg(feature = "pine")]
+    pub async fn add_volume_profile(
+        &self,
+        session: &str,
+        study_id: &str,
+        series_id: &str,
+        options: VolumeProfileOptions,
+    ) -> Result<()> {
+        let script_type = options.script_type();
         let payloads: Vec<Value> = vec![
             Value::from(session),
             Value::from(study_id),
             Value::from("st1"),
             Value::from(series_id),
+            Value::from(script_type.to_string()),
+            options.inputs(),
+        ];
+        self.send("create_study", &payloads).await?;
+        let study = StudySnapshot {
+            chart_session: Ustr::from(session),
+            series_id: Ustr::from(series_id),
+            study_id: Ustr::from(study_id),
+            script_id: Ustr::default(),
+            script_version: Ustr::default(),
+            script_type,
+            inputs: HashMap::new(),
+            volume_profile: Some(options),
+        };
+        self.data_handler
+            .metadata
+            .created_studies
+            .insert((study.chart_session, study.study_id), study);
+        Ok(())
+    }
+
+    #[cfg(feature = "pine")]
+    async fn send_create_study(
+        &self,
+        session: &str,
+        study_id: &str,
+        parent_id: &str,
+        indicator: PineIndicator,
+        inputs: &HashMap<Ustr, Value>,
+    ) -> Result<()> {
+        let inputs = indicator.to_study_inputs_with(inputs)?;
+        let payloads: Vec<Value> = vec![
+            Value::from(session),
+            Value::from(study_id),
+            Value::from("st1"),
+            Value::from(parent_id),
             Value::from(indicator.script_type.to_string()),
             inputs,
         ];
@@ -682,6 +1365,7 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "pine")]
     pub async fn modify_study(
         &self,
         session: &str,
@@ -689,24 +1373,73 @@ impl WebSocketClient {
         series_id: &str,
         indicator: PineIndicator,
     ) -> Result<()> {
-        let inputs = indicator.to_study_inputs()?;
+        self.modify_study_with(session, study_id, series_id, indicator, &HashMap::new())
+            .await
+    }
+
+    /// Send `modify_study` with `inputs` overriding the indicator's default
+    /// values, see [`PineIndicator::to_study_inputs_with`].
+    #[cfg(feature = "pine")]
+    pub async fn modify_study_with(
+        &self,
+        session: &str,
+        study_id: &str,
+        series_id: &str,
+        indicator: PineIndicator,
+        inputs: &HashMap<Ustr, Value>,
+    ) -> Result<()> {
+        let study_inputs = indicator.to_study_inputs_with(inputs)?;
         let payloads: Vec<Value> = vec![
             Value::from(session),
             Value::from(study_id),
             Value::from("st1"),
             Value::from(series_id),
             Value::from(indicator.script_type.to_string()),
-            inputs,
+            study_inputs,
         ];
         self.send("modify_study", &payloads).await?;
+        let key = (Ustr::from(session), Ustr::from(study_id));
+        if let Some(mut study) = self.data_handler.metadata.created_studies.get_mut(&key) {
+            study.inputs = inputs.clone();
+        }
         Ok(())
     }
+    #[cfg(feature = "pine")]
     pub async fn remove_study(&self, session: &str, study_id: &str) -> Result<()> {
         self.send("remove_study", &payload!(session, study_id))
             .await?;
+        let metadata = &self.data_handler.metadata;
+        // Keep the series' own study from being recreated on reconnect
+        for mut entry in metadata.series.iter_mut() {
+            if entry.chart_session.as_str() == session
+                && let Some(study) = entry.options.study_config
+                && metadata
+                    .studies
+                    .get(&study.script_id)
+                    .is_some_and(|id| id.as_str() == study_id)
+            {
+                entry.options.study_config = None;
+            }
+        }
+        metadata.studies.retain(|_, id| id.as_str() != study_id);
+        // TradingView drops the studies computed on it along with it
+        let mut removed = vec![Ustr::from(study_id)];
+        while let Some(id) = removed.pop() {
+            metadata
+                .created_studies
+                .retain(|(chart_session, _), study| {
+                    let gone = chart_session.as_str() == session
+                        && (study.study_id == id || study.series_id == id);
+                    if gone && study.study_id != id {
+                        removed.push(study.study_id);
+                    }
+                    !gone
+                });
+        }
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn create_series(
         &self,
         session: &str,
@@ -715,29 +1448,19 @@ impl WebSocketClient {
         series_symbol_id: &str,
         config: ChartOptions,
     ) -> Result<()> {
-        let range = match (&config.range, config.from, config.to) {
-            (Some(range), _, _) => range.to_string(),
-            (None, Some(from), Some(to)) => format!("r,{from}:{to}"),
-            _ => Default::default(),
-        };
-
-        self.send(
-            "create_series",
-            &payload!(
-                session,
-                series_id,
-                series_version,
-                series_symbol_id,
-                config.interval.to_string(),
-                config.bar_count,
-                range // |r,1626220800:1628640000|1D|5d|1M|3M|6M|YTD|12M|60M|ALL|
-            ),
-        )
-        .await?;
+        let params = protocol::series_params(
+            session,
+            series_id,
+            series_version,
+            series_symbol_id,
+            &config,
+        );
+        self.send("create_series", &params).await?;
 
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn modify_series(
         &self,
         session: &str,
@@ -746,35 +1469,79 @@ impl WebSocketClient {
         series_symbol_id: &str,
         config: ChartOptions,
     ) -> Result<()> {
-        let range = match (&config.range, config.from, config.to) {
-            (Some(range), _, _) => range.to_string(),
-            (None, Some(from), Some(to)) => format!("r,{from}:{to}"),
-            _ => Default::default(),
+        let params = protocol::series_params(
+            session,
+            series_id,
+            series_version,
+            series_symbol_id,
+            &config,
+        );
+        self.send("modify_series", &params).await?;
+        // The forming bar belongs to the old interval or range
+        self.data_handler.forget_bars(&Ustr::from(series_id));
+
+        Ok(())
+    }
+
+    /// Switch `series_id` to `interval` in place, keeping the studies attached
+    /// to it. `bar_count` replaces the number of bars loaded if given.
+    ///
+    /// The new resolution is kept for reconnects and snapshots.
+    #[cfg(feature = "chart")]
+    pub async fn set_series_interval(
+        &self,
+        series_id: &str,
+        interval: Interval,
+        bar_count: Option<u64>,
+    ) -> Result<()> {
+        let series_id = Ustr::from(series_id);
+        let Some(series) = self
+            .data_handler
+            .metadata
+            .series
+            .get(&series_id)
+            .map(|series| series.clone())
+        else {
+            return Err(Error::Internal(ustr(&format!(
+                "Unknown series: {series_id}"
+            ))));
         };
+        let (chart_session, mut options) = (series.chart_session, series.options);
+        options.interval = interval;
+        if let Some(bar_count) = bar_count {
+            options.bar_count = bar_count;
+        }
 
-        self.send(
-            "modify_series",
-            &payload!(
-                session,
-                series_id,
-                series_version,
-                series_symbol_id,
-                config.interval.to_string(),
-                config.bar_count,
-                range // |r,1626220800:1628640000|1D|5d|1M|3M|6M|YTD|12M|60M|ALL|
-            ),
+        self.modify_series(
+            &chart_session,
+            &series_id,
+            &series.series_version,
+            &series.symbol_series_id,
+            options,
         )
         .await?;
 
+        if let Some(mut series) = self.data_handler.metadata.series.get_mut(&series_id) {
+            series.options = options;
+        }
+        // Bars of the old resolution must not be mixed with the new ones
+        self.clear_chart_state(&chart_session).await;
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn remove_series(&self, session: &str, series_id: &str) -> Result<()> {
         self.send("remove_series", &payload!(session, series_id))
             .await?;
+        let series_id = Ustr::from(series_id);
+        self.data_handler.metadata.series.remove(&series_id);
+        self.data_handler.metadata.backfill.remove(&series_id);
+        self.data_handler.forget_bars(&series_id);
+        self.clear_chart_state(session).await;
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn resolve_symbol(
         &self,
         session: &str,
@@ -783,55 +1550,47 @@ impl WebSocketClient {
         config: ChartOptions,
         replay_session: Option<&str>,
     ) -> Result<()> {
-        self.send(
-            "resolve_symbol",
-            &payload!(
-                session,
-                symbol_series_id,
-                symbol_init(
-                    symbol,
-                    config.adjustment,
-                    config.currency,
-                    config.session_type,
-                    replay_session
-                )?
-            ),
-        )
-        .await?;
+        let params =
+            protocol::resolve_symbol(session, symbol_series_id, symbol, &config, replay_session)?;
+        self.send("resolve_symbol", &params).await?;
         Ok(())
     }
 
     pub async fn delete(&self) -> Result<()> {
-        // Collect all sessions first to avoid holding iterator while making async calls
-        let chart_sessions: Vec<Ustr> = self
-            .data_handler
-            .metadata
-            .series
-            .iter()
-            .map(|entry| entry.value().chart_session)
-            .collect();
-
         // Delete quote session first
+        #[cfg(feature = "quote")]
         if let Err(e) = self.delete_quote_session().await {
             warn!("Failed to delete quote session: {:?}", e);
         }
 
-        // Delete all chart sessions in parallel for better performance
-        let delete_futures: Vec<_> = chart_sessions
-            .iter()
-            .map(|session| self.delete_chart_session(session))
-            .collect();
-
-        // Execute all deletions concurrently
-        let results = join_all(delete_futures).await;
-
-        // Log any errors but don't fail the entire operation
-        for (i, result) in results.into_iter().enumerate() {
-            if let Err(e) = result {
-                warn!(
-                    "Failed to delete chart session {}: {:?}",
-                    chart_sessions[i], e
-                );
+        #[cfg(feature = "chart")]
+        {
+            // Collect all sessions first to avoid holding iterator while making async calls
+            let chart_sessions: Vec<Ustr> = self
+                .data_handler
+                .metadata
+                .series
+                .iter()
+                .map(|entry| entry.value().chart_session)
+                .collect();
+
+            // Delete all chart sessions in parallel for better performance
+            let delete_futures: Vec<_> = chart_sessions
+                .iter()
+                .map(|session| self.delete_chart_session(session))
+                .collect();
+
+            // Execute all deletions concurrently
+            let results = join_all(delete_futures).await;
+
+            // Log any errors but don't fail the entire operation
+            for (i, result) in results.into_iter().enumerate() {
+                if let Err(e) = result {
+                    warn!(
+                        "Failed to delete chart session {}: {:?}",
+                        chart_sessions[i], e
+                    );
+                }
             }
         }
 
@@ -839,6 +1598,8 @@ impl WebSocketClient {
         self.data_handler.metadata.series.clear();
         self.data_handler.metadata.studies.clear();
         self.data_handler.metadata.quotes.clear();
+        self.data_handler.metadata.quote_symbols.clear();
+        self.data_handler.metadata.backfill.clear();
 
         // Reset counters
         self.series_count.store(0, Ordering::SeqCst);
@@ -856,6 +1617,7 @@ impl WebSocketClient {
 
     // End TradingView WebSocket methods
 
+    #[cfg(feature = "replay")]
     pub async fn set_replay(
         &self,
         symbol: &str,
@@ -883,6 +1645,7 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "pine")]
     pub async fn set_study(
         &self,
         study: StudyOptions,
@@ -893,33 +1656,107 @@ impl WebSocketClient {
 
         let study_id = Ustr::from(&format!("st{study_count}"));
 
-        let indicator = PineIndicator::build()
-            .fetch(&study.script_id, &study.script_version, study.script_type)
+        let indicator = self
+            .fetch_indicator(&study.script_id, &study.script_version, study.script_type)
             .await?;
 
-        let key = Ustr::from(&indicator.metadata.data.id);
-
-        self.data_handler.metadata.studies.insert(key, study_id);
+        self.data_handler
+            .metadata
+            .studies
+            .insert(study.script_id, study_id);
 
-        self.create_study(chart_session, &study_id, series_id, indicator)
-            .await?;
+        self.send_create_study(
+            chart_session,
+            &study_id,
+            series_id,
+            indicator,
+            &HashMap::new(),
+        )
+        .await?;
         Ok(())
     }
 
+    #[cfg(feature = "chart")]
     pub async fn set_market(&self, options: ChartOptions) -> Result<()> {
+        self.add_market(options).await?;
+        Ok(())
+    }
+
+    /// Like [`WebSocketClient::set_market`], but returns the chart session
+    /// and series id the market was created under.
+    #[cfg(feature = "chart")]
+    pub async fn add_market(&self, options: ChartOptions) -> Result<(Ustr, Ustr)> {
         let series_count = self.series_count.fetch_add(1, Ordering::SeqCst) + 1;
-        let symbol_series_id = format!("sds_sym_{series_count}");
         let series_id = Ustr::from(&format!("sds_{series_count}"));
-        let series_version = format!("s{series_count}");
         let chart_session = Ustr::from(&gen_session_id("cs"));
+        self.open_market(chart_session, series_id, options).await?;
+        Ok((chart_session, series_id))
+    }
+
+    #[cfg(feature = "chart")]
+    async fn open_market(
+        &self,
+        chart_session: Ustr,
+        series_id: Ustr,
+        options: ChartOptions,
+    ) -> Result<()> {
         let symbol = format!("{}:{}", options.exchange, options.symbol);
+
+        // Registered up front so the resolved symbol can be matched to its options
+        let (symbol_series_id, series_version) = protocol::series_ids(&series_id);
+        let series_info = SeriesInfo {
+            chart_session,
+            options,
+            symbol_series_id: Ustr::from(&symbol_series_id),
+            series_version: Ustr::from(&series_version),
+            backfill: false,
+        };
+        self.data_handler
+            .metadata
+            .series
+            .insert(series_id, series_info);
+
+        if let Err(e) = self
+            .init_market(chart_session, series_id, &symbol, options)
+            .await
+        {
+            self.data_handler.metadata.series.remove(&series_id);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "chart")]
+    async fn init_market(
+        &self,
+        chart_session: Ustr,
+        series_id: Ustr,
+        symbol: &str,
+        options: ChartOptions,
+    ) -> Result<()> {
+        let (symbol_series_id, series_version) = protocol::series_ids(&series_id);
         self.create_chart_session(&chart_session).await?;
 
-        if options.replay_mode {
-            self.set_replay(&symbol, options, &chart_session, &symbol_series_id)
+        if let (true, Some(replay_session)) = (options.replay_mode, options.replay_session) {
+            // The replay session was set up by the caller, e.g. a `ReplaySession`
+            self.resolve_symbol(
+                &chart_session,
+                &symbol_series_id,
+                symbol,
+                options,
+                Some(&replay_session),
+            )
+            .await?;
+        } else if options.replay_mode {
+            #[cfg(feature = "replay")]
+            self.set_replay(symbol, options, &chart_session, &symbol_series_id)
                 .await?;
+            #[cfg(not(feature = "replay"))]
+            return Err(Error::Config(ustr(
+                "Replay mode needs the `replay` feature",
+            )));
         } else {
-            self.resolve_symbol(&chart_session, &symbol_series_id, &symbol, options, None)
+            self.resolve_symbol(&chart_session, &symbol_series_id, symbol, options, None)
                 .await?;
         }
 
@@ -932,23 +1769,14 @@ impl WebSocketClient {
         )
         .await?;
 
+        #[cfg(feature = "pine")]
         if let Some(study) = options.study_config {
             self.set_study(study, &chart_session, &series_id).await?;
         }
-
-        let series_info = SeriesInfo {
-            chart_session,
-            options,
-        };
-
-        self.data_handler
-            .metadata
-            .series
-            .insert(series_id, series_info);
-
         Ok(())
     }
 
+    #[doc(hidden)]
     pub async fn subscribe(&self) -> Result<()> {
         let read = self.read.lock().await;
         if let Err(e) = self.event_loop(read).await {
@@ -964,6 +1792,98 @@ impl WebSocketClient {
         self.closed.cancelled().await;
     }
 
+    /// Token that stops the reader loop and prevents reconnects once cancelled.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Poll the headlines of `symbols` every `interval` and dispatch each new
+    /// item to the `on_news` callback, as [`TradingViewResponse::News`] on the data channel.
+    ///
+    /// Headlines already published when a symbol is first polled successfully
+    /// are skipped, fetch them with [`crate::client::news::get_news`]. The task
+    /// stops with the client.
+    ///
+    /// [`TradingViewResponse::News`]: crate::live::handler::message::TradingViewResponse::News
+    pub fn stream_news(&self, symbols: &[&str], interval: Duration) -> JoinHandle<()> {
+        let handler = self.data_handler.handler.clone();
+        let cancel = self.cancel.child_token();
+        let mut feed = FeedPoller::new(
+            symbols,
+            |news: &News| news.id.clone(),
+            |symbol| async move { get_news().symbol(&symbol).call().await }.boxed(),
+        );
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                select! {
+                    _ = cancel.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+
+                let mut fresh = feed.poll().await;
+                // oldest first, so callbacks see headlines in publication order
+                fresh.sort_by_key(|news| news.published);
+                for news in fresh {
+                    dispatch(&handler.on_news, news).await;
+                }
+            }
+            debug!("news stream stopped");
+        })
+    }
+
+    /// Poll the most recent ideas and Minds posts of `symbols` every `interval`
+    /// and dispatch each new one to the `on_social_post` callback, as
+    /// [`TradingViewResponse::SocialPost`] on the data channel.
+    ///
+    /// Posts already published when a symbol is first polled successfully are
+    /// skipped, fetch them with [`crate::client::social`]. The task stops with
+    /// the client.
+    ///
+    /// [`TradingViewResponse::SocialPost`]: crate::live::handler::message::TradingViewResponse::SocialPost
+    pub fn stream_social(&self, symbols: &[&str], interval: Duration) -> JoinHandle<()> {
+        let handler = self.data_handler.handler.clone();
+        let cancel = self.cancel.child_token();
+        let mut feed = FeedPoller::new(symbols, SocialPost::key, |symbol| {
+            async move {
+                let (ideas, minds) = tokio::try_join!(
+                    get_ideas().symbol(&symbol).call(),
+                    get_minds().symbol(&symbol).call(),
+                )?;
+                Ok(ideas
+                    .items
+                    .into_iter()
+                    .map(SocialPost::Idea)
+                    .chain(minds.posts.into_iter().map(SocialPost::Minds))
+                    .collect())
+            }
+            .boxed()
+        });
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                select! {
+                    _ = cancel.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+
+                let mut fresh = feed.poll().await;
+                // oldest first, so callbacks see posts in publication order
+                fresh.sort_by_key(SocialPost::published);
+                for post in fresh {
+                    dispatch(&handler.on_social_post, post).await;
+                }
+            }
+            debug!("social stream stopped");
+        })
+    }
+
+    pub fn journal(&self) -> Option<&Arc<EventJournal>> {
+        self.journal.as_ref()
+    }
+
     /// Fire-and-forget ping. Ignores `WouldBlock` when write buffer is full.
     pub async fn try_ping(&self) -> Result<()> {
         if self.is_closed().await {
@@ -975,6 +1895,7 @@ impl WebSocketClient {
         Ok(())
     }
 
+    #[cfg(feature = "quote")]
     pub async fn ensure_quote_session(&self) -> Result<()> {
         let quote_session = self.quote_session.read().await;
         if quote_session.is_empty() {
@@ -988,23 +1909,32 @@ impl WebSocketClient {
     }
 }
 
+/// Number of a `sds_{n}` series id.
+#[cfg(feature = "chart")]
+fn series_number(series_id: &str) -> Option<u16> {
+    series_id.strip_prefix("sds_")?.parse().ok()
+}
+
 impl Socket for WebSocketClient {
-    async fn event_loop(
-        &self,
-        mut read: MutexGuard<'_, SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
-    ) -> Result<()> {
+    async fn event_loop(&self, mut read: MutexGuard<'_, FrameStream>) -> Result<()> {
         info!("WebSocket event loop started");
 
+        // A socket closed by us is still read until the server answers the
+        // close frame, so the frames it sent before are not lost
         loop {
-            if self.is_closed.load(Ordering::Relaxed) {
-                info!("WebSocket is closed, ending event loop");
-                break;
-            }
-
             trace!("waiting for next message");
-            match timeout(Duration::from_secs(30), read.next()).await {
+            let next = select! {
+                _ = self.cancel.cancelled() => {
+                    info!("WebSocket cancelled, ending event loop");
+                    self.is_closed.store(true, Ordering::Relaxed);
+                    break;
+                }
+                next = timeout(self.keepalive.ping_interval(), read.next()) => next,
+            };
+            match next {
                 Ok(Some(Ok(message))) => {
                     trace!("Received message: {:?}", message);
+                    *self.last_frame.write().await = Instant::now();
                     if let Err(e) = self.handle_raw_messages(message).await {
                         warn!("Error handling message: {}", e);
                         self.handle_error(e, ustr("handle_raw_messages")).await?;
@@ -1016,15 +1946,26 @@ impl Socket for WebSocketClient {
                         }
                     }
                 }
+                // The server dropped the socket instead of answering our close frame
+                Ok(Some(Err(e))) if self.is_closed.load(Ordering::Relaxed) => {
+                    debug!("WebSocket closed without closing handshake: {}", e);
+                    break;
+                }
                 Ok(Some(Err(e))) => {
                     error!("Error reading message: {:#?}", e);
                     self.is_closed.store(true, Ordering::Relaxed);
+                    // A reconnect swaps the read half, which needs the lock
+                    drop(read);
                     self.handle_error(
                         Error::WebSocket(e.to_string().into()),
                         ustr("event_loop_read"),
                     )
                     .await?;
-                    return Err(Error::Internal(ustr(&e.to_string())));
+                    if self.is_closed.load(Ordering::Relaxed) {
+                        return Err(Error::Internal(ustr(&e.to_string())));
+                    }
+                    // Reconnected, keep reading the new socket
+                    read = self.read.lock().await;
                 }
                 Ok(None) => {
                     info!("WebSocket stream ended");
@@ -1036,6 +1977,20 @@ impl Socket for WebSocketClient {
                     if self.is_closed.load(Ordering::Relaxed) {
                         break;
                     }
+                    let silence = self.last_frame.read().await.elapsed();
+                    if silence >= self.keepalive.max_silence() {
+                        warn!("No frames received for {:?}, connection is stale", silence);
+                        dispatch(
+                            &self.data_handler.handler.on_connection_stale,
+                            ConnectionStaleMsg { silence },
+                        )
+                        .await;
+                        if self.keepalive.reconnect_on_stale {
+                            // The command runner health check picks this up and reconnects
+                            self.is_closed.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
                     // Send a ping to check if connection is still alive
                     if let Err(e) = self.try_ping().await {
                         warn!("Ping failed during timeout: {}", e);
@@ -1049,12 +2004,31 @@ impl Socket for WebSocketClient {
         Ok(())
     }
 
+    #[tracing::instrument(name = "frame", level = "debug", skip_all, fields(bytes = raw.len()))]
     async fn handle_raw_messages(&self, raw: Message) -> Result<()> {
         match &raw {
             Message::Text(text) => {
                 trace!("Received text message: {}", text);
-                self.handle_parsed_messages(parse_packet(text), &raw)
-                    .await?;
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(JournalDirection::Received, text);
+                }
+                let Some(text) = self.middleware.inbound(text) else {
+                    trace!("Middleware dropped frame");
+                    return Ok(());
+                };
+                let mut messages =
+                    std::mem::take(&mut *self.parse_buf.lock().unwrap_or_else(|e| e.into_inner()));
+                // Journaled frames keep their raw payload, so only skip `Value` without a journal
+                parse_packet_into(
+                    &text,
+                    self.journal.is_none(),
+                    self.data_handler.event_mask(),
+                    &mut messages,
+                );
+                let result = self.handle_parsed_messages(&mut messages).await;
+                messages.clear();
+                *self.parse_buf.lock().unwrap_or_else(|e| e.into_inner()) = messages;
+                result?;
             }
             Message::Close(msg) => {
                 warn!("Connection closed with code: {:?}", msg);
@@ -1084,58 +2058,21 @@ impl Socket for WebSocketClient {
 
     async fn handle_parsed_messages(
         &self,
-        messages: Vec<SocketMessage<SocketMessageDe>>,
-        raw: &Message,
+        messages: &mut Vec<SocketMessage<SocketMessageDe>>,
     ) -> Result<()> {
-        for message in messages {
-            match message {
-                SocketMessage::SocketServerInfo(info) => {
-                    trace!("received server info: {:?}", info);
-                }
-                SocketMessage::SocketMessage(msg) => {
-                    trace!(
-                        "Processing socket message: method={}, params={:?}",
-                        msg.m, msg.p
-                    );
-                    if let Err(e) = self.handle_message_data(msg).await {
-                        self.handle_error(e, ustr("handle_message_data")).await?;
-                    }
-                }
-                SocketMessage::Other(value) => {
-                    trace!("Received other message: {:?}", value);
-                    if value.is_number() {
-                        debug!("handling heartbeat message: {:?}", value);
-                        if let Err(e) = self.ping(raw).await {
-                            self.handle_error(e, ustr("ping_response")).await?;
-                        }
-                    } else if value.is_string() {
-                        trace!("Received string message: {:?}", value);
-                    } else if let Ok(server_info) = SocketServerInfo::deserialize(&value) {
-                        info!("{}", server_info);
-                    } else {
-                        warn!("Received unrecognized message: {:?}", value);
-                    }
-                }
-                SocketMessage::Unknown(s) => {
-                    warn!("unknown message: {:?}", s);
-                }
+        for message in messages.drain(..) {
+            if let (Some(journal), SocketMessage::SocketMessage(msg)) = (&self.journal, &message) {
+                journal.record(JournalDirection::Received, &msg.m, &msg.p);
+            }
+            if let Some(heartbeat) = protocol::dispatch(&self.data_handler, message).await
+                && let Err(e) = self.ping(&Message::Text(heartbeat.into())).await
+            {
+                self.handle_error(e, ustr("ping_response")).await?;
             }
         }
         Ok(())
     }
 
-    async fn handle_message_data(&self, message: SocketMessageDe) -> Result<()> {
-        debug!(
-            "Handling message: method={}, params_count={}",
-            message.m,
-            message.p.len()
-        );
-        let event = TradingViewDataEvent::from(message.m.to_owned());
-        debug!("Mapped to event: {:?}", event);
-        self.data_handler.handle_events(event, &message.p).await;
-        Ok(())
-    }
-
     async fn handle_error(&self, error: Error, context: Ustr) -> Result<()> {
         let context_str = context.as_str();
 
@@ -1211,3 +2148,68 @@ impl Socket for WebSocketClient {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "pine"))]
+mod tests {
+    use super::*;
+    use crate::live::mock::{MockScript, MockServer};
+
+    #[tokio::test]
+    async fn test_remove_study_keeps_series_study() {
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(tx)
+            .endpoint(server.url())
+            .build()
+            .await
+            .unwrap();
+
+        // A series with its own RSI as `st1` and a created MACD as `st2`
+        let session = Ustr::from("cs_1");
+        let rsi = StudyOptions {
+            script_id: Ustr::from("STD;RSI"),
+            ..Default::default()
+        };
+        let mut options = ChartOptions::new_with("AAPL", "NASDAQ", Interval::OneDay);
+        options.study_config = Some(rsi);
+        let metadata = &ws.data_handler.metadata;
+        metadata.series.insert(
+            Ustr::from("sds_1"),
+            SeriesInfo {
+                chart_session: session,
+                options,
+                symbol_series_id: Ustr::from("sds_sym_1"),
+                series_version: Ustr::from("s1"),
+                backfill: false,
+            },
+        );
+        metadata.studies.insert(rsi.script_id, Ustr::from("st1"));
+        metadata.created_studies.insert(
+            (session, Ustr::from("st2")),
+            StudySnapshot {
+                chart_session: session,
+                series_id: Ustr::from("sds_1"),
+                study_id: Ustr::from("st2"),
+                script_id: Ustr::from("STD;MACD"),
+                script_version: Ustr::from("1"),
+                script_type: Default::default(),
+                inputs: Default::default(),
+                volume_profile: None,
+            },
+        );
+
+        ws.remove_study("cs_1", "st2").await.unwrap();
+        let snapshot = ws.snapshot().await;
+        assert!(snapshot.studies.is_empty());
+        assert_eq!(
+            snapshot.series[0].options.study_config.map(|s| s.script_id),
+            Some(rsi.script_id)
+        );
+
+        ws.remove_study("cs_1", "st1").await.unwrap();
+        let snapshot = ws.snapshot().await;
+        assert!(snapshot.series[0].options.study_config.is_none());
+        ws.close().await.unwrap();
+    }
+}
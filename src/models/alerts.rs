@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Comparison evaluated by a price alert.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertConditionType {
+    #[default]
+    Cross,
+    CrossUp,
+    CrossDown,
+    Greater,
+    Less,
+    EnterChannel,
+    ExitChannel,
+    InsideChannel,
+    OutsideChannel,
+    MovingUp,
+    MovingDown,
+    MovingUpPercent,
+    MovingDownPercent,
+    /// Fires on an `alertcondition()`/`alert()` call of a Pine script.
+    #[serde(other)]
+    Other,
+}
+
+/// How often an alert may fire once its condition is met.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertFrequency {
+    #[default]
+    OnFirstFire,
+    OncePerBar,
+    OncePerBarClose,
+    OncePerMinute,
+}
+
+/// One operand of an alert condition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertSeries {
+    /// Bars of the alert's own symbol.
+    Barset,
+    /// Fixed price level.
+    Value { value: f64 },
+    /// Plot of an indicator on the chart, kept as sent by TradingView.
+    #[serde(untagged)]
+    Study(Map<String, Value>),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertCondition {
+    #[serde(rename = "type")]
+    pub condition_type: AlertConditionType,
+    pub frequency: AlertFrequency,
+    pub series: Vec<AlertSeries>,
+    /// Fields of indicator conditions this crate does not model.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl AlertCondition {
+    /// Price of the symbol compared against a fixed level.
+    pub fn price(condition_type: AlertConditionType, level: f64) -> Self {
+        Self {
+            condition_type,
+            series: vec![AlertSeries::Barset, AlertSeries::Value { value: level }],
+            ..Default::default()
+        }
+    }
+
+    /// Price of the symbol compared against a `[lower, upper]` channel.
+    pub fn channel(condition_type: AlertConditionType, lower: f64, upper: f64) -> Self {
+        Self {
+            condition_type,
+            series: vec![
+                AlertSeries::Barset,
+                AlertSeries::Value { value: upper },
+                AlertSeries::Value { value: lower },
+            ],
+            ..Default::default()
+        }
+    }
+
+    pub fn with_frequency(mut self, frequency: AlertFrequency) -> Self {
+        self.frequency = frequency;
+        self
+    }
+}
+
+/// An alert configured on the account.
+///
+/// Unknown fields are kept in `extra` so an alert fetched with
+/// [`crate::client::alerts::list_alerts`] can be modified and sent back without losing data.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Alert {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert_id: Option<i64>,
+    /// Symbol init string, e.g. `={"symbol":"BINANCE:BTCUSDT"}`.
+    pub symbol: String,
+    pub resolution: String,
+    pub condition: AlertCondition,
+    pub name: Option<String>,
+    pub message: String,
+    pub active: bool,
+    pub expiration: Option<String>,
+    pub auto_deactivate: bool,
+    pub email: bool,
+    pub sms_over_email: bool,
+    pub mobile_push: bool,
+    pub popup: bool,
+    pub sound_file: Option<String>,
+    pub web_hook: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fire_time: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Notification sent when an alert fires.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertFire {
+    #[serde(alias = "id")]
+    pub fire_id: i64,
+    #[serde(alias = "aid")]
+    pub alert_id: i64,
+    #[serde(alias = "sym")]
+    pub symbol: String,
+    #[serde(alias = "res")]
+    pub resolution: String,
+    pub name: Option<String>,
+    #[serde(alias = "desc")]
+    pub message: String,
+    pub fire_time: Option<String>,
+    pub bar_time: Option<String>,
+}
+
+/// `{"s": "ok", "r": ...}` envelope used by the alerts API.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AlertResponse<T> {
+    pub s: String,
+    pub r: Option<T>,
+    pub errmsg: Option<String>,
+}
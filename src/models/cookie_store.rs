@@ -0,0 +1,120 @@
+use crate::{Error, Result, UserCookies};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fs, io::Write, path::Path};
+use ustr::Ustr;
+
+/// Version written by [`UserCookies::save_to_file`]. Files without a version
+/// are bare `UserCookies` JSON, as written by older releases.
+const COOKIE_FILE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct StoredCookies {
+    version: u32,
+    saved_at: DateTime<Utc>,
+    cookies: UserCookies,
+}
+
+impl UserCookies {
+    /// Serialize the cookies into the versioned format read by
+    /// [`UserCookies::from_stored`].
+    pub fn to_stored(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&StoredCookies {
+            version: COOKIE_FILE_VERSION,
+            saved_at: Utc::now(),
+            cookies: self.clone(),
+        })?)
+    }
+
+    pub fn from_stored(contents: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(contents)?;
+        match value.get("version").and_then(Value::as_u64) {
+            None => Ok(serde_json::from_value(value)?),
+            Some(version) if version <= COOKIE_FILE_VERSION as u64 => {
+                Ok(serde_json::from_value::<StoredCookies>(value)?.cookies)
+            }
+            Some(version) => Err(Error::Config(Ustr::from(&format!(
+                "unsupported cookie file version {version}, expected at most {COOKIE_FILE_VERSION}"
+            )))),
+        }
+    }
+
+    /// Save the session to `path` so later runs can skip the login.
+    ///
+    /// The file is replaced atomically and, on Unix, only readable by the
+    /// current user since it grants full access to the account.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        file.write_all(self.to_stored()?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load a session saved with [`UserCookies::save_to_file`].
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_stored(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl UserCookies {
+    /// Store the session in the OS keyring under `service` and `account`.
+    pub fn save_to_keyring(&self, service: &str, account: &str) -> Result<()> {
+        keyring::Entry::new(service, account)?.set_password(&self.to_stored()?)?;
+        Ok(())
+    }
+
+    /// Load a session stored with [`UserCookies::save_to_keyring`].
+    pub fn load_from_keyring(service: &str, account: &str) -> Result<Self> {
+        Self::from_stored(&keyring::Entry::new(service, account)?.get_password()?)
+    }
+
+    pub fn delete_from_keyring(service: &str, account: &str) -> Result<()> {
+        keyring::Entry::new(service, account)?.delete_credential()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_round_trip() {
+        let cookies = UserCookies {
+            id: 42,
+            username: "trader".to_string(),
+            session: "session".to_string(),
+            auth_token: "token".to_string(),
+            ..Default::default()
+        };
+        let path = std::env::temp_dir()
+            .join(format!("tradingview-cookies-{}", std::process::id()))
+            .join("cookies.json");
+
+        cookies.save_to_file(&path).unwrap();
+        let loaded = UserCookies::load_from_file(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(loaded.id, 42);
+        assert_eq!(loaded.session, "session");
+
+        let legacy = serde_json::to_string(&cookies).unwrap();
+        assert_eq!(
+            UserCookies::from_stored(&legacy).unwrap().auth_token,
+            "token"
+        );
+        let future = r#"{ "version": 99, "cookies": {} }"#;
+        assert!(UserCookies::from_stored(future).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "i8", into = "i8")]
+pub enum EventImportance {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl From<i8> for EventImportance {
+    fn from(value: i8) -> Self {
+        match value {
+            i8::MIN..=-1 => EventImportance::Low,
+            0 => EventImportance::Medium,
+            1..=i8::MAX => EventImportance::High,
+        }
+    }
+}
+
+impl From<EventImportance> for i8 {
+    fn from(value: EventImportance) -> Self {
+        match value {
+            EventImportance::Low => -1,
+            EventImportance::Medium => 0,
+            EventImportance::High => 1,
+        }
+    }
+}
+
+/// Scheduled macro release such as CPI, non-farm payrolls or a rate decision.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EconomicEvent {
+    pub id: Ustr,
+    pub title: Ustr,
+    /// ISO 3166 alpha-2 country code, e.g. `US` or `EU`.
+    pub country: Ustr,
+    pub indicator: Ustr,
+    /// Economics symbol of the indicator, e.g. `ECONOMICS:USIRYY`.
+    pub ticker: Option<Ustr>,
+    pub comment: Option<String>,
+    pub category: Option<Ustr>,
+    /// Period the release covers, e.g. `Jan` or `Q4`.
+    pub period: Option<Ustr>,
+    pub source: Option<Ustr>,
+    pub currency: Option<Ustr>,
+    pub unit: Option<Ustr>,
+    pub scale: Option<Ustr>,
+    pub importance: EventImportance,
+    /// Scheduled release time.
+    pub date: Option<DateTime<Utc>>,
+    /// `None` until the value is published.
+    pub actual: Option<f64>,
+    pub forecast: Option<f64>,
+    pub previous: Option<f64>,
+}
+
+impl EconomicEvent {
+    pub fn is_released(&self) -> bool {
+        self.actual.is_some()
+    }
+
+    /// `actual - forecast`, once both are known.
+    pub fn surprise(&self) -> Option<f64> {
+        Some(self.actual? - self.forecast?)
+    }
+}
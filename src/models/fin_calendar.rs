@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use ustr::Ustr;
+
+/// Scanner dates are unix seconds, sometimes sent as floats.
+fn unix_seconds<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let seconds = Option::<f64>::deserialize(deserializer)?;
+    Ok(seconds.and_then(|s| DateTime::from_timestamp(s as i64, 0)))
+}
+
+/// Last reported and next scheduled earnings of one symbol.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EarningsEvent {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub name: Ustr,
+    pub currency: Option<Ustr>,
+    #[serde(
+        rename(deserialize = "earnings_release_next_date"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub next_release: Option<DateTime<Utc>>,
+    #[serde(
+        rename(deserialize = "earnings_release_date"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub last_release: Option<DateTime<Utc>>,
+    /// EPS estimate for the next fiscal quarter.
+    #[serde(rename(deserialize = "earnings_per_share_forecast_next_fq"))]
+    pub eps_estimate: Option<f64>,
+    /// Reported EPS of the last fiscal quarter.
+    #[serde(rename(deserialize = "earnings_per_share_fq"))]
+    pub eps_actual: Option<f64>,
+    #[serde(rename(deserialize = "eps_surprise_fq"))]
+    pub eps_surprise: Option<f64>,
+    #[serde(rename(deserialize = "eps_surprise_percent_fq"))]
+    pub eps_surprise_percent: Option<f64>,
+    #[serde(rename(deserialize = "revenue_forecast_next_fq"))]
+    pub revenue_estimate: Option<f64>,
+    #[serde(rename(deserialize = "revenue_fq"))]
+    pub revenue_actual: Option<f64>,
+}
+
+/// Last paid and next scheduled dividend of one symbol.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DividendEvent {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub name: Ustr,
+    pub currency: Option<Ustr>,
+    #[serde(
+        rename(deserialize = "dividend_ex_date_upcoming"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub ex_date: Option<DateTime<Utc>>,
+    #[serde(
+        rename(deserialize = "dividend_payment_date_upcoming"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub pay_date: Option<DateTime<Utc>>,
+    #[serde(rename(deserialize = "dividend_amount_upcoming"))]
+    pub amount: Option<f64>,
+    #[serde(
+        rename(deserialize = "dividend_ex_date_recent"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub last_ex_date: Option<DateTime<Utc>>,
+    #[serde(
+        rename(deserialize = "dividend_payment_date_recent"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub last_pay_date: Option<DateTime<Utc>>,
+    #[serde(rename(deserialize = "dividend_amount_recent"))]
+    pub last_amount: Option<f64>,
+    /// Trailing dividend yield in percent.
+    #[serde(rename(deserialize = "dividends_yield_current"))]
+    pub yield_percent: Option<f64>,
+}
@@ -0,0 +1,82 @@
+use crate::FinancialPeriod;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ustr::Ustr;
+
+/// Which financial statement to fetch with
+/// [`crate::client::fundamentals::get_financial_statement`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatementKind {
+    IncomeStatement,
+    BalanceSheet,
+    CashFlow,
+}
+
+impl StatementKind {
+    /// Scanner fields of the statement, without period suffix.
+    pub fn fields(self) -> &'static [&'static str] {
+        match self {
+            StatementKind::IncomeStatement => &[
+                "total_revenue",
+                "cost_of_goods",
+                "gross_profit",
+                "research_and_dev",
+                "oper_income",
+                "ebitda",
+                "pretax_income",
+                "income_tax",
+                "net_income",
+                "earnings_per_share_basic",
+                "earnings_per_share_diluted",
+            ],
+            StatementKind::BalanceSheet => &[
+                "total_assets",
+                "total_current_assets",
+                "cash_n_short_term_invest",
+                "total_liabilities",
+                "total_current_liabilities",
+                "total_debt",
+                "total_equity",
+            ],
+            StatementKind::CashFlow => &[
+                "cash_f_operating_activities",
+                "cash_f_investing_activities",
+                "cash_f_financing_activities",
+                "capital_expenditures",
+                "free_cash_flow",
+            ],
+        }
+    }
+}
+
+/// Value of one field for one fiscal period.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FinancialPoint {
+    /// Unix time the fiscal period ended, if reported.
+    pub period_end: Option<i64>,
+    /// `None` where the company did not report the field.
+    pub value: Option<f64>,
+}
+
+/// History of financial statement fields of one symbol, oldest period first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialStatement {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub period: FinancialPeriod,
+    /// Currency the values are reported in.
+    pub currency: Option<Ustr>,
+    /// Series keyed by scanner field, e.g. `total_revenue`.
+    pub series: BTreeMap<Ustr, Vec<FinancialPoint>>,
+}
+
+impl FinancialStatement {
+    pub fn get(&self, field: &str) -> Option<&[FinancialPoint]> {
+        self.series.get(&Ustr::from(field)).map(Vec::as_slice)
+    }
+
+    /// Most recent reported value of `field`.
+    pub fn latest(&self, field: &str) -> Option<f64> {
+        self.get(field)?.iter().rev().find_map(|point| point.value)
+    }
+}
@@ -0,0 +1,371 @@
+use crate::Interval;
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+const MAIN_SERIES_ID: &str = "_seriesId";
+const SCRIPT_SOURCE_TYPE: &str = "Script@tv-scripting-101!";
+
+/// A Pine study attached to a saved layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
+pub struct LayoutStudy {
+    /// Script id, e.g. `STD;RSI` or `PUB;abc123`.
+    #[builder(into)]
+    pub pine_id: String,
+    #[builder(into, default = "last")]
+    pub pine_version: String,
+    /// Input values keyed by input id (`in_0`, `in_1`, ...).
+    #[builder(default)]
+    pub inputs: Map<String, Value>,
+}
+
+impl LayoutStudy {
+    pub fn with_input(mut self, id: &str, value: impl Into<Value>) -> Self {
+        self.inputs.insert(id.to_owned(), value.into());
+        self
+    }
+}
+
+/// A single-chart layout that can be pushed to the account with
+/// [`crate::client::layouts::save_layout`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::{ChartLayout, Interval, LayoutStudy};
+///
+/// let layout = ChartLayout::builder()
+///     .name("BTC momentum")
+///     .symbol("BINANCE:BTCUSDT")
+///     .interval(Interval::FourHours)
+///     .studies(vec![LayoutStudy::builder().pine_id("STD;RSI").build().with_input("in_0", 21)])
+///     .build();
+///
+/// let content = layout.to_content();
+/// assert_eq!(ChartLayout::from_content(&content).unwrap().studies, layout.studies);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
+pub struct ChartLayout {
+    /// Numeric id of the layout to overwrite, `None` creates a new layout.
+    pub id: Option<i64>,
+    #[builder(into)]
+    pub name: String,
+    /// `EXCHANGE:SYMBOL` shown on the chart.
+    #[builder(into)]
+    pub symbol: String,
+    #[builder(default)]
+    pub interval: Interval,
+    #[builder(default)]
+    pub studies: Vec<LayoutStudy>,
+}
+
+impl ChartLayout {
+    /// Serialize the layout into the `content` document stored by TradingView.
+    pub fn to_content(&self) -> Value {
+        let mut sources = vec![json!({
+            "type": "MainSeries",
+            "id": MAIN_SERIES_ID,
+            "zorder": 0,
+            "state": {
+                "symbol": self.symbol,
+                "interval": self.interval.to_string(),
+                "style": 1,
+            },
+        })];
+        for (i, study) in self.studies.iter().enumerate() {
+            let mut inputs = study.inputs.clone();
+            inputs.insert("pineId".into(), study.pine_id.clone().into());
+            inputs.insert("pineVersion".into(), study.pine_version.clone().into());
+            sources.push(json!({
+                "type": SCRIPT_SOURCE_TYPE,
+                "id": format!("st{i}"),
+                "ownerSource": MAIN_SERIES_ID,
+                "zorder": i + 1,
+                "state": { "inputs": inputs },
+            }));
+        }
+
+        json!({
+            "name": self.name,
+            "layout": "s",
+            "charts": [{
+                "panes": [{ "sources": sources, "mainSourceId": MAIN_SERIES_ID }],
+                "timeScale": {},
+                "chartProperties": {},
+            }],
+        })
+    }
+
+    /// Read back the first chart of a stored layout document.
+    ///
+    /// Only the main series and Pine studies are kept, drawings and styling are ignored.
+    pub fn from_content(content: &Value) -> Option<Self> {
+        let chart = content.get("charts")?.get(0)?;
+        let sources = chart
+            .get("panes")?
+            .as_array()?
+            .iter()
+            .filter_map(|pane| pane.get("sources")?.as_array())
+            .flatten();
+
+        let mut main = None;
+        let mut studies = Vec::new();
+        for source in sources {
+            let state = source.get("state");
+            match source.get("type").and_then(Value::as_str) {
+                Some("MainSeries") => main = state,
+                Some(t) if t.starts_with("Script@") => {
+                    let Some(mut inputs) = state
+                        .and_then(|s| s.get("inputs"))
+                        .and_then(Value::as_object)
+                        .cloned()
+                    else {
+                        continue;
+                    };
+                    let Some(Value::String(pine_id)) = inputs.remove("pineId") else {
+                        continue;
+                    };
+                    let pine_version = match inputs.remove("pineVersion") {
+                        Some(Value::String(v)) => v,
+                        _ => "last".to_owned(),
+                    };
+                    studies.push(LayoutStudy {
+                        pine_id,
+                        pine_version,
+                        inputs,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let main = main?;
+        Some(Self {
+            id: None,
+            name: content.get("name")?.as_str()?.to_owned(),
+            symbol: main.get("symbol")?.as_str()?.to_owned(),
+            interval: main
+                .get("interval")
+                .and_then(Value::as_str)
+                .and_then(|resolution| resolution.parse().ok())
+                .unwrap_or_default(),
+            studies,
+        })
+    }
+}
+
+/// Kind of a drawing, from the `LineTool*` type TradingView stores it under.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DrawingKind {
+    TrendLine,
+    Ray,
+    ExtendedLine,
+    HorizontalLine,
+    HorizontalRay,
+    VerticalLine,
+    Rectangle,
+    FibRetracement,
+    FibExtension,
+    LongPosition,
+    ShortPosition,
+    Text,
+    /// Any other tool, with its type such as `LineToolPitchfork`.
+    Other(String),
+}
+
+impl From<&str> for DrawingKind {
+    fn from(tool: &str) -> Self {
+        match tool {
+            "LineToolTrendLine" => DrawingKind::TrendLine,
+            "LineToolRay" => DrawingKind::Ray,
+            "LineToolExtended" => DrawingKind::ExtendedLine,
+            "LineToolHorzLine" => DrawingKind::HorizontalLine,
+            "LineToolHorzRay" => DrawingKind::HorizontalRay,
+            "LineToolVertLine" => DrawingKind::VerticalLine,
+            "LineToolRectangle" => DrawingKind::Rectangle,
+            "LineToolFibRetracement" => DrawingKind::FibRetracement,
+            "LineToolTrendBasedFibExtension" => DrawingKind::FibExtension,
+            "LineToolRiskRewardLong" => DrawingKind::LongPosition,
+            "LineToolRiskRewardShort" => DrawingKind::ShortPosition,
+            "LineToolText" | "LineToolNote" | "LineToolCallout" => DrawingKind::Text,
+            other => DrawingKind::Other(other.to_owned()),
+        }
+    }
+}
+
+/// Anchor of a drawing on the chart.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrawingPoint {
+    /// Unix time of the bar the point is attached to.
+    pub time: i64,
+    /// Bars right of `time`, for points drawn into the future.
+    pub offset: i64,
+    pub price: f64,
+}
+
+/// A drawing on a saved layout, see
+/// [`crate::client::layouts::get_drawings`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Drawing {
+    pub id: String,
+    pub kind: DrawingKind,
+    /// `EXCHANGE:SYMBOL` the drawing belongs to, if it is tied to one.
+    pub symbol: Option<String>,
+    pub points: Vec<DrawingPoint>,
+    /// Style and tool specific settings, as stored by TradingView.
+    pub state: Value,
+}
+
+impl Drawing {
+    /// Read a `LineTool*` source of a layout document or of the drawings
+    /// storage, `None` for any other source.
+    pub fn from_source(source: &Value) -> Option<Self> {
+        // Stored drawings nest the tool one level deeper than layout sources
+        let tool = match source.get("type").and_then(Value::as_str) {
+            Some(_) => source,
+            None => source.get("state")?,
+        };
+        let kind = tool.get("type")?.as_str()?;
+        if !kind.starts_with("LineTool") {
+            return None;
+        }
+        let points = tool
+            .get("points")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|point| {
+                Some(DrawingPoint {
+                    time: point.get("time_t")?.as_i64()?,
+                    offset: point.get("offset").and_then(Value::as_i64).unwrap_or(0),
+                    price: point.get("price")?.as_f64()?,
+                })
+            })
+            .collect();
+        let state = tool.get("state").cloned().unwrap_or(Value::Null);
+        let symbol = source
+            .get("symbol")
+            .or_else(|| state.get("symbol"))
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+
+        Some(Self {
+            id: source.get("id")?.as_str()?.to_owned(),
+            kind: DrawingKind::from(kind),
+            symbol,
+            points,
+            state,
+        })
+    }
+
+    /// Every drawing on the charts of a stored layout document.
+    pub fn from_content(content: &Value) -> Vec<Self> {
+        content
+            .get("charts")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|chart| chart.get("panes")?.as_array())
+            .flatten()
+            .filter_map(|pane| pane.get("sources")?.as_array())
+            .flatten()
+            .filter_map(Self::from_source)
+            .collect()
+    }
+
+    /// Visible levels of a fib retracement or extension, e.g. `0.618`.
+    pub fn fib_levels(&self) -> Vec<f64> {
+        let mut levels: Vec<f64> = self
+            .state
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| key.starts_with("level"))
+            .filter(|(_, level)| level.get("visible").and_then(Value::as_bool) != Some(false))
+            .filter_map(|(_, level)| level.get("coeff")?.as_f64())
+            .collect();
+        levels.sort_by(f64::total_cmp);
+        levels
+    }
+
+    /// Stop and target of a long or short position, in ticks from the entry
+    /// at the first point.
+    pub fn position_levels(&self) -> Option<(f64, f64)> {
+        if !matches!(
+            self.kind,
+            DrawingKind::LongPosition | DrawingKind::ShortPosition
+        ) {
+            return None;
+        }
+        Some((
+            self.state.get("stopLevel")?.as_f64()?,
+            self.state.get("profitLevel")?.as_f64()?,
+        ))
+    }
+}
+
+/// Metadata of a layout saved on the account.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedLayout {
+    pub id: i64,
+    /// Short id used in `https://www.tradingview.com/chart/<image_url>/` links.
+    pub image_url: String,
+    pub name: String,
+    pub symbol: String,
+    pub resolution: String,
+    pub modified_iso: Option<i64>,
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drawings_from_content() {
+        let content = json!({
+            "charts": [{
+                "panes": [{
+                    "sources": [
+                        { "type": "MainSeries", "id": "_seriesId", "state": { "symbol": "NASDAQ:AAPL" } },
+                        {
+                            "type": "LineToolFibRetracement",
+                            "id": "fib1",
+                            "points": [
+                                { "time_t": 1_700_000_000, "offset": 0, "price": 180.0 },
+                                { "time_t": 1_700_086_400, "offset": 0, "price": 190.0 },
+                            ],
+                            "state": {
+                                "symbol": "NASDAQ:AAPL",
+                                "level1": { "coeff": 0.0, "visible": true },
+                                "level2": { "coeff": 0.618, "visible": true },
+                                "level3": { "coeff": 0.5, "visible": false },
+                            },
+                        },
+                    ],
+                }],
+            }],
+        });
+        let drawings = Drawing::from_content(&content);
+        assert_eq!(drawings.len(), 1);
+        assert_eq!(drawings[0].kind, DrawingKind::FibRetracement);
+        assert_eq!(drawings[0].symbol.as_deref(), Some("NASDAQ:AAPL"));
+        assert_eq!(drawings[0].points[1].price, 190.0);
+        assert_eq!(drawings[0].fib_levels(), vec![0.0, 0.618]);
+
+        // Drawings storage nests the tool under `state`
+        let stored = json!({
+            "id": "pos1",
+            "symbol": "BINANCE:BTCUSDT",
+            "state": {
+                "type": "LineToolRiskRewardLong",
+                "points": [{ "time_t": 1_700_000_000, "offset": 0, "price": 35_000.0 }],
+                "state": { "stopLevel": 500.0, "profitLevel": 1_500.0 },
+            },
+        });
+        let position = Drawing::from_source(&stored).unwrap();
+        assert_eq!(position.kind, DrawingKind::LongPosition);
+        assert_eq!(position.position_levels(), Some((500.0, 1_500.0)));
+    }
+}
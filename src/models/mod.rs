@@ -1,14 +1,36 @@
 pub use self::MarketType::*;
+pub use self::alerts::*;
+pub use self::economic_calendar::*;
+pub use self::fin_calendar::*;
+pub use self::financials::*;
+pub use self::layout::*;
 pub use self::news::*;
+pub use self::social::*;
+pub use self::sparks::*;
+pub use self::symbol_details::*;
+pub use self::technical_rating::*;
+pub use self::watchlist::*;
 pub use crate::chart::*;
 pub use crate::quote::models::*;
 
 use chrono::Duration;
 use iso_currency::Currency;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+use ustr::Ustr;
+pub mod alerts;
+mod cookie_store;
+pub mod economic_calendar;
+pub mod fin_calendar;
+pub mod financials;
+pub mod layout;
 pub mod news;
 pub mod pine_indicator;
+pub mod social;
+pub mod sparks;
+pub mod symbol_details;
+pub mod technical_rating;
+pub mod watchlist;
 
 pub trait MarketSymbol {
     fn new<S: Into<String>>(symbol: S, exchange: S) -> Self;
@@ -128,6 +150,60 @@ impl Symbol {
     pub fn id(&self) -> String {
         format!("{}:{}", self.exchange, self.symbol)
     }
+
+    /// Chart options for this symbol at `interval`.
+    pub fn chart_options(&self, interval: Interval) -> ChartOptions {
+        ChartOptions::new_with(&self.symbol, &self.exchange, interval)
+    }
+}
+
+/// Continuous futures symbol such as `CME_MINI:ES1!`, which TradingView rolls
+/// over to the next contract as the current one expires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ContinuousFuture {
+    pub exchange: Ustr,
+    /// Product root, e.g. `ES`.
+    pub root: Ustr,
+    /// `1` for the front contract, `2` for the one after it and so on.
+    pub contract: u8,
+}
+
+impl ContinuousFuture {
+    pub fn new(exchange: &str, root: &str, contract: u8) -> Self {
+        Self {
+            exchange: Ustr::from(exchange),
+            root: Ustr::from(root),
+            contract,
+        }
+    }
+
+    /// Parse `EXCHANGE:ROOTN!`, returning `None` for any other symbol.
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let (exchange, ticker) = symbol.split_once(':')?;
+        let ticker = ticker.strip_suffix('!')?;
+        let root = ticker.trim_end_matches(|c: char| c.is_ascii_digit());
+        let contract = ticker[root.len()..].parse().ok()?;
+        if root.is_empty() || contract == 0 {
+            return None;
+        }
+        Some(Self::new(exchange, root, contract))
+    }
+
+    /// Ticker without the exchange, e.g. `ES1!`.
+    pub fn symbol(&self) -> String {
+        format!("{}{}!", self.root, self.contract)
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.exchange, self.symbol())
+    }
+
+    /// Chart options for this contract at `interval`, back-adjusted for the
+    /// price gaps between rollovers when `backadjustment` is set.
+    pub fn chart_options(&self, interval: Interval, backadjustment: bool) -> ChartOptions {
+        ChartOptions::new_with(&self.symbol(), &self.exchange, interval)
+            .backadjustment(backadjustment)
+    }
 }
 
 #[derive(Clone, PartialEq, Deserialize, Serialize, Debug, Default, Hash)]
@@ -157,7 +233,30 @@ impl Display for SessionType {
     }
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Serialize, Copy)]
+/// Trading session a quote or bar belongs to.
+#[derive(Debug, Default, Clone, Serialize, Copy, PartialEq, Eq, Hash)]
+pub enum MarketSession {
+    Regular,
+    PreMarket,
+    PostMarket,
+    /// Outside of any session the symbol trades in.
+    #[default]
+    Closed,
+}
+
+impl<'de> Deserialize<'de> for MarketSession {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Quote sessions report `market`, `pre_market`, `post_market` and `out_of_session`
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "market" | "Regular" => MarketSession::Regular,
+            "pre_market" | "PreMarket" => MarketSession::PreMarket,
+            "post_market" | "PostMarket" => MarketSession::PostMarket,
+            _ => MarketSession::Closed,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, Copy)]
 pub enum MarketAdjustment {
     #[default]
     Splits,
@@ -490,9 +589,11 @@ impl From<Interval> for Duration {
     }
 }
 
-impl From<&str> for Interval {
-    fn from(value: &str) -> Self {
-        match value {
+impl Interval {
+    /// The interval of a short name such as `1m`, `4h`, `1d` or `7d`, or
+    /// `None` for an unknown name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
             "1s" => Interval::OneSecond,
             "5s" => Interval::FiveSeconds,
             "10s" => Interval::TenSeconds,
@@ -513,8 +614,16 @@ impl From<&str> for Interval {
             "120d" => Interval::OneQuarter,
             "180d" => Interval::SixMonths,
             "1y" => Interval::Yearly,
-            _ => Interval::OneDay,
-        }
+            _ => return None,
+        })
+    }
+}
+
+impl From<&str> for Interval {
+    /// [`Interval::from_name`], falling back to [`Interval::OneDay`] for
+    /// unknown names.
+    fn from(value: &str) -> Self {
+        Interval::from_name(value).unwrap_or(Interval::OneDay)
     }
 }
 
@@ -546,6 +655,34 @@ impl Display for Interval {
     }
 }
 
+impl FromStr for Interval {
+    type Err = crate::Error;
+
+    /// Parse a TradingView resolution as formatted by [`Interval`]'s
+    /// `Display`, e.g. `1S`, `15`, `4H`, `1D` or `12M`. Hours given as bare
+    /// minute counts such as `240` and the `D`, `W` and `M` shorthands of
+    /// the web UI are accepted too.
+    fn from_str(resolution: &str) -> crate::Result<Self> {
+        let resolution = match resolution {
+            "D" => "1D",
+            "W" => "1W",
+            "M" => "1M",
+            r => r,
+        };
+        let find = |s: &str| (0..=19u8).map(Interval::from).find(|i| i.to_string() == s);
+        find(resolution)
+            .or_else(|| match resolution.parse::<u32>() {
+                Ok(minutes) if minutes % 60 == 0 => find(&format!("{}H", minutes / 60)),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                crate::Error::TypeConversion(Ustr::from(&format!(
+                    "Unknown resolution: {resolution}"
+                )))
+            })
+    }
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, Copy)]
 pub enum LanguageCode {
     Arabic,
@@ -1005,3 +1142,36 @@ impl Display for EconomicCategory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_continuous_future() {
+        let es = ContinuousFuture::parse("CME_MINI:ES1!").unwrap();
+        assert_eq!(es, ContinuousFuture::new("CME_MINI", "ES", 1));
+        assert_eq!(es.id(), "CME_MINI:ES1!");
+
+        let zn = ContinuousFuture::parse("CBOT:ZN2!").unwrap();
+        assert_eq!((zn.root.as_str(), zn.contract), ("ZN", 2));
+
+        assert!(ContinuousFuture::parse("NASDAQ:AAPL").is_none());
+        assert!(ContinuousFuture::parse("CME_MINI:ESZ2024").is_none());
+        assert!(ContinuousFuture::parse("CME_MINI:ES!").is_none());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        for i in 0..=19u8 {
+            let interval = Interval::from(i);
+            assert_eq!(interval.to_string().parse::<Interval>().unwrap(), interval);
+        }
+        assert_eq!("240".parse::<Interval>().unwrap(), Interval::FourHours);
+        assert_eq!("W".parse::<Interval>().unwrap(), Interval::OneWeek);
+        assert_eq!(Interval::from_name("4h"), Some(Interval::FourHours));
+        assert_eq!(Interval::from_name("2d"), None);
+        assert!("90".parse::<Interval>().is_err());
+        assert!("1h".parse::<Interval>().is_err());
+    }
+}
@@ -1,15 +1,34 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::{collections::HashMap, convert::Infallible, str::FromStr};
 use ustr::Ustr;
 
 use crate::{
-    Result,
-    chart::study::{IndicatorInput, InputValue},
-    client::misc::get_indicator_metadata,
+    Error, Result,
+    client::misc::{get_indicator_metadata, get_indicator_metadata_uncached},
     models::{FinancialPeriod, UserCookies},
 };
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum IndicatorInput {
+    String(Ustr),
+    IndicatorInput(InputValue),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InputValue {
+    pub v: Value,
+    pub f: Value,
+    pub t: Value,
+}
+
+impl InputValue {
+    pub fn new(v: Value, f: Value, t: Value) -> InputValue {
+        InputValue { v, f, t }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum BuiltinIndicators {
     All,
@@ -59,12 +78,23 @@ pub struct PineInfoExtra {
     pub is_new: bool,
     pub is_pine_editor_new_template: bool,
     pub is_updated: bool,
-    pub kind: String,
+    pub kind: ScriptType,
     pub short_description: String,
     pub source_inputs_count: i64,
     pub tags: Vec<String>,
 }
 
+/// Pine source of one script version, see `client::pine::get_script_source`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PineSource {
+    #[serde(skip_deserializing)]
+    pub script_id: Ustr,
+    pub script_name: String,
+    pub version: String,
+    pub source: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TranslateResponse {
     pub success: bool,
@@ -95,7 +125,7 @@ pub struct PineSearchResult {
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PineSearchExtra {
-    pub kind: Option<String>,
+    pub kind: Option<ScriptType>,
     pub source_inputs_count: Option<i64>,
     #[serde(rename = "isMTFResolution")]
     pub is_mtf_resolution: Option<bool>,
@@ -167,9 +197,93 @@ pub struct PineInput {
     pub tooltip: Option<String>,
     #[serde(rename(deserialize = "type"))]
     pub input_type: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
 }
 
+impl PineInput {
+    /// Check `value` against the type, range and options of this input.
+    ///
+    /// Returns a description of the problem if the value is rejected.
+    pub fn validate(&self, value: &Value) -> std::result::Result<(), String> {
+        match self.input_type.as_str() {
+            "integer" => {
+                let integral = value.is_i64()
+                    || value.is_u64()
+                    || value.as_f64().is_some_and(|v| v.fract() == 0.0);
+                if !integral {
+                    return Err(format!("expected integer, got {value}"));
+                }
+            }
+            "float" | "price" if !value.is_number() => {
+                return Err(format!("expected number, got {value}"));
+            }
+            "bool" if !value.is_boolean() => {
+                return Err(format!("expected bool, got {value}"));
+            }
+            "source" | "text" | "string" | "symbol" | "session" | "color" if !value.is_string() => {
+                return Err(format!("expected string, got {value}"));
+            }
+            "resolution" => match value.as_str() {
+                Some(resolution) if is_resolution(resolution) => {}
+                _ => {
+                    return Err(format!(
+                        "expected resolution such as \"60\" or \"1D\", got {value}"
+                    ));
+                }
+            },
+            _ => {}
+        }
+
+        if let Some(number) = value.as_f64() {
+            if let Some(min) = self.min.filter(|&min| number < min) {
+                return Err(format!("{number} is below the minimum {min}"));
+            }
+            if let Some(max) = self.max.filter(|&max| number > max) {
+                return Err(format!("{number} is above the maximum {max}"));
+            }
+        }
+
+        // Plots of other studies are not among the listed sources
+        let study_plot = self.input_type == "source" && value.as_str().is_some_and(is_study_plot);
+        if !self.options.is_empty() && !study_plot {
+            let option = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            if !self.options.contains(&option) {
+                return Err(format!(
+                    "{value} is not one of [{}]",
+                    self.options.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `""` (chart resolution), `"60"`, `"1D"`, `"W"` and the like.
+fn is_resolution(value: &str) -> bool {
+    let digits = value.trim_end_matches(['S', 'H', 'D', 'W', 'M']);
+    let units = value.len() - digits.len();
+    digits.chars().all(|c| c.is_ascii_digit()) && units <= 1
+}
+
+/// Value of a `source` input reading plot `plot` of the study `study_id`,
+/// to compute one study on the output of another, e.g. an EMA of an RSI.
+pub fn study_plot_source(study_id: &str, plot: usize) -> String {
+    format!("{study_id}${plot}")
+}
+
+fn is_study_plot(value: &str) -> bool {
+    value
+        .rsplit_once('$')
+        .is_some_and(|(study, plot)| !study.is_empty() && plot.parse::<usize>().is_ok())
+}
+
+/// Study type sent with `create_study`, serialized as that string.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, Copy)]
+#[serde(from = "String", into = "String")]
 pub enum ScriptType {
     #[default]
     Script,
@@ -182,6 +296,23 @@ pub enum ScriptType {
     SessionRoughVolumeByPrice,
     SessionDetailedVolumeByPrice,
     VisibleVolumeByPrice,
+    /// Any other study type, sent as is.
+    Custom(Ustr),
+}
+
+impl ScriptType {
+    const KNOWN: [ScriptType; 10] = [
+        ScriptType::Script,
+        ScriptType::IntervalScript,
+        ScriptType::StrategyScript,
+        ScriptType::VolumeBasicStudies,
+        ScriptType::FixedBasicStudies,
+        ScriptType::FixedVolumeByPrice,
+        ScriptType::SessionVolumeByPrice,
+        ScriptType::SessionRoughVolumeByPrice,
+        ScriptType::SessionDetailedVolumeByPrice,
+        ScriptType::VisibleVolumeByPrice,
+    ];
 }
 
 impl std::fmt::Display for ScriptType {
@@ -201,13 +332,58 @@ impl std::fmt::Display for ScriptType {
                 write!(f, "VbPSessionsDetailed@tv-volumebyprice-53!")
             }
             ScriptType::VisibleVolumeByPrice => write!(f, "VbPVisible@tv-volumebyprice-53"),
+            ScriptType::Custom(script_type) => write!(f, "{script_type}"),
+        }
+    }
+}
+
+impl FromStr for ScriptType {
+    type Err = Infallible;
+
+    /// Parse a `create_study` type such as `Script@tv-scripting-101!`, a
+    /// script kind such as `study` or `strategy`, or a variant name. Anything
+    /// else becomes [`ScriptType::Custom`].
+    fn from_str(value: &str) -> std::result::Result<Self, Infallible> {
+        let value = value.trim();
+        if let Some(known) = Self::KNOWN
+            .into_iter()
+            .find(|known| known.to_string().trim_end_matches('!') == value.trim_end_matches('!'))
+        {
+            return Ok(known);
         }
+        let script_type = match value.to_ascii_lowercase().as_str() {
+            "script" | "study" | "indicator" => ScriptType::Script,
+            "strategy" | "strategyscript" => ScriptType::StrategyScript,
+            "intervalscript" => ScriptType::IntervalScript,
+            "volumebasicstudies" => ScriptType::VolumeBasicStudies,
+            "fixedbasicstudies" => ScriptType::FixedBasicStudies,
+            "fixedvolumebyprice" => ScriptType::FixedVolumeByPrice,
+            "sessionvolumebyprice" => ScriptType::SessionVolumeByPrice,
+            "sessionroughvolumebyprice" => ScriptType::SessionRoughVolumeByPrice,
+            "sessiondetailedvolumebyprice" => ScriptType::SessionDetailedVolumeByPrice,
+            "visiblevolumebyprice" => ScriptType::VisibleVolumeByPrice,
+            _ => ScriptType::Custom(Ustr::from(value)),
+        };
+        Ok(script_type)
+    }
+}
+
+impl From<&str> for ScriptType {
+    fn from(value: &str) -> Self {
+        let Ok(script_type) = value.parse();
+        script_type
     }
 }
 
 impl From<String> for ScriptType {
-    fn from(_value: String) -> Self {
-        todo!()
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<ScriptType> for String {
+    fn from(value: ScriptType) -> Self {
+        value.to_string()
     }
 }
 
@@ -221,6 +397,7 @@ pub struct PineIndicator {
 
 pub struct PineIndicatorBuilder {
     user: Option<UserCookies>,
+    bypass_cache: bool,
 }
 
 impl PineIndicatorBuilder {
@@ -229,15 +406,23 @@ impl PineIndicatorBuilder {
         self
     }
 
+    /// Skip the metadata cache and query TradingView directly.
+    pub fn bypass_cache(&mut self) -> &mut Self {
+        self.bypass_cache = true;
+        self
+    }
+
     pub async fn fetch(
         &mut self,
         script_id: &str,
         script_version: &str,
         script_type: ScriptType,
     ) -> Result<PineIndicator> {
-        let metadata = match &self.user {
-            Some(user) => get_indicator_metadata(Some(user), script_id, script_version).await?,
-            None => get_indicator_metadata(None, script_id, script_version).await?,
+        let user = self.user.as_ref();
+        let metadata = if self.bypass_cache {
+            get_indicator_metadata_uncached(user, script_id, script_version).await?
+        } else {
+            get_indicator_metadata(user, script_id, script_version).await?
         };
         Ok(PineIndicator {
             script_id: Ustr::from(script_id),
@@ -250,10 +435,59 @@ impl PineIndicatorBuilder {
 
 impl PineIndicator {
     pub fn build() -> PineIndicatorBuilder {
-        PineIndicatorBuilder { user: None }
+        PineIndicatorBuilder {
+            user: None,
+            bypass_cache: false,
+        }
     }
 
     pub fn to_study_inputs(&self) -> Result<Value> {
+        self.to_study_inputs_with(&HashMap::new())
+    }
+
+    /// Start a [`StudyInputs`] builder for overriding the inputs of this indicator.
+    pub fn inputs(&self) -> StudyInputs<'_> {
+        StudyInputs {
+            indicator: self,
+            overrides: HashMap::new(),
+        }
+    }
+
+    fn find_input(&self, key: &str) -> Option<&PineInput> {
+        self.metadata
+            .data
+            .inputs
+            .iter()
+            .find(|input| input.id == key || input.name == key)
+    }
+
+    /// Check every override against its [`PineInput`] definition, reporting all
+    /// offending inputs at once as [`Error::InvalidStudyInput`].
+    pub fn validate_inputs(&self, overrides: &HashMap<Ustr, Value>) -> Result<()> {
+        let mut problems: Vec<String> = overrides
+            .iter()
+            .filter_map(|(key, value)| match self.find_input(key) {
+                None => Some(format!("{key}: unknown input")),
+                Some(input) => input
+                    .validate(value)
+                    .err()
+                    .map(|reason| format!("{} ({}): {reason}", input.name, input.id)),
+            })
+            .collect();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        problems.sort();
+        Err(Error::InvalidStudyInput(Ustr::from(&problems.join("; "))))
+    }
+
+    /// Like [`PineIndicator::to_study_inputs`], with the default value of each
+    /// input in `overrides` replaced. Keys match an input's id (`in_0`) or name (`Length`).
+    ///
+    /// Overrides are validated with [`PineIndicator::validate_inputs`] first.
+    pub fn to_study_inputs_with(&self, overrides: &HashMap<Ustr, Value>) -> Result<Value> {
+        self.validate_inputs(overrides)?;
+
         let mut inputs: HashMap<Ustr, IndicatorInput> = HashMap::new();
         inputs.insert(
             Ustr::from("text"),
@@ -271,10 +505,13 @@ impl PineIndicator {
             if input.id == "text" || input.id == "pineId" || input.id == "pineVersion" {
                 return;
             }
+            let value = overrides
+                .get(&Ustr::from(&input.id))
+                .or_else(|| overrides.get(&Ustr::from(&input.name)));
             inputs.insert(
                 Ustr::from(&input.id),
                 IndicatorInput::IndicatorInput(InputValue {
-                    v: input.defval.clone(),
+                    v: value.unwrap_or(&input.defval).clone(),
                     f: Value::from(input.is_fake),
                     t: Value::from(input.input_type.clone()),
                 }),
@@ -285,3 +522,143 @@ impl PineIndicator {
         Ok(json_value)
     }
 }
+
+/// Validated overrides for the inputs of a [`PineIndicator`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::pine_indicator::{PineIndicator, ScriptType};
+///
+/// # async fn run() -> tradingview::Result<()> {
+/// let rsi = PineIndicator::build()
+///     .fetch("STD;RSI", "31.0", ScriptType::Script)
+///     .await?;
+/// let inputs = rsi.inputs().set("Length", 21).set("Source", "hl2").build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StudyInputs<'a> {
+    indicator: &'a PineIndicator,
+    overrides: HashMap<Ustr, Value>,
+}
+
+impl StudyInputs<'_> {
+    /// Override the input whose id or name is `key`.
+    pub fn set(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.overrides.insert(Ustr::from(key), value.into());
+        self
+    }
+
+    /// Feed the `source` input `key` from plot `plot` of the study `study_id`,
+    /// see [`study_plot_source`].
+    pub fn source_study(self, key: &str, study_id: &str, plot: usize) -> Self {
+        self.set(key, study_plot_source(study_id, plot))
+    }
+
+    pub fn overrides(&self) -> &HashMap<Ustr, Value> {
+        &self.overrides
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.indicator.validate_inputs(&self.overrides)
+    }
+
+    /// Validated overrides, e.g. for [`crate::websocket::WebSocketClient::modify_study_with`].
+    pub fn into_overrides(self) -> Result<HashMap<Ustr, Value>> {
+        self.validate()?;
+        Ok(self.overrides)
+    }
+
+    /// Validated `create_study` inputs payload.
+    pub fn build(self) -> Result<Value> {
+        self.indicator.to_study_inputs_with(&self.overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(id: &str, name: &str, input_type: &str) -> PineInput {
+        PineInput {
+            id: id.to_string(),
+            name: name.to_string(),
+            input_type: input_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_script_type() {
+        for known in ScriptType::KNOWN {
+            assert_eq!(known.to_string().parse::<ScriptType>(), Ok(known));
+        }
+        assert_eq!(ScriptType::from("strategy"), ScriptType::StrategyScript);
+        assert_eq!(ScriptType::from("Study"), ScriptType::Script);
+        // Serialized before script types were written as strings
+        assert_eq!(
+            ScriptType::from("VisibleVolumeByPrice"),
+            ScriptType::VisibleVolumeByPrice
+        );
+
+        let custom = ScriptType::from("Overlay@tv-basicstudies-1!");
+        assert_eq!(
+            custom,
+            ScriptType::Custom(Ustr::from("Overlay@tv-basicstudies-1!"))
+        );
+        assert_eq!(custom.to_string(), "Overlay@tv-basicstudies-1!");
+        let json = serde_json::to_value(custom).unwrap();
+        assert_eq!(serde_json::from_value::<ScriptType>(json).unwrap(), custom);
+    }
+
+    #[test]
+    fn test_validate_inputs() {
+        let mut length = input("in_0", "Length", "integer");
+        length.min = Some(1.0);
+        let mut source = input("in_1", "Source", "source");
+        source.options = vec!["close".into(), "hl2".into()];
+        let indicator = PineIndicator {
+            script_id: Ustr::from("STD;RSI"),
+            script_version: Ustr::from("31.0"),
+            script_type: ScriptType::Script,
+            metadata: PineMetadata {
+                data: PineMetadataInfo {
+                    inputs: vec![length, source, input("in_2", "Timeframe", "resolution")],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        };
+
+        assert!(
+            indicator
+                .inputs()
+                .set("Length", 14)
+                .set("in_1", "hl2")
+                .set("Timeframe", "1D")
+                .validate()
+                .is_ok()
+        );
+        let Err(Error::InvalidStudyInput(message)) = indicator
+            .inputs()
+            .set("Length", 0)
+            .set("Source", "open")
+            .set("Timeframe", "hourly")
+            .set("Offset", 1)
+            .validate()
+        else {
+            panic!("expected invalid inputs");
+        };
+        assert!(message.contains("Length (in_0): 0 is below the minimum 1"));
+        assert!(message.contains("Source (in_1): \"open\" is not one of [close, hl2]"));
+        assert!(message.contains("Timeframe (in_2)"));
+        assert!(message.contains("Offset: unknown input"));
+
+        let on_rsi = indicator.inputs().source_study("Source", "rsi", 0);
+        assert_eq!(on_rsi.overrides()[&Ustr::from("Source")], "rsi$0");
+        assert!(on_rsi.validate().is_ok());
+        assert!(indicator.inputs().set("Source", "$0").validate().is_err());
+    }
+}
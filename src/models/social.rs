@@ -0,0 +1,138 @@
+use chrono::DateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Timestamps come as unix seconds or, on Minds, as RFC 3339 strings.
+fn unix_seconds<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(time) => DateTime::parse_from_rfc3339(&time)
+            .map(|time| time.timestamp())
+            .unwrap_or_default(),
+        value => value.as_f64().unwrap_or_default() as i64,
+    })
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdeaSort {
+    #[default]
+    Recent,
+    Popular,
+}
+
+/// Author of an idea or a Minds post.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SocialAuthor {
+    pub id: i64,
+    pub username: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdeaImage {
+    pub big: Option<String>,
+    pub middle: Option<String>,
+}
+
+/// A public trade idea published on a symbol.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Idea {
+    pub id: i64,
+    /// `EXCHANGE:SYMBOL` the idea was fetched for.
+    pub symbol: String,
+    #[serde(rename(deserialize = "name"))]
+    pub title: String,
+    #[serde(rename(deserialize = "description"))]
+    pub body: String,
+    #[serde(rename(deserialize = "chart_url"))]
+    pub url: String,
+    pub image: IdeaImage,
+    #[serde(rename(deserialize = "user"))]
+    pub author: SocialAuthor,
+    #[serde(rename(deserialize = "likes_count"))]
+    pub agrees: u64,
+    #[serde(rename(deserialize = "comments_count"))]
+    pub comments: u64,
+    /// Unix seconds.
+    #[serde(
+        rename(deserialize = "date_timestamp"),
+        deserialize_with = "unix_seconds"
+    )]
+    pub published: i64,
+}
+
+impl Idea {
+    /// Chart snapshot of the idea, largest size first.
+    pub fn image_url(&self) -> Option<&str> {
+        self.image.big.as_deref().or(self.image.middle.as_deref())
+    }
+}
+
+/// A short post from the Minds feed of a symbol.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MindsPost {
+    pub uid: String,
+    /// `EXCHANGE:SYMBOL` the post was fetched for.
+    pub symbol: String,
+    pub text: String,
+    pub url: String,
+    pub author: SocialAuthor,
+    #[serde(rename(deserialize = "total_likes"))]
+    pub agrees: u64,
+    #[serde(rename(deserialize = "total_comments"))]
+    pub comments: u64,
+    /// Unix seconds.
+    #[serde(rename(deserialize = "created"), deserialize_with = "unix_seconds")]
+    pub published: i64,
+}
+
+/// One page of a Minds feed.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MindsPage {
+    pub posts: Vec<MindsPost>,
+    /// Cursor of the next page, `None` once the feed is exhausted.
+    pub next: Option<String>,
+}
+
+/// An idea or Minds post polled by [`crate::websocket::WebSocketClient::stream_social`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SocialPost {
+    Idea(Idea),
+    Minds(MindsPost),
+}
+
+impl SocialPost {
+    /// Identifier unique across ideas and Minds posts.
+    pub fn key(&self) -> String {
+        match self {
+            SocialPost::Idea(idea) => format!("idea:{}", idea.id),
+            SocialPost::Minds(post) => format!("minds:{}", post.uid),
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            SocialPost::Idea(idea) => &idea.symbol,
+            SocialPost::Minds(post) => &post.symbol,
+        }
+    }
+
+    pub fn author(&self) -> &SocialAuthor {
+        match self {
+            SocialPost::Idea(idea) => &idea.author,
+            SocialPost::Minds(post) => &post.author,
+        }
+    }
+
+    pub fn published(&self) -> i64 {
+        match self {
+            SocialPost::Idea(idea) => idea.published,
+            SocialPost::Minds(post) => post.published,
+        }
+    }
+}
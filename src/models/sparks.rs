@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// Time span covered by a spark.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SparkRange {
+    #[default]
+    OneDay,
+    FiveDays,
+    OneMonth,
+    ThreeMonths,
+    SixMonths,
+    YearToDate,
+    OneYear,
+    FiveYears,
+    All,
+}
+
+impl std::fmt::Display for SparkRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let range = match self {
+            SparkRange::OneDay => "1D",
+            SparkRange::FiveDays => "5D",
+            SparkRange::OneMonth => "1M",
+            SparkRange::ThreeMonths => "3M",
+            SparkRange::SixMonths => "6M",
+            SparkRange::YearToDate => "YTD",
+            SparkRange::OneYear => "12M",
+            SparkRange::FiveYears => "60M",
+            SparkRange::All => "ALL",
+        };
+        write!(f, "{range}")
+    }
+}
+
+/// Compact close price history of one symbol, meant for sparkline widgets.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Spark {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: String,
+    /// Unix timestamps in seconds, empty when the endpoint only sends prices.
+    #[serde(alias = "t")]
+    pub timestamps: Vec<i64>,
+    #[serde(alias = "c", alias = "prices")]
+    pub closes: Vec<f64>,
+    /// Close of the session before the range, the baseline of the sparkline.
+    #[serde(alias = "prev_close", alias = "pc")]
+    pub previous_close: Option<f64>,
+}
+
+impl Spark {
+    pub fn last(&self) -> Option<f64> {
+        self.closes.last().copied()
+    }
+
+    /// Change over the range in percent, relative to `previous_close` when known.
+    pub fn change_percent(&self) -> Option<f64> {
+        let first = self
+            .previous_close
+            .or_else(|| self.closes.first().copied())?;
+        let last = self.last()?;
+        (first != 0.0).then(|| (last - first) / first * 100.0)
+    }
+}
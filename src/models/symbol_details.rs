@@ -0,0 +1,55 @@
+use crate::TradingCalendar;
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+/// Full metadata of one symbol, resolved over REST instead of a chart session.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolDetails {
+    /// `EXCHANGE:SYMBOL` the details were requested for.
+    pub symbol: Ustr,
+    pub name: Ustr,
+    pub description: Ustr,
+    #[serde(rename(deserialize = "type"))]
+    pub market_type: Ustr,
+    pub subtype: Option<Ustr>,
+    pub exchange: Ustr,
+    pub listed_exchange: Option<Ustr>,
+    #[serde(alias = "currency_code")]
+    pub currency: Option<Ustr>,
+    pub country: Option<Ustr>,
+    pub sector: Option<Ustr>,
+    pub industry: Option<Ustr>,
+    pub isin: Option<Ustr>,
+    pub timezone: Option<Ustr>,
+    /// Regular trading session, e.g. `0930-1600`.
+    pub session: Option<Ustr>,
+    pub session_holidays: Option<Ustr>,
+    /// Prices are quoted in multiples of `minmov / pricescale`.
+    pub pricescale: Option<f64>,
+    pub minmov: Option<f64>,
+    #[serde(alias = "point_value")]
+    pub pointvalue: Option<f64>,
+    pub fractional: Option<bool>,
+}
+
+impl SymbolDetails {
+    /// Smallest price increment, derived from `minmov` and `pricescale`.
+    pub fn tick_size(&self) -> Option<f64> {
+        match (self.minmov, self.pricescale) {
+            (Some(minmov), Some(pricescale)) if pricescale > 0.0 => Some(minmov / pricescale),
+            _ => None,
+        }
+    }
+
+    /// Currency value of one full point move, defaults to 1 when TradingView omits it.
+    pub fn point_value(&self) -> f64 {
+        self.pointvalue.unwrap_or(1.0)
+    }
+
+    /// Trading hours and holidays of the symbol. The scanner reports no
+    /// extended hours or early closes, see [`crate::SymbolInfo::calendar`] for those.
+    pub fn calendar(&self) -> TradingCalendar {
+        TradingCalendar::from(self)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::Interval;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ustr::Ustr;
+
+/// Reading of a rating gauge, as labelled in the TradingView UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Recommendation {
+    StrongSell,
+    Sell,
+    Neutral,
+    Buy,
+    StrongBuy,
+}
+
+impl Recommendation {
+    /// Label of a `Recommend.*` value between `-1` and `1`.
+    pub fn from_value(value: f64) -> Self {
+        match value {
+            v if v < -0.5 => Recommendation::StrongSell,
+            v if v < -0.1 => Recommendation::Sell,
+            v if v > 0.5 => Recommendation::StrongBuy,
+            v if v > 0.1 => Recommendation::Buy,
+            _ => Recommendation::Neutral,
+        }
+    }
+}
+
+/// Vote of a single indicator within a rating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Vote {
+    Sell,
+    Neutral,
+    Buy,
+}
+
+impl Vote {
+    /// `-1`, `0` or `1` as used by the scanner `Rec.*` columns.
+    pub fn from_value(value: f64) -> Self {
+        match value {
+            v if v < 0.0 => Vote::Sell,
+            v if v > 0.0 => Vote::Buy,
+            _ => Vote::Neutral,
+        }
+    }
+}
+
+/// Technical ratings of one symbol on one interval, the gauge TradingView
+/// shows on its technicals page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechnicalRating {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub interval: Interval,
+    /// `Recommend.All`, from `-1` for strong sell to `1` for strong buy.
+    pub all: f64,
+    /// `Recommend.MA`
+    pub moving_averages: f64,
+    /// `Recommend.Other`
+    pub oscillators: f64,
+    /// Votes of the oscillators, keyed by indicator such as `RSI` or `MACD`.
+    pub oscillator_votes: BTreeMap<Ustr, Vote>,
+    /// Votes of the moving averages, keyed by indicator such as `EMA20`.
+    pub moving_average_votes: BTreeMap<Ustr, Vote>,
+}
+
+impl TechnicalRating {
+    pub fn recommendation(&self) -> Recommendation {
+        Recommendation::from_value(self.all)
+    }
+
+    pub fn moving_average_recommendation(&self) -> Recommendation {
+        Recommendation::from_value(self.moving_averages)
+    }
+
+    pub fn oscillator_recommendation(&self) -> Recommendation {
+        Recommendation::from_value(self.oscillators)
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Entries starting with this prefix are section headers, not symbols.
+pub const WATCHLIST_SECTION_PREFIX: &str = "###";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Watchlist {
+    pub id: i64,
+    pub name: String,
+    /// `custom` for user lists, `colored` for flag lists.
+    #[serde(rename = "type")]
+    pub list_type: String,
+    /// `EXCHANGE:SYMBOL` entries, interleaved with `###Section` headers.
+    pub symbols: Vec<String>,
+    pub active: bool,
+    pub shared: bool,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Watchlist {
+    /// Symbols of the list without section headers.
+    pub fn tickers(&self) -> impl Iterator<Item = &str> {
+        self.symbols
+            .iter()
+            .map(String::as_str)
+            .filter(|s| !s.starts_with(WATCHLIST_SECTION_PREFIX))
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.tickers().any(|s| s.eq_ignore_ascii_case(symbol))
+    }
+}
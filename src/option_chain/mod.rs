@@ -0,0 +1,236 @@
+//! Option chains from the TradingView options scanner.
+//!
+//! Contracts are listed per underlying, and [`OptionContract::symbol`] can be
+//! passed to chart and quote sessions like any other symbol.
+
+pub mod models;
+
+pub use models::*;
+
+use crate::{
+    Error, Result, UserCookies,
+    screener::{Filter, FilterOperation, ScreenerMarket, ScreenerQuery, ScreenerRow},
+};
+use bon::builder;
+use chrono::NaiveDate;
+use tracing::warn;
+use ustr::Ustr;
+
+const CONTRACT_COLUMNS: &[&str] = &[
+    "root",
+    "expiration",
+    "strike",
+    "option-type",
+    "bid",
+    "ask",
+    "theoPrice",
+    "iv",
+    "bid_iv",
+    "ask_iv",
+    "delta",
+    "gamma",
+    "theta",
+    "vega",
+    "rho",
+];
+const SCAN_PAGE_SIZE: u64 = 1000;
+
+fn chain_query(underlying: &str, columns: &[&str]) -> ScreenerQuery {
+    ScreenerQuery::new(ScreenerMarket::Options)
+        .columns(columns)
+        .index_filter("underlying_symbol", &[underlying])
+        .range(0, SCAN_PAGE_SIZE)
+}
+
+/// Parse one scanned row, rows without a side are rejected when the side was requested.
+fn contract_from_row(row: &ScreenerRow, with_type: bool) -> Result<OptionContract> {
+    if with_type && row.str("option-type").is_none() {
+        return Err(Error::Internal(Ustr::from(&format!(
+            "option contract {} has no option type",
+            row.symbol
+        ))));
+    }
+    row.typed()
+}
+
+/// Scan every page of `query`, skipping rows that do not parse so one bad
+/// contract does not fail the whole chain.
+async fn scan_contracts(
+    client: Option<&UserCookies>,
+    query: ScreenerQuery,
+) -> Result<Vec<OptionContract>> {
+    let with_type = query.columns.iter().any(|c| c.as_str() == "option-type");
+    let rows = query.paginate(client).collect_all().await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            contract_from_row(row, with_type)
+                .inspect_err(|e| warn!("skipping option contract {}: {e}", row.symbol))
+                .ok()
+        })
+        .collect())
+}
+
+/// Option contracts of `underlying`, optionally of one expiration or side only.
+///
+/// Open interest is not part of every options feed and is only requested
+/// with `open_interest(true)`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::option_chain::get_option_chain;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let chain = get_option_chain().underlying("NASDAQ:AAPL").call().await?;
+///     let Some(&expiration) = chain.expirations().first() else {
+///         return Ok(());
+///     };
+///     for call in chain.calls().filter(|c| c.expiration == Some(expiration)) {
+///         println!("{} {} iv {:?}", call.symbol, call.strike, call.iv);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+#[tracing::instrument(skip(client))]
+pub async fn get_option_chain(
+    client: Option<&UserCookies>,
+    /// `EXCHANGE:SYMBOL` of the underlying, e.g. `NASDAQ:AAPL`.
+    underlying: &str,
+    expiration: Option<NaiveDate>,
+    option_type: Option<OptionType>,
+    #[builder(default)] open_interest: bool,
+) -> Result<OptionChain> {
+    let mut columns = CONTRACT_COLUMNS.to_vec();
+    if open_interest {
+        columns.push("open_interest");
+    }
+    let mut query = chain_query(underlying, &columns);
+    if let Some(expiration) = expiration {
+        query = query.filter(Filter::new(
+            "expiration",
+            FilterOperation::Equal,
+            expiration_number(expiration),
+        ));
+    }
+    if let Some(option_type) = option_type {
+        query = query.filter(Filter::new(
+            "option-type",
+            FilterOperation::Equal,
+            option_type.to_string(),
+        ));
+    }
+    let mut contracts = scan_contracts(client, query).await?;
+    contracts.sort_by(|a, b| {
+        (a.expiration, a.option_type as u8)
+            .cmp(&(b.expiration, b.option_type as u8))
+            .then(a.strike.total_cmp(&b.strike))
+    });
+    Ok(OptionChain {
+        underlying: Ustr::from(underlying),
+        contracts,
+    })
+}
+
+/// Expirations listed for `underlying`, earliest first.
+#[tracing::instrument(skip(client))]
+pub async fn get_option_expirations(
+    client: Option<&UserCookies>,
+    underlying: &str,
+) -> Result<Vec<NaiveDate>> {
+    let query = chain_query(underlying, &["expiration"]);
+    let chain = OptionChain {
+        underlying: Ustr::from(underlying),
+        contracts: scan_contracts(client, query).await?,
+    };
+    Ok(chain.expirations())
+}
+
+/// Strikes listed for `underlying` at `expiration`, lowest first.
+#[tracing::instrument(skip(client))]
+pub async fn get_option_strikes(
+    client: Option<&UserCookies>,
+    underlying: &str,
+    expiration: NaiveDate,
+) -> Result<Vec<f64>> {
+    let query = chain_query(underlying, &["expiration", "strike"]).filter(Filter::new(
+        "expiration",
+        FilterOperation::Equal,
+        expiration_number(expiration),
+    ));
+    let chain = OptionChain {
+        underlying: Ustr::from(underlying),
+        contracts: scan_contracts(client, query).await?,
+    };
+    Ok(chain.strikes(Some(expiration)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn row(symbol: &str, values: Vec<(&str, Value)>) -> ScreenerRow {
+        ScreenerRow {
+            symbol: Ustr::from(symbol),
+            values: values
+                .into_iter()
+                .map(|(column, value)| (Ustr::from(column), value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_contract_from_row() {
+        let row = row(
+            "OPRA:AAPL250117P192.5",
+            vec![
+                ("root", json!("AAPL")),
+                ("expiration", json!(20250117)),
+                ("strike", json!(192.5)),
+                ("option-type", json!("put")),
+                ("bid", json!(1.2)),
+                ("ask", json!(1.4)),
+                ("iv", json!(0.31)),
+            ],
+        );
+
+        let contract = contract_from_row(&row, true).unwrap();
+        let expiration = NaiveDate::from_ymd_opt(2025, 1, 17).unwrap();
+        assert_eq!(contract.expiration, Some(expiration));
+        assert_eq!(contract.option_type, OptionType::Put);
+        assert_eq!(contract.exchange(), "OPRA");
+        assert_eq!(contract.ticker(), "AAPL250117P192.5");
+        assert!((contract.mid().unwrap() - 1.3).abs() < 1e-9);
+        assert_eq!(
+            option_symbol(OPRA, "AAPL", expiration, OptionType::Put, 192.5),
+            contract.symbol
+        );
+        assert_eq!(expiration_number(expiration), 20250117);
+
+        let body = chain_query("NASDAQ:AAPL", &["strike"]).body(0);
+        assert_eq!(
+            body["index_filters"],
+            json!([{ "name": "underlying_symbol", "values": ["NASDAQ:AAPL"] }])
+        );
+    }
+
+    #[test]
+    fn test_contract_without_type() {
+        let untyped = row(
+            "OPRA:AAPL250117C150.0",
+            vec![("expiration", json!(20250117)), ("strike", json!(150.0))],
+        );
+        assert!(contract_from_row(&untyped, true).is_err());
+        // Expiration and strike scans do not request the side
+        assert_eq!(contract_from_row(&untyped, false).unwrap().strike, 150.0);
+
+        let unknown = row(
+            "OPRA:AAPL250117C150.0",
+            vec![("strike", json!(150.0)), ("option-type", json!("straddle"))],
+        );
+        assert!(contract_from_row(&unknown, true).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Interval, chart::ChartOptions};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
+use ustr::Ustr;
+
+/// Exchange TradingView lists US equity options under.
+pub const OPRA: &str = "OPRA";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    #[default]
+    Call,
+    Put,
+}
+
+impl OptionType {
+    /// `C` or `P`, as used in option symbols.
+    pub fn code(self) -> char {
+        match self {
+            OptionType::Call => 'C',
+            OptionType::Put => 'P',
+        }
+    }
+}
+
+impl std::fmt::Display for OptionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionType::Call => write!(f, "call"),
+            OptionType::Put => write!(f, "put"),
+        }
+    }
+}
+
+/// Expiration as the scanner sends and filters it, `20250117`.
+pub(crate) fn expiration_number(date: NaiveDate) -> u32 {
+    date.year() as u32 * 10_000 + date.month() * 100 + date.day()
+}
+
+fn expiration_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let number = Option::<f64>::deserialize(deserializer)?.map(|n| n as u32);
+    Ok(number.and_then(|n| NaiveDate::from_ymd_opt((n / 10_000) as i32, n / 100 % 100, n % 100)))
+}
+
+/// TradingView symbol of an option contract, e.g. `OPRA:AAPL250117C150.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use chrono::NaiveDate;
+/// use tradingview::option_chain::{OPRA, OptionType, option_symbol};
+///
+/// let expiration = NaiveDate::from_ymd_opt(2025, 1, 17).unwrap();
+/// let symbol = option_symbol(OPRA, "AAPL", expiration, OptionType::Call, 150.0);
+/// assert_eq!(symbol, "OPRA:AAPL250117C150.0");
+/// ```
+pub fn option_symbol(
+    exchange: &str,
+    root: &str,
+    expiration: NaiveDate,
+    option_type: OptionType,
+    strike: f64,
+) -> Ustr {
+    let strike = if strike.fract() == 0.0 {
+        format!("{strike:.1}")
+    } else {
+        strike.to_string()
+    };
+    Ustr::from(&format!(
+        "{exchange}:{root}{}{}{strike}",
+        expiration.format("%y%m%d"),
+        option_type.code()
+    ))
+}
+
+/// One option contract with its quote and greeks, where available.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptionContract {
+    /// `EXCHANGE:SYMBOL`, usable in chart and quote sessions as is.
+    pub symbol: Ustr,
+    /// Option root, usually the underlying ticker.
+    pub root: Ustr,
+    #[serde(deserialize_with = "expiration_date")]
+    pub expiration: Option<NaiveDate>,
+    pub strike: f64,
+    #[serde(rename(deserialize = "option-type"))]
+    pub option_type: OptionType,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    #[serde(rename(deserialize = "theoPrice"))]
+    pub theoretical_price: Option<f64>,
+    /// Implied volatility as a fraction, `0.25` for 25%.
+    pub iv: Option<f64>,
+    pub bid_iv: Option<f64>,
+    pub ask_iv: Option<f64>,
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub theta: Option<f64>,
+    pub vega: Option<f64>,
+    pub rho: Option<f64>,
+    /// Only filled when requested, see
+    /// [`get_option_chain`](super::get_option_chain).
+    pub open_interest: Option<f64>,
+}
+
+impl OptionContract {
+    pub fn exchange(&self) -> &str {
+        self.symbol
+            .split_once(':')
+            .map_or(OPRA, |(exchange, _)| exchange)
+    }
+
+    pub fn ticker(&self) -> &str {
+        self.symbol
+            .split_once(':')
+            .map_or(self.symbol.as_str(), |(_, ticker)| ticker)
+    }
+
+    /// Mid price between bid and ask, if both are quoted.
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.bid? + self.ask?) / 2.0)
+    }
+
+    /// Chart options for this contract at `interval`.
+    pub fn chart_options(&self, interval: Interval) -> ChartOptions {
+        ChartOptions::new_with(self.ticker(), self.exchange(), interval)
+    }
+}
+
+/// Option contracts of one underlying.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionChain {
+    /// `EXCHANGE:SYMBOL` of the underlying.
+    pub underlying: Ustr,
+    pub contracts: Vec<OptionContract>,
+}
+
+impl OptionChain {
+    /// Distinct expirations, earliest first.
+    pub fn expirations(&self) -> Vec<NaiveDate> {
+        let mut expirations: Vec<_> = self.contracts.iter().filter_map(|c| c.expiration).collect();
+        expirations.sort_unstable();
+        expirations.dedup();
+        expirations
+    }
+
+    /// Distinct strikes, lowest first, of every or only of one expiration.
+    pub fn strikes(&self, expiration: Option<NaiveDate>) -> Vec<f64> {
+        let mut strikes: Vec<_> = self
+            .contracts
+            .iter()
+            .filter(|c| expiration.is_none() || c.expiration == expiration)
+            .map(|c| c.strike)
+            .collect();
+        strikes.sort_unstable_by(f64::total_cmp);
+        strikes.dedup();
+        strikes
+    }
+
+    pub fn calls(&self) -> impl Iterator<Item = &OptionContract> {
+        self.contracts
+            .iter()
+            .filter(|c| c.option_type == OptionType::Call)
+    }
+
+    pub fn puts(&self) -> impl Iterator<Item = &OptionContract> {
+        self.contracts
+            .iter()
+            .filter(|c| c.option_type == OptionType::Put)
+    }
+
+    pub fn contract(
+        &self,
+        expiration: NaiveDate,
+        strike: f64,
+        option_type: OptionType,
+    ) -> Option<&OptionContract> {
+        self.contracts.iter().find(|c| {
+            c.expiration == Some(expiration) && c.strike == strike && c.option_type == option_type
+        })
+    }
+}
@@ -1,8 +1,8 @@
 pub use crate::live::models::DataServer;
 pub use crate::models::SymbolType::*;
 pub use crate::models::{
-    ChartOptions, CryptoType, FundsType, Interval, MarketSymbol, MarketType, OHLCV, PriceIterable,
-    StocksType, StudyOptions, SymbolType,
+    ChartOptions, CryptoType, FundsType, Interval, MarketSymbol, MarketType, OHLCV, Ohlcv,
+    PriceIterable, StocksType, StudyOptions, SymbolType,
 };
 pub use crate::models::{
     CryptoType::Fundamental as CryptoFundamental, CryptoType::Futures as CryptoFutures,
@@ -1,4 +1,6 @@
 pub mod models;
+pub mod options;
+pub mod state;
 pub(crate) mod utils;
 
 lazy_static::lazy_static! {
@@ -1,3 +1,8 @@
+use crate::MarketSession;
+#[cfg(feature = "timezone")]
+use chrono::{DateTime, TimeZone};
+#[cfg(feature = "timezone")]
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
@@ -47,4 +52,20 @@ pub struct QuoteValue {
     pub exchange: Option<Ustr>,
     #[serde(default, rename(deserialize = "type"))]
     pub market_type: Option<Ustr>,
+    /// Session the quote was last updated in, e.g. pre-market with
+    /// [`crate::quote::options::QuoteSessionOptions::session`] set to extended hours.
+    #[serde(default, rename(deserialize = "current_session"))]
+    pub session: Option<MarketSession>,
+}
+
+#[cfg(feature = "timezone")]
+impl QuoteValue {
+    /// Time of the last trade in `timezone`, e.g. the exchange timezone from
+    /// [`crate::SymbolInfo::tz`].
+    pub fn datetime_in(&self, timezone: Tz) -> Option<DateTime<Tz>> {
+        let timestamp = self.timestamp?;
+        timezone
+            .timestamp_millis_opt((timestamp * 1000.0) as i64)
+            .single()
+    }
 }
@@ -0,0 +1,277 @@
+use crate::{SessionType, quote::ALL_QUOTE_FIELDS};
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use ustr::Ustr;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuoteSessionKind {
+    /// Regular `qs_*` session that keeps streaming updates.
+    #[default]
+    Streaming,
+    /// `qs_snapshoter_*` session, used by the web UI for one-off snapshots.
+    Snapshot,
+}
+
+impl QuoteSessionKind {
+    #[cfg(feature = "quote")]
+    pub(crate) fn session_prefix(self) -> &'static str {
+        match self {
+            QuoteSessionKind::Streaming => "qs",
+            QuoteSessionKind::Snapshot => "qs_snapshoter",
+        }
+    }
+}
+
+/// How symbols are subscribed on the quote session.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuoteMode {
+    /// `quote_add_symbols` with the configured fields.
+    #[default]
+    Full,
+    /// Every added symbol is also sent with `quote_fast_symbols` so its
+    /// updates are pushed in real time rather than throttled.
+    Fast,
+    /// Fast symbols limited to the [`QuoteFields`] price group, the lightest
+    /// way to track hundreds of tickers.
+    PriceOnly,
+}
+
+impl QuoteMode {
+    pub fn is_fast(self) -> bool {
+        matches!(self, QuoteMode::Fast | QuoteMode::PriceOnly)
+    }
+
+    /// Fields actually requested for the `configured` ones.
+    pub fn fields(self, configured: &[Ustr]) -> Vec<Ustr> {
+        match self {
+            QuoteMode::PriceOnly => QuoteFields::default().fields(),
+            QuoteMode::Full | QuoteMode::Fast => configured.to_vec(),
+        }
+    }
+}
+
+/// Groups of quote fields to request, see [`QuoteSessionOptions::fields`].
+/// Only the price fields are enabled by default.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::quote::options::{QuoteFields, QuoteSessionOptions};
+///
+/// let fields = QuoteFields::builder()
+///     .bid_ask(true)
+///     .volume(true)
+///     .extra(vec!["market_cap_basic".into()])
+///     .build();
+/// let options = QuoteSessionOptions::builder().fields(fields.into()).build();
+/// assert!(options.fields.contains(&"bid_size".into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Builder)]
+pub struct QuoteFields {
+    /// `lp`, `lp_time`, `ch` and `chp`.
+    #[builder(default = true)]
+    pub price: bool,
+    /// Best bid and ask with their sizes.
+    #[builder(default)]
+    pub bid_ask: bool,
+    #[builder(default)]
+    pub volume: bool,
+    /// Open, high, low and previous close of the day.
+    #[builder(default)]
+    pub daily: bool,
+    /// Pre- and post-market price and change, `rtc`, `rch` and `rchp`.
+    #[builder(default)]
+    pub extended_hours: bool,
+    /// Description, exchange, currency, tick size and other static fields.
+    #[builder(default)]
+    pub info: bool,
+    /// Market session and trading status.
+    #[builder(default)]
+    pub status: bool,
+    /// Sector, industry, market cap, earnings and dividends.
+    #[builder(default)]
+    pub fundamentals: bool,
+    /// Any other fields, requested as given.
+    #[builder(default)]
+    pub extra: Vec<Ustr>,
+}
+
+impl Default for QuoteFields {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl QuoteFields {
+    const PRICE: &[&str] = &["lp", "lp_time", "ch", "chp"];
+    const BID_ASK: &[&str] = &["bid", "ask", "bid_size", "ask_size"];
+    const VOLUME: &[&str] = &["volume"];
+    const DAILY: &[&str] = &["open_price", "high_price", "low_price", "prev_close_price"];
+    const EXTENDED_HOURS: &[&str] = &["rch", "rchp", "rtc", "rtc_time"];
+    const INFO: &[&str] = &[
+        "currency_id",
+        "description",
+        "exchange",
+        "format",
+        "fractional",
+        "language",
+        "local_description",
+        "logoid",
+        "minmov",
+        "minmove2",
+        "original_name",
+        "pricescale",
+        "pro_name",
+        "short_name",
+        "type",
+        "timezone",
+        "country",
+        "provider_id",
+    ];
+    const STATUS: &[&str] = &["current_session", "is_tradable", "status", "update_mode"];
+    const FUNDAMENTALS: &[&str] = &[
+        "fundamentals",
+        "industry",
+        "sector",
+        "basic_eps_net_income",
+        "beta_1_year",
+        "market_cap_basic",
+        "earnings_per_share_basic_ttm",
+        "price_earnings_ttm",
+        "dividends_yield",
+    ];
+
+    /// Every group, the same fields as [`ALL_QUOTE_FIELDS`].
+    pub fn all() -> Self {
+        Self {
+            price: true,
+            bid_ask: true,
+            volume: true,
+            daily: true,
+            extended_hours: true,
+            info: true,
+            status: true,
+            fundamentals: true,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Field names of the enabled groups followed by `extra`, without duplicates.
+    pub fn fields(&self) -> Vec<Ustr> {
+        let groups = [
+            (self.price, Self::PRICE),
+            (self.bid_ask, Self::BID_ASK),
+            (self.volume, Self::VOLUME),
+            (self.daily, Self::DAILY),
+            (self.extended_hours, Self::EXTENDED_HOURS),
+            (self.info, Self::INFO),
+            (self.status, Self::STATUS),
+            (self.fundamentals, Self::FUNDAMENTALS),
+        ];
+        let mut fields: Vec<Ustr> = groups
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .flat_map(|(_, names)| names.iter().map(|name| Ustr::from(name)))
+            .collect();
+        for field in &self.extra {
+            if !fields.contains(field) {
+                fields.push(*field);
+            }
+        }
+        fields
+    }
+}
+
+impl From<QuoteFields> for Vec<Ustr> {
+    fn from(fields: QuoteFields) -> Self {
+        fields.fields()
+    }
+}
+
+/// Options applied when a quote session is created.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tradingview::quote::options::QuoteSessionOptions;
+///
+/// let options = QuoteSessionOptions::builder()
+///     .fields(vec!["lp".into(), "ch".into(), "chp".into()])
+///     .symbols(vec!["NASDAQ:AAPL".into()])
+///     .fast_symbols(vec!["NASDAQ:AAPL".into()])
+///     .conflation(Duration::from_millis(250))
+///     .flush_trades(true)
+///     .build();
+/// assert_eq!(options.fields.len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
+pub struct QuoteSessionOptions {
+    #[builder(default)]
+    pub kind: QuoteSessionKind,
+    #[builder(default)]
+    #[serde(default)]
+    pub mode: QuoteMode,
+    /// Fields requested with `quote_set_fields`, defaults to [`ALL_QUOTE_FIELDS`].
+    /// Build a smaller set with [`QuoteFields`] for long symbol lists.
+    #[builder(default = ALL_QUOTE_FIELDS.iter().map(|f| Ustr::from(f)).collect())]
+    pub fields: Vec<Ustr>,
+    /// Symbols added to the session right after it is created.
+    #[builder(default)]
+    pub symbols: Vec<Ustr>,
+    /// Symbols marked with `quote_fast_symbols` for real-time priority.
+    /// Unused with a fast [`QuoteMode`], which marks every symbol.
+    #[builder(default)]
+    pub fast_symbols: Vec<Ustr>,
+    /// Coalescing window for quote callbacks of the same symbol. Updates
+    /// arriving inside the window are merged and emitted once it ends.
+    pub conflation: Option<Duration>,
+    /// Emit updates carrying a last price right away instead of coalescing them.
+    #[builder(default)]
+    #[serde(default)]
+    pub flush_trades: bool,
+    /// Trading hours quoted for every symbol, e.g. [`SessionType::Extended`]
+    /// to include pre- and post-market prices.
+    #[serde(default)]
+    pub session: Option<SessionType>,
+    /// Also emit the merged [`QuoteSnapshot`](crate::quote::state::QuoteSnapshot) through
+    /// `on_quote_snapshot` whenever a quote is emitted.
+    #[builder(default)]
+    #[serde(default)]
+    pub emit_snapshots: bool,
+}
+
+impl Default for QuoteSessionOptions {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_fields_all_matches_defaults() {
+        let mut all = QuoteFields::all().fields();
+        let mut expected: Vec<Ustr> = ALL_QUOTE_FIELDS.iter().map(|f| Ustr::from(f)).collect();
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+        assert_eq!(QuoteFields::default().fields().len(), 4);
+    }
+
+    #[test]
+    fn test_quote_mode_fields() {
+        let all = QuoteFields::all().fields();
+        assert_eq!(
+            QuoteMode::PriceOnly.fields(&all),
+            QuoteFields::default().fields()
+        );
+        assert_eq!(QuoteMode::Fast.fields(&all), all);
+        assert_eq!(QuoteMode::Full.fields(&all), all);
+        assert!(QuoteMode::Fast.is_fast() && QuoteMode::PriceOnly.is_fast());
+        assert!(!QuoteMode::Full.is_fast());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::quote::{models::QuoteValue, utils::merge_quotes};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+/// Latest known value of every quote field for one symbol.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuoteSnapshot {
+    /// Symbol the quote session reported, e.g. `NASDAQ:AAPL`.
+    pub symbol: Ustr,
+    pub value: QuoteValue,
+    /// Number of deltas merged into this snapshot.
+    pub updates: u64,
+}
+
+/// Accumulates partial [`QuoteValue`] deltas into a [`QuoteSnapshot`] per symbol.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::quote::{models::QuoteValue, state::QuoteStateTracker};
+///
+/// let tracker = QuoteStateTracker::new();
+/// let price = QuoteValue { price: Some(190.0), ..Default::default() };
+/// let bid = QuoteValue { bid: Some(189.9), ..Default::default() };
+///
+/// tracker.update("NASDAQ:AAPL".into(), &price);
+/// let snapshot = tracker.update("NASDAQ:AAPL".into(), &bid);
+///
+/// assert_eq!(snapshot.value.price, Some(190.0));
+/// assert_eq!(snapshot.value.bid, Some(189.9));
+/// assert_eq!(snapshot.updates, 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct QuoteStateTracker {
+    quotes: DashMap<Ustr, QuoteSnapshot>,
+}
+
+impl QuoteStateTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge `delta` into the snapshot of `symbol` and return the result.
+    pub fn update(&self, symbol: Ustr, delta: &QuoteValue) -> QuoteSnapshot {
+        let mut entry = self.quotes.entry(symbol).or_insert_with(|| QuoteSnapshot {
+            symbol,
+            ..Default::default()
+        });
+        entry.value = merge_quotes(&entry.value, delta);
+        entry.updates += 1;
+        *entry
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<QuoteSnapshot> {
+        self.quotes.get(&Ustr::from(symbol)).map(|entry| *entry)
+    }
+
+    /// Snapshots of every tracked symbol, in no particular order.
+    pub fn snapshots(&self) -> Vec<QuoteSnapshot> {
+        self.quotes.iter().map(|entry| *entry).collect()
+    }
+
+    pub fn remove(&self, symbol: &str) -> Option<QuoteSnapshot> {
+        self.quotes
+            .remove(&Ustr::from(symbol))
+            .map(|(_, snapshot)| snapshot)
+    }
+
+    pub fn clear(&self) {
+        self.quotes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_keeps_fields_per_symbol() {
+        let tracker = QuoteStateTracker::new();
+        let aapl = Ustr::from("NASDAQ:AAPL");
+        let msft = Ustr::from("NASDAQ:MSFT");
+
+        tracker.update(
+            aapl,
+            &QuoteValue {
+                price: Some(190.0),
+                volume: Some(1_000.0),
+                ..Default::default()
+            },
+        );
+        tracker.update(
+            msft,
+            &QuoteValue {
+                price: Some(410.0),
+                ..Default::default()
+            },
+        );
+        let snapshot = tracker.update(
+            aapl,
+            &QuoteValue {
+                price: Some(191.0),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(snapshot.symbol, aapl);
+        assert_eq!(snapshot.value.price, Some(191.0));
+        assert_eq!(snapshot.value.volume, Some(1_000.0));
+        assert_eq!(snapshot.updates, 2);
+        assert_eq!(tracker.get("NASDAQ:MSFT").unwrap().value.price, Some(410.0));
+
+        tracker.remove("NASDAQ:MSFT");
+        assert_eq!(tracker.len(), 1);
+    }
+}
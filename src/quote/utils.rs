@@ -19,5 +19,6 @@ pub fn merge_quotes(quote_old: &QuoteValue, quote_new: &QuoteValue) -> QuoteValu
         symbol: quote_new.symbol.or(quote_old.symbol),
         exchange: quote_new.exchange.or(quote_old.exchange),
         market_type: quote_new.market_type.or(quote_old.market_type),
+        session: quote_new.session.or(quote_old.session),
     }
 }
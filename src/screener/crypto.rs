@@ -0,0 +1,324 @@
+use super::{Filter, FilterOperation, ScreenerMarket, ScreenerQuery, ScreenerRow, SortOrder};
+use crate::{Result, UserCookies, utils::get_symbol_fields};
+use bon::builder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ustr::Ustr;
+
+/// Market cap of every coin TradingView tracks.
+static TOTAL_MARKET_CAP: &str = "CRYPTOCAP:TOTAL";
+/// Quote currencies of the pairs [`get_aggregated_price`] looks at by default.
+const USD_QUOTES: &[&str] = &["USD", "USDT", "USDC"];
+
+/// Order of the coins in [`get_crypto_overview`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoinSort {
+    #[default]
+    MarketCap,
+    Volume,
+    Gainers,
+    Losers,
+}
+
+impl CoinSort {
+    /// The coin screener query behind this ranking.
+    pub fn query(self) -> ScreenerQuery {
+        let query = ScreenerQuery::new(ScreenerMarket::Coin).columns(&[
+            "base_currency",
+            "base_currency_desc",
+            "crypto_total_rank",
+            "close",
+            "24h_close_change|5",
+            "market_cap_calc",
+            "24h_vol_cmc",
+            "circulating_supply",
+        ]);
+        match self {
+            CoinSort::MarketCap => query.sort_by("market_cap_calc", SortOrder::Descending),
+            CoinSort::Volume => query.sort_by("24h_vol_cmc", SortOrder::Descending),
+            CoinSort::Gainers => query.sort_by("24h_close_change|5", SortOrder::Descending),
+            CoinSort::Losers => query.sort_by("24h_close_change|5", SortOrder::Ascending),
+        }
+    }
+}
+
+/// One coin of the coin screener, priced in USD.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoinEntry {
+    /// `EXCHANGE:SYMBOL`, e.g. `CRYPTO:BTCUSD`.
+    pub symbol: Ustr,
+    /// Ticker of the coin, e.g. `BTC`.
+    pub base_currency: Ustr,
+    pub name: Ustr,
+    /// Rank by market cap.
+    pub rank: Option<u32>,
+    pub price: Option<f64>,
+    pub change_percent_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub circulating_supply: Option<f64>,
+    /// Share of the total crypto market cap, in percent.
+    pub dominance: Option<f64>,
+}
+
+impl From<ScreenerRow> for CoinEntry {
+    fn from(row: ScreenerRow) -> Self {
+        let text = |column: &str| row.str(column).map(Ustr::from).unwrap_or_default();
+        Self {
+            symbol: row.symbol,
+            base_currency: text("base_currency"),
+            name: text("base_currency_desc"),
+            rank: row.f64("crypto_total_rank").map(|rank| rank as u32),
+            price: row.f64("close"),
+            change_percent_24h: row.f64("24h_close_change|5"),
+            market_cap: row.f64("market_cap_calc"),
+            volume_24h: row.f64("24h_vol_cmc"),
+            circulating_supply: row.f64("circulating_supply"),
+            dominance: None,
+        }
+    }
+}
+
+/// Ranked coins and the total market cap they are measured against.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CryptoOverview {
+    pub total_market_cap: Option<f64>,
+    pub coins: Vec<CoinEntry>,
+}
+
+impl CryptoOverview {
+    fn new(total_market_cap: Option<f64>, mut coins: Vec<CoinEntry>) -> Self {
+        for coin in &mut coins {
+            coin.dominance = match (coin.market_cap, total_market_cap) {
+                (Some(cap), Some(total)) if total > 0.0 => Some(cap / total * 100.0),
+                _ => None,
+            };
+        }
+        Self {
+            total_market_cap,
+            coins,
+        }
+    }
+
+    /// The coin with the ticker `base_currency`, e.g. `BTC`.
+    pub fn coin(&self, base_currency: &str) -> Option<&CoinEntry> {
+        self.coins
+            .iter()
+            .find(|coin| coin.base_currency.as_str() == base_currency)
+    }
+}
+
+/// Fetch `limit` coins of the coin screener from `offset` on, ordered by
+/// `sort`, with their dominance of the total crypto market cap.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::screener::get_crypto_overview;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let overview = get_crypto_overview().limit(10).call().await?;
+///     for coin in &overview.coins {
+///         println!("{:?} {} {:?}%", coin.rank, coin.name, coin.dominance);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+pub async fn get_crypto_overview(
+    client: Option<&UserCookies>,
+    #[builder(default)] sort: CoinSort,
+    #[builder(default = 0)] offset: u64,
+    #[builder(default = 100)] limit: u64,
+) -> Result<CryptoOverview> {
+    let result = sort.query().range(offset, limit).scan(client).await?;
+    let coins = result.rows.into_iter().map(CoinEntry::from).collect();
+    let total: Value = get_symbol_fields(client, TOTAL_MARKET_CAP, "close").await?;
+    Ok(CryptoOverview::new(
+        total.get("close").and_then(Value::as_f64),
+        coins,
+    ))
+}
+
+/// Price of one trading pair of a coin on one exchange.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangePrice {
+    /// `EXCHANGE:SYMBOL`, e.g. `BINANCE:BTCUSDT`.
+    pub symbol: Ustr,
+    pub exchange: Ustr,
+    pub quote_currency: Ustr,
+    pub price: Option<f64>,
+    /// Traded volume of the last 24 hours, in USD.
+    pub volume_24h: Option<f64>,
+}
+
+impl From<ScreenerRow> for ExchangePrice {
+    fn from(row: ScreenerRow) -> Self {
+        let text = |column: &str| row.str(column).map(Ustr::from).unwrap_or_default();
+        Self {
+            symbol: row.symbol,
+            exchange: text("exchange"),
+            quote_currency: text("currency"),
+            price: row.f64("close"),
+            volume_24h: row.f64("24h_vol|5"),
+        }
+    }
+}
+
+/// Prices of one coin across exchanges, most traded pair first.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregatedPrice {
+    pub base_currency: Ustr,
+    pub exchanges: Vec<ExchangePrice>,
+}
+
+impl AggregatedPrice {
+    /// Volume weighted average price over the pairs reporting a volume.
+    pub fn vwap(&self) -> Option<f64> {
+        let (notional, volume) = self
+            .exchanges
+            .iter()
+            .filter_map(|pair| Some((pair.price?, pair.volume_24h?)))
+            .fold((0.0, 0.0), |(notional, volume), (price, pair_volume)| {
+                (notional + price * pair_volume, volume + pair_volume)
+            });
+        (volume > 0.0).then(|| notional / volume)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let mut prices: Vec<f64> = self.exchanges.iter().filter_map(|p| p.price).collect();
+        prices.sort_by(f64::total_cmp);
+        let mid = prices.len() / 2;
+        match prices.len() {
+            0 => None,
+            len if len.is_multiple_of(2) => Some((prices[mid - 1] + prices[mid]) / 2.0),
+            _ => Some(prices[mid]),
+        }
+    }
+
+    /// Highest minus lowest price across exchanges.
+    pub fn spread(&self) -> Option<f64> {
+        let prices = self.exchanges.iter().filter_map(|p| p.price);
+        let (low, high) = prices.fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), p| {
+            (low.min(p), high.max(p))
+        });
+        (low <= high).then_some(high - low)
+    }
+}
+
+/// The crypto screener query listing the `quotes` pairs of `base_currency`.
+fn pairs_query(base_currency: &str, quotes: &[&str]) -> ScreenerQuery {
+    ScreenerQuery::new(ScreenerMarket::Crypto)
+        .columns(&["exchange", "currency", "close", "24h_vol|5"])
+        .filter(Filter::new(
+            "base_currency",
+            FilterOperation::Equal,
+            base_currency,
+        ))
+        .filter(Filter::new(
+            "currency",
+            FilterOperation::InRange,
+            quotes.to_vec(),
+        ))
+        .sort_by("24h_vol|5", SortOrder::Descending)
+}
+
+/// Fetch the price of `base_currency`, e.g. `BTC`, on up to `limit` exchange
+/// pairs quoted in one of `quotes`, for comparing or aggregating prices.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::screener::get_aggregated_price;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let btc = get_aggregated_price().base_currency("BTC").call().await?;
+///     println!("VWAP {:?} across {} pairs", btc.vwap(), btc.exchanges.len());
+///     Ok(())
+/// }
+/// ```
+#[builder]
+pub async fn get_aggregated_price(
+    client: Option<&UserCookies>,
+    base_currency: &str,
+    #[builder(default = USD_QUOTES)] quotes: &[&str],
+    #[builder(default = 50)] limit: u64,
+) -> Result<AggregatedPrice> {
+    let result = pairs_query(base_currency, quotes)
+        .range(0, limit)
+        .scan(client)
+        .await?;
+    Ok(AggregatedPrice {
+        base_currency: Ustr::from(base_currency),
+        exchanges: result.rows.into_iter().map(ExchangePrice::from).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(symbol: &str, values: Value) -> ScreenerRow {
+        ScreenerRow {
+            symbol: Ustr::from(symbol),
+            values: values
+                .as_object()
+                .unwrap()
+                .iter()
+                .map(|(column, value)| (Ustr::from(column), value.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_coins_and_aggregated_prices() {
+        let btc = row(
+            "CRYPTO:BTCUSD",
+            json!({
+                "base_currency": "BTC",
+                "base_currency_desc": "Bitcoin",
+                "crypto_total_rank": 1,
+                "close": 65000.0,
+                "market_cap_calc": 1.3e12,
+                "circulating_supply": 19_700_000,
+            }),
+        );
+        let overview = CryptoOverview::new(Some(2.6e12), vec![CoinEntry::from(btc)]);
+        let btc = overview.coin("BTC").unwrap();
+        assert_eq!(btc.rank, Some(1));
+        assert_eq!(btc.dominance, Some(50.0));
+        assert_eq!(btc.circulating_supply, Some(19_700_000.0));
+        assert_eq!(
+            CoinSort::Losers.query().sort.unwrap().sort_order,
+            SortOrder::Ascending
+        );
+
+        let body = pairs_query("BTC", &["USD", "USDT"]).body(0);
+        assert_eq!(
+            body["filter"][1],
+            json!({ "left": "currency", "operation": "in_range", "right": ["USD", "USDT"] })
+        );
+        let price = AggregatedPrice {
+            base_currency: Ustr::from("BTC"),
+            exchanges: vec![
+                ExchangePrice::from(row(
+                    "BINANCE:BTCUSDT",
+                    json!({ "exchange": "BINANCE", "close": 100.0, "24h_vol|5": 3.0 }),
+                )),
+                ExchangePrice::from(row(
+                    "COINBASE:BTCUSD",
+                    json!({ "exchange": "COINBASE", "close": 104.0, "24h_vol|5": 1.0 }),
+                )),
+                ExchangePrice::from(row("KRAKEN:XBTUSD", json!({ "close": 102.0 }))),
+            ],
+        };
+        assert_eq!(price.vwap(), Some(101.0));
+        assert_eq!(price.median(), Some(102.0));
+        assert_eq!(price.spread(), Some(4.0));
+        assert_eq!(AggregatedPrice::default().vwap(), None);
+        assert_eq!(AggregatedPrice::default().spread(), None);
+    }
+}
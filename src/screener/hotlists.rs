@@ -0,0 +1,122 @@
+use super::{Filter, FilterOperation, ScreenerMarket, ScreenerQuery, ScreenerRow, SortOrder};
+use crate::{Result, UserCookies};
+use bon::builder;
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+/// Ready-made scanner queries for the usual market movers lists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hotlist {
+    #[default]
+    Gainers,
+    Losers,
+    /// Highest traded volume of the session.
+    MostActive,
+    /// Symbols trading at their 52-week high.
+    NewHighs,
+}
+
+impl Hotlist {
+    /// The scanner query behind this hotlist.
+    pub fn query(self, market: ScreenerMarket) -> ScreenerQuery {
+        let query = ScreenerQuery::new(market).columns(&["name", "close", "change", "volume"]);
+        match self {
+            Hotlist::Gainers => query
+                .filter(Filter::new("change", FilterOperation::Greater, 0))
+                .sort_by("change", SortOrder::Descending),
+            Hotlist::Losers => query
+                .filter(Filter::new("change", FilterOperation::Less, 0))
+                .sort_by("change", SortOrder::Ascending),
+            Hotlist::MostActive => query.sort_by("volume", SortOrder::Descending),
+            Hotlist::NewHighs => query
+                .filter(Filter::new(
+                    "high",
+                    FilterOperation::GreaterOrEqual,
+                    "price_52_week_high",
+                ))
+                .sort_by("change", SortOrder::Descending),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotlistEntry {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub name: Ustr,
+    pub price: Option<f64>,
+    pub change_percent: Option<f64>,
+    pub volume: Option<f64>,
+}
+
+impl From<ScreenerRow> for HotlistEntry {
+    fn from(row: ScreenerRow) -> Self {
+        Self {
+            symbol: row.symbol,
+            name: row.str("name").map(Ustr::from).unwrap_or_default(),
+            price: row.f64("close"),
+            change_percent: row.f64("change"),
+            volume: row.f64("volume"),
+        }
+    }
+}
+
+/// Fetch the top `limit` symbols of `list` in `market`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::screener::{Hotlist, ScreenerMarket, get_hotlist};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let gainers = get_hotlist()
+///         .market(ScreenerMarket::America)
+///         .list(Hotlist::Gainers)
+///         .limit(10)
+///         .call()
+///         .await?;
+///     for entry in gainers {
+///         println!("{} {:?}%", entry.symbol, entry.change_percent);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[builder]
+pub async fn get_hotlist(
+    client: Option<&UserCookies>,
+    #[builder(default)] market: ScreenerMarket,
+    #[builder(default)] list: Hotlist,
+    #[builder(default = 20)] limit: u64,
+) -> Result<Vec<HotlistEntry>> {
+    let result = list.query(market).range(0, limit).scan(client).await?;
+    Ok(result.rows.into_iter().map(HotlistEntry::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hotlist_entry_from_row() {
+        let query = Hotlist::Losers.query(ScreenerMarket::Crypto);
+        assert_eq!(query.sort.unwrap().sort_order, SortOrder::Ascending);
+
+        let row = ScreenerRow {
+            symbol: Ustr::from("NASDAQ:AAPL"),
+            values: [
+                (Ustr::from("name"), json!("AAPL")),
+                (Ustr::from("close"), json!(190.5)),
+                (Ustr::from("change"), json!(-1.25)),
+                (Ustr::from("volume"), json!(52_000_000)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let entry = HotlistEntry::from(row);
+        assert_eq!(entry.name.as_str(), "AAPL");
+        assert_eq!(entry.change_percent, Some(-1.25));
+        assert_eq!(entry.volume, Some(52_000_000.0));
+    }
+}
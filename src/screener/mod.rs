@@ -0,0 +1,270 @@
+//! TradingView stock, crypto and forex screener.
+//!
+//! Queries are sent to the same scanner endpoint the screener pages on
+//! tradingview.com use, so every screener column and filter is available.
+
+pub mod crypto;
+pub mod hotlists;
+pub mod models;
+
+pub use crypto::*;
+pub use hotlists::*;
+pub use models::*;
+
+use crate::{Error, Page, Paginator, Result, UserCookies, utils::post_json};
+use futures_util::FutureExt;
+use serde_json::{Value, json};
+use ustr::Ustr;
+
+static SCANNER_URL: &str = "https://scanner.tradingview.com";
+/// Rows requested per page when none is set with [`ScreenerQuery::range`].
+const DEFAULT_PAGE_SIZE: u64 = 50;
+
+/// Scanner query: markets, columns, filters, sort order and result range.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::screener::{ScreenerMarket, ScreenerQuery, SortOrder};
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let result = ScreenerQuery::new(ScreenerMarket::America)
+///         .columns(&["name", "close", "volume", "SMA200"])
+///         .filter_expr("close > SMA200")?
+///         .filter_expr("volume > 1000000")?
+///         .sort_by("volume", SortOrder::Descending)
+///         .range(0, 20)
+///         .scan(None)
+///         .await?;
+///
+///     println!("{} matches", result.total_count);
+///     for row in result.rows {
+///         println!("{} {:?}", row.symbol, row.f64("close"));
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenerQuery {
+    pub markets: Vec<ScreenerMarket>,
+    pub columns: Vec<Ustr>,
+    pub filters: Vec<Filter>,
+    pub index_filters: Vec<IndexFilter>,
+    pub sort: Option<Sort>,
+    /// Restrict the scan to these `EXCHANGE:SYMBOL` tickers.
+    pub tickers: Vec<Ustr>,
+    /// Symbol types such as `stock`, `fund` or `dr`, all types when empty.
+    pub types: Vec<Ustr>,
+    pub offset: u64,
+    pub limit: u64,
+}
+
+impl Default for ScreenerQuery {
+    fn default() -> Self {
+        Self::new(ScreenerMarket::default())
+    }
+}
+
+impl ScreenerQuery {
+    pub fn new(market: ScreenerMarket) -> Self {
+        Self {
+            markets: vec![market],
+            columns: vec![Ustr::from("name")],
+            filters: Vec::new(),
+            index_filters: Vec::new(),
+            sort: None,
+            tickers: Vec::new(),
+            types: Vec::new(),
+            offset: 0,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Scan several markets at once through the `global` scanner.
+    pub fn markets(mut self, markets: &[ScreenerMarket]) -> Self {
+        self.markets = markets.to_vec();
+        self
+    }
+
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().copied().map(Ustr::from).collect();
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Add a filter parsed with [`Filter::from_str`](std::str::FromStr::from_str),
+    /// e.g. `close > SMA200`.
+    pub fn filter_expr(self, expr: &str) -> Result<Self> {
+        Ok(self.filter(expr.parse()?))
+    }
+
+    pub fn index_filter(mut self, name: &str, values: &[&str]) -> Self {
+        self.index_filters.push(IndexFilter {
+            name: Ustr::from(name),
+            values: values.iter().copied().map(Ustr::from).collect(),
+        });
+        self
+    }
+
+    pub fn sort_by(mut self, column: &str, order: SortOrder) -> Self {
+        self.sort = Some(Sort {
+            sort_by: Ustr::from(column),
+            sort_order: order,
+        });
+        self
+    }
+
+    pub fn tickers(mut self, tickers: &[&str]) -> Self {
+        self.tickers = tickers.iter().copied().map(Ustr::from).collect();
+        self
+    }
+
+    pub fn types(mut self, types: &[&str]) -> Self {
+        self.types = types.iter().copied().map(Ustr::from).collect();
+        self
+    }
+
+    /// Return `limit` rows starting at `offset`.
+    pub fn range(mut self, offset: u64, limit: u64) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    fn url(&self) -> String {
+        match self.markets.as_slice() {
+            [market] => format!("{SCANNER_URL}/{market}/scan"),
+            _ => format!("{SCANNER_URL}/global/scan"),
+        }
+    }
+
+    pub(crate) fn body(&self, offset: u64) -> Value {
+        let mut body = json!({
+            "markets": self.markets,
+            "columns": self.columns,
+            "filter": self.filters,
+            "symbols": {
+                "query": { "types": self.types },
+                "tickers": self.tickers,
+            },
+            "options": { "lang": "en" },
+            "range": [offset, offset + self.limit],
+        });
+        if let Some(sort) = &self.sort {
+            body["sort"] = json!(sort);
+        }
+        if !self.index_filters.is_empty() {
+            body["index_filters"] = json!(self.index_filters);
+        }
+        body
+    }
+
+    /// Run the query and return the rows in [`ScreenerQuery::range`].
+    #[tracing::instrument(skip(self, client), fields(url = %self.url()))]
+    pub async fn scan(&self, client: Option<&UserCookies>) -> Result<ScreenerResult> {
+        self.scan_at(client, self.offset).await
+    }
+
+    async fn scan_at(&self, client: Option<&UserCookies>, offset: u64) -> Result<ScreenerResult> {
+        let resp: ScanResponse = post_json(client, &self.url(), &self.body(offset))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        self.rows(resp)
+    }
+
+    fn rows(&self, resp: ScanResponse) -> Result<ScreenerResult> {
+        let rows = resp
+            .data
+            .into_iter()
+            .map(|row| {
+                if row.d.len() != self.columns.len() {
+                    return Err(Error::JsonParse(Ustr::from(&format!(
+                        "screener row {} has {} values for {} columns",
+                        row.s,
+                        row.d.len(),
+                        self.columns.len()
+                    ))));
+                }
+                Ok(ScreenerRow {
+                    symbol: row.s,
+                    values: self.columns.iter().copied().zip(row.d).collect(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(ScreenerResult {
+            total_count: resp.total_count,
+            rows,
+        })
+    }
+
+    /// Lazily page through every matching row, [`ScreenerQuery::range`] sets
+    /// the first offset and the page size.
+    pub fn paginate(self, client: Option<&UserCookies>) -> Paginator<ScreenerRow> {
+        let client = client.cloned();
+        let start = self.offset;
+        Paginator::new(start, move |offset| {
+            let query = self.clone();
+            let client = client.clone();
+            async move {
+                let result = query.scan_at(client.as_ref(), offset).await?;
+                let fetched = offset + result.rows.len() as u64;
+                let next =
+                    (!result.rows.is_empty() && fetched < result.total_count).then_some(fetched);
+                Ok(Page {
+                    items: result.rows,
+                    next,
+                })
+            }
+            .boxed()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_and_rows() {
+        let query = ScreenerQuery::new(ScreenerMarket::Crypto)
+            .columns(&["name", "close"])
+            .filter_expr("close > SMA200")
+            .unwrap()
+            .sort_by("volume", SortOrder::Descending)
+            .range(0, 10);
+
+        assert_eq!(query.url(), "https://scanner.tradingview.com/crypto/scan");
+        let body = query.body(20);
+        assert_eq!(body["markets"], json!(["crypto"]));
+        assert_eq!(
+            body["filter"],
+            json!([{ "left": "close", "operation": "greater", "right": "SMA200" }])
+        );
+        assert_eq!(
+            body["sort"],
+            json!({ "sortBy": "volume", "sortOrder": "desc" })
+        );
+        assert_eq!(body["range"], json!([20, 30]));
+
+        let result = query
+            .rows(
+                serde_json::from_value(json!({
+                    "totalCount": 1,
+                    "data": [{ "s": "BINANCE:BTCUSDT", "d": ["BTCUSDT", 65000.5] }]
+                }))
+                .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.rows[0].f64("close"), Some(65000.5));
+        assert_eq!(result.rows[0].str("name"), Some("BTCUSDT"));
+        assert!("close >".parse::<Filter>().is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, str::FromStr};
+use ustr::Ustr;
+
+/// Scanner market, the `{market}` part of `scanner.tradingview.com/{market}/scan`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreenerMarket {
+    #[default]
+    America,
+    Crypto,
+    Coin,
+    Forex,
+    Futures,
+    Cfd,
+    Bond,
+    /// Option contracts, filtered per underlying with [`IndexFilter`].
+    Options,
+    Global,
+    /// Any other scanner market, e.g. `uk`, `germany` or `india`.
+    Other(Ustr),
+}
+
+impl std::fmt::Display for ScreenerMarket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let market = match self {
+            ScreenerMarket::America => "america",
+            ScreenerMarket::Crypto => "crypto",
+            ScreenerMarket::Coin => "coin",
+            ScreenerMarket::Forex => "forex",
+            ScreenerMarket::Futures => "futures",
+            ScreenerMarket::Cfd => "cfd",
+            ScreenerMarket::Bond => "bond",
+            ScreenerMarket::Options => "options",
+            ScreenerMarket::Global => "global",
+            ScreenerMarket::Other(market) => market.as_str(),
+        };
+        write!(f, "{market}")
+    }
+}
+
+impl Serialize for ScreenerMarket {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperation {
+    Greater,
+    #[serde(rename = "egreater")]
+    GreaterOrEqual,
+    Less,
+    #[serde(rename = "eless")]
+    LessOrEqual,
+    Equal,
+    #[serde(rename = "nequal")]
+    NotEqual,
+    InRange,
+    NotInRange,
+    Crosses,
+    CrossesAbove,
+    CrossesBelow,
+    /// Substring match on text columns.
+    Match,
+    Empty,
+    #[serde(rename = "nempty")]
+    NotEmpty,
+}
+
+/// One scanner filter, `left operation right`.
+///
+/// `right` may be a literal or the name of another column, e.g.
+/// `close > SMA200` compares two columns of the same row.
+///
+/// # Examples
+///
+/// ```rust
+/// use tradingview::screener::{Filter, FilterOperation};
+///
+/// let filter: Filter = "close > SMA200".parse().unwrap();
+/// assert_eq!(filter.operation, FilterOperation::Greater);
+/// assert_eq!(filter.right, "SMA200");
+///
+/// let filter: Filter = "volume >= 1e6".parse().unwrap();
+/// assert_eq!(filter.right, 1e6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Filter {
+    pub left: Ustr,
+    pub operation: FilterOperation,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub right: Value,
+}
+
+impl Filter {
+    pub fn new(left: &str, operation: FilterOperation, right: impl Into<Value>) -> Self {
+        Self {
+            left: Ustr::from(left),
+            operation,
+            right: right.into(),
+        }
+    }
+
+    /// `left` between `low` and `high`, inclusive.
+    pub fn in_range(left: &str, low: impl Into<Value>, high: impl Into<Value>) -> Self {
+        Self::new(
+            left,
+            FilterOperation::InRange,
+            Value::Array(vec![low.into(), high.into()]),
+        )
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    /// Parse `left <op> right` with `>`, `>=`, `<`, `<=`, `==`, `!=`, `crosses`,
+    /// `crosses_above`, `crosses_below` or `match` as operator.
+    fn from_str(expr: &str) -> Result<Self> {
+        let invalid = || Error::Internal(Ustr::from(&format!("invalid screener filter: {expr}")));
+        let mut parts = expr.split_whitespace();
+        let (Some(left), Some(op), Some(right), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let operation = match op {
+            ">" => FilterOperation::Greater,
+            ">=" => FilterOperation::GreaterOrEqual,
+            "<" => FilterOperation::Less,
+            "<=" => FilterOperation::LessOrEqual,
+            "=" | "==" => FilterOperation::Equal,
+            "!=" => FilterOperation::NotEqual,
+            "crosses" => FilterOperation::Crosses,
+            "crosses_above" => FilterOperation::CrossesAbove,
+            "crosses_below" => FilterOperation::CrossesBelow,
+            "match" => FilterOperation::Match,
+            _ => return Err(invalid()),
+        };
+        let right = match right {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            number => number
+                .parse::<f64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::from(number.trim_matches(['"', '\'']))),
+        };
+        Ok(Self::new(left, operation, right))
+    }
+}
+
+/// Index lookup done before the filters run, e.g. every option of an
+/// `underlying_symbol`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexFilter {
+    pub name: Ustr,
+    pub values: Vec<Ustr>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[serde(rename = "asc")]
+    Ascending,
+    #[default]
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sort {
+    pub sort_by: Ustr,
+    pub sort_order: SortOrder,
+}
+
+/// One scanned symbol with its requested columns.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenerRow {
+    /// `EXCHANGE:SYMBOL`
+    pub symbol: Ustr,
+    pub values: HashMap<Ustr, Value>,
+}
+
+impl ScreenerRow {
+    pub fn get(&self, column: &str) -> Option<&Value> {
+        self.values.get(&Ustr::from(column))
+    }
+
+    pub fn f64(&self, column: &str) -> Option<f64> {
+        self.get(column).and_then(Value::as_f64)
+    }
+
+    pub fn str(&self, column: &str) -> Option<&str> {
+        self.get(column).and_then(Value::as_str)
+    }
+
+    /// Deserialize the row into `T`, with the symbol available as `symbol`
+    /// and each column under its scanner name.
+    pub fn typed<T: DeserializeOwned>(&self) -> Result<T> {
+        let mut map: Map<String, Value> = self
+            .values
+            .iter()
+            .map(|(column, value)| (column.to_string(), value.clone()))
+            .collect();
+        map.insert("symbol".to_string(), Value::from(self.symbol.as_str()));
+        Ok(serde_json::from_value(Value::Object(map))?)
+    }
+}
+
+/// One page of scanner results.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenerResult {
+    /// Number of symbols matching the filters, across all pages.
+    pub total_count: u64,
+    pub rows: Vec<ScreenerRow>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct ScanResponse {
+    pub total_count: u64,
+    pub data: Vec<ScanRow>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ScanRow {
+    pub s: Ustr,
+    pub d: Vec<Value>,
+}
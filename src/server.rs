@@ -0,0 +1,548 @@
+//! HTTP gateway exposing the live pipeline to non-Rust services, behind the
+//! `server` feature.
+//!
+//! - `GET /ohlcv?symbol=NASDAQ:AAPL&interval=1d&bars=300` returns bars as
+//!   JSON, see [`fetch_ohlcv`].
+//! - `GET /indicator?symbol=NASDAQ:AAPL&interval=1d&script_id=STD;RSI`
+//!   returns the study updates received until the study completed.
+//! - `POST /commands` queues a [`Command`], as JSON, on the shared connection.
+//! - `GET /events` streams every [`TradingViewResponse`] of the shared
+//!   connection as server-sent events, one JSON object per event.
+//! - `GET /ws` streams the same events over a websocket, which also takes
+//!   [`Command`]s as JSON text frames.
+//!
+//! Intervals are short names such as `1h` and `1d` or resolutions such as
+//! `1H` and `240`, anything else is answered with `400`, as are more than
+//! [`MAX_BARS`] bars. Commands run on the operator's account, so they are
+//! refused unless the gateway was built with `accept_commands`, and a
+//! `bearer_token` protects every route.
+//!
+//! Events are serialized once and shared by every subscriber. Subscribers
+//! falling more than `capacity` events behind skip the ones they missed.
+
+use crate::{
+    CancellationToken, ChartOptions, DataPoint, Error, Interval, Result, StudyOptions,
+    StudyResponseData,
+    error::TradingViewError,
+    fetch_ohlcv,
+    live::{
+        handler::{
+            message::{Command, TradingViewResponse},
+            stream::{connect, create_stream},
+            types::CommandTx,
+        },
+        models::DataServer,
+        websocket::WebSocketClient,
+    },
+    pine_indicator::ScriptType,
+};
+use axum::{
+    Json, Router,
+    extract::Request,
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{StatusCode, header::AUTHORIZATION},
+    middleware::{self, Next},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post},
+};
+use bon::bon;
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::{convert::Infallible, sync::Arc, time::Duration};
+use tokio::{
+    net::{TcpListener, ToSocketAddrs},
+    sync::broadcast::{self, error::RecvError},
+};
+use tracing::{debug, info, warn};
+use ustr::{Ustr, ustr};
+
+/// Time an `/indicator` request waits for its study to complete.
+const STUDY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Most bars an `/ohlcv` or `/indicator` request may ask for.
+pub const MAX_BARS: u64 = 20_000;
+
+/// Shared connection and event fan-out behind the HTTP routes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::server::Gateway;
+///
+/// #[tokio::main]
+/// async fn main() -> tradingview::Result<()> {
+///     let gateway = Gateway::connect().call().await?;
+///     gateway.serve("127.0.0.1:8080").await
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Gateway {
+    commands: CommandTx,
+    events: broadcast::Sender<Arc<str>>,
+    auth_token: Option<Ustr>,
+    bearer_token: Option<Ustr>,
+    accept_commands: bool,
+    cancel: CancellationToken,
+}
+
+#[bon]
+impl Gateway {
+    /// Open the shared connection whose events are fanned out.
+    #[builder]
+    pub async fn connect(
+        auth_token: Option<&str>,
+        #[builder(default = DataServer::ProData)] server: DataServer,
+        /// Events buffered per subscriber, `1024` by default.
+        #[builder(default = 1024)]
+        capacity: usize,
+        /// Cancelling this token stops the server and closes the connection.
+        cancellation_token: Option<&CancellationToken>,
+        /// Required as `Authorization: Bearer <token>` on every route, which
+        /// are open to anyone reaching the port otherwise.
+        bearer_token: Option<&str>,
+        /// Queue commands from `POST /commands` and `/ws` on the shared
+        /// connection. Off by default, since they run on the operator's
+        /// account.
+        #[builder(default)]
+        accept_commands: bool,
+    ) -> Result<Self> {
+        let cancel = cancellation_token
+            .map(CancellationToken::child_token)
+            .unwrap_or_default();
+        let (events, commands) = connect()
+            .maybe_auth_token(auth_token)
+            .server(server)
+            .cancellation_token(&cancel)
+            .call()
+            .await?;
+        let mut gateway = Self::new(
+            commands.into_inner(),
+            events,
+            capacity,
+            auth_token.map(Ustr::from),
+            cancel,
+        );
+        gateway.bearer_token = bearer_token.map(Ustr::from);
+        gateway.accept_commands = accept_commands;
+        Ok(gateway)
+    }
+}
+
+impl Gateway {
+    fn new(
+        commands: CommandTx,
+        events: impl Stream<Item = TradingViewResponse> + Send + 'static,
+        capacity: usize,
+        auth_token: Option<Ustr>,
+        cancel: CancellationToken,
+    ) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        tokio::spawn({
+            let tx = tx.clone();
+            async move {
+                let mut events = std::pin::pin!(events);
+                while let Some(response) = events.next().await {
+                    // Serialize once, however many subscribers there are
+                    match serde_json::to_string(&response) {
+                        Ok(json) => {
+                            // No receivers only means nobody is subscribed
+                            let _ = tx.send(Arc::from(json));
+                        }
+                        Err(e) => warn!("failed to serialize event: {e}"),
+                    }
+                }
+                debug!("gateway event stream ended");
+            }
+        });
+        Self {
+            commands,
+            events: tx,
+            auth_token,
+            bearer_token: None,
+            accept_commands: false,
+            cancel,
+        }
+    }
+
+    /// Routes of the gateway, to serve directly or nest into another router.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/ohlcv", get(ohlcv))
+            .route("/indicator", get(indicator))
+            .route("/commands", post(command))
+            .route("/events", get(events))
+            .route("/ws", get(websocket))
+            .route_layer(middleware::from_fn_with_state(self.clone(), authorize))
+            .with_state(self.clone())
+    }
+
+    /// Serve [`Gateway::router`] on `addr` until the cancellation token given
+    /// to [`Gateway::connect`] is cancelled.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        info!("gateway listening on {}", listener.local_addr()?);
+        let shutdown = self.cancel.clone().cancelled_owned();
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(shutdown)
+            .await?;
+        Ok(())
+    }
+
+    /// JSON of every event from now on.
+    fn subscribe(&self) -> impl Stream<Item = Arc<str>> + Send + use<> {
+        futures_util::stream::unfold(self.events.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(json) => return Some((json, rx)),
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("gateway subscriber skipped {missed} events");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    fn send(&self, command: Command) -> std::result::Result<(), ApiError> {
+        if !self.accept_commands {
+            return Err(ApiError::Rejected(
+                StatusCode::FORBIDDEN,
+                "the gateway does not accept commands".into(),
+            ));
+        }
+        self.commands
+            .send(command)
+            .map_err(|_| ApiError::Failed(Error::Cancelled))
+    }
+}
+
+/// Reject requests without the gateway's bearer token, if it has one.
+async fn authorize(State(gateway): State<Gateway>, request: Request, next: Next) -> Response {
+    if let Some(token) = gateway.bearer_token {
+        let given = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if !constant_time_eq(given.as_bytes(), token.as_bytes()) {
+            return ApiError::Rejected(
+                StatusCode::UNAUTHORIZED,
+                "missing or wrong bearer token".into(),
+            )
+            .into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Compare without returning early, so the time taken does not tell how much
+/// of the token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Failure of a route, answered with a `{"error": ..}` body.
+#[derive(Debug, thiserror::Error)]
+enum ApiError {
+    /// The request failed, the status depends on the error.
+    #[error(transparent)]
+    Failed(#[from] Error),
+    /// The request was refused before doing anything.
+    #[error("{1}")]
+    Rejected(StatusCode, String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::Failed(
+                Error::TradingView { .. } | Error::Config(_) | Error::InvalidStudyInput(_),
+            ) => StatusCode::BAD_REQUEST,
+            Self::Failed(Error::Timeout(_)) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Failed(Error::Cancelled) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Failed(_) => StatusCode::BAD_GATEWAY,
+            Self::Rejected(status, _) => status,
+        };
+        let body = Json(json!({ "error": self.to_string() }));
+        (status, body).into_response()
+    }
+}
+
+/// `interval` as a short name such as `1h` or `1d`, or a resolution such as
+/// `1H` or `240`.
+fn parse_interval(interval: &str) -> std::result::Result<Interval, ApiError> {
+    Interval::from_name(interval)
+        .or_else(|| interval.parse().ok())
+        .ok_or_else(|| {
+            ApiError::Rejected(
+                StatusCode::BAD_REQUEST,
+                format!("unknown interval: {interval}"),
+            )
+        })
+}
+
+fn check_bars(bars: u64) -> std::result::Result<u64, ApiError> {
+    if bars > MAX_BARS {
+        return Err(ApiError::Rejected(
+            StatusCode::BAD_REQUEST,
+            format!("bars must be at most {MAX_BARS}"),
+        ));
+    }
+    Ok(bars)
+}
+
+fn default_interval() -> String {
+    "1d".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct OhlcvQuery {
+    /// `EXCHANGE:SYMBOL`.
+    symbol: String,
+    #[serde(default = "default_interval")]
+    interval: String,
+    #[serde(default = "default_bars")]
+    bars: u64,
+}
+
+fn default_bars() -> u64 {
+    500
+}
+
+async fn ohlcv(
+    State(gateway): State<Gateway>,
+    Query(query): Query<OhlcvQuery>,
+) -> std::result::Result<Json<Vec<DataPoint>>, ApiError> {
+    let interval = parse_interval(&query.interval)?;
+    let bars = check_bars(query.bars)?;
+    let auth_token = gateway.auth_token.as_deref();
+    let bars = fetch_ohlcv(&query.symbol, interval, bars, auth_token).await?;
+    Ok(Json(bars))
+}
+
+#[derive(Debug, Deserialize)]
+struct IndicatorQuery {
+    /// `EXCHANGE:SYMBOL`.
+    symbol: String,
+    #[serde(default = "default_interval")]
+    interval: String,
+    #[serde(default = "default_bars")]
+    bars: u64,
+    /// e.g. `STD;RSI` or `PUB;...`.
+    script_id: Ustr,
+    script_version: Option<Ustr>,
+    script_type: Option<ScriptType>,
+}
+
+async fn indicator(
+    State(gateway): State<Gateway>,
+    Query(query): Query<IndicatorQuery>,
+) -> std::result::Result<Json<Vec<StudyResponseData>>, ApiError> {
+    let (exchange, symbol) = query.symbol.split_once(':').ok_or(Error::TradingView {
+        source: TradingViewError::MissingExchange,
+    })?;
+    let options = ChartOptions::builder()
+        .symbol(symbol.into())
+        .exchange(exchange.into())
+        .interval(parse_interval(&query.interval)?)
+        .bar_count(check_bars(query.bars)?)
+        .study_config(StudyOptions {
+            script_id: query.script_id,
+            script_version: query.script_version.unwrap_or_else(|| ustr("last")),
+            script_type: query.script_type.unwrap_or(ScriptType::IntervalScript),
+        })
+        .build();
+    let data = fetch_study(gateway.auth_token.as_deref(), options).await?;
+    Ok(Json(data))
+}
+
+/// Open a connection for `options` and collect the study updates until the
+/// study completed.
+async fn fetch_study(
+    auth_token: Option<&str>,
+    options: ChartOptions,
+) -> Result<Vec<StudyResponseData>> {
+    let (data_tx, mut events) = create_stream();
+    let ws = WebSocketClient::builder()
+        .maybe_auth_token(auth_token)
+        .data_tx(data_tx)
+        .build()
+        .await?;
+    Arc::clone(&ws).spawn_reader_task();
+
+    let collect = async {
+        ws.set_market(options).await?;
+        let mut data = Vec::new();
+        while let Some(response) = events.next().await {
+            match response {
+                TradingViewResponse::StudyData(_, update) => data.push(update),
+                TradingViewResponse::StudyCompleted(_) => return Ok(data),
+                TradingViewResponse::Error(error, _) => return Err(error),
+                _ => {}
+            }
+        }
+        Err(Error::Cancelled)
+    };
+    let result = tokio::time::timeout(STUDY_TIMEOUT, collect).await;
+    if let Err(e) = ws.close().await {
+        debug!("failed to close study connection: {e}");
+    }
+    result.map_err(|_| Error::Timeout(ustr("study did not complete")))?
+}
+
+async fn command(
+    State(gateway): State<Gateway>,
+    Json(command): Json<Command>,
+) -> std::result::Result<StatusCode, ApiError> {
+    gateway.send(command)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn events(
+    State(gateway): State<Gateway>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let events = gateway
+        .subscribe()
+        .map(|json| Ok(Event::default().data(&*json)));
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn websocket(State(gateway): State<Gateway>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| serve_websocket(gateway, socket))
+}
+
+async fn serve_websocket(gateway: Gateway, socket: WebSocket) {
+    let (mut write, mut read) = socket.split();
+    let mut events = std::pin::pin!(gateway.subscribe());
+    loop {
+        tokio::select! {
+            Some(json) = events.next() => {
+                if write.send(Message::Text(json.to_string().into())).await.is_err() {
+                    break;
+                }
+            }
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<Command>(text.as_str()) {
+                        Ok(command) => {
+                            if let Err(e) = gateway.send(command) {
+                                warn!("failed to queue websocket command: {e}");
+                            }
+                        }
+                        Err(e) => warn!("invalid websocket command: {e}"),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = gateway.cancel.cancelled() => break,
+        }
+    }
+    debug!("gateway websocket closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live::handler::message::ReplayOk;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_gateway_routes() {
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let events = crate::live::handler::stream::ResponseStream::new(event_rx);
+        let mut gateway = Gateway::new(command_tx, events, 16, None, CancellationToken::new());
+        gateway.accept_commands = true;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let router = gateway.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let http = reqwest::Client::new();
+
+        let accepted = http
+            .post(format!("{base}/commands"))
+            .json(&json!("Ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(accepted.status(), reqwest::StatusCode::ACCEPTED);
+        assert!(matches!(command_rx.recv().await, Some(Command::Ping)));
+
+        let missing_exchange = http
+            .get(format!("{base}/ohlcv?symbol=AAPL"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing_exchange.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let mut sse = http.get(format!("{base}/events")).send().await.unwrap();
+        event_tx
+            .send(TradingViewResponse::ReplayOk(ReplayOk {
+                session: Ustr::from("rs_test"),
+                request_id: None,
+            }))
+            .unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(5), sse.chunk())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let chunk = String::from_utf8_lossy(&chunk);
+        assert!(chunk.starts_with("data: "), "{chunk}");
+        assert!(chunk.contains(r#""ReplayOk""#), "{chunk}");
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_requests() {
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let (_event_tx, event_rx) = mpsc::unbounded_channel();
+        let events = crate::live::handler::stream::ResponseStream::new(event_rx);
+        let mut gateway = Gateway::new(command_tx, events, 16, None, CancellationToken::new());
+        gateway.bearer_token = Some(Ustr::from("secret"));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let router = gateway.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let http = reqwest::Client::new();
+        let status = |request: reqwest::RequestBuilder| async move {
+            request.send().await.unwrap().status()
+        };
+
+        let ping = || http.post(format!("{base}/commands")).json(&json!("Ping"));
+        assert_eq!(status(ping()).await, reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(ping().bearer_auth("wrong")).await,
+            reqwest::StatusCode::UNAUTHORIZED
+        );
+        // Commands are opt-in
+        assert_eq!(
+            status(ping().bearer_auth("secret")).await,
+            reqwest::StatusCode::FORBIDDEN
+        );
+        assert!(command_rx.try_recv().is_err());
+
+        for query in [
+            "symbol=NASDAQ:AAPL&interval=2d",
+            "symbol=NASDAQ:AAPL&interval=1d&bars=1000000",
+        ] {
+            let ohlcv = http
+                .get(format!("{base}/ohlcv?{query}"))
+                .bearer_auth("secret");
+            assert_eq!(status(ohlcv).await, reqwest::StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(parse_interval("1M").unwrap(), Interval::OneMonth);
+        assert_eq!(parse_interval("1m").unwrap(), Interval::OneMinute);
+        assert_eq!(parse_interval("240").unwrap(), Interval::FourHours);
+    }
+}
@@ -0,0 +1,101 @@
+//! Counters, gauges and histograms emitted through the [`metrics`] crate
+//! when the `metrics` feature is enabled, and compiled out otherwise.
+//!
+//! Install any `metrics` recorder, e.g. `metrics-exporter-prometheus`, and
+//! call `describe` once to register units and help texts. The latency
+//! histogram relies on `std::time::Instant`, so the feature is not supported
+//! on `wasm32-unknown-unknown`.
+//!
+//! [`metrics`]: https://docs.rs/metrics
+
+/// Counter of parsed server messages, labelled by `event`.
+pub const EVENTS_RECEIVED: &str = "tradingview_events_received_total";
+/// Counter of server messages whose handling failed, labelled by `event`.
+pub const EVENT_ERRORS: &str = "tradingview_event_errors_total";
+/// Counter of frames that could not be parsed at all.
+pub const PARSE_FAILURES: &str = "tradingview_parse_failures_total";
+/// Histogram of the seconds spent handling a message, callbacks included,
+/// labelled by `event`.
+pub const HANDLER_LATENCY: &str = "tradingview_handler_latency_seconds";
+/// Gauge of responses waiting in a bounded response channel.
+pub const QUEUE_DEPTH: &str = "tradingview_queue_depth";
+/// Counter of responses dropped by a full response channel.
+pub const QUEUE_DROPPED: &str = "tradingview_queue_dropped_total";
+/// Counter of reconnects, labelled by `outcome`, `ok` or `error`.
+pub const RECONNECTS: &str = "tradingview_reconnects_total";
+
+/// Register the unit and description of every metric above.
+#[cfg(feature = "metrics")]
+pub fn describe() {
+    use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+
+    describe_counter!(EVENTS_RECEIVED, "Server messages received per event type");
+    describe_counter!(EVENT_ERRORS, "Server messages whose handling failed");
+    describe_counter!(PARSE_FAILURES, "Frames that could not be parsed");
+    describe_histogram!(
+        HANDLER_LATENCY,
+        Unit::Seconds,
+        "Time spent handling a message, callbacks included"
+    );
+    describe_gauge!(QUEUE_DEPTH, "Responses queued in the response channel");
+    describe_counter!(
+        QUEUE_DROPPED,
+        "Responses dropped by a full response channel"
+    );
+    describe_counter!(RECONNECTS, "Websocket reconnects");
+}
+
+/// Times the handling of one server message.
+pub(crate) struct EventTimer {
+    #[cfg(feature = "metrics")]
+    started: std::time::Instant,
+}
+
+impl EventTimer {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    pub(crate) fn finish(self, event: &'static str, failed: bool) {
+        #[cfg(feature = "metrics")]
+        {
+            let elapsed = self.started.elapsed().as_secs_f64();
+            metrics::counter!(EVENTS_RECEIVED, "event" => event).increment(1);
+            metrics::histogram!(HANDLER_LATENCY, "event" => event).record(elapsed);
+            if failed {
+                metrics::counter!(EVENT_ERRORS, "event" => event).increment(1);
+            }
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = (event, failed);
+    }
+}
+
+pub(crate) fn parse_failed() {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(PARSE_FAILURES).increment(1);
+}
+
+pub(crate) fn queue_depth(depth: usize) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(QUEUE_DEPTH).set(depth as f64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = depth;
+}
+
+pub(crate) fn queue_dropped(count: u64) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(QUEUE_DROPPED).increment(count);
+    #[cfg(not(feature = "metrics"))]
+    let _ = count;
+}
+
+pub(crate) fn reconnected(ok: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(RECONNECTS, "outcome" => if ok { "ok" } else { "error" }).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = ok;
+}
@@ -0,0 +1,375 @@
+//! Pluggable connections for the REST and websocket clients.
+//!
+//! An [`HttpTransport`] replaces the HTTP client of every REST request. A
+//! `WebSocketTransport` only replaces how the socket of a
+//! [`crate::websocket::WebSocketClient`] is opened: its frames stay
+//! `tungstenite` messages and the client itself still runs on tokio. Other
+//! runtimes need a tungstenite binding and a tokio compatibility layer.
+
+use crate::Result;
+use futures_util::future::BoxFuture;
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "native")]
+pub use native::*;
+
+lazy_static::lazy_static! {
+    static ref HTTP_TRANSPORT: RwLock<Option<Arc<dyn HttpTransport>>> = RwLock::new(None);
+}
+
+/// Sends the REST requests of this crate in place of reqwest's tokio based
+/// client, e.g. to run them on async-std or smol.
+///
+/// Requests arrive complete with the TradingView headers and session cookie.
+/// A transport built on another HTTP client can hand its answer back through
+/// `reqwest::Response::from(http::Response<Vec<u8>>)`.
+pub trait HttpTransport: Send + Sync {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>>;
+}
+
+/// Send every REST request through `transport`, or reqwest's own client when `None`.
+pub fn set_http_transport(transport: Option<Arc<dyn HttpTransport>>) {
+    *HTTP_TRANSPORT.write().unwrap_or_else(|e| e.into_inner()) = transport;
+}
+
+/// The transport installed with [`set_http_transport`].
+pub fn http_transport() -> Option<Arc<dyn HttpTransport>> {
+    HTTP_TRANSPORT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+#[cfg(feature = "native")]
+mod native {
+    use crate::{Error, Result, config::ProxyConfig};
+    use futures_util::{Sink, SinkExt, Stream, StreamExt, future::BoxFuture};
+    use std::pin::Pin;
+    #[cfg(feature = "rustls-tls")]
+    use std::sync::Arc;
+    use tokio_tungstenite::{
+        Connector, client_async_tls_with_config, connect_async_tls_with_config,
+        tungstenite::{
+            handshake::client::Request,
+            protocol::{Message, WebSocketConfig},
+        },
+    };
+    use tracing::{debug, info};
+
+    /// Write half of a websocket opened by a [`WebSocketTransport`].
+    pub type FrameSink = Pin<Box<dyn Sink<Message, Error = Error> + Send>>;
+    /// Read half of a websocket opened by a [`WebSocketTransport`].
+    pub type FrameStream = Pin<Box<dyn Stream<Item = Result<Message>> + Send>>;
+
+    /// Opens the websocket of a [`crate::websocket::WebSocketClient`], on
+    /// connect and on every reconnect.
+    ///
+    /// Frames are `tungstenite` messages, so runtimes with their own
+    /// tungstenite binding such as `async-tungstenite` only need to wrap
+    /// their halves. The client's timers and reader task still run on tokio,
+    /// on other executors through a compatibility layer like `async-compat`.
+    pub trait WebSocketTransport: Send + Sync {
+        /// Connect with `request`, which already carries the TradingView
+        /// headers, through `proxy` if one is configured.
+        fn connect<'a>(
+            &'a self,
+            request: Request,
+            proxy: Option<&'a ProxyConfig>,
+        ) -> BoxFuture<'a, Result<(FrameSink, FrameStream)>>;
+    }
+
+    /// Default transport on `tokio-tungstenite`.
+    #[derive(Debug, Default, Clone)]
+    pub struct TokioTransport {
+        #[cfg(feature = "rustls-tls")]
+        tls: Option<Arc<rustls::ClientConfig>>,
+        #[cfg(feature = "deflate")]
+        compression: bool,
+    }
+
+    impl TokioTransport {
+        /// Negotiate TLS with `config`, e.g. one built by
+        /// [`crate::config::TlsConfig::client_config`], instead of the
+        /// bundled webpki roots.
+        #[cfg(feature = "rustls-tls")]
+        pub fn with_rustls(config: Arc<rustls::ClientConfig>) -> Self {
+            Self {
+                tls: Some(config),
+                #[cfg(feature = "deflate")]
+                compression: false,
+            }
+        }
+
+        /// Offer permessage-deflate, so the server compresses the frames it
+        /// sends if it supports the extension. Frames sent by the client are
+        /// not compressed.
+        #[cfg(feature = "deflate")]
+        pub fn with_compression(mut self, compression: bool) -> Self {
+            self.compression = compression;
+            self
+        }
+
+        fn connector(&self) -> Option<Connector> {
+            #[cfg(feature = "rustls-tls")]
+            if let Some(config) = &self.tls {
+                return Some(Connector::Rustls(Arc::clone(config)));
+            }
+            None
+        }
+    }
+
+    #[cfg(feature = "deflate")]
+    impl TokioTransport {
+        /// Open the socket and TLS session here, since the frames have to be
+        /// inflated between TLS and tungstenite.
+        async fn connect_compressed(
+            &self,
+            mut request: Request,
+            proxy: Option<&ProxyConfig>,
+            conf: WebSocketConfig,
+        ) -> Result<(FrameSink, FrameStream)> {
+            use crate::live::deflate::{InflateStream, OFFER};
+            use tokio_tungstenite::{
+                MaybeTlsStream, client_async_with_config,
+                tungstenite::http::header::{HeaderValue, SEC_WEBSOCKET_EXTENSIONS},
+            };
+
+            request
+                .headers_mut()
+                .insert(SEC_WEBSOCKET_EXTENSIONS, HeaderValue::from_static(OFFER));
+            let uri = request.uri();
+            let host = uri.host().unwrap_or_default().to_owned();
+            let secure = uri.scheme_str() != Some("ws");
+            let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+            let stream = match proxy {
+                Some(proxy) => {
+                    debug!("Connecting to {} through proxy", host);
+                    crate::live::proxy::connect(proxy, &host, port).await?
+                }
+                None => tokio::net::TcpStream::connect((host.as_str(), port)).await?,
+            };
+            let stream = if secure {
+                let config = match &self.tls {
+                    Some(config) => Arc::clone(config),
+                    None => crate::config::TlsConfig::default().client_config()?,
+                };
+                let name = rustls::pki_types::ServerName::try_from(host)
+                    .map_err(|e| Error::Config(ustr::Ustr::from(&format!("TLS: {e}"))))?;
+                let stream = tokio_rustls::TlsConnector::from(config)
+                    .connect(name, stream)
+                    .await?;
+                MaybeTlsStream::Rustls(stream)
+            } else {
+                MaybeTlsStream::Plain(stream)
+            };
+
+            let (socket, response) =
+                client_async_with_config(request, InflateStream::new(stream), Some(conf)).await?;
+            info!(
+                "WebSocket connected with status: {}, compressed: {}",
+                response.status(),
+                response.headers().contains_key(SEC_WEBSOCKET_EXTENSIONS)
+            );
+
+            let (write, read) = socket.split();
+            let write: FrameSink = Box::pin(write.sink_map_err(Error::from));
+            let read: FrameStream = Box::pin(read.map(|frame| frame.map_err(Error::from)));
+            Ok((write, read))
+        }
+    }
+
+    impl WebSocketTransport for TokioTransport {
+        fn connect<'a>(
+            &'a self,
+            request: Request,
+            proxy: Option<&'a ProxyConfig>,
+        ) -> BoxFuture<'a, Result<(FrameSink, FrameStream)>> {
+            Box::pin(async move {
+                // Configure WebSocket with larger message size limits
+                let conf = WebSocketConfig::default()
+                    .read_buffer_size(1024 * 1024)
+                    .write_buffer_size(1024 * 1024);
+
+                #[cfg(feature = "deflate")]
+                if self.compression {
+                    return self.connect_compressed(request, proxy, conf).await;
+                }
+
+                let (socket, response) = match proxy {
+                    Some(proxy) => {
+                        let host = request.uri().host().unwrap_or_default().to_owned();
+                        let port = request.uri().port_u16().unwrap_or(443);
+                        debug!("Connecting to {} through proxy", host);
+                        let stream = crate::live::proxy::connect(proxy, &host, port).await?;
+                        client_async_tls_with_config(request, stream, Some(conf), self.connector())
+                            .await?
+                    }
+                    None => {
+                        connect_async_tls_with_config(request, Some(conf), false, self.connector())
+                            .await?
+                    }
+                };
+
+                info!("WebSocket connected with status: {}", response.status());
+
+                let (write, read) = socket.split();
+                let write: FrameSink = Box::pin(write.sink_map_err(Error::from));
+                let read: FrameStream = Box::pin(read.map(|frame| frame.map_err(Error::from)));
+                Ok((write, read))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::send_through;
+    use std::sync::Mutex;
+
+    /// Answers every request with `ok` and keeps it for inspection.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<reqwest::Request>>);
+
+    impl HttpTransport for Recorder {
+        fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+            self.0
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(request);
+            Box::pin(async { Ok(http::Response::new("ok").into()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_transport_gets_complete_requests() {
+        let transport = Recorder::default();
+        let request = reqwest::Client::new().get("https://www.tradingview.com/");
+        let response = send_through(&transport, request, Some("sessionid=abc"))
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let requests = transport.0.lock().unwrap();
+        let headers = requests[0].headers();
+        assert_eq!(headers["cookie"], "sessionid=abc");
+        assert_eq!(headers["user-agent"], crate::UA);
+        assert_eq!(headers["origin"], "https://www.tradingview.com");
+    }
+
+    #[cfg(all(feature = "native", feature = "quote"))]
+    #[tokio::test]
+    async fn test_websocket_transport_carries_frames() {
+        use crate::{
+            Error,
+            config::ProxyConfig,
+            live::mock::{MockScript, MockServer},
+            websocket::WebSocketClient,
+        };
+        use futures_util::{SinkExt, future};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio_tungstenite::tungstenite::{Message, handshake::client::Request};
+
+        /// Opens sockets through the tokio transport, counting connections and sent frames.
+        #[derive(Default)]
+        struct Counting {
+            connects: AtomicUsize,
+            sent: Arc<AtomicUsize>,
+        }
+
+        impl WebSocketTransport for Counting {
+            fn connect<'a>(
+                &'a self,
+                request: Request,
+                proxy: Option<&'a ProxyConfig>,
+            ) -> BoxFuture<'a, Result<(FrameSink, FrameStream)>> {
+                self.connects.fetch_add(1, Ordering::Relaxed);
+                let sent = Arc::clone(&self.sent);
+                Box::pin(async move {
+                    let (write, read) = TokioTransport::default().connect(request, proxy).await?;
+                    let write: FrameSink = Box::pin(write.with(move |frame: Message| {
+                        sent.fetch_add(1, Ordering::Relaxed);
+                        future::ready(Ok::<_, Error>(frame))
+                    }));
+                    Ok((write, read))
+                })
+            }
+        }
+
+        let server = MockServer::start(MockScript::new()).await.unwrap();
+        let transport = Arc::new(Counting::default());
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let ws = WebSocketClient::builder()
+            .data_tx(tx)
+            .endpoint(server.url())
+            .transport(Arc::clone(&transport) as Arc<dyn WebSocketTransport>)
+            .build()
+            .await
+            .unwrap();
+        ws.add_symbols(&["NASDAQ:AAPL"]).await.unwrap();
+
+        assert_eq!(transport.connects.load(Ordering::Relaxed), 1);
+        // quote_create_session, quote_set_fields and quote_add_symbols
+        assert_eq!(transport.sent.load(Ordering::Relaxed), 3);
+        ws.close().await.unwrap();
+    }
+
+    #[cfg(feature = "deflate")]
+    #[tokio::test]
+    async fn test_tokio_transport_inflates_frames() {
+        use flate2::{Compress, Compression, FlushCompress};
+        use futures_util::StreamExt;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_tungstenite::tungstenite::{
+            Message, client::IntoClientRequest, handshake::derive_accept_key,
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(socket.read_u8().await.unwrap());
+            }
+            let request = String::from_utf8(request).unwrap();
+            let key = request
+                .lines()
+                .filter_map(|line| line.split_once(": "))
+                .find(|(name, _)| name.eq_ignore_ascii_case("sec-websocket-key"))
+                .unwrap()
+                .1;
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\n\
+                 Upgrade: websocket\r\nSec-WebSocket-Accept: {}\r\n\
+                 Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n",
+                derive_accept_key(key.as_bytes())
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            let mut payload = Vec::with_capacity(64);
+            Compress::new(Compression::default(), false)
+                .compress_vec(b"~m~5~m~hello", &mut payload, FlushCompress::Sync)
+                .unwrap();
+            payload.truncate(payload.len() - 4);
+            let mut frame = vec![0x80 | 0x40 | 0x1, payload.len() as u8];
+            frame.extend(payload);
+            socket.write_all(&frame).await.unwrap();
+            request
+        });
+
+        let transport = TokioTransport::default().with_compression(true);
+        let request = url.as_str().into_client_request().unwrap();
+        let (_write, mut read) = transport.connect(request, None).await.unwrap();
+        assert_eq!(
+            read.next().await.unwrap().unwrap(),
+            Message::text("~m~5~m~hello")
+        );
+        let request = server.await.unwrap();
+        assert!(
+            request
+                .to_lowercase()
+                .contains("sec-websocket-extensions: permessage-deflate")
+        );
+    }
+}
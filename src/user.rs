@@ -2,14 +2,117 @@ pub use crate::models::UserCookies;
 use crate::{
     Result,
     error::{Error, LoginError},
-    utils::build_request,
+    utils::{build_request, execute},
 };
+use futures_util::future::BoxFuture;
 use google_authenticator::{GA_AUTH, get_code};
 use reqwest::{Response, header::CONTENT_TYPE};
 use serde::Deserialize;
 use serde_json::Value;
+use std::{future::Future, path::Path};
 use tracing::{debug, error, info, warn};
 
+/// Times a challenge is handed to the [`ChallengeResolver`] before giving up.
+const MAX_CHALLENGE_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChallengeKind {
+    /// reCAPTCHA shown after repeated or suspicious sign-ins.
+    Captcha,
+    /// Confirmation of an unknown device, usually with a code sent by email.
+    DeviceVerification,
+}
+
+/// Challenge TradingView answered a sign-in attempt with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginChallenge {
+    pub kind: ChallengeKind,
+    /// TradingView error code, e.g. `recaptcha_required`.
+    pub code: String,
+    /// Human readable message from TradingView.
+    pub message: String,
+    /// Full sign-in response, for resolvers that need more details.
+    pub raw: Value,
+}
+
+impl LoginChallenge {
+    /// Detect a challenge in a sign-in response, `None` for anything else.
+    pub fn from_response(response: &Value) -> Option<Self> {
+        let code = response.get("code").and_then(Value::as_str)?;
+        let kind = if code.contains("captcha") {
+            ChallengeKind::Captcha
+        } else if code.contains("verification") || code.contains("confirm") {
+            ChallengeKind::DeviceVerification
+        } else {
+            return None;
+        };
+        Some(Self {
+            kind,
+            code: code.to_string(),
+            message: response["error"].as_str().unwrap_or_default().to_string(),
+            raw: response.clone(),
+        })
+    }
+}
+
+/// Extra sign-in form fields answering a [`LoginChallenge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChallengeSolution {
+    pub fields: Vec<(String, String)>,
+}
+
+impl ChallengeSolution {
+    /// Answer a captcha with the token returned by the reCAPTCHA widget.
+    pub fn captcha(token: &str) -> Self {
+        Self::field("g-recaptcha-response", token)
+    }
+
+    pub fn field(name: &str, value: &str) -> Self {
+        Self {
+            fields: vec![(name.to_string(), value.to_string())],
+        }
+    }
+}
+
+/// Solves login challenges, by asking the user or through a solving service.
+///
+/// Implemented for closures returning a future.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tradingview::{
+///     UserCookies,
+///     user::{ChallengeSolution, LoginChallenge},
+/// };
+///
+/// # async fn run() -> tradingview::Result<()> {
+/// let resolver = |challenge: LoginChallenge| async move {
+///     println!("{}: paste the captcha token", challenge.message);
+///     let mut token = String::new();
+///     std::io::stdin().read_line(&mut token)?;
+///     Ok::<_, tradingview::Error>(ChallengeSolution::captcha(token.trim()))
+/// };
+/// let cookies = UserCookies::new()
+///     .login_with_resolver("user", "password", None, Some(&resolver))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ChallengeResolver: Send + Sync {
+    fn resolve(&self, challenge: &LoginChallenge) -> BoxFuture<'static, Result<ChallengeSolution>>;
+}
+
+impl<F, Fut> ChallengeResolver for F
+where
+    F: Fn(LoginChallenge) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<ChallengeSolution>> + Send + 'static,
+{
+    fn resolve(&self, challenge: &LoginChallenge) -> BoxFuture<'static, Result<ChallengeSolution>> {
+        Box::pin(self(challenge.clone()))
+    }
+}
+
 impl UserCookies {
     pub fn new() -> Self {
         Default::default()
@@ -20,40 +123,85 @@ impl UserCookies {
         username: &str,
         password: &str,
         totp_secret: Option<&str>,
+    ) -> Result<Self> {
+        self.login_with_resolver(username, password, totp_secret, None)
+            .await
+    }
+
+    /// Like [`UserCookies::login`], handing captcha and device verification
+    /// challenges to `resolver` and resubmitting the sign-in with its solution.
+    pub async fn login_with_resolver(
+        &mut self,
+        username: &str,
+        password: &str,
+        totp_secret: Option<&str>,
+        resolver: Option<&dyn ChallengeResolver>,
     ) -> Result<Self> {
         let client = build_request(None)?;
-        let response = client
-            .post("https://www.tradingview.com/accounts/signin/")
-            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
-            .body(format!(
-                "username={username}&password={password}&remember=true"
-            ))
-            .send()
-            .await?;
+        let mut solution = ChallengeSolution::default();
+        let mut attempts = 0;
+        let (session, signature, device_token, response) = loop {
+            let mut form = vec![
+                ("username", username),
+                ("password", password),
+                ("remember", "true"),
+            ];
+            form.extend(
+                solution
+                    .fields
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str())),
+            );
+            let request = client
+                .post("https://www.tradingview.com/accounts/signin/")
+                .form(&form);
+            let response = execute(request, None).await?;
 
-        let (session, signature, device_token) =
-            response
-                .cookies()
-                .fold((None, None, None), |session_cookies, cookie| {
-                    match cookie.name() {
-                        "sessionid" => (
-                            Some(cookie.value().to_string()),
-                            session_cookies.1,
-                            session_cookies.2,
-                        ),
-                        "sessionid_sign" => (
-                            session_cookies.0,
-                            Some(cookie.value().to_string()),
-                            session_cookies.2,
-                        ),
-                        "device_t" => (
-                            session_cookies.0,
-                            session_cookies.1,
-                            Some(cookie.value().to_string()),
-                        ),
-                        _ => session_cookies,
-                    }
+            let (session, signature, device_token) =
+                response
+                    .cookies()
+                    .fold((None, None, None), |session_cookies, cookie| {
+                        match cookie.name() {
+                            "sessionid" => (
+                                Some(cookie.value().to_string()),
+                                session_cookies.1,
+                                session_cookies.2,
+                            ),
+                            "sessionid_sign" => (
+                                session_cookies.0,
+                                Some(cookie.value().to_string()),
+                                session_cookies.2,
+                            ),
+                            "device_t" => (
+                                session_cookies.0,
+                                session_cookies.1,
+                                Some(cookie.value().to_string()),
+                            ),
+                            _ => session_cookies,
+                        }
+                    });
+            let response: Value = response.json().await?;
+
+            let Some(challenge) = LoginChallenge::from_response(&response) else {
+                break (session, signature, device_token, response);
+            };
+            let Some(resolver) = resolver else {
+                error!("login requires solving a {:?} challenge", challenge.kind);
+                return Err(Error::Login {
+                    source: LoginError::ChallengeRequired,
+                });
+            };
+            if attempts == MAX_CHALLENGE_ATTEMPTS {
+                error!("login challenge was not accepted after {attempts} attempts");
+                return Err(Error::Login {
+                    source: LoginError::ChallengeFailed,
                 });
+            }
+            attempts += 1;
+            info!("resolving {:?} login challenge", challenge.kind);
+            solution = resolver.resolve(&challenge).await?;
+        };
+
         if session.is_none() || signature.is_none() {
             error!("unable to login, username or password is invalid");
             return Err(Error::Login {
@@ -66,8 +214,6 @@ impl UserCookies {
             user: UserCookies,
         }
 
-        let response: Value = response.json().await?;
-
         let user: UserCookies;
 
         if response["error"] == *"" {
@@ -144,6 +290,24 @@ impl UserCookies {
         })
     }
 
+    /// Load the session saved at `path`, or log in and save it there.
+    pub async fn login_cached(
+        path: impl AsRef<Path>,
+        username: &str,
+        password: &str,
+        totp_secret: Option<&str>,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        match Self::load_from_file(path) {
+            Ok(cookies) if !cookies.session.is_empty() => return Ok(cookies),
+            Ok(_) => {}
+            Err(e) => debug!("no saved session at {}: {e}", path.display()),
+        }
+        let cookies = Self::new().login(username, password, totp_secret).await?;
+        cookies.save_to_file(path)?;
+        Ok(cookies)
+    }
+
     async fn handle_mfa(totp_secret: &str, session: &str, signature: &str) -> Result<Response> {
         if totp_secret.is_empty() {
             return Err(Error::Login {
@@ -151,11 +315,10 @@ impl UserCookies {
             });
         }
 
-        let client = build_request(Some(&format!(
-            "sessionid={session}; sessionid_sign={signature};"
-        )))?;
+        let cookie = format!("sessionid={session}; sessionid_sign={signature};");
+        let client = build_request(Some(&cookie))?;
 
-        let response = client
+        let request = client
             .post("https://www.tradingview.com/accounts/two-factor/signin/totp/")
             .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
             .body(format!(
@@ -169,9 +332,8 @@ impl UserCookies {
                         });
                     }
                 }
-            ))
-            .send()
-            .await?;
+            ));
+        let response = execute(request, Some(&cookie)).await?;
 
         if response.status().is_success() {
             Ok(response)
@@ -182,3 +344,29 @@ impl UserCookies {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_login_challenge_from_response() {
+        let challenge = LoginChallenge::from_response(&json!({
+            "error": "Please confirm that you are not a robot by clicking the captcha box.",
+            "code": "recaptcha_required"
+        }))
+        .unwrap();
+        assert_eq!(challenge.kind, ChallengeKind::Captcha);
+        assert!(challenge.message.contains("robot"));
+
+        assert_eq!(
+            LoginChallenge::from_response(&json!({ "error": "2FA_required" })),
+            None
+        );
+        assert_eq!(
+            ChallengeSolution::captcha("token").fields,
+            vec![("g-recaptcha-response".to_string(), "token".to_string())]
+        );
+    }
+}
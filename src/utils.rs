@@ -1,32 +1,35 @@
+#[cfg(feature = "quote")]
+use crate::SessionType;
 use crate::{
-    Result, UserCookies,
-    live::models::{SocketMessage, SocketMessageDe},
-    models::{MarketAdjustment, SessionType},
+    Error, Result, UserCookies,
+    chart::ChartOptions,
+    client::{rate_limit::throttle, retry::with_retry},
+    error::TradingViewError,
+    live::{
+        handler::types::EventMask,
+        models::{QuoteFrame, SocketMessage, SocketMessageDe, TradingViewDataEvent},
+    },
 };
+#[cfg(feature = "pine")]
 use base64::engine::{Engine as _, general_purpose::STANDARD as BASE64};
-use iso_currency::Currency;
 use rand::{Rng, distr::Alphanumeric};
-use regex::Regex;
 use reqwest::{
-    Response,
-    header::{ACCEPT, COOKIE, HeaderMap, HeaderValue, ORIGIN, REFERER},
-};
-use serde::Serialize;
-use serde_json::Value;
-use std::{
-    collections::HashMap,
-    io::{Cursor, prelude::*},
+    RequestBuilder, Response, StatusCode,
+    header::{ACCEPT, COOKIE, HeaderMap, HeaderValue, ORIGIN, REFERER, USER_AGENT},
 };
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+#[cfg(feature = "pine")]
+use std::io::{Cursor, prelude::*};
+#[cfg(feature = "native")]
 use tokio_tungstenite::tungstenite::protocol::Message;
 use tracing::{debug, error};
 use ustr::Ustr;
+#[cfg(feature = "pine")]
 use zip::ZipArchive;
 
-lazy_static::lazy_static! {
-    static ref CLEANER_REGEX: Regex = Regex::new(r"~h~").expect("Failed to compile regex");
-    static ref SPLITTER_REGEX: Regex = Regex::new(r"~m~\d+~m~").expect("Failed to compile regex");
-}
-
+#[doc(hidden)]
 #[macro_export]
 macro_rules! payload {
     ($($payload:expr),*) => {
@@ -37,7 +40,8 @@ macro_rules! payload {
     };
 }
 
-pub fn build_request(cookie: Option<&str>) -> Result<reqwest::Client> {
+/// Headers sent with every REST request.
+fn default_headers(cookie: Option<&str>) -> Result<HeaderMap> {
     let mut headers = HeaderMap::new();
     headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
     headers.insert(
@@ -51,20 +55,74 @@ pub fn build_request(cookie: Option<&str>) -> Result<reqwest::Client> {
     if let Some(cookie) = cookie {
         headers.insert(COOKIE, HeaderValue::from_str(cookie)?);
     }
+    Ok(headers)
+}
 
-    let mut client = reqwest::Client::builder()
-        .default_headers(headers)
-        .https_only(true)
-        .user_agent(crate::UA);
-    #[cfg(feature = "rustls-tls")]
-    {
-        client = client.use_rustls_tls();
+/// Send `request` through the installed [`crate::transport::HttpTransport`],
+/// or reqwest's own client when none is installed.
+pub(crate) async fn execute(request: RequestBuilder, cookie: Option<&str>) -> Result<Response> {
+    match crate::transport::http_transport() {
+        Some(transport) => send_through(transport.as_ref(), request, cookie).await,
+        None => Ok(request.send().await?),
     }
-    #[cfg(feature = "native-tls")]
-    {
-        client = client.use_native_tls();
+}
+
+/// Send `request` through `transport`, adding the headers reqwest's client
+/// would otherwise apply itself.
+pub(crate) async fn send_through(
+    transport: &dyn crate::transport::HttpTransport,
+    request: RequestBuilder,
+    cookie: Option<&str>,
+) -> Result<Response> {
+    let mut request = request.build()?;
+    let mut headers = default_headers(cookie)?;
+    headers.insert(USER_AGENT, HeaderValue::from_static(crate::UA));
+    for (name, value) in headers {
+        if let Some(name) = name
+            && !request.headers().contains_key(&name)
+        {
+            request.headers_mut().insert(name, value);
+        }
     }
-    let client = client.build()?;
+    transport.execute(request).await
+}
+
+pub fn build_request(cookie: Option<&str>) -> Result<reqwest::Client> {
+    let client = client_builder(default_headers(cookie)?)?;
+    #[cfg(not(target_arch = "wasm32"))]
+    let client = client.https_only(true);
+    Ok(client.build()?)
+}
+
+/// A client builder sending `headers` through the configured TLS roots and
+/// proxy, without restricting the URL scheme.
+pub(crate) fn client_builder(headers: HeaderMap) -> Result<reqwest::ClientBuilder> {
+    // On wasm reqwest goes through `fetch`, which sets the user agent itself and has no proxies
+    #[cfg(target_arch = "wasm32")]
+    let client = reqwest::Client::builder().default_headers(headers);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let client = {
+        let mut client = reqwest::Client::builder()
+            .default_headers(headers)
+            .user_agent(crate::UA)
+            .timeout(crate::config::request_timeout());
+        #[cfg(feature = "rustls-tls")]
+        {
+            client = match crate::config::tls_client_config() {
+                Some(tls) => client.use_preconfigured_tls((*tls).clone()),
+                None => client.use_rustls_tls(),
+            };
+        }
+        #[cfg(feature = "native-tls")]
+        {
+            client = client.use_native_tls();
+        }
+        if let Some(proxy) = crate::config::proxy() {
+            client = client.proxy(reqwest::Proxy::all(proxy.to_url()?.as_str())?);
+        }
+        client
+    };
     Ok(client)
 }
 
@@ -83,66 +141,187 @@ pub fn gen_id() -> String {
     result
 }
 
+/// Split a websocket message into its `~m~<len>~m~<payload>` frames without
+/// copying, stripping the `~h~` prefix of heartbeat payloads.
+pub(crate) fn split_frames(message: &str) -> impl Iterator<Item = &str> {
+    let mut rest = message;
+    std::iter::from_fn(move || {
+        loop {
+            let body = rest.strip_prefix("~m~")?;
+            let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+            let Some(payload) = body[digits..].strip_prefix("~m~") else {
+                rest = "";
+                return None;
+            };
+            // The length counts UTF-16 units, so only trust it when it lands on the next frame
+            let end = body[..digits]
+                .parse::<usize>()
+                .ok()
+                .filter(|&len| {
+                    len <= payload.len()
+                        && payload.is_char_boundary(len)
+                        && (len == payload.len() || payload[len..].starts_with("~m~"))
+                })
+                .or_else(|| payload.find("~m~"))
+                .unwrap_or(payload.len());
+            let frame = &payload[..end];
+            rest = &payload[end..];
+            let frame = frame.strip_prefix("~h~").unwrap_or(frame);
+            if !frame.is_empty() {
+                return Some(frame);
+            }
+        }
+    })
+}
+
+#[cfg(feature = "simd-json")]
+thread_local! {
+    /// `simd-json` parses in place, so frames are copied into this buffer
+    /// instead of a fresh allocation per frame.
+    static FRAME_SCRATCH: std::cell::RefCell<Vec<u8>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Deserialize one frame, with `simd-json` when the feature is enabled.
+fn from_frame<T: DeserializeOwned>(frame: &str) -> std::result::Result<T, String> {
+    #[cfg(feature = "simd-json")]
+    {
+        FRAME_SCRATCH.with_borrow_mut(|bytes| {
+            bytes.clear();
+            bytes.extend_from_slice(frame.as_bytes());
+            simd_json::serde::from_slice(bytes).map_err(|e| e.to_string())
+        })
+    }
+    #[cfg(not(feature = "simd-json"))]
+    {
+        serde_json::from_str(frame).map_err(|e| e.to_string())
+    }
+}
+
+/// Method of a `{"m":...}` frame, read without deserializing it.
+fn frame_method(frame: &str) -> Option<&str> {
+    let (method, _) = frame.strip_prefix(r#"{"m":""#)?.split_once('"')?;
+    Some(method)
+}
+
+/// Parse every frame of a websocket message.
+///
+/// Method calls are deserialized straight into [`SocketMessageDe`] and, with
+/// `typed_quotes`, quote updates into [`SocketMessage::QuoteData`], skipping
+/// the untagged [`SocketMessage`] fallback used for everything else. Method
+/// calls of an event class outside `mask` are dropped unparsed.
 #[inline]
-pub fn parse_packet(message: &str) -> Vec<SocketMessage<SocketMessageDe>> {
-    if message.is_empty() {
-        return vec![];
-    }
-
-    let cleaned_message = CLEANER_REGEX.replace_all(message, "");
-    let packets: Vec<SocketMessage<SocketMessageDe>> = SPLITTER_REGEX
-        .split(&cleaned_message)
-        .filter(|packet| !packet.is_empty())
-        .map(|packet| match serde_json::from_str(packet) {
-            Ok(value) => value,
-            Err(error) => {
-                if error.is_syntax() {
-                    error!("error parsing packet, invalid JSON: {}", error);
-                } else {
+pub(crate) fn parse_packet(
+    message: &str,
+    typed_quotes: bool,
+    mask: EventMask,
+) -> Vec<SocketMessage<SocketMessageDe>> {
+    let mut messages = Vec::new();
+    parse_packet_into(message, typed_quotes, mask, &mut messages);
+    messages
+}
+
+/// [`parse_packet`] appending to `messages`, so the reader can reuse one
+/// buffer for every websocket message.
+#[tracing::instrument(name = "parse", level = "trace", skip_all)]
+pub(crate) fn parse_packet_into(
+    message: &str,
+    typed_quotes: bool,
+    mask: EventMask,
+    messages: &mut Vec<SocketMessage<SocketMessageDe>>,
+) {
+    let frames = split_frames(message)
+        .filter(|frame| {
+            mask.is_all()
+                || frame_method(frame)
+                    .is_none_or(|m| mask.intersects(TradingViewDataEvent::from(m).class()))
+        })
+        .map(|frame| {
+            if typed_quotes
+                && frame.starts_with(r#"{"m":"qsd""#)
+                && let Ok(quote) = from_frame::<QuoteFrame>(frame)
+                && quote.p.1.status == "ok"
+            {
+                return SocketMessage::QuoteData(quote.p.1);
+            }
+            if frame.starts_with(r#"{"m":"#)
+                && let Ok(message) = from_frame::<SocketMessageDe>(frame)
+            {
+                return SocketMessage::SocketMessage(message);
+            }
+            match from_frame(frame) {
+                Ok(value) => value,
+                Err(error) => {
                     error!("error parsing packet: {}", error);
+                    SocketMessage::Unknown(frame.to_string())
                 }
-                SocketMessage::Unknown(packet.to_string())
             }
-        })
-        .collect();
-
-    packets
+        });
+    messages.extend(frames);
 }
 
 #[inline]
-pub fn format_packet<T: Serialize>(packet: T) -> Result<Message> {
+pub fn format_frame<T: Serialize>(packet: T) -> Result<String> {
     let json_string = serde_json::to_string(&packet)?;
     let formatted_message = format!("~m~{}~m~{}", json_string.len(), json_string);
     debug!("Formatted packet: {}", formatted_message);
-    Ok(Message::Text(formatted_message.into()))
+    Ok(formatted_message)
 }
 
+#[cfg(feature = "native")]
 #[inline]
-pub fn symbol_init(
-    symbol: &str,
-    adjustment: Option<MarketAdjustment>,
-    currency: Option<Currency>,
-    session_type: Option<SessionType>,
-    replay: Option<&str>,
-) -> Result<String> {
-    let mut symbol_init: HashMap<Ustr, Ustr> = HashMap::new();
+pub fn format_packet<T: Serialize>(packet: T) -> Result<Message> {
+    Ok(Message::Text(format_frame(packet)?.into()))
+}
+
+/// `symbol` as sent to a quote session, wrapped into a symbol init string
+/// when quoted for a specific `session`.
+#[cfg(feature = "quote")]
+pub(crate) fn quote_symbol_payload(symbol: &str, session: Option<SessionType>) -> Result<Value> {
+    match session {
+        Some(session) => Ok(Value::from(symbol_init(
+            symbol,
+            &ChartOptions::default().session_type(session),
+            None,
+        )?)),
+        None => Ok(Value::from(symbol)),
+    }
+}
+
+#[inline]
+pub fn symbol_init(symbol: &str, options: &ChartOptions, replay: Option<&str>) -> Result<String> {
+    let mut symbol_init: HashMap<Ustr, Value> = HashMap::new();
     if let Some(s) = replay {
-        symbol_init.insert(Ustr::from("replay"), Ustr::from(s));
+        symbol_init.insert(Ustr::from("replay"), Value::from(s));
+    }
+    if let Some(a) = options.adjustment {
+        symbol_init.insert(Ustr::from("adjustment"), Value::from(a.to_string()));
+    }
+    if options.backadjustment {
+        symbol_init.insert(Ustr::from("backadjustment"), Value::from("default"));
     }
-    if let Some(a) = adjustment {
-        symbol_init.insert(Ustr::from("adjustment"), Ustr::from(&a.to_string()));
+    if let Some(s) = options.settlement_as_close {
+        symbol_init.insert(Ustr::from("settlement-as-close"), Value::from(s));
     }
-    symbol_init.insert(Ustr::from("symbol"), Ustr::from(symbol));
-    if let Some(c) = currency {
-        symbol_init.insert(Ustr::from("currency-id"), Ustr::from(c.code()));
+    symbol_init.insert(Ustr::from("symbol"), Value::from(symbol));
+    if let Some(c) = options.currency {
+        symbol_init.insert(Ustr::from("currency-id"), Value::from(c.code()));
     }
-    if let Some(s) = session_type {
-        symbol_init.insert(Ustr::from("session"), Ustr::from(&s.to_string()));
+    if let Some(s) = options.session_type {
+        symbol_init.insert(Ustr::from("session"), Value::from(s.to_string()));
     }
-    let symbol_init_json = serde_json::to_value(&symbol_init)?;
+    let symbol_init_json = match options.style.chart_type() {
+        // Alternative chart styles wrap the symbol into the study building their bars
+        Some(chart_type) => json!({
+            "symbol": symbol_init,
+            "type": chart_type.to_string(),
+            "inputs": options.style.inputs(),
+        }),
+        None => serde_json::to_value(&symbol_init)?,
+    };
     Ok(format!("={symbol_init_json}"))
 }
 
+#[cfg(feature = "pine")]
 pub fn _parse_compressed(data: &str) -> Result<Value> {
     let decoded_data = BASE64.decode(data)?;
     let mut zip = ZipArchive::new(Cursor::new(decoded_data))?;
@@ -158,26 +337,107 @@ pub async fn get(
     url: &str,
     queries: &[(&str, &str)],
 ) -> Result<Response> {
-    let c = match client {
-        Some(client) => {
-            let cookie = format!(
-                "sessionid={}; sessionid_sign={}; device_t={};",
-                client.session, client.session_signature, client.device_token
-            );
-            build_request(Some(&cookie))?
-        }
-        None => build_request(None)?,
-    };
+    send(client, url, true, |http| http.get(url).query(queries)).await
+}
+
+static SYMBOL_URL: &str = "https://scanner.tradingview.com/symbol";
+
+/// Scanner columns `fields`, comma separated, of one `EXCHANGE:SYMBOL`. The
+/// object is empty when the scanner does not know the symbol.
+pub async fn get_symbol_fields(
+    client: Option<&UserCookies>,
+    symbol: &str,
+    fields: &str,
+) -> Result<Value> {
+    Ok(get(
+        client,
+        SYMBOL_URL,
+        &[("symbol", symbol), ("fields", fields), ("no_404", "true")],
+    )
+    .await?
+    .error_for_status()?
+    .json()
+    .await?)
+}
 
-    let response = c.get(url).query(queries).send().await?;
-    Ok(response)
+pub async fn post_json<T: Serialize + ?Sized>(
+    client: Option<&UserCookies>,
+    url: &str,
+    body: &T,
+) -> Result<Response> {
+    send(client, url, false, |http| http.post(url).json(body)).await
+}
+
+pub async fn post_form<T: Serialize + ?Sized>(
+    client: Option<&UserCookies>,
+    url: &str,
+    form: &T,
+) -> Result<Response> {
+    send(client, url, false, |http| http.post(url).form(form)).await
+}
+
+pub async fn put_form<T: Serialize + ?Sized>(
+    client: Option<&UserCookies>,
+    url: &str,
+    form: &T,
+) -> Result<Response> {
+    send(client, url, true, |http| http.put(url).form(form)).await
+}
+
+pub async fn delete(client: Option<&UserCookies>, url: &str) -> Result<Response> {
+    send(client, url, true, |http| http.delete(url)).await
+}
+
+/// Send the request built by `request` through the rate limiter, retrying
+/// transient failures with the installed [`crate::client::retry::RetryPolicy`].
+async fn send(
+    client: Option<&UserCookies>,
+    url: &str,
+    idempotent: bool,
+    request: impl Fn(&reqwest::Client) -> RequestBuilder,
+) -> Result<Response> {
+    let cookie = client.map(session_cookie);
+    let http = build_request(cookie.as_deref())?;
+    with_retry(url, idempotent, || async {
+        let _permit = throttle(url).await;
+        let response = execute(request(&http), cookie.as_deref()).await?;
+        check_status(client, response)
+    })
+    .await
+}
+
+pub(crate) fn session_cookie(client: &UserCookies) -> String {
+    format!(
+        "sessionid={}; sessionid_sign={}; device_t={};",
+        client.session, client.session_signature, client.device_token
+    )
+}
+
+/// Turn a `401` on an authenticated request into an expired-session error, a
+/// `429` into [`Error::RateLimited`] and server errors into [`Error::HttpStatus`].
+fn check_status(client: Option<&UserCookies>, response: Response) -> Result<Response> {
+    match response.status() {
+        StatusCode::UNAUTHORIZED if client.is_some() => {
+            Err(TradingViewError::InvalidSessionId.into())
+        }
+        StatusCode::TOO_MANY_REQUESTS => {
+            Err(Error::RateLimited(Ustr::from(response.url().as_str())))
+        }
+        status if status.is_server_error() => Err(Error::HttpStatus(
+            status.as_u16(),
+            Ustr::from(response.url().as_str()),
+        )),
+        _ => Ok(response),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use iso_currency::Currency;
     use serde_json::json;
 
     use crate::{
+        chart::{BoxSize, ChartStyle, PriceSource},
         models::{MarketAdjustment, SessionType},
         utils::*,
     };
@@ -188,12 +448,33 @@ mod tests {
         let messages =
             std::fs::read_to_string(format!("{current_dir}/tests/data/socket_messages.txt"))
                 .unwrap();
-        let result = parse_packet(messages.as_str());
+        let result = parse_packet(messages.as_str(), true, EventMask::all());
 
         let data = result;
         assert_eq!(data.len(), 42);
     }
 
+    #[test]
+    fn test_split_frames() {
+        let frames: Vec<&str> = split_frames("~m~4~m~~h~7~m~11~m~{\"m\":\"é\"}~m~2~m~{}").collect();
+        assert_eq!(frames, vec!["7", "{\"m\":\"é\"}", "{}"]);
+
+        let quote =
+            r#"~m~0~m~{"m":"qsd","p":["qs_1",{"n":"BINANCE:BTCUSDT","s":"ok","v":{"lp":1.5}}]}"#;
+        let parsed = parse_packet(quote, true, EventMask::all());
+        assert!(matches!(&parsed[..], [SocketMessage::QuoteData(q)] if q.value.price == Some(1.5)));
+        assert!(matches!(
+            &parse_packet(quote, false, EventMask::all())[..],
+            [SocketMessage::SocketMessage(_)]
+        ));
+        // Masked events are skipped, heartbeats are kept
+        let frames = format!("{quote}~m~4~m~~h~7");
+        assert_eq!(
+            parse_packet(&frames, true, EventMask::all() - EventMask::QUOTE).len(),
+            1
+        );
+    }
+
     #[test]
     fn test_gen_session_id() {
         let session_type = "qc";
@@ -204,26 +485,48 @@ mod tests {
 
     #[test]
     fn test_symbol_init() {
-        let test1 = symbol_init("NSE:NIFTY", None, None, None, None);
+        let test1 = symbol_init("NSE:NIFTY", &ChartOptions::default(), None);
         assert!(test1.is_ok());
         assert_eq!(test1.unwrap(), r#"={"symbol":"NSE:NIFTY"}"#.to_string());
 
-        let test2 = symbol_init(
-            "HOSE:FPT",
-            Some(MarketAdjustment::Dividends),
-            Some(Currency::USD),
-            Some(SessionType::Extended),
-            Some("aaaaaaaaaaaa"),
-        );
+        let options = ChartOptions::default()
+            .adjustment(MarketAdjustment::Dividends)
+            .backadjustment(true)
+            .settlement_as_close(false)
+            .currency(Currency::USD)
+            .session_type(SessionType::Extended);
+        let test2 = symbol_init("HOSE:FPT", &options, Some("aaaaaaaaaaaa"));
         assert!(test2.is_ok());
         let test2_json: Value = serde_json::from_str(&test2.unwrap().replace('=', "")).unwrap();
         let expected2_json = json!({
             "adjustment": "dividends",
+            "backadjustment": "default",
+            "settlement-as-close": false,
             "currency-id": "USD",
             "replay": "aaaaaaaaaaaa",
             "session": "extended",
             "symbol": "HOSE:FPT"
         });
         assert_eq!(test2_json, expected2_json);
+
+        let renko = symbol_init(
+            "BINANCE:BTCUSDT",
+            &ChartOptions::default().style(ChartStyle::Renko {
+                source: PriceSource::Close,
+                box_size: BoxSize::Traditional(50.0),
+                wicks: true,
+            }),
+            None,
+        )
+        .unwrap();
+        let renko_json: Value = serde_json::from_str(&renko[1..]).unwrap();
+        assert_eq!(
+            renko_json,
+            json!({
+                "symbol": { "symbol": "BINANCE:BTCUSDT" },
+                "type": "BarSetRenko@tv-prostudies-40!",
+                "inputs": { "source": "close", "wicks": true, "style": "Traditional", "boxSize": 50.0 }
+            })
+        );
     }
 }